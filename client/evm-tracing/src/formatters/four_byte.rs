@@ -0,0 +1,140 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::listeners::four_byte::Listener;
+use crate::types::single::TransactionTrace;
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = Vec<TransactionTrace>;
+
+	fn format(listener: Listener) -> Option<Vec<TransactionTrace>> {
+		Some(
+			listener
+				.entries
+				.into_iter()
+				.map(|entry| {
+					TransactionTrace::FourByte(
+						entry
+							.into_iter()
+							.map(|((selector, size), count)| {
+								(format!("0x{}-{}", hex::encode(selector), size), count)
+							})
+							.collect(),
+					)
+				})
+				.collect(),
+		)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::ResponseFormatter;
+	use ethereum_types::{H160, U256};
+	use evm_tracing_events::{Context, Event, EvmEvent, Listener as ListenerT};
+
+	fn call(input: Vec<u8>) -> Event {
+		call_to(H160::default(), input)
+	}
+
+	fn call_to(code_address: H160, input: Vec<u8>) -> Event {
+		Event::Evm(EvmEvent::Call {
+			code_address,
+			transfer: None,
+			input,
+			target_gas: None,
+			is_static: false,
+			context: Context {
+				address: H160::default(),
+				caller: H160::default(),
+				apparent_value: U256::zero(),
+			},
+		})
+	}
+
+	#[test]
+	fn counts_selectors() {
+		let mut listener = Listener::default();
+		listener.event(Event::Evm(EvmEvent::TransactCall {
+			caller: H160::default(),
+			address: H160::default(),
+			value: U256::zero(),
+			data: vec![0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x00],
+			gas_limit: 0,
+		}));
+		// Root call, already counted from `TransactCall`.
+		listener.event(call(vec![0xa9, 0x05, 0x9c, 0xbb, 0x00, 0x00]));
+		listener.event(call(vec![0x70, 0xa0, 0x82, 0x31]));
+		listener.event(call(vec![0x70, 0xa0, 0x82, 0x31]));
+		// Too short to hold a selector.
+		listener.event(call(vec![0x70, 0xa0]));
+
+		let traces = Formatter::format(listener).unwrap();
+		assert_eq!(
+			serde_json::to_value(&traces).unwrap(),
+			serde_json::json!([{
+				"0x70a08231-0": 2,
+				"0xa9059cbb-2": 1,
+			}])
+		);
+	}
+
+	#[test]
+	fn skips_calls_to_precompiles() {
+		let mut listener = Listener::default();
+		// Calls to precompiles are only counted as the transaction itself.
+		listener.event(Event::Evm(EvmEvent::TransactCall {
+			caller: H160::default(),
+			address: H160::from_low_u64_be(2050),
+			value: U256::zero(),
+			data: vec![0xa9, 0x05, 0x9c, 0xbb],
+			gas_limit: 0,
+		}));
+		listener.event(call_to(
+			H160::from_low_u64_be(2050),
+			vec![0xa9, 0x05, 0x9c, 0xbb],
+		));
+		listener.event(call_to(
+			H160::from_low_u64_be(1),
+			vec![0x70, 0xa0, 0x82, 0x31],
+		));
+		listener.event(call_to(
+			H160::from_low_u64_be(2050),
+			vec![0x70, 0xa0, 0x82, 0x31],
+		));
+		let mut asset = [0xffu8; 20];
+		asset[19] = 1;
+		listener.event(call_to(H160::from(asset), vec![0x70, 0xa0, 0x82, 0x31]));
+		// A regular contract.
+		listener.event(call_to(
+			H160::repeat_byte(0x11),
+			vec![0x70, 0xa0, 0x82, 0x31],
+		));
+
+		let traces = Formatter::format(listener).unwrap();
+		assert_eq!(
+			serde_json::to_value(&traces).unwrap(),
+			serde_json::json!([{
+				"0x70a08231-0": 1,
+				"0xa9059cbb-0": 1,
+			}])
+		);
+	}
+}
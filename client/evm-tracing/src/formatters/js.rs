@@ -0,0 +1,348 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runs Geth-compatible Javascript tracers against the steps and call frames of a transaction,
+//! using an embedded QuickJS engine.
+//!
+//! Unlike Geth, the tracer is not run while the transaction executes but once it has been
+//! replayed, which has some limits:
+//! - The state cannot be accessed, so tracers using `db` are rejected before the transaction is
+//!   replayed.
+//! - All the steps are buffered in memory before the tracer runs, which is bounded by the maximum
+//!   number of steps of a request (`--ethapi-debug-max-steps`).
+//! - The timeout of the request covers both the replay and the tracer, whose engine is also
+//!   bounded in memory (`--ethapi-debug-js-memory-limit`) and stack size
+//!   (`--ethapi-debug-js-max-stack-size`).
+//! - `getRefund` of the steps and `getGasUsed` of the call frames always return 0.
+
+use crate::listeners::js::{Frame, Listener};
+use crate::types::single::{RawStepLog, TransactionTrace};
+
+use ethereum_types::H160;
+use evm_tracing_events::{opcodes_string, runtime::Opcode};
+use rquickjs::{Context, Function, Runtime, Value};
+use serde_json::{json, Map, Value as JsonValue};
+use std::{
+	collections::btree_map::BTreeMap,
+	time::{Duration, Instant},
+	vec::Vec,
+};
+
+/// Script evaluating to the function replaying the events against a tracer object.
+const DRIVER: &str = include_str!("js_driver.js");
+
+pub struct Formatter;
+
+impl Formatter {
+	/// Checks that `tracer` can be run, before the transaction is replayed for it. Tracers
+	/// accessing a member of `db` are rejected, as the state is not available. Only declaring the
+	/// `db` argument is allowed.
+	///
+	/// This is a best-effort search of the source, not a parse: it also rejects `db.` and `db[`
+	/// inside strings and comments, and misses accesses through an alias of `db`, which then fail
+	/// when the tracer runs.
+	pub fn check(tracer: &str) -> Result<(), String> {
+		let is_identifier = |c: char| c.is_ascii_alphanumeric() || c == '_' || c == '$';
+		let references_db = tracer.match_indices("db").any(|(index, _)| {
+			let before = tracer[..index].chars().next_back();
+			let after = tracer[index + 2..].trim_start().chars().next();
+			!before.map_or(false, is_identifier) && matches!(after, Some('.') | Some('['))
+		});
+		if references_db {
+			return Err(
+				"state access (`db`) is not supported by Javascript tracers; this best-effort check \
+				also rejects `db.` and `db[` inside strings and comments"
+					.to_string(),
+			);
+		}
+		Ok(())
+	}
+
+	/// Runs the Javascript `tracer` object against the events collected by `listener`, failing if
+	/// it takes more than `timeout`, allocates more than `memory_limit` bytes or uses a stack
+	/// larger than `max_stack_size` bytes.
+	pub fn format(
+		listener: Listener,
+		tracer: &str,
+		timeout: Duration,
+		memory_limit: usize,
+		max_stack_size: usize,
+	) -> Result<TransactionTrace, String> {
+		let data = Self::data(listener)?.to_string();
+
+		let runtime = Runtime::new().map_err(|e| format!("{}", e))?;
+		runtime.set_memory_limit(memory_limit);
+		runtime.set_max_stack_size(max_stack_size);
+		let deadline = Instant::now() + timeout;
+		runtime.set_interrupt_handler(Some(Box::new(move || Instant::now() > deadline)));
+		let context = Context::full(&runtime).map_err(|e| format!("{}", e))?;
+
+		let result = context.with(|ctx| -> rquickjs::Result<String> {
+			let driver: Function = ctx.eval(DRIVER)?;
+			let tracer: Value = ctx.eval(format!("({})", tracer))?;
+			driver.call((tracer, data))
+		});
+
+		match result {
+			Ok(json) => {
+				serde_json::from_str::<JsonValue>(&json)
+					.map_err(|e| format!("tracer returned an invalid result: {}", e))?;
+				Ok(TransactionTrace::Js(json.into_bytes()))
+			}
+			Err(_) if Instant::now() > deadline => Err("execution timeout".to_string()),
+			Err(e) => Err(format!("{}", e)),
+		}
+	}
+
+	/// JSON document provided to the driver script, holding the context of the transaction and
+	/// its steps interleaved with the call frames.
+	fn data(listener: Listener) -> Result<JsonValue, String> {
+		let Listener {
+			raw,
+			frames,
+			root,
+			root_error,
+			..
+		} = listener;
+
+		let mut ctx = match root {
+			Some(root) => frame(root),
+			None => return Err("Trace result is empty.".to_string()),
+		};
+		ctx.insert("gasUsed".into(), json!(raw.final_gas));
+		ctx.insert("output".into(), json!(bytes(&raw.return_value)));
+		if let Some(error) = root_error {
+			ctx.insert(
+				"error".into(),
+				json!(String::from_utf8_lossy(&error).into_owned()),
+			);
+		}
+
+		let opcodes: BTreeMap<String, u8> = (0..=255u8)
+			.map(|byte| (op_name(&opcodes_string(Opcode(byte))), byte))
+			.collect();
+
+		let mut events = Vec::with_capacity(raw.step_logs.len() + frames.len());
+		let mut frames = frames.into_iter().peekable();
+		for (index, step) in raw.step_logs.iter().enumerate() {
+			while let Some((_, frame)) = frames.next_if(|(at, _)| *at <= index) {
+				events.push(frame_event(frame));
+			}
+			events.push(step_event(step, &opcodes));
+		}
+		events.extend(frames.map(|(_, frame)| frame_event(frame)));
+
+		Ok(json!({
+			"ctx": ctx,
+			"events": events,
+		}))
+	}
+}
+
+fn op_name(op: &[u8]) -> String {
+	String::from_utf8_lossy(op).to_uppercase()
+}
+
+fn bytes(bytes: &[u8]) -> String {
+	format!("0x{}", hex::encode(bytes))
+}
+
+fn address(address: &H160) -> String {
+	format!("0x{:x}", address)
+}
+
+fn frame(frame: Frame) -> Map<String, JsonValue> {
+	let mut object = Map::new();
+	match frame {
+		Frame::Enter {
+			call_type,
+			from,
+			to,
+			input,
+			gas,
+			value,
+			address: context_address,
+		} => {
+			object.insert("type".into(), json!(op_name(&call_type)));
+			object.insert("from".into(), json!(address(&from)));
+			object.insert("to".into(), json!(address(&to)));
+			object.insert("address".into(), json!(address(&context_address)));
+			object.insert("input".into(), json!(bytes(&input)));
+			object.insert("gas".into(), json!(gas));
+			object.insert("value".into(), json!(format!("0x{:x}", value)));
+		}
+		Frame::Exit { output, error } => {
+			object.insert("output".into(), json!(bytes(&output)));
+			if let Some(error) = error {
+				object.insert(
+					"error".into(),
+					json!(String::from_utf8_lossy(&error).into_owned()),
+				);
+			}
+		}
+	}
+	object
+}
+
+fn frame_event(event: Frame) -> JsonValue {
+	match event {
+		Frame::Enter { .. } => json!({ "enter": frame(event) }),
+		Frame::Exit { .. } => json!({ "exit": frame(event) }),
+	}
+}
+
+fn step_event(step: &RawStepLog, opcodes: &BTreeMap<String, u8>) -> JsonValue {
+	let op = op_name(&step.op);
+	let memory: Vec<u8> = step
+		.memory
+		.iter()
+		.flatten()
+		.flat_map(|word| word.as_bytes().to_vec())
+		.collect();
+	let stack: Vec<String> = step
+		.stack
+		.iter()
+		.flatten()
+		.map(|word| format!("0x{:x}", word))
+		.collect();
+
+	json!({
+		"step": {
+			"opNumber": opcodes.get(&op).copied().unwrap_or_default(),
+			"op": op,
+			"pc": step.pc.low_u64(),
+			"gas": step.gas.low_u64(),
+			"cost": step.gas_cost.low_u64(),
+			"depth": step.depth.low_u64(),
+			"stack": stack,
+			"memory": bytes(&memory),
+		}
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::U256;
+
+	const MEMORY_LIMIT: usize = 16 * 1024 * 1024;
+	const MAX_STACK_SIZE: usize = 256 * 1024;
+
+	#[test]
+	fn runs_tracer() {
+		let mut listener = Listener::default();
+		listener.root = Some(Frame::Enter {
+			call_type: b"CALL".to_vec(),
+			from: H160::repeat_byte(0x11),
+			to: H160::repeat_byte(0x22),
+			input: vec![],
+			gas: 100_000,
+			value: U256::zero(),
+			address: H160::repeat_byte(0x22),
+		});
+		listener.raw.step_logs = vec![RawStepLog {
+			depth: U256::one(),
+			gas: U256::from(100_000),
+			gas_cost: U256::from(3),
+			memory: None,
+			op: b"PUSH1".to_vec(),
+			pc: U256::zero(),
+			stack: Some(vec![]),
+			storage: None,
+		}];
+
+		let trace = Formatter::format(
+			listener,
+			"{ count: 0, step: function(log) { this.count += log.op.toNumber(); }, \
+			 result: function(ctx) { return { count: this.count, type: ctx.type }; } }",
+			Duration::from_secs(5),
+			MEMORY_LIMIT,
+			MAX_STACK_SIZE,
+		)
+		.unwrap();
+		assert_eq!(
+			serde_json::to_value(&trace).unwrap(),
+			json!({ "count": 0x60, "type": "CALL" })
+		);
+	}
+
+	#[test]
+	fn rejects_state_access() {
+		assert!(Formatter::check(
+			"{ step: function(log, db) { db.getBalance(log.contract.getAddress()); }, \
+			 result: function() { return null; } }"
+		)
+		.is_err());
+		assert!(Formatter::check(
+			"{ step: function(log, db) { db ['getNonce'](log.contract.getAddress()); }, \
+			 result: function() { return null; } }"
+		)
+		.is_err());
+		assert!(Formatter::check(
+			"{ dbg: 0, step: function(log, db) { this.dbg++; }, \
+			 result: function(ctx, db) { return this.dbg; } }"
+		)
+		.is_ok());
+	}
+
+	#[test]
+	fn enforces_timeout() {
+		let mut listener = Listener::default();
+		listener.root = Some(Frame::Enter {
+			call_type: b"CALL".to_vec(),
+			from: H160::default(),
+			to: H160::default(),
+			input: vec![],
+			gas: 0,
+			value: U256::zero(),
+			address: H160::default(),
+		});
+
+		let result = Formatter::format(
+			listener,
+			"{ result: function() { while (true) {} } }",
+			Duration::from_millis(100),
+			MEMORY_LIMIT,
+			MAX_STACK_SIZE,
+		);
+		assert_eq!(result, Err("execution timeout".to_string()));
+	}
+
+	#[test]
+	fn enforces_memory_limit() {
+		let mut listener = Listener::default();
+		listener.root = Some(Frame::Enter {
+			call_type: b"CALL".to_vec(),
+			from: H160::default(),
+			to: H160::default(),
+			input: vec![],
+			gas: 0,
+			value: U256::zero(),
+			address: H160::default(),
+		});
+
+		let result = Formatter::format(
+			listener,
+			"{ result: function() { \
+			 let data = []; while (true) { data.push('a'.repeat(1024)); } } }",
+			Duration::from_secs(5),
+			MEMORY_LIMIT,
+			MAX_STACK_SIZE,
+		);
+		assert!(result.is_err());
+		assert_ne!(result, Err("execution timeout".to_string()));
+	}
+}
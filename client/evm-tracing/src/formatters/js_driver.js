@@ -0,0 +1,178 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+// Replays the steps and call frames of a transaction against a Geth-compatible tracer object,
+// and returns the JSON of its result. Numbers are exposed as native `BigInt`s and byte arrays as
+// `Uint8Array`s.
+(function (tracer, json) {
+  const data = JSON.parse(json);
+
+  function bytes(hex) {
+    const digits = hex.startsWith("0x") ? hex.slice(2) : hex;
+    const out = new Uint8Array(digits.length / 2);
+    for (let i = 0; i < out.length; i++) {
+      out[i] = parseInt(digits.substr(i * 2, 2), 16);
+    }
+    return out;
+  }
+
+  function toHex(array) {
+    return "0x" + Array.from(array, (byte) => byte.toString(16).padStart(2, "0")).join("");
+  }
+
+  function toWord(value) {
+    const word = new Uint8Array(32);
+    const array = typeof value === "string" ? bytes(value) : value;
+    word.set(array.slice(-32), Math.max(0, 32 - array.length));
+    return word;
+  }
+
+  // Helpers available to tracers, as in Geth.
+  globalThis.bigInt = (value) => BigInt(value);
+  globalThis.toHex = toHex;
+  globalThis.toWord = toWord;
+  globalThis.toAddress = (value) => {
+    const array = typeof value === "string" ? bytes(value) : value;
+    return toWord(array).slice(12);
+  };
+  globalThis.slice = (array, start, end) => array.slice(start, end);
+
+  // State is not available once the transaction has been replayed.
+  function unavailable() {
+    throw new Error("state access is not available");
+  }
+  const db = {
+    getBalance: unavailable,
+    getNonce: unavailable,
+    getCode: unavailable,
+    getState: unavailable,
+    exists: unavailable,
+  };
+
+  function contract(frame) {
+    return {
+      getAddress: () => bytes(frame.address),
+      getCaller: () => bytes(frame.from),
+      getValue: () => BigInt(frame.value),
+      getInput: () => bytes(frame.input),
+    };
+  }
+
+  function log(step, contract) {
+    const memory = bytes(step.memory);
+    return {
+      op: {
+        toNumber: () => step.opNumber,
+        toString: () => step.op,
+        isPush: () => step.opNumber >= 0x60 && step.opNumber <= 0x7f,
+      },
+      stack: {
+        peek: (index) => {
+          if (index < 0 || index >= step.stack.length) {
+            throw new Error("tracer accessed out of bound stack: size " + step.stack.length);
+          }
+          return BigInt(step.stack[step.stack.length - 1 - index]);
+        },
+        length: () => step.stack.length,
+      },
+      memory: {
+        slice: (start, end) => {
+          const out = new Uint8Array(Math.max(0, end - start));
+          out.set(memory.slice(start, end));
+          return out;
+        },
+        getUint: (offset) => {
+          const word = new Uint8Array(32);
+          word.set(memory.slice(offset, offset + 32));
+          return BigInt(toHex(word));
+        },
+        length: () => memory.length,
+      },
+      contract: contract,
+      getPC: () => step.pc,
+      getGas: () => step.gas,
+      getCost: () => step.cost,
+      getDepth: () => step.depth,
+      getRefund: () => 0,
+      getError: () => undefined,
+    };
+  }
+
+  function callFrame(frame) {
+    return {
+      getType: () => frame.type,
+      getFrom: () => bytes(frame.from),
+      getTo: () => bytes(frame.to),
+      getInput: () => bytes(frame.input),
+      getGas: () => frame.gas,
+      getValue: () => BigInt(frame.value),
+    };
+  }
+
+  function frameResult(frame) {
+    return {
+      // Gas used by sub-calls is not tracked.
+      getGasUsed: () => 0,
+      getOutput: () => bytes(frame.output),
+      getError: () => frame.error,
+    };
+  }
+
+  const contracts = [contract(data.ctx)];
+  let lastLog = undefined;
+  for (const event of data.events) {
+    if (event.step) {
+      lastLog = log(event.step, contracts[contracts.length - 1]);
+      if (tracer.step) {
+        tracer.step(lastLog, db);
+      }
+    } else if (event.enter) {
+      contracts.push(contract(event.enter));
+      if (tracer.enter) {
+        tracer.enter(callFrame(event.enter));
+      }
+    } else if (event.exit) {
+      contracts.pop();
+      if (tracer.exit) {
+        tracer.exit(frameResult(event.exit));
+      }
+    }
+  }
+
+  // The transaction failed on its last step, for another reason than a revert.
+  if (data.ctx.error && data.ctx.error !== "execution reverted" && lastLog && tracer.fault) {
+    tracer.fault(lastLog, db);
+  }
+
+  const ctx = {
+    type: data.ctx.type,
+    from: bytes(data.ctx.from),
+    to: bytes(data.ctx.to),
+    input: bytes(data.ctx.input),
+    gas: data.ctx.gas,
+    gasUsed: data.ctx.gasUsed,
+    value: BigInt(data.ctx.value),
+    output: bytes(data.ctx.output),
+  };
+  if (data.ctx.error) {
+    ctx.error = data.ctx.error;
+  }
+
+  const result = JSON.stringify(tracer.result(ctx, db), (_key, value) =>
+    typeof value === "bigint" ? value.toString() : value
+  );
+  return result === undefined ? "null" : result;
+});
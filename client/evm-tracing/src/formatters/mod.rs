@@ -16,11 +16,23 @@
 
 pub mod blockscout;
 pub mod call_tracer;
+pub mod four_byte;
+#[cfg(feature = "js-tracer")]
+pub mod js;
+pub mod noop;
+pub mod opcount;
+pub mod prestate;
 pub mod raw;
 pub mod trace_filter;
 
 pub use blockscout::Formatter as Blockscout;
 pub use call_tracer::Formatter as CallTracer;
+pub use four_byte::Formatter as FourByte;
+#[cfg(feature = "js-tracer")]
+pub use js::Formatter as Js;
+pub use noop::Formatter as Noop;
+pub use opcount::Formatter as Opcount;
+pub use prestate::Formatter as Prestate;
 pub use raw::Formatter as Raw;
 pub use trace_filter::Formatter as TraceFilter;
 
@@ -0,0 +1,33 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::listeners::noop::Listener;
+use crate::types::single::{NoopTrace, TransactionTrace};
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = Vec<TransactionTrace>;
+
+	fn format(listener: Listener) -> Option<Vec<TransactionTrace>> {
+		Some(
+			(0..listener.transactions)
+				.map(|_| TransactionTrace::Noop(NoopTrace {}))
+				.collect(),
+		)
+	}
+}
@@ -0,0 +1,305 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::listeners::prestate::{Account, Entry, Listener};
+use crate::types::single::{PrestateAccount, PrestateTrace, TransactionTrace};
+
+use ethereum_types::{H160, H256};
+use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
+
+pub struct Formatter;
+
+impl super::ResponseFormatter for Formatter {
+	type Listener = Listener;
+	type Response = Vec<TransactionTrace>;
+
+	fn format(mut listener: Listener) -> Option<Vec<TransactionTrace>> {
+		// Remove transactions that didn't reach the EVM.
+		// I.e. InvalidNonce or other pallet_evm::runner exits
+		listener.entries.retain(|entry| !entry.pre.is_empty());
+		let diff_mode = listener.diff_mode;
+		let traces = listener
+			.entries
+			.into_iter()
+			.map(|entry| {
+				TransactionTrace::Prestate(if diff_mode {
+					diff(entry)
+				} else {
+					prestate(entry)
+				})
+			})
+			.collect::<Vec<_>>();
+		if traces.is_empty() {
+			return None;
+		}
+		Some(traces)
+	}
+}
+
+/// Full account state, omitting the fields Geth doesn't output for empty values.
+fn account(account: &Account) -> PrestateAccount {
+	PrestateAccount {
+		balance: Some(account.balance),
+		nonce: Some(account.nonce.low_u64()).filter(|nonce| *nonce != 0),
+		code: Some(account.code.clone()).filter(|code| !code.is_empty()),
+		storage: Some(account.storage.clone()).filter(|storage| !storage.is_empty()),
+	}
+}
+
+/// A contract deployed by the transaction didn't exist before it, unless some funds had been sent
+/// to its address beforehand.
+fn is_new(entry: &Entry, address: &H160, before: &Account) -> bool {
+	entry.created.contains(address) && before.is_empty()
+}
+
+fn prestate(entry: Entry) -> PrestateTrace {
+	PrestateTrace::Prestate(
+		entry
+			.pre
+			.iter()
+			.filter(|(address, before)| !is_new(&entry, address, before))
+			.map(|(address, before)| (*address, account(before)))
+			.collect(),
+	)
+}
+
+fn diff(entry: Entry) -> PrestateTrace {
+	let mut pre = BTreeMap::new();
+	let mut post = BTreeMap::new();
+
+	for (address, before) in entry.pre.iter() {
+		let after = entry.post.get(address).cloned().unwrap_or_default();
+
+		// Destroyed accounts only appear in the pre state.
+		if after.is_empty() && !before.is_empty() {
+			pre.insert(*address, account(before));
+			continue;
+		}
+
+		let mut modified = false;
+		let mut changes = PrestateAccount::default();
+		if after.balance != before.balance {
+			changes.balance = Some(after.balance);
+			modified = true;
+		}
+		if after.nonce != before.nonce {
+			changes.nonce = Some(after.nonce.low_u64());
+			modified = true;
+		}
+		if after.code != before.code {
+			changes.code = Some(after.code.clone());
+			modified = true;
+		}
+
+		// Only the modified slots are reported, and cleared slots are omitted from the post state.
+		let mut storage_before = BTreeMap::new();
+		let mut storage_after = BTreeMap::new();
+		for (index, value) in before.storage.iter() {
+			let new_value = after.storage.get(index).cloned().unwrap_or_default();
+			if new_value == *value {
+				continue;
+			}
+			modified = true;
+			storage_before.insert(*index, *value);
+			if new_value != H256::zero() {
+				storage_after.insert(*index, new_value);
+			}
+		}
+
+		if !modified {
+			continue;
+		}
+		if !is_new(&entry, address, before) {
+			let mut before = account(before);
+			before.storage = Some(storage_before).filter(|storage| !storage.is_empty());
+			pre.insert(*address, before);
+		}
+		changes.storage = Some(storage_after).filter(|storage| !storage.is_empty());
+		post.insert(*address, changes);
+	}
+
+	PrestateTrace::Diff { pre, post }
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::formatters::ResponseFormatter;
+	use ethereum_types::U256;
+
+	fn listener(diff_mode: bool) -> Listener {
+		let sender = H160::repeat_byte(0x11);
+		let contract = H160::repeat_byte(0x22);
+		let slot = H256::from_low_u64_be(1);
+
+		let mut entry = Entry::default();
+		entry.pre.insert(
+			sender,
+			Account {
+				balance: U256::from(100),
+				nonce: U256::from(1),
+				..Default::default()
+			},
+		);
+		entry.pre.insert(
+			contract,
+			Account {
+				code: vec![0x60, 0x00],
+				storage: vec![(slot, H256::from_low_u64_be(7))].into_iter().collect(),
+				..Default::default()
+			},
+		);
+		entry.post.insert(
+			sender,
+			Account {
+				balance: U256::from(90),
+				nonce: U256::from(2),
+				..Default::default()
+			},
+		);
+		entry.post.insert(
+			contract,
+			Account {
+				code: vec![0x60, 0x00],
+				storage: vec![(slot, H256::zero())].into_iter().collect(),
+				..Default::default()
+			},
+		);
+
+		let mut listener = Listener::new(diff_mode);
+		listener.entries = vec![entry];
+		listener
+	}
+
+	/// Listener fed with the events the runtime emits for a call to a contract storing `1` at
+	/// slot `0`, the sender state being read before the fee is withdrawn.
+	fn listener_from_events(diff_mode: bool) -> Listener {
+		use evm_tracing_events::{
+			runtime::StateStage, Event, EvmEvent, Listener as ListenerT, RuntimeEvent,
+		};
+
+		let sender = H160::repeat_byte(0x11);
+		let contract = H160::repeat_byte(0x22);
+		let code = vec![0x60, 0x01, 0x60, 0x00, 0x55];
+		let balance = U256::from(1_000_000);
+		let fee = U256::from(42_000);
+		let account_state = |stage, address, balance, nonce: u64, code: &Vec<u8>| {
+			Event::Runtime(RuntimeEvent::AccountState {
+				stage,
+				address,
+				balance,
+				nonce: nonce.into(),
+				code: code.clone(),
+			})
+		};
+		let storage_state = |stage, value| {
+			Event::Runtime(RuntimeEvent::StorageState {
+				stage,
+				address: contract,
+				index: H256::zero(),
+				value: H256::from_low_u64_be(value),
+			})
+		};
+
+		let mut listener = Listener::new(diff_mode);
+		for event in vec![
+			account_state(StateStage::Pre, sender, balance, 5, &vec![]),
+			Event::Evm(EvmEvent::TransactCall {
+				caller: sender,
+				address: contract,
+				value: U256::zero(),
+				data: vec![],
+				gas_limit: 42_000,
+			}),
+			account_state(StateStage::Pre, contract, U256::zero(), 0, &code),
+			storage_state(StateStage::Pre, 0),
+			account_state(StateStage::Post, sender, balance - fee, 6, &vec![]),
+			account_state(StateStage::Post, contract, U256::zero(), 0, &code),
+			storage_state(StateStage::Post, 1),
+		] {
+			listener.event(event);
+		}
+		listener
+	}
+
+	#[test]
+	fn matches_geth_fixture() {
+		let fixture: serde_json::Value =
+			serde_json::from_str(include_str!("testdata/prestate_tracer.json")).unwrap();
+
+		let traces = Formatter::format(listener_from_events(false)).unwrap();
+		assert_eq!(serde_json::to_value(&traces[0]).unwrap(), fixture["result"]);
+
+		let traces = Formatter::format(listener_from_events(true)).unwrap();
+		assert_eq!(
+			serde_json::to_value(&traces[0]).unwrap(),
+			fixture["diffResult"]
+		);
+	}
+
+	#[test]
+	fn formats_prestate() {
+		let traces = Formatter::format(listener(false)).unwrap();
+		assert_eq!(
+			serde_json::to_value(&traces[0]).unwrap(),
+			serde_json::json!({
+				"0x1111111111111111111111111111111111111111": {
+					"balance": "0x64",
+					"nonce": 1,
+				},
+				"0x2222222222222222222222222222222222222222": {
+					"balance": "0x0",
+					"code": "0x6000",
+					"storage": {
+						"0x0000000000000000000000000000000000000000000000000000000000000001":
+							"0x0000000000000000000000000000000000000000000000000000000000000007",
+					},
+				},
+			})
+		);
+	}
+
+	#[test]
+	fn formats_diff() {
+		let traces = Formatter::format(listener(true)).unwrap();
+		assert_eq!(
+			serde_json::to_value(&traces[0]).unwrap(),
+			serde_json::json!({
+				"pre": {
+					"0x1111111111111111111111111111111111111111": {
+						"balance": "0x64",
+						"nonce": 1,
+					},
+					"0x2222222222222222222222222222222222222222": {
+						"balance": "0x0",
+						"code": "0x6000",
+						"storage": {
+							"0x0000000000000000000000000000000000000000000000000000000000000001":
+								"0x0000000000000000000000000000000000000000000000000000000000000007",
+						},
+					},
+				},
+				"post": {
+					"0x1111111111111111111111111111111111111111": {
+						"balance": "0x5a",
+						"nonce": 2,
+					},
+					"0x2222222222222222222222222222222222222222": {},
+				},
+			})
+		);
+	}
+}
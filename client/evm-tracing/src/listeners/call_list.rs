@@ -599,7 +599,7 @@ impl Listener {
 	}
 }
 
-fn error_message(error: &ExitError) -> Vec<u8> {
+pub(crate) fn error_message(error: &ExitError) -> Vec<u8> {
 	match error {
 		ExitError::StackUnderflow => "stack underflow",
 		ExitError::StackOverflow => "stack overflow",
@@ -0,0 +1,104 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::H160;
+use evm_tracing_events::{Event, EvmEvent, Listener as ListenerT};
+use std::{collections::btree_map::BTreeMap, vec, vec::Vec};
+
+/// Counts the function selectors of the calls performed by each transaction, keyed by selector
+/// and size of the arguments like Geth's 4byteTracer does.
+///
+/// Like Geth, the calls made to precompiles are skipped, but not the transaction itself. Which
+/// addresses are precompiles depends on the runtime, and is thus provided by the caller.
+pub struct Listener {
+	// One map per traced transaction.
+	pub entries: Vec<BTreeMap<(Vec<u8>, usize), u64>>,
+
+	/// `EvmEvent::TransactCall` is followed by an `EvmEvent::Call` for the same call, which must
+	/// not be counted twice.
+	skip_next_call: bool,
+
+	/// When tracing a block `Event::CallListNew` is emitted before each Ethereum transaction is
+	/// processed. The first one must not create a new entry.
+	call_list_first_transaction: bool,
+
+	/// Whether a precompile lives at the given address.
+	is_precompile: fn(&H160) -> bool,
+}
+
+impl Listener {
+	pub fn new(is_precompile: fn(&H160) -> bool) -> Self {
+		Self {
+			entries: vec![BTreeMap::new()],
+			skip_next_call: false,
+			call_list_first_transaction: true,
+			is_precompile,
+		}
+	}
+
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	fn count(&mut self, input: &[u8]) {
+		if input.len() < 4 {
+			return;
+		}
+		if let Some(entry) = self.entries.last_mut() {
+			*entry
+				.entry((input[..4].to_vec(), input.len() - 4))
+				.or_insert(0) += 1;
+		}
+	}
+
+	pub fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::TransactCall { data, .. } => {
+				self.count(&data);
+				self.skip_next_call = true;
+			}
+			EvmEvent::Call {
+				code_address,
+				input,
+				..
+			} => {
+				if self.skip_next_call {
+					self.skip_next_call = false;
+				} else if !(self.is_precompile)(&code_address) {
+					self.count(&input);
+				}
+			}
+			_ => (),
+		}
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Evm(e) => self.evm_event(e),
+			Event::CallListNew() => {
+				if !self.call_list_first_transaction {
+					self.skip_next_call = false;
+					self.entries.push(BTreeMap::new());
+				} else {
+					self.call_list_first_transaction = false;
+				}
+			}
+			_ => (),
+		};
+	}
+}
@@ -0,0 +1,257 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::{call_list::error_message, raw};
+use ethereum_types::{H160, U256};
+use evm_tracing_events::{
+	runtime::{Capture, ExitReason},
+	Event, EvmEvent, Listener as ListenerT, RuntimeEvent,
+};
+use std::{vec, vec::Vec};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Frame {
+	/// A call or contract creation starts.
+	Enter {
+		/// Uppercase opcode that started the frame (i.e. "CALL" or "CREATE2").
+		call_type: Vec<u8>,
+		from: H160,
+		to: H160,
+		input: Vec<u8>,
+		gas: u64,
+		value: U256,
+		/// Address whose storage is used by the frame, which differs from `to` in a delegate call.
+		address: H160,
+	},
+	/// The last entered frame ends.
+	Exit {
+		output: Vec<u8>,
+		error: Option<Vec<u8>>,
+	},
+}
+
+/// Collects the steps of a single transaction alongside its call frames, to be replayed by a
+/// Javascript tracer once the transaction is traced.
+pub struct Listener {
+	/// Steps of the transaction. Storage is not collected.
+	pub raw: raw::Listener,
+	/// Sub-call frames alongside the amount of steps executed before they happened.
+	pub frames: Vec<(usize, Frame)>,
+	/// Root call of the transaction, as a `Frame::Enter`.
+	pub root: Option<Frame>,
+	/// Error of the root call, if any.
+	pub root_error: Option<Vec<u8>>,
+
+	/// Amount of calls currently executing, including the root one.
+	depth: usize,
+	/// Opcode of the pending sub-call, known by the trap preceding the call event.
+	call_type: Option<Vec<u8>>,
+}
+
+impl Default for Listener {
+	fn default() -> Self {
+		Self {
+			raw: raw::Listener::new(true, false, false),
+			frames: vec![],
+			root: None,
+			root_error: None,
+			depth: 0,
+			call_type: None,
+		}
+	}
+}
+
+impl Listener {
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	fn enter(&mut self, frame: Frame) {
+		if self.depth == 0 {
+			// Only set if not already provided by the `EvmEvent::TransactX` event.
+			if self.root.is_none() {
+				self.root = Some(frame);
+			}
+		} else {
+			self.frames.push((self.raw.step_logs.len(), frame));
+		}
+		self.depth += 1;
+	}
+
+	pub fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::TransactCall {
+				caller,
+				address,
+				value,
+				data,
+				gas_limit,
+			} => {
+				self.root = Some(Frame::Enter {
+					call_type: b"CALL".to_vec(),
+					from: caller,
+					to: address,
+					input: data,
+					gas: gas_limit,
+					value,
+					address,
+				});
+			}
+			EvmEvent::TransactCreate {
+				caller,
+				value,
+				init_code,
+				gas_limit,
+				address,
+			} => {
+				self.root = Some(Frame::Enter {
+					call_type: b"CREATE".to_vec(),
+					from: caller,
+					to: address,
+					input: init_code,
+					gas: gas_limit,
+					value,
+					address,
+				});
+			}
+			EvmEvent::TransactCreate2 {
+				caller,
+				value,
+				init_code,
+				gas_limit,
+				address,
+				..
+			} => {
+				self.root = Some(Frame::Enter {
+					call_type: b"CREATE2".to_vec(),
+					from: caller,
+					to: address,
+					input: init_code,
+					gas: gas_limit,
+					value,
+					address,
+				});
+			}
+			EvmEvent::Call {
+				code_address,
+				input,
+				target_gas,
+				context,
+				..
+			} => {
+				let call_type = self.call_type.take().unwrap_or_else(|| b"CALL".to_vec());
+				self.enter(Frame::Enter {
+					call_type,
+					from: context.caller,
+					to: code_address,
+					input,
+					gas: target_gas.unwrap_or_default(),
+					value: context.apparent_value,
+					address: context.address,
+				});
+			}
+			EvmEvent::Create {
+				caller,
+				address,
+				value,
+				init_code,
+				target_gas,
+				..
+			} => {
+				let call_type = self.call_type.take().unwrap_or_else(|| b"CREATE".to_vec());
+				self.enter(Frame::Enter {
+					call_type,
+					from: caller,
+					to: address,
+					input: init_code,
+					gas: target_gas.unwrap_or_default(),
+					value,
+					address,
+				});
+			}
+			EvmEvent::Suicide {
+				address,
+				target,
+				balance,
+			} => {
+				// Like Geth, a self destruct is reported as a frame without any step.
+				let steps = self.raw.step_logs.len();
+				self.frames.push((
+					steps,
+					Frame::Enter {
+						call_type: b"SELFDESTRUCT".to_vec(),
+						from: address,
+						to: target,
+						input: vec![],
+						gas: 0,
+						value: balance,
+						address: target,
+					},
+				));
+				self.frames.push((
+					steps,
+					Frame::Exit {
+						output: vec![],
+						error: None,
+					},
+				));
+			}
+			EvmEvent::Exit {
+				reason,
+				return_value,
+			} => {
+				let error = match reason {
+					ExitReason::Succeed(_) => None,
+					ExitReason::Revert(_) => Some(b"execution reverted".to_vec()),
+					ExitReason::Error(error) => Some(error_message(&error)),
+					ExitReason::Fatal(_) => Some(b"fatal error".to_vec()),
+				};
+
+				self.depth = self.depth.saturating_sub(1);
+				if self.depth == 0 {
+					self.root_error = error;
+				} else {
+					self.frames.push((
+						self.raw.step_logs.len(),
+						Frame::Exit {
+							output: return_value,
+							error,
+						},
+					));
+				}
+			}
+		}
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Evm(e) => self.evm_event(e),
+			Event::Runtime(e) => {
+				if let RuntimeEvent::StepResult {
+					result: Err(Capture::Trap(opcode)),
+					..
+				} = &e
+				{
+					self.call_type = Some(opcode.to_ascii_uppercase());
+				}
+				self.raw.event(Event::Runtime(e));
+			}
+			e => self.raw.event(e),
+		};
+	}
+}
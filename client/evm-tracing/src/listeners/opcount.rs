@@ -0,0 +1,63 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use evm_tracing_events::{Event, Listener as ListenerT, RuntimeEvent};
+use std::{vec, vec::Vec};
+
+/// Counts the opcodes executed by each transaction, like Geth's opcountTracer.
+pub struct Listener {
+	// One counter per traced transaction.
+	pub entries: Vec<u64>,
+
+	/// When tracing a block `Event::CallListNew` is emitted before each Ethereum transaction is
+	/// processed. The first one must not create a new entry.
+	call_list_first_transaction: bool,
+}
+
+impl Default for Listener {
+	fn default() -> Self {
+		Self {
+			entries: vec![0],
+			call_list_first_transaction: true,
+		}
+	}
+}
+
+impl Listener {
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Runtime(RuntimeEvent::Step { .. }) => {
+				if let Some(count) = self.entries.last_mut() {
+					*count += 1;
+				}
+			}
+			Event::CallListNew() => {
+				if !self.call_list_first_transaction {
+					self.entries.push(0);
+				} else {
+					self.call_list_first_transaction = false;
+				}
+			}
+			_ => (),
+		};
+	}
+}
@@ -0,0 +1,240 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use ethereum_types::{H160, H256, U256};
+use evm_tracing_events::{
+	runtime::StateStage, Event, EvmEvent, Listener as ListenerT, RuntimeEvent,
+};
+use std::{
+	collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+	vec,
+	vec::Vec,
+};
+
+/// State of an account as read by the runtime tracer.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Account {
+	pub balance: U256,
+	pub nonce: U256,
+	pub code: Vec<u8>,
+	pub storage: BTreeMap<H256, H256>,
+}
+
+impl Account {
+	/// An account that did not exist in the state.
+	pub fn is_empty(&self) -> bool {
+		self.balance.is_zero()
+			&& self.nonce.is_zero()
+			&& self.code.is_empty()
+			&& self.storage.values().all(|value| value.is_zero())
+	}
+}
+
+/// Accounts touched by a single transaction.
+#[derive(Clone, Default, Debug, PartialEq, Eq)]
+pub struct Entry {
+	/// State before the transaction first accessed each account.
+	pub pre: BTreeMap<H160, Account>,
+	/// State once the transaction is applied.
+	pub post: BTreeMap<H160, Account>,
+	/// Contracts deployed by the transaction.
+	pub created: BTreeSet<H160>,
+}
+
+pub struct Listener {
+	/// Only report the accounts modified by the transaction, before and after.
+	pub diff_mode: bool,
+
+	// One entry per traced transaction.
+	pub entries: Vec<Entry>,
+
+	/// When tracing a block `Event::CallListNew` is emitted before each Ethereum transaction is
+	/// processed. The first one must not create a new entry.
+	call_list_first_transaction: bool,
+}
+
+impl Listener {
+	pub fn new(diff_mode: bool) -> Self {
+		Self {
+			diff_mode,
+			entries: vec![Entry::default()],
+			call_list_first_transaction: true,
+		}
+	}
+
+	pub fn using<R, F: FnOnce() -> R>(&mut self, f: F) -> R {
+		evm_tracing_events::using(self, f)
+	}
+
+	fn entry(&mut self) -> &mut Entry {
+		if self.entries.is_empty() {
+			self.entries.push(Entry::default());
+		}
+		self.entries
+			.last_mut()
+			.expect("an entry has just been pushed; qed")
+	}
+
+	pub fn evm_event(&mut self, event: EvmEvent) {
+		match event {
+			EvmEvent::Create { address, .. }
+			| EvmEvent::TransactCreate { address, .. }
+			| EvmEvent::TransactCreate2 { address, .. } => {
+				self.entry().created.insert(address);
+			}
+			_ => (),
+		}
+	}
+
+	pub fn runtime_event(&mut self, event: RuntimeEvent) {
+		match event {
+			RuntimeEvent::AccountState {
+				stage,
+				address,
+				balance,
+				nonce,
+				code,
+			} => {
+				let entry = self.entry();
+				let accounts = match stage {
+					StateStage::Pre => &mut entry.pre,
+					StateStage::Post => &mut entry.post,
+				};
+				let account = accounts.entry(address).or_default();
+				account.balance = balance;
+				account.nonce = nonce;
+				account.code = code;
+			}
+			RuntimeEvent::StorageState {
+				stage,
+				address,
+				index,
+				value,
+			} => {
+				let entry = self.entry();
+				let accounts = match stage {
+					StateStage::Pre => &mut entry.pre,
+					StateStage::Post => &mut entry.post,
+				};
+				accounts
+					.entry(address)
+					.or_default()
+					.storage
+					.insert(index, value);
+			}
+			// We ignore other kinds of message if any (new ones may be added in the future).
+			#[allow(unreachable_patterns)]
+			_ => (),
+		}
+	}
+}
+
+impl ListenerT for Listener {
+	fn event(&mut self, event: Event) {
+		match event {
+			Event::Evm(e) => self.evm_event(e),
+			Event::Runtime(e) => self.runtime_event(e),
+			Event::CallListNew() => {
+				if !self.call_list_first_transaction {
+					self.entries.push(Entry::default());
+				} else {
+					self.call_list_first_transaction = false;
+				}
+			}
+			_ => (),
+		};
+	}
+
+	fn state_tracing(&self) -> bool {
+		true
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn account_state(stage: StateStage, address: H160, balance: u64) -> Event {
+		Event::Runtime(RuntimeEvent::AccountState {
+			stage,
+			address,
+			balance: balance.into(),
+			nonce: U256::zero(),
+			code: vec![],
+		})
+	}
+
+	fn storage_state(stage: StateStage, address: H160, index: u64, value: u64) -> Event {
+		Event::Runtime(RuntimeEvent::StorageState {
+			stage,
+			address,
+			index: H256::from_low_u64_be(index),
+			value: H256::from_low_u64_be(value),
+		})
+	}
+
+	#[test]
+	fn collects_pre_and_post_state() {
+		let address = H160::repeat_byte(0x11);
+		let mut listener = Listener::new(false);
+		listener.event(account_state(StateStage::Pre, address, 10));
+		listener.event(storage_state(StateStage::Pre, address, 1, 0));
+		listener.event(account_state(StateStage::Post, address, 5));
+		listener.event(storage_state(StateStage::Post, address, 1, 2));
+
+		assert_eq!(listener.entries.len(), 1);
+		let entry = &listener.entries[0];
+		assert_eq!(entry.pre[&address].balance, U256::from(10));
+		assert_eq!(
+			entry.pre[&address].storage[&H256::from_low_u64_be(1)],
+			H256::zero()
+		);
+		assert_eq!(entry.post[&address].balance, U256::from(5));
+		assert_eq!(
+			entry.post[&address].storage[&H256::from_low_u64_be(1)],
+			H256::from_low_u64_be(2)
+		);
+	}
+
+	#[test]
+	fn new_entry_per_block_transaction() {
+		let address = H160::repeat_byte(0x11);
+		let mut listener = Listener::new(false);
+		listener.event(Event::CallListNew());
+		listener.event(account_state(StateStage::Pre, address, 10));
+		listener.event(Event::CallListNew());
+		listener.event(account_state(StateStage::Pre, address, 8));
+
+		assert_eq!(listener.entries.len(), 2);
+		assert_eq!(listener.entries[0].pre[&address].balance, U256::from(10));
+		assert_eq!(listener.entries[1].pre[&address].balance, U256::from(8));
+	}
+
+	#[test]
+	fn records_created_contracts() {
+		let address = H160::repeat_byte(0x22);
+		let mut listener = Listener::new(false);
+		listener.event(Event::Evm(EvmEvent::TransactCreate {
+			caller: H160::repeat_byte(0x11),
+			value: U256::zero(),
+			init_code: vec![],
+			gas_limit: 0,
+			address,
+		}));
+
+		assert!(listener.entries[0].created.contains(&address));
+	}
+}
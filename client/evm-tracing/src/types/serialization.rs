@@ -19,7 +19,7 @@
 use ethereum_types::{H256, U256};
 use serde::{
 	ser::{Error, SerializeSeq},
-	Serializer,
+	Serialize, Serializer,
 };
 
 pub fn seq_h256_serialize<S>(data: &Option<Vec<H256>>, serializer: S) -> Result<S::Ok, S::Error>
@@ -107,3 +107,12 @@ where
 {
 	serializer.serialize_str(&format!("0x{:x}", data))
 }
+
+pub fn json_serialize<S>(json: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+where
+	S: Serializer,
+{
+	let value: serde_json::Value =
+		serde_json::from_slice(json).map_err(|_| S::Error::custom("Json serialize error."))?;
+	value.serialize(serializer)
+}
@@ -23,7 +23,7 @@ use super::serialization::*;
 use serde::Serialize;
 
 use codec::{Decode, Encode};
-use ethereum_types::{H256, U256};
+use ethereum_types::{H160, H256, U256};
 use sp_std::{collections::btree_map::BTreeMap, vec::Vec};
 
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
@@ -33,7 +33,7 @@ pub enum Call {
 	CallTracer(crate::formatters::call_tracer::CallTracerCall),
 }
 
-#[derive(Clone, Copy, Eq, PartialEq, Debug, Encode, Decode)]
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode)]
 pub enum TraceType {
 	/// Classic geth with no javascript based tracing.
 	Raw {
@@ -45,6 +45,16 @@ pub enum TraceType {
 	CallList,
 	/// A single block trace. Use in `debug_traceTransactionByNumber` / `traceTransactionByHash`.
 	Block,
+	/// State of the accounts touched by the transaction (i.e. prestateTracer).
+	Prestate { diff_mode: bool },
+	/// Function selectors called by the transaction (i.e. 4byteTracer).
+	FourByte,
+	/// Amount of opcodes executed by the transaction (i.e. opcountTracer).
+	Opcount,
+	/// Empty output (i.e. noopTracer).
+	Noop,
+	/// Custom Javascript tracer.
+	Js { tracer: String },
 }
 
 /// Single transaction trace.
@@ -64,6 +74,52 @@ pub enum TransactionTrace {
 	CallList(Vec<Call>),
 	/// Used by Geth's callTracer.
 	CallListNested(Call),
+	/// Used by Geth's prestateTracer.
+	Prestate(PrestateTrace),
+	/// Used by Geth's 4byteTracer.
+	FourByte(BTreeMap<String, u64>),
+	/// Used by Geth's opcountTracer.
+	Opcount(u64),
+	/// Used by Geth's noopTracer.
+	Noop(NoopTrace),
+	/// JSON result of a Javascript tracer.
+	Js(#[serde(serialize_with = "json_serialize")] Vec<u8>),
+}
+
+/// Serialized as an empty object.
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
+pub struct NoopTrace {}
+
+#[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
+#[serde(untagged)]
+pub enum PrestateTrace {
+	/// State of the accounts touched by the transaction, before it was applied.
+	Prestate(BTreeMap<H160, PrestateAccount>),
+	/// State of the accounts modified by the transaction, before and after it was applied
+	/// (`diffMode`).
+	Diff {
+		pre: BTreeMap<H160, PrestateAccount>,
+		post: BTreeMap<H160, PrestateAccount>,
+	},
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PrestateAccount {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub balance: Option<U256>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub nonce: Option<u64>,
+
+	#[serde(
+		serialize_with = "option_bytes_0x_serialize",
+		skip_serializing_if = "Option::is_none"
+	)]
+	pub code: Option<Vec<u8>>,
+
+	#[serde(skip_serializing_if = "Option::is_none")]
+	pub storage: Option<BTreeMap<H256, H256>>,
 }
 
 #[derive(Clone, Eq, PartialEq, Debug, Encode, Decode, Serialize)]
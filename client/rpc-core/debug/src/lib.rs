@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 use ethereum_types::H256;
+use fc_rpc_core::types::CallRequest;
 use futures::{compat::Compat, future::BoxFuture};
 use jsonrpc_core::Result as RpcResult;
 use jsonrpc_derive::rpc;
@@ -29,11 +30,21 @@ pub struct TraceParams {
 	pub disable_storage: Option<bool>,
 	pub disable_memory: Option<bool>,
 	pub disable_stack: Option<bool>,
-	/// Javascript tracer (we just check if it's Blockscout tracer string)
+	/// Name of a native tracer, or Javascript tracer (only run with the `js-tracer` feature).
 	pub tracer: Option<String>,
+	pub tracer_config: Option<TracerConfig>,
+	/// Go duration after which the request is interrupted (i.e. "5s").
 	pub timeout: Option<String>,
 }
 
+/// Options of the native tracers.
+#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TracerConfig {
+	/// Only output the accounts modified by the transaction (prestateTracer).
+	pub diff_mode: Option<bool>,
+}
+
 #[rpc(server)]
 pub trait Debug {
 	#[rpc(name = "debug_traceTransaction")]
@@ -48,4 +59,14 @@ pub trait Debug {
 		id: RequestBlockId,
 		params: Option<TraceParams>,
 	) -> Compat<BoxFuture<'static, RpcResult<Vec<single::TransactionTrace>>>>;
+	/// Trace a call executed on top of the state of the requested block. Without `to`, the
+	/// trace is the one of a contract creation with `data` as init code. The call doesn't pay for
+	/// its gas: `gas` defaults to the gas limit of the block and cannot exceed it.
+	#[rpc(name = "debug_traceCall")]
+	fn trace_call(
+		&self,
+		call_request: CallRequest,
+		id: RequestBlockId,
+		params: Option<TraceParams>,
+	) -> Compat<BoxFuture<'static, RpcResult<single::TransactionTrace>>>;
 }
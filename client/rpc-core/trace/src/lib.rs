@@ -14,7 +14,8 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::H160;
+use ethereum_types::{H160, H256};
+use fc_rpc_core::types::Index;
 use futures::{compat::Compat, future::BoxFuture};
 use jsonrpc_derive::rpc;
 use moonbeam_client_evm_tracing::types::block::TransactionTrace;
@@ -30,12 +31,33 @@ pub trait Trace {
 		&self,
 		filter: FilterRequest,
 	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Vec<TransactionTrace>>>>;
+
+	#[rpc(name = "trace_block")]
+	fn block(
+		&self,
+		block: RequestBlockId,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Vec<TransactionTrace>>>>;
+
+	#[rpc(name = "trace_transaction")]
+	fn transaction(
+		&self,
+		transaction_hash: H256,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Option<Vec<TransactionTrace>>>>>;
+
+	/// Returns the trace of the transaction with the given trace address.
+	#[rpc(name = "trace_get")]
+	fn get(
+		&self,
+		transaction_hash: H256,
+		indices: Vec<Index>,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Option<TransactionTrace>>>>;
 }
 
-#[derive(Clone, Eq, PartialEq, Debug, Deserialize)]
+#[derive(Clone, Default, Eq, PartialEq, Debug, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FilterRequest {
-	/// (optional?) From this block.
+	/// (optional?) From this block. Can be a block number, hash or tag, "pending" being the block
+	/// built from the ready transactions of the pool.
 	pub from_block: Option<RequestBlockId>,
 
 	/// (optional?) To this block.
@@ -52,4 +74,23 @@ pub struct FilterRequest {
 
 	/// (optional) Integer number of traces to display in a batch.
 	pub count: Option<u32>,
+
+	/// (optional) How `from_address` and `to_address` are combined when both are provided.
+	/// Defaults to `intersection`.
+	pub mode: Option<FilterMode>,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FilterMode {
+	/// Traces matching `from_address` or `to_address`.
+	Union,
+	/// Traces matching both `from_address` and `to_address`.
+	Intersection,
+}
+
+impl Default for FilterMode {
+	fn default() -> Self {
+		FilterMode::Intersection
+	}
 }
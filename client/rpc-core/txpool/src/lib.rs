@@ -14,24 +14,65 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-use ethereum_types::U256;
+use ethereum_types::{H160, U256};
 use jsonrpc_core::Result;
 use jsonrpc_derive::rpc;
+use jsonrpc_pubsub::{typed::Subscriber, SubscriptionId};
+use std::collections::HashMap;
 
 mod types;
 
-pub use crate::types::{Get as GetT, Summary, Transaction, TransactionMap, TxPoolResult};
+pub use crate::types::{
+	Get as GetT, Pagination, Summary, Transaction, TransactionEvent, TransactionMap, TxPoolResult,
+};
 
 pub use rpc_impl_TxPool::gen_server::TxPool as TxPoolServer;
+pub use rpc_impl_TxPoolPubSub::gen_server::TxPoolPubSub as TxPoolPubSubServer;
 
 #[rpc(server)]
 pub trait TxPool {
+	/// Transactions of the pool grouped by sender, optionally paginated over the senders.
 	#[rpc(name = "txpool_content")]
-	fn content(&self) -> Result<TxPoolResult<TransactionMap<Transaction>>>;
+	fn content(
+		&self,
+		pagination: Option<Pagination>,
+	) -> Result<TxPoolResult<TransactionMap<Transaction>>>;
 
+	/// Transactions of the pool sent by the given address, keyed by nonce.
+	#[rpc(name = "txpool_contentFrom")]
+	fn content_from(&self, address: H160) -> Result<TxPoolResult<HashMap<U256, Transaction>>>;
+
+	/// Summaries of the transactions of the pool grouped by sender, optionally restricted to a
+	/// single sender and paginated over the senders.
 	#[rpc(name = "txpool_inspect")]
-	fn inspect(&self) -> Result<TxPoolResult<TransactionMap<Summary>>>;
+	fn inspect(
+		&self,
+		address: Option<H160>,
+		pagination: Option<Pagination>,
+	) -> Result<TxPoolResult<TransactionMap<Summary>>>;
 
 	#[rpc(name = "txpool_status")]
 	fn status(&self) -> Result<TxPoolResult<U256>>;
 }
+
+#[rpc(server)]
+pub trait TxPoolPubSub {
+	/// RPC Metadata
+	type Metadata;
+
+	/// Stream the Ethereum transactions entering the ready or future queues, with their full
+	/// body, and the ones replaced in or dropped from the pool.
+	#[pubsub(
+		subscription = "txpool_subscription",
+		subscribe,
+		name = "txpool_subscribe"
+	)]
+	fn subscribe(&self, _: Self::Metadata, _: Subscriber<TransactionEvent>);
+
+	#[pubsub(
+		subscription = "txpool_subscription",
+		unsubscribe,
+		name = "txpool_unsubscribe"
+	)]
+	fn unsubscribe(&self, _: Option<Self::Metadata>, _: SubscriptionId) -> Result<bool>;
+}
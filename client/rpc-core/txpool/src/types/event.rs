@@ -0,0 +1,34 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::Transaction;
+use ethereum_types::H256;
+use serde::Serialize;
+
+/// Change of the Ethereum transactions of the pool, sent to `txpool_subscribe` subscribers.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum TransactionEvent {
+	/// The transaction entered the ready queue, either when submitted or when its nonce gap was
+	/// filled.
+	Pending { transaction: Transaction },
+	/// The transaction entered the future queue, waiting for a transaction with a lower nonce.
+	Queued { transaction: Transaction },
+	/// The transaction has been replaced by another one with the same sender and nonce.
+	Replaced { hash: H256, by: H256 },
+	/// The transaction has been removed from the pool without being included in a block.
+	Dropped { hash: H256 },
+}
@@ -15,14 +15,16 @@
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
 mod content;
+mod event;
 mod inspect;
 
 use ethereum::TransactionV0 as EthereumTransaction;
 use ethereum_types::{H160, H256, U256};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 pub use self::content::Transaction;
+pub use self::event::TransactionEvent;
 pub use self::inspect::Summary;
 
 pub type TransactionMap<T> = HashMap<H160, HashMap<U256, T>>;
@@ -33,6 +35,19 @@ pub struct TxPoolResult<T: Serialize> {
 	pub queued: T,
 }
 
+/// Page of senders to return. Senders are ordered by address, and the transactions of a sender
+/// (both pending and queued) are never split across pages.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Pagination {
+	/// (optional) Only return the senders with a greater address than this one, usually the last
+	/// sender of the previous page.
+	pub after: Option<H160>,
+
+	/// (optional) Maximum number of senders to return.
+	pub count: Option<u32>,
+}
+
 pub trait Get {
 	fn get(hash: H256, from_address: H160, txn: &EthereumTransaction) -> Self;
 }
@@ -18,7 +18,7 @@ use futures::{
 	future::{BoxFuture, TryFutureExt},
 	FutureExt, SinkExt, StreamExt,
 };
-use jsonrpc_core::Result as RpcResult;
+use jsonrpc_core::{Error as RpcError, ErrorCode, Result as RpcResult};
 pub use moonbeam_rpc_core_debug::{Debug as DebugT, DebugServer, TraceParams};
 
 use tokio::{
@@ -26,25 +26,44 @@ use tokio::{
 	sync::{oneshot, Semaphore},
 };
 
-use ethereum_types::{H128, H256};
+use ethereum_types::{H128, H160, H256};
+use evm_tracing_events::{Event, Listener as ListenerT, RuntimeEvent};
 use fc_rpc::{frontier_backend_client, internal_err};
+use fc_rpc_core::types::CallRequest;
 use fp_rpc::EthereumRuntimeRPCApi;
 use moonbeam_client_evm_tracing::{formatters::ResponseFormatter, types::single};
 use moonbeam_rpc_core_types::{RequestBlockId, RequestBlockTag};
 use moonbeam_rpc_primitives_debug::{DebugRuntimeApi, TracerInput};
 use sc_client_api::backend::Backend;
-use sp_api::{BlockId, Core, HeaderT, ProvideRuntimeApi};
+use sp_api::{ApiExt, BlockId, Core, HeaderT, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{
 	Backend as BlockchainBackend, Error as BlockChainError, HeaderBackend, HeaderMetadata,
 };
 use sp_runtime::traits::{Block as BlockT, UniqueSaturatedInto};
 use sp_utils::mpsc::TracingUnboundedSender;
-use std::{future::Future, marker::PhantomData, str::FromStr, sync::Arc};
+use std::{
+	future::Future,
+	io,
+	marker::PhantomData,
+	panic::{self, AssertUnwindSafe},
+	str::FromStr,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+/// Error code of a request interrupted because it didn't complete before its timeout.
+pub const TIMEOUT_ERROR_CODE: i64 = -32010;
+/// Error code of a request whose trace exceeds the maximum number of steps or response size.
+pub const LIMIT_EXCEEDED_ERROR_CODE: i64 = -32011;
+
+/// Amount of events forwarded to a listener between two checks of the request deadline.
+const DEADLINE_CHECK_INTERVAL: u64 = 256;
 
 pub enum RequesterInput {
 	Transaction(H256),
 	Block(RequestBlockId),
+	Call(CallRequest, RequestBlockId),
 }
 
 pub enum Response {
@@ -56,6 +75,220 @@ pub type Responder = oneshot::Sender<RpcResult<Response>>;
 pub type DebugRequester =
 	TracingUnboundedSender<((RequesterInput, Option<TraceParams>), Responder)>;
 
+/// Resources a single debug request is allowed to use.
+#[derive(Clone, Copy, Debug)]
+pub struct DebugLimits {
+	/// Duration after which a request without a `timeout` parameter is interrupted.
+	pub default_timeout: Duration,
+	/// Maximum number of steps collected by opcode-level tracers (raw and Javascript).
+	pub max_steps: u64,
+	/// Maximum size (in bytes) of a serialized response.
+	pub max_response_size: usize,
+	/// Maximum memory (in bytes) allocated by the engine running a Javascript tracer.
+	pub js_memory_limit: usize,
+	/// Maximum stack size (in bytes) of the engine running a Javascript tracer.
+	pub js_max_stack_size: usize,
+}
+
+/// Limits of a single request, checked by the blocking task handling it.
+struct RequestLimits {
+	deadline: Instant,
+	max_steps: u64,
+	max_response_size: usize,
+	#[cfg(feature = "js-tracer")]
+	js_memory_limit: usize,
+	#[cfg(feature = "js-tracer")]
+	js_max_stack_size: usize,
+}
+
+impl RequestLimits {
+	fn new(limits: &DebugLimits, params: &Option<TraceParams>) -> RpcResult<Self> {
+		let timeout = match params.as_ref().and_then(|params| params.timeout.as_ref()) {
+			Some(timeout) => parse_duration(timeout)
+				.ok_or_else(|| internal_err(format!("invalid timeout {:?}", timeout)))?,
+			None => limits.default_timeout,
+		};
+		let deadline = Instant::now()
+			.checked_add(timeout)
+			.ok_or_else(|| internal_err(format!("invalid timeout {:?}", timeout)))?;
+
+		Ok(Self {
+			deadline,
+			max_steps: limits.max_steps,
+			max_response_size: limits.max_response_size,
+			#[cfg(feature = "js-tracer")]
+			js_memory_limit: limits.js_memory_limit,
+			#[cfg(feature = "js-tracer")]
+			js_max_stack_size: limits.js_max_stack_size,
+		})
+	}
+
+	/// Time left before the request times out.
+	fn remaining(&self) -> Duration {
+		self.deadline.saturating_duration_since(Instant::now())
+	}
+
+	/// Runs `f` using a `Limiter` in front of the listener of a tracer, and returns the listener
+	/// unless the request exceeded its limits. `count_steps` enables the maximum number of steps.
+	fn trace<L, F>(&self, listener: L, count_steps: bool, f: F) -> RpcResult<L>
+	where
+		L: ListenerT + 'static,
+		F: FnOnce() -> RpcResult<moonbeam_rpc_primitives_debug::Response>,
+	{
+		let mut limiter = Limiter {
+			listener,
+			deadline: self.deadline,
+			max_steps: if count_steps {
+				Some(self.max_steps)
+			} else {
+				None
+			},
+			events: 0,
+			steps: 0,
+			exceeded: None,
+		};
+		let result = panic::catch_unwind(AssertUnwindSafe(|| {
+			evm_tracing_events::using(&mut limiter, f)
+		}));
+
+		if let Some(e) = limiter.exceeded {
+			return Err(e);
+		}
+		let result = match result {
+			Ok(result) => result,
+			Err(payload) => panic::resume_unwind(payload),
+		};
+		if Instant::now() > self.deadline {
+			return Err(timeout_err());
+		}
+		result?;
+		Ok(limiter.listener)
+	}
+
+	/// Serializes the response without storing it, stopping as soon as it goes over the maximum
+	/// response size.
+	fn check_response_size(&self, response: &Response) -> RpcResult<()> {
+		let mut writer = SizeWriter {
+			size: 0,
+			max_size: self.max_response_size,
+		};
+		let result = match response {
+			Response::Single(trace) => serde_json::to_writer(&mut writer, trace),
+			Response::Block(traces) => serde_json::to_writer(&mut writer, traces),
+		};
+
+		match result {
+			Ok(()) => Ok(()),
+			Err(_) if writer.size > writer.max_size => Err(limit_err(format!(
+				"response exceeds the maximum size of {} bytes",
+				writer.max_size
+			))),
+			Err(e) => Err(internal_err(format!(
+				"Failed to serialize response : {:?}",
+				e
+			))),
+		}
+	}
+}
+
+/// Forwards the events of a traced runtime call to the listener of a tracer, until the request
+/// exceeds its limits.
+///
+/// The runtime call is then stopped by unwinding out of the host function that emitted the event,
+/// which the executor reports like a panic of the runtime. A runtime call is thus only stopped at
+/// its next event, and one that emits none runs to completion.
+struct Limiter<L> {
+	listener: L,
+	deadline: Instant,
+	max_steps: Option<u64>,
+	events: u64,
+	steps: u64,
+	exceeded: Option<RpcError>,
+}
+
+impl<L: ListenerT> ListenerT for Limiter<L> {
+	fn event(&mut self, event: Event) {
+		if self.exceeded.is_some() {
+			return;
+		}
+
+		self.events += 1;
+		if self.events % DEADLINE_CHECK_INTERVAL == 0 && Instant::now() > self.deadline {
+			self.stop(timeout_err());
+		}
+
+		if let (Some(max_steps), Event::Runtime(RuntimeEvent::Step { .. })) =
+			(self.max_steps, &event)
+		{
+			self.steps += 1;
+			if self.steps > max_steps {
+				self.stop(limit_err(format!(
+					"trace exceeds the maximum of {} steps",
+					max_steps
+				)));
+			}
+		}
+
+		self.listener.event(event);
+	}
+
+	fn state_tracing(&self) -> bool {
+		self.listener.state_tracing()
+	}
+}
+
+impl<L> Limiter<L> {
+	/// Records why the request is interrupted, and stops the traced runtime call. The unwinding
+	/// doesn't invoke the panic hook, and is caught by `RequestLimits::trace` if the executor
+	/// doesn't catch it first.
+	fn stop(&mut self, error: RpcError) -> ! {
+		self.exceeded = Some(error);
+		panic::resume_unwind(Box::new(LimitExceeded))
+	}
+}
+
+/// Payload of the unwinding stopping a runtime call whose request exceeded its limits.
+struct LimitExceeded;
+
+/// Counts the bytes written to it, and fails once they go over `max_size`.
+struct SizeWriter {
+	size: usize,
+	max_size: usize,
+}
+
+impl io::Write for SizeWriter {
+	fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+		self.size = self.size.saturating_add(buf.len());
+		if self.size > self.max_size {
+			return Err(io::Error::new(
+				io::ErrorKind::Other,
+				"maximum response size exceeded",
+			));
+		}
+		Ok(buf.len())
+	}
+
+	fn flush(&mut self) -> io::Result<()> {
+		Ok(())
+	}
+}
+
+fn timeout_err() -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(TIMEOUT_ERROR_CODE),
+		message: "execution timeout".to_string(),
+		data: None,
+	}
+}
+
+fn limit_err<T: ToString>(message: T) -> RpcError {
+	RpcError {
+		code: ErrorCode::ServerError(LIMIT_EXCEEDED_ERROR_CODE),
+		message: message.to_string(),
+		data: None,
+	}
+}
+
 pub struct Debug {
 	pub requester: DebugRequester,
 }
@@ -138,6 +371,43 @@ impl DebugT for Debug {
 		.boxed()
 		.compat()
 	}
+
+	/// Handler for `debug_traceCall` request. Communicates with the service-defined task
+	/// using channels.
+	fn trace_call(
+		&self,
+		call_request: CallRequest,
+		id: RequestBlockId,
+		params: Option<TraceParams>,
+	) -> Compat<BoxFuture<'static, RpcResult<single::TransactionTrace>>> {
+		let mut requester = self.requester.clone();
+
+		async move {
+			let (tx, rx) = oneshot::channel();
+			// Send a message from the rpc handler to the service level task.
+			requester
+				.send(((RequesterInput::Call(call_request, id), params), tx))
+				.await
+				.map_err(|err| {
+					internal_err(format!(
+						"failed to send request to debug service : {:?}",
+						err
+					))
+				})?;
+
+			// Receive a message from the service level task and send the rpc response.
+			rx.await
+				.map_err(|err| {
+					internal_err(format!("debug service dropped the channel : {:?}", err))
+				})?
+				.map(|res| match res {
+					Response::Single(res) => res,
+					_ => unreachable!(),
+				})
+		}
+		.boxed()
+		.compat()
+	}
 }
 
 pub struct DebugHandler<B: BlockT, C, BE>(PhantomData<(B, C, BE)>);
@@ -155,11 +425,16 @@ where
 {
 	/// Task spawned at service level that listens for messages on the rpc channel and spawns
 	/// blocking tasks using a permit pool.
+	///
+	/// `is_precompile` tells whether a precompile of the runtime lives at a given address, as
+	/// calls to precompiles are not counted by the 4byte tracer.
 	pub fn task(
 		client: Arc<C>,
 		backend: Arc<BE>,
 		frontier_backend: Arc<fc_db::Backend<B>>,
 		permit_pool: Arc<Semaphore>,
+		limits: DebugLimits,
+		is_precompile: fn(&H160) -> bool,
 	) -> (impl Future<Output = ()>, DebugRequester) {
 		let (tx, mut rx): (DebugRequester, _) =
 			sp_utils::mpsc::tracing_unbounded("debug-requester");
@@ -167,89 +442,56 @@ where
 		let fut = async move {
 			loop {
 				match rx.next().await {
-					Some((
-						(RequesterInput::Transaction(transaction_hash), params),
-						response_tx,
-					)) => {
-						let client = client.clone();
-						let backend = backend.clone();
-						let frontier_backend = frontier_backend.clone();
-						let permit_pool = permit_pool.clone();
-						// Note on spawned tasks https://tokio.rs/tokio/tutorial/spawning#tasks.
-						//
-						// Substrate uses the default value for `core_threads` (number of cores of the
-						// machine running the node) and `max_threads` (512 total).
-						//
-						// Task below is spawned in the substrate's built tokio::Runtime, so they share
-						// the same thread pool as the rest of the service-spawned tasks. Additionally,
-						// blocking tasks use a more restrictive permit pool shared by trace modules.
-						// https://docs.rs/tokio/0.2.23/tokio/sync/struct.Semaphore.html
-						tokio::task::spawn(async move {
-							let _ = response_tx.send(
-								async {
-									let _permit = permit_pool.acquire().await;
-									tokio::task::spawn_blocking(move || {
-										Self::handle_transaction_request(
-											client.clone(),
-											backend.clone(),
-											frontier_backend.clone(),
-											transaction_hash,
-											params,
-										)
-									})
-									.await
-									.map_err(|e| {
-										internal_err(format!(
-											"Internal error on spawned task : {:?}",
-											e
-										))
-									})?
-								}
-								.await,
-							);
-						});
-					}
-					Some(((RequesterInput::Block(request_block_id), params), response_tx)) => {
+					Some(((input, params), response_tx)) => {
+						let request_limits = match RequestLimits::new(&limits, &params) {
+							Ok(request_limits) => request_limits,
+							Err(e) => {
+								let _ = response_tx.send(Err(e));
+								continue;
+							}
+						};
 						let client = client.clone();
 						let backend = backend.clone();
 						let frontier_backend = frontier_backend.clone();
-						let permit_pool = permit_pool.clone();
-						// Note on spawned tasks https://tokio.rs/tokio/tutorial/spawning#tasks.
-						//
-						// Substrate uses the default value for `core_threads` (number of cores of the
-						// machine running the node) and `max_threads` (512 total).
-						//
-						// Task below is spawned in the substrate's built tokio::Runtime, so they share
-						// the same thread pool as the rest of the service-spawned tasks. Additionally,
-						// blocking tasks use a more restrictive permit pool shared by trace modules.
-						// https://docs.rs/tokio/0.2.23/tokio/sync/struct.Semaphore.html
-						tokio::task::spawn(async move {
-							println!("--> Request2");
-							let _ = response_tx.send(
-								async {
-									let _permit = permit_pool.acquire().await;
-
-									println!("--> Request3");
-									tokio::task::spawn_blocking(move || {
-										Self::handle_block_request(
-											client.clone(),
-											backend.clone(),
-											frontier_backend.clone(),
-											request_block_id,
-											params,
-										)
-									})
-									.await
-									.map_err(|e| {
-										internal_err(format!(
-											"Internal error on spawned task : {:?}",
-											e
-										))
-									})?
-								}
-								.await,
-							);
-						});
+						let handle = move |limits: &RequestLimits| match input {
+							RequesterInput::Transaction(transaction_hash) => {
+								Self::handle_transaction_request(
+									client,
+									backend,
+									frontier_backend,
+									transaction_hash,
+									params,
+									limits,
+									is_precompile,
+								)
+							}
+							RequesterInput::Block(request_block_id) => Self::handle_block_request(
+								client,
+								backend,
+								frontier_backend,
+								request_block_id,
+								params,
+								limits,
+								is_precompile,
+							),
+							RequesterInput::Call(call_request, request_block_id) => {
+								Self::handle_call_request(
+									client,
+									frontier_backend,
+									call_request,
+									request_block_id,
+									params,
+									limits,
+									is_precompile,
+								)
+							}
+						};
+						Self::spawn_request(
+							permit_pool.clone(),
+							request_limits,
+							response_tx,
+							handle,
+						);
 					}
 					_ => {}
 				}
@@ -258,24 +500,115 @@ where
 		(fut, tx)
 	}
 
+	/// Runs a request in a blocking task, and sends its response unless it times out first.
+	///
+	/// A request that times out is answered right away. The runtime call it is replaying is
+	/// stopped at its next event by the `Limiter`, and its blocking task holds its permit until
+	/// then. Timeouts thus bound the response time of the requests, and the time during which
+	/// they use a permit as long as their runtime call keeps emitting events.
+	fn spawn_request<H>(
+		permit_pool: Arc<Semaphore>,
+		limits: RequestLimits,
+		response_tx: Responder,
+		handle: H,
+	) where
+		H: FnOnce(&RequestLimits) -> RpcResult<Response> + Send + 'static,
+	{
+		// Note on spawned tasks https://tokio.rs/tokio/tutorial/spawning#tasks.
+		//
+		// Substrate uses the default value for `core_threads` (number of cores of the
+		// machine running the node) and `max_threads` (512 total).
+		//
+		// Task below is spawned in the substrate's built tokio::Runtime, so they share
+		// the same thread pool as the rest of the service-spawned tasks. Additionally,
+		// blocking tasks use a more restrictive permit pool shared by trace modules.
+		// https://docs.rs/tokio/0.2.23/tokio/sync/struct.Semaphore.html
+		tokio::task::spawn(async move {
+			let _permit = permit_pool.acquire().await;
+
+			// The request may have timed out while waiting for a permit.
+			let remaining = limits.remaining();
+			if remaining == Duration::from_secs(0) {
+				let _ = response_tx.send(Err(timeout_err()));
+				return;
+			}
+
+			let mut task = tokio::task::spawn_blocking(move || -> RpcResult<Response> {
+				let response = handle(&limits)?;
+				limits.check_response_size(&response)?;
+				Ok(response)
+			});
+			let result = tokio::time::timeout(remaining, &mut task).await;
+			match result {
+				Ok(response) => {
+					let _ = response_tx.send(
+						response
+							.map_err(|e| {
+								internal_err(format!("Internal error on spawned task : {:?}", e))
+							})
+							.and_then(|response| response),
+					);
+				}
+				Err(_) => {
+					let _ = response_tx.send(Err(timeout_err()));
+					// The runtime call is stopped at its first event past the deadline. The permit
+					// is only released once it returns, so that the amount of concurrent replays
+					// stays bounded.
+					let _ = task.await;
+				}
+			}
+		});
+	}
+
 	fn handle_params(params: Option<TraceParams>) -> RpcResult<(TracerInput, single::TraceType)> {
 		// Set trace input and type
 		match params {
 			Some(TraceParams {
 				tracer: Some(tracer),
+				tracer_config,
 				..
 			}) => {
 				let hash: H128 = sp_io::hashing::twox_128(&tracer.as_bytes()).into();
 				let blockscout_hash = H128::from_str("0x94d9f08796f91eb13a2e82a6066882f7").unwrap();
 				let tracer = if hash == blockscout_hash {
-					Some(TracerInput::Blockscout)
-				} else if tracer == "callTracer" {
-					Some(TracerInput::CallTracer)
+					Some((TracerInput::Blockscout, single::TraceType::CallList))
 				} else {
-					None
+					match tracer.as_str() {
+						"callTracer" => {
+							Some((TracerInput::CallTracer, single::TraceType::CallList))
+						}
+						"prestateTracer" => Some((
+							TracerInput::PrestateTracer,
+							single::TraceType::Prestate {
+								diff_mode: tracer_config
+									.and_then(|config| config.diff_mode)
+									.unwrap_or(false),
+							},
+						)),
+						"4byteTracer" => {
+							Some((TracerInput::FourByteTracer, single::TraceType::FourByte))
+						}
+						"opcountTracer" => {
+							Some((TracerInput::OpcountTracer, single::TraceType::Opcount))
+						}
+						"noopTracer" => Some((TracerInput::NoopTracer, single::TraceType::Noop)),
+						#[cfg(feature = "js-tracer")]
+						_ => {
+							moonbeam_client_evm_tracing::formatters::Js::check(&tracer)
+								.map_err(internal_err)?;
+							Some((
+								TracerInput::None,
+								single::TraceType::Js {
+									tracer: tracer.clone(),
+								},
+							))
+						}
+						#[cfg(not(feature = "js-tracer"))]
+						_ => None,
+					}
 				};
 				if let Some(tracer) = tracer {
-					Ok((tracer, single::TraceType::CallList))
+					Ok(tracer)
 				} else {
 					return Err(internal_err(format!(
 						"javascript based tracing is not available (hash :{:?})",
@@ -302,16 +635,13 @@ where
 		}
 	}
 
-	fn handle_block_request(
-		client: Arc<C>,
-		backend: Arc<BE>,
-		frontier_backend: Arc<fc_db::Backend<B>>,
+	/// Resolves the substrate block a request refers to.
+	fn reference_id(
+		client: &C,
+		frontier_backend: &fc_db::Backend<B>,
 		request_block_id: RequestBlockId,
-		params: Option<TraceParams>,
-	) -> RpcResult<Response> {
-		let (tracer_input, trace_type) = Self::handle_params(params)?;
-
-		let reference_id: BlockId<B> = match request_block_id {
+	) -> RpcResult<BlockId<B>> {
+		match request_block_id {
 			RequestBlockId::Number(n) => Ok(BlockId::Number(n.unique_saturated_into())),
 			RequestBlockId::Tag(RequestBlockTag::Latest) => {
 				Ok(BlockId::Number(client.info().best_number))
@@ -323,13 +653,28 @@ where
 				Err(internal_err("'pending' blocks are not supported"))
 			}
 			RequestBlockId::Hash(eth_hash) => {
-				match frontier_backend_client::load_hash::<B>(frontier_backend.as_ref(), eth_hash) {
+				match frontier_backend_client::load_hash::<B>(frontier_backend, eth_hash) {
 					Ok(Some(id)) => Ok(id),
 					Ok(_) => Err(internal_err("Block hash not found".to_string())),
 					Err(e) => Err(e),
 				}
 			}
-		}?;
+		}
+	}
+
+	fn handle_block_request(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		frontier_backend: Arc<fc_db::Backend<B>>,
+		request_block_id: RequestBlockId,
+		params: Option<TraceParams>,
+		limits: &RequestLimits,
+		is_precompile: fn(&H160) -> bool,
+	) -> RpcResult<Response> {
+		let (tracer_input, trace_type) = Self::handle_params(params)?;
+
+		let reference_id =
+			Self::reference_id(client.as_ref(), frontier_backend.as_ref(), request_block_id)?;
 
 		// Get ApiRef. This handle allow to keep changes between txs in an internal buffer.
 		let api = client.runtime_api();
@@ -382,8 +727,11 @@ where
 
 		return match trace_type {
 			single::TraceType::CallList => {
-				let mut proxy = moonbeam_client_evm_tracing::listeners::CallList::default();
-				proxy.using(f)?;
+				let mut proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::CallList::default(),
+					false,
+					f,
+				)?;
 				proxy.finish_transaction();
 				let response = match tracer_input {
 					TracerInput::CallTracer => {
@@ -399,6 +747,54 @@ where
 
 				Ok(Response::Block(response))
 			}
+			single::TraceType::Prestate { diff_mode } => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Prestate::new(diff_mode),
+					false,
+					f,
+				)?;
+				let response = moonbeam_client_evm_tracing::formatters::Prestate::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+
+				Ok(Response::Block(response))
+			}
+			single::TraceType::FourByte => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::FourByte::new(is_precompile),
+					false,
+					f,
+				)?;
+				let response = moonbeam_client_evm_tracing::formatters::FourByte::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+
+				Ok(Response::Block(response))
+			}
+			single::TraceType::Opcount => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Opcount::default(),
+					false,
+					f,
+				)?;
+				let response = moonbeam_client_evm_tracing::formatters::Opcount::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+
+				Ok(Response::Block(response))
+			}
+			single::TraceType::Noop => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Noop::default(),
+					false,
+					f,
+				)?;
+				let response = moonbeam_client_evm_tracing::formatters::Noop::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+
+				Ok(Response::Block(response))
+			}
 			not_supported => Err(internal_err(format!(
 				"Bug: `handle_block_request` does not support {:?}.",
 				not_supported
@@ -419,6 +815,8 @@ where
 		frontier_backend: Arc<fc_db::Backend<B>>,
 		transaction_hash: H256,
 		params: Option<TraceParams>,
+		limits: &RequestLimits,
+		is_precompile: fn(&H160) -> bool,
 	) -> RpcResult<Response> {
 		let (tracer_input, trace_type) = Self::handle_params(params)?;
 
@@ -473,54 +871,278 @@ where
 					Ok(moonbeam_rpc_primitives_debug::Response::Single)
 				};
 
-				return match trace_type {
-					single::TraceType::Raw {
+				return Self::trace_single(tracer_input, trace_type, limits, is_precompile, f);
+			}
+		}
+		Err(internal_err("Runtime block call failed".to_string()))
+	}
+
+	/// Traces a call on top of the state of a given block.
+	///
+	/// No extrinsic is applied: the call is executed over the state of the requested block, and
+	/// its changes only live in-memory for the lifetime of the ApiRef.
+	fn handle_call_request(
+		client: Arc<C>,
+		frontier_backend: Arc<fc_db::Backend<B>>,
+		call_request: CallRequest,
+		request_block_id: RequestBlockId,
+		params: Option<TraceParams>,
+		limits: &RequestLimits,
+		is_precompile: fn(&H160) -> bool,
+	) -> RpcResult<Response> {
+		let (tracer_input, trace_type) = Self::handle_params(params)?;
+
+		let reference_id =
+			Self::reference_id(client.as_ref(), frontier_backend.as_ref(), request_block_id)?;
+
+		// Get ApiRef.
+		let api = client.runtime_api();
+
+		// `trace_call` is only available from the version 2 of the runtime api.
+		let api_version = api
+			.api_version::<dyn DebugRuntimeApi<B>>(&reference_id)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?
+			.ok_or_else(|| internal_err("Runtime api version call failed (not found)"))?;
+		if api_version < 2 {
+			return Err(internal_err(
+				"debug_traceCall is not supported by the runtime of the requested block",
+			));
+		}
+
+		let CallRequest {
+			from,
+			to,
+			gas_price,
+			gas,
+			value,
+			data,
+			nonce,
+		} = call_request;
+
+		// The call doesn't pay for its gas, which is bounded by the gas limit of the requested
+		// block, and defaults to it.
+		let block_gas_limit = api
+			.current_block(&reference_id)
+			.map_err(|e| internal_err(format!("Runtime block call failed: {:?}", e)))?
+			.map(|block| block.header.gas_limit)
+			.ok_or_else(|| internal_err("Block unavailable, cannot query gas limit"))?;
+		let gas_limit = match gas {
+			Some(gas) if gas > block_gas_limit => {
+				return Err(internal_err(format!(
+					"Gas limit {} exceeds the block gas limit {}",
+					gas, block_gas_limit
+				)))
+			}
+			Some(gas) => gas,
+			None => block_gas_limit,
+		};
+
+		let f = || -> RpcResult<_> {
+			let _result = api
+				.trace_call(
+					&reference_id,
+					from.unwrap_or_default(),
+					to,
+					data.map(|d| d.0).unwrap_or_default(),
+					value.unwrap_or_default(),
+					gas_limit,
+					gas_price,
+					nonce,
+				)
+				.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?
+				.map_err(|e| internal_err(format!("DispatchError: {:?}", e)))?;
+
+			Ok(moonbeam_rpc_primitives_debug::Response::Single)
+		};
+
+		Self::trace_single(tracer_input, trace_type, limits, is_precompile, f)
+	}
+
+	/// Runs the provided closure under the listener of the requested trace type, and formats its
+	/// events as a single transaction trace.
+	fn trace_single<F>(
+		tracer_input: TracerInput,
+		trace_type: single::TraceType,
+		limits: &RequestLimits,
+		is_precompile: fn(&H160) -> bool,
+		f: F,
+	) -> RpcResult<Response>
+	where
+		F: FnOnce() -> RpcResult<moonbeam_rpc_primitives_debug::Response>,
+	{
+		match trace_type {
+			single::TraceType::Raw {
+				disable_storage,
+				disable_memory,
+				disable_stack,
+			} => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Raw::new(
 						disable_storage,
 						disable_memory,
 						disable_stack,
-					} => {
-						let mut proxy = moonbeam_client_evm_tracing::listeners::Raw::new(
-							disable_storage,
-							disable_memory,
-							disable_stack,
-						);
-						proxy.using(f)?;
-						Ok(Response::Single(
-							moonbeam_client_evm_tracing::formatters::Raw::format(proxy).unwrap(),
-						))
+					),
+					true,
+					f,
+				)?;
+				Ok(Response::Single(
+					moonbeam_client_evm_tracing::formatters::Raw::format(proxy).unwrap(),
+				))
+			}
+			single::TraceType::CallList => {
+				let mut proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::CallList::default(),
+					false,
+					f,
+				)?;
+				proxy.finish_transaction();
+				let response = match tracer_input {
+					TracerInput::Blockscout => {
+						moonbeam_client_evm_tracing::formatters::Blockscout::format(proxy)
+							.ok_or("Trace result is empty.")
+							.map_err(|e| internal_err(format!("{:?}", e)))
 					}
-					single::TraceType::CallList => {
-						let mut proxy = moonbeam_client_evm_tracing::listeners::CallList::default();
-						proxy.using(f)?;
-						proxy.finish_transaction();
-						let response = match tracer_input {
-							TracerInput::Blockscout => {
-								moonbeam_client_evm_tracing::formatters::Blockscout::format(proxy)
-									.ok_or("Trace result is empty.")
-									.map_err(|e| internal_err(format!("{:?}", e)))
-							}
-							TracerInput::CallTracer => {
-								let mut res =
-									moonbeam_client_evm_tracing::formatters::CallTracer::format(
-										proxy,
-									)
-									.ok_or("Trace result is empty.")
-									.map_err(|e| internal_err(format!("{:?}", e)))?;
-								Ok(res.pop().unwrap())
-							}
-							_ => Err(internal_err(format!(
-								"Bug: failed to resolve the tracer format."
-							))),
-						}?;
-						Ok(Response::Single(response))
+					TracerInput::CallTracer => {
+						let mut res =
+							moonbeam_client_evm_tracing::formatters::CallTracer::format(proxy)
+								.ok_or("Trace result is empty.")
+								.map_err(|e| internal_err(format!("{:?}", e)))?;
+						Ok(res.pop().unwrap())
 					}
-					not_supported => Err(internal_err(format!(
-						"Bug: `handle_transaction_request` does not support {:?}.",
-						not_supported
+					_ => Err(internal_err(format!(
+						"Bug: failed to resolve the tracer format."
 					))),
-				};
+				}?;
+				Ok(Response::Single(response))
+			}
+			single::TraceType::Prestate { diff_mode } => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Prestate::new(diff_mode),
+					false,
+					f,
+				)?;
+				let mut res = moonbeam_client_evm_tracing::formatters::Prestate::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+				Ok(Response::Single(res.pop().unwrap()))
+			}
+			single::TraceType::FourByte => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::FourByte::new(is_precompile),
+					false,
+					f,
+				)?;
+				let mut res = moonbeam_client_evm_tracing::formatters::FourByte::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+				Ok(Response::Single(res.pop().unwrap()))
+			}
+			single::TraceType::Opcount => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Opcount::default(),
+					false,
+					f,
+				)?;
+				let mut res = moonbeam_client_evm_tracing::formatters::Opcount::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+				Ok(Response::Single(res.pop().unwrap()))
+			}
+			single::TraceType::Noop => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Noop::default(),
+					false,
+					f,
+				)?;
+				let mut res = moonbeam_client_evm_tracing::formatters::Noop::format(proxy)
+					.ok_or("Trace result is empty.")
+					.map_err(|e| internal_err(format!("{:?}", e)))?;
+				Ok(Response::Single(res.pop().unwrap()))
+			}
+			#[cfg(feature = "js-tracer")]
+			single::TraceType::Js { tracer } => {
+				let proxy = limits.trace(
+					moonbeam_client_evm_tracing::listeners::Js::default(),
+					true,
+					f,
+				)?;
+				let response = moonbeam_client_evm_tracing::formatters::Js::format(
+					proxy,
+					&tracer,
+					limits.remaining(),
+					limits.js_memory_limit,
+					limits.js_max_stack_size,
+				)
+				.map_err(|e| {
+					if limits.remaining() == Duration::from_secs(0) {
+						timeout_err()
+					} else {
+						internal_err(e)
+					}
+				})?;
+				Ok(Response::Single(response))
 			}
+			not_supported => Err(internal_err(format!(
+				"Bug: `trace_single` does not support {:?}.",
+				not_supported
+			))),
 		}
-		Err(internal_err("Runtime block call failed".to_string()))
+	}
+}
+
+/// Parses a Go duration (i.e. "500ms", "1.5s" or "1m30s"), which is the format of
+/// `TraceParams.timeout`.
+fn parse_duration(duration: &str) -> Option<Duration> {
+	let mut rest = duration.trim();
+	if rest == "0" {
+		return Some(Duration::from_secs(0));
+	}
+	if rest.is_empty() {
+		return None;
+	}
+
+	let mut seconds = 0f64;
+	while !rest.is_empty() {
+		let number_len = rest.find(|c: char| !(c.is_ascii_digit() || c == '.'))?;
+		let value: f64 = rest[..number_len].parse().ok()?;
+		rest = &rest[number_len..];
+
+		let unit_len = rest
+			.find(|c: char| c.is_ascii_digit() || c == '.')
+			.unwrap_or_else(|| rest.len());
+		let unit = match &rest[..unit_len] {
+			"ns" => 1e-9,
+			"us" | "µs" => 1e-6,
+			"ms" => 1e-3,
+			"s" => 1.0,
+			"m" => 60.0,
+			"h" => 3600.0,
+			_ => return None,
+		};
+		seconds += value * unit;
+		rest = &rest[unit_len..];
+	}
+
+	// Durations which don't fit in a `Duration` are invalid.
+	if !seconds.is_finite() || seconds >= u64::MAX as f64 {
+		return None;
+	}
+	Some(Duration::from_secs_f64(seconds))
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn parses_go_durations() {
+		assert_eq!(parse_duration("0"), Some(Duration::from_secs(0)));
+		assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+		assert_eq!(parse_duration("1.5s"), Some(Duration::from_millis(1500)));
+		assert_eq!(parse_duration("1m30s"), Some(Duration::from_secs(90)));
+		assert_eq!(parse_duration("10"), None);
+		assert_eq!(parse_duration("5 seconds"), None);
+		assert_eq!(parse_duration(""), None);
+		assert_eq!(parse_duration("99999999999999999999999h"), None);
 	}
 }
@@ -0,0 +1,168 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent cache of the `trace_filter` traces, stored in a RocksDB database.
+//! Traces are keyed by Substrate block hash, so the traces of a block never become invalid,
+//! but the ones of a block retracted by a reorg are useless and should be removed.
+
+use codec::{Decode, Encode};
+use ethereum_types::H256;
+use moonbeam_client_evm_tracing::types::block::TransactionTrace;
+use sp_database::{Database, Transaction};
+use std::{path::Path, sync::Arc};
+
+mod columns {
+	pub const NUM_COLUMNS: u32 = 2;
+
+	pub const META: u32 = 0;
+	pub const TRACES: u32 = 1;
+}
+
+/// Key of the number of the latest block traced by the pre-tracing task, in the `META` column.
+const LATEST_PRETRACED: &[u8] = b"LATEST_PRETRACED";
+
+pub struct TraceDb {
+	db: Arc<dyn Database<H256>>,
+}
+
+impl TraceDb {
+	/// Open (or create) the database at the given path.
+	pub fn open(path: &Path) -> Result<Self, String> {
+		let db_config = kvdb_rocksdb::DatabaseConfig::with_columns(columns::NUM_COLUMNS);
+		let path = path
+			.to_str()
+			.ok_or_else(|| "Invalid database path".to_string())?;
+		let db = kvdb_rocksdb::Database::open(&db_config, &path).map_err(|e| format!("{}", e))?;
+
+		Ok(Self {
+			db: sp_database::as_database(db),
+		})
+	}
+
+	/// Database kept in memory, for tests.
+	#[cfg(test)]
+	pub(crate) fn in_memory() -> Self {
+		Self {
+			db: Arc::new(sp_database::MemDb::default()),
+		}
+	}
+
+	/// Traces of the block with the given hash, if they have been stored.
+	pub fn traces(&self, block_hash: H256) -> Result<Option<Vec<TransactionTrace>>, String> {
+		match self.db.get(columns::TRACES, &block_hash.encode()) {
+			Some(raw) => Vec::<TransactionTrace>::decode(&mut &raw[..])
+				.map(Some)
+				.map_err(|e| format!("{:?}", e)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn insert_traces(
+		&self,
+		block_hash: H256,
+		traces: &[TransactionTrace],
+	) -> Result<(), String> {
+		let mut transaction = Transaction::new();
+		transaction.set(columns::TRACES, &block_hash.encode(), &traces.encode());
+		self.db.commit(transaction).map_err(|e| format!("{:?}", e))
+	}
+
+	pub fn remove_traces(&self, block_hashes: &[H256]) -> Result<(), String> {
+		let mut transaction = Transaction::new();
+		for block_hash in block_hashes {
+			transaction.remove(columns::TRACES, &block_hash.encode());
+		}
+		self.db.commit(transaction).map_err(|e| format!("{:?}", e))
+	}
+
+	/// Number of the latest finalized block traced by the pre-tracing task.
+	pub fn latest_pretraced(&self) -> Result<Option<u32>, String> {
+		match self.db.get(columns::META, LATEST_PRETRACED) {
+			Some(raw) => u32::decode(&mut &raw[..])
+				.map(Some)
+				.map_err(|e| format!("{:?}", e)),
+			None => Ok(None),
+		}
+	}
+
+	pub fn set_latest_pretraced(&self, block_number: u32) -> Result<(), String> {
+		let mut transaction = Transaction::new();
+		transaction.set(columns::META, LATEST_PRETRACED, &block_number.encode());
+		self.db.commit(transaction).map_err(|e| format!("{:?}", e))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::{H160, U256};
+	use moonbeam_client_evm_tracing::types::{block, CallType};
+
+	fn trace(transaction_position: u32) -> TransactionTrace {
+		TransactionTrace {
+			action: block::TransactionTraceAction::Call {
+				call_type: CallType::Call,
+				from: H160::repeat_byte(0x11),
+				gas: U256::zero(),
+				input: vec![],
+				to: H160::repeat_byte(0x22),
+				value: U256::zero(),
+			},
+			block_hash: H256::default(),
+			block_number: 1,
+			output: block::TransactionTraceOutput::Result(block::TransactionTraceResult::Call {
+				gas_used: U256::zero(),
+				output: vec![],
+			}),
+			subtraces: 0,
+			trace_address: vec![],
+			transaction_hash: H256::default(),
+			transaction_position,
+		}
+	}
+
+	#[test]
+	fn inserts_and_removes_traces() {
+		let trace_db = TraceDb::in_memory();
+		let first = H256::repeat_byte(1);
+		let second = H256::repeat_byte(2);
+
+		assert_eq!(trace_db.traces(first), Ok(None));
+
+		trace_db
+			.insert_traces(first, &[trace(0), trace(1)])
+			.unwrap();
+		trace_db.insert_traces(second, &[]).unwrap();
+		assert_eq!(trace_db.traces(first), Ok(Some(vec![trace(0), trace(1)])));
+		// Blocks without any trace are stored too, so they are not traced again.
+		assert_eq!(trace_db.traces(second), Ok(Some(vec![])));
+
+		trace_db.remove_traces(&[first]).unwrap();
+		assert_eq!(trace_db.traces(first), Ok(None));
+		assert_eq!(trace_db.traces(second), Ok(Some(vec![])));
+	}
+
+	#[test]
+	fn stores_latest_pretraced() {
+		let trace_db = TraceDb::in_memory();
+		assert_eq!(trace_db.latest_pretraced(), Ok(None));
+
+		trace_db.set_latest_pretraced(5).unwrap();
+		assert_eq!(trace_db.latest_pretraced(), Ok(Some(5)));
+		trace_db.set_latest_pretraced(8).unwrap();
+		assert_eq!(trace_db.latest_pretraced(), Ok(Some(8)));
+	}
+}
@@ -14,22 +14,31 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
-//! `trace_filter` RPC handler and its associated service task.
+//! `trace_filter`, `trace_block`, `trace_transaction` and `trace_get` RPC handlers and their
+//! associated service task.
 //! The RPC handler rely on `CacheTask` which provides a future that must be run inside a tokio
 //! executor.
 //!
 //! The implementation is composed of multiple tasks :
-//! - Many calls the the RPC handler `Trace::filter`, communicating with the main task.
+//! - Many calls the the RPC handler `Trace`, communicating with the main task.
 //! - A main `CacheTask` managing the cache and the communication between tasks.
 //! - For each traced block an async task responsible to wait for a permit, spawn a blocking
 //!   task and waiting for the result, then send it to the main `CacheTask`.
+//!
+//! Traces can additionally be stored on disk in a `TraceDb`, which `CacheTask` reads before
+//! replaying a block. A `TraceDbTask` keeps it up to date by tracing the newly finalized blocks
+//! and removing the blocks retracted by a reorg.
+
+mod db;
+
+pub use db::TraceDb;
 
 use futures::{
 	compat::Compat,
 	future::{BoxFuture, TryFutureExt},
 	select,
 	stream::FuturesUnordered,
-	FutureExt, SinkExt, StreamExt,
+	FutureExt, SinkExt, Stream, StreamExt,
 };
 use std::{collections::BTreeMap, future::Future, marker::PhantomData, sync::Arc, time::Duration};
 use tokio::{
@@ -39,7 +48,9 @@ use tokio::{
 use tracing::{instrument, Instrument};
 
 use jsonrpc_core::Result;
-use sc_client_api::backend::Backend;
+use sc_client_api::{backend::Backend, BlockchainEvents};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionPool};
+use sha3::{Digest, Keccak256};
 use sp_api::{BlockId, Core, HeaderT, ProvideRuntimeApi};
 use sp_block_builder::BlockBuilder;
 use sp_blockchain::{
@@ -48,55 +59,83 @@ use sp_blockchain::{
 use sp_runtime::traits::Block as BlockT;
 use sp_utils::mpsc::TracingUnboundedSender;
 
-use ethereum_types::H256;
-use fc_rpc::internal_err;
+use ethereum_types::{H160, H256};
+use fc_rpc::{frontier_backend_client, internal_err};
+use fc_rpc_core::types::Index;
 use fp_rpc::EthereumRuntimeRPCApi;
 
 use moonbeam_client_evm_tracing::{
 	formatters::ResponseFormatter,
 	types::block::{self, TransactionTrace},
 };
-pub use moonbeam_rpc_core_trace::{FilterRequest, Trace as TraceT, TraceServer};
+pub use moonbeam_rpc_core_trace::{FilterMode, FilterRequest, Trace as TraceT, TraceServer};
 use moonbeam_rpc_core_types::{RequestBlockId, RequestBlockTag};
 use moonbeam_rpc_primitives_debug::DebugRuntimeApi;
 
 /// RPC handler. Will communicate with a `CacheTask` through a `CacheRequester`.
-pub struct Trace<B, C> {
+pub struct Trace<B: BlockT, C, P> {
 	_phantom: PhantomData<B>,
 	client: Arc<C>,
+	frontier_backend: Arc<fc_db::Backend<B>>,
+	transaction_pool: Arc<P>,
 	requester: CacheRequester,
 	max_count: u32,
 }
 
-impl<B, C> Clone for Trace<B, C> {
+impl<B: BlockT, C, P> Clone for Trace<B, C, P> {
 	fn clone(&self) -> Self {
 		Self {
 			_phantom: PhantomData::default(),
 			client: Arc::clone(&self.client),
+			frontier_backend: Arc::clone(&self.frontier_backend),
+			transaction_pool: Arc::clone(&self.transaction_pool),
 			requester: self.requester.clone(),
 			max_count: self.max_count,
 		}
 	}
 }
 
-impl<B, C> Trace<B, C>
+/// A block whose traces are requested.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum TracedBlock {
+	/// Block of the canonical chain, with its Substrate hash.
+	Hash(H256),
+	/// Block built from the ready transactions of the pool on top of the best block.
+	Pending,
+}
+
+impl<B, C, P> Trace<B, C, P>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
 	B::Header: HeaderT<Number = u32>,
+	C: ProvideRuntimeApi<B>,
 	C: HeaderMetadata<B, Error = BlockChainError> + HeaderBackend<B>,
 	C: Send + Sync + 'static,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block = B> + 'static,
 {
 	/// Create a new RPC handler.
-	pub fn new(client: Arc<C>, requester: CacheRequester, max_count: u32) -> Self {
+	pub fn new(
+		client: Arc<C>,
+		frontier_backend: Arc<fc_db::Backend<B>>,
+		transaction_pool: Arc<P>,
+		requester: CacheRequester,
+		max_count: u32,
+	) -> Self {
 		Self {
 			client,
+			frontier_backend,
+			transaction_pool,
 			requester,
 			max_count,
 			_phantom: PhantomData::default(),
 		}
 	}
 
-	/// Convert an optional block ID (number or tag) to a block height.
+	/// Convert an optional block ID to a block height. The height of the pending block is the one
+	/// following the best block.
 	fn block_id(&self, id: Option<RequestBlockId>) -> Result<u32> {
 		match id {
 			Some(RequestBlockId::Number(n)) => Ok(n),
@@ -105,10 +144,179 @@ where
 			}
 			Some(RequestBlockId::Tag(RequestBlockTag::Earliest)) => Ok(0),
 			Some(RequestBlockId::Tag(RequestBlockTag::Pending)) => {
-				Err(internal_err("'pending' is not supported"))
+				Ok(self.client.info().best_number.saturating_add(1))
+			}
+			Some(RequestBlockId::Hash(eth_hash)) => {
+				let block_id = frontier_backend_client::load_hash::<B>(
+					self.frontier_backend.as_ref(),
+					eth_hash,
+				)?
+				.ok_or_else(|| internal_err(format!("Block hash {} not found", eth_hash)))?;
+				let block_hash = match block_id {
+					BlockId::Hash(block_hash) => block_hash,
+					BlockId::Number(block_height) => return Ok(block_height),
+				};
+
+				// Ranges are made of canonical blocks.
+				let block_height = self
+					.client
+					.number(block_hash)
+					.map_err(|e| {
+						internal_err(format!(
+							"Error when fetching block {} number : {:?}",
+							eth_hash, e
+						))
+					})?
+					.ok_or_else(|| internal_err(format!("Block hash {} not found", eth_hash)))?;
+				if self.client.hash(block_height).ok().flatten() != Some(block_hash) {
+					return Err(internal_err(format!(
+						"Block hash {} is not part of the canonical chain",
+						eth_hash
+					)));
+				}
+
+				Ok(block_height)
+			}
+		}
+	}
+
+	/// Get the block to trace at the given height, which is pending if it follows the best
+	/// block.
+	fn traced_block(&self, block_height: u32) -> Result<TracedBlock> {
+		if block_height == self.client.info().best_number.saturating_add(1) {
+			return Ok(TracedBlock::Pending);
+		}
+
+		Ok(TracedBlock::Hash(self.block_hash(block_height)?))
+	}
+
+	/// Get the Substrate block hash at the given height.
+	fn block_hash(&self, block_height: u32) -> Result<H256> {
+		let block_id = BlockId::<B>::Number(block_height);
+		let block_header = self
+			.client
+			.header(block_id)
+			.map_err(|e| {
+				internal_err(format!(
+					"Error when fetching block {} header : {:?}",
+					block_height, e
+				))
+			})?
+			.ok_or_else(|| {
+				internal_err(format!("Block with height {} don't exist", block_height))
+			})?;
+
+		Ok(block_header.hash())
+	}
+
+	/// Get the Substrate hash of the canonical block including the given Ethereum transaction,
+	/// if any.
+	fn transaction_block_hash(&self, transaction_hash: H256) -> Result<Option<H256>> {
+		let transaction_metadata = self
+			.frontier_backend
+			.mapping()
+			.transaction_metadata(&transaction_hash)
+			.map_err(|e| {
+				internal_err(format!(
+					"Error when fetching transaction {} metadata : {:?}",
+					transaction_hash, e
+				))
+			})?;
+
+		// A transaction can be included in multiple forks, only the canonical one is traced.
+		for metadata in transaction_metadata {
+			let block_hash = metadata.block_hash;
+			if let Ok(Some(number)) = self.client.number(block_hash) {
+				if let Ok(Some(canonical_hash)) = self.client.hash(number) {
+					if canonical_hash == block_hash {
+						return Ok(Some(block_hash));
+					}
+				}
 			}
-			Some(RequestBlockId::Hash(_)) => Err(internal_err("Block hash not supported")),
 		}
+
+		Ok(None)
+	}
+
+	/// Fetch the traces of a single block from the cache, in a batch of its own.
+	async fn block_traces(&self, block_hash: H256) -> Result<Vec<TransactionTrace>> {
+		let batch_id = self.requester.start_batch(vec![block_hash]).await?;
+		let res = self.requester.get_traces(block_hash).await;
+		self.requester.stop_batch(batch_id).await;
+
+		res
+	}
+
+	/// Trace the pending block, made of the ready transactions of the pool. It is replayed on
+	/// each request as it is never cached.
+	async fn pending_traces(&self) -> Result<Vec<TransactionTrace>> {
+		let extrinsics: Vec<_> = self
+			.transaction_pool
+			.ready()
+			.map(|in_pool_transaction| in_pool_transaction.data().clone())
+			.collect();
+		let client = Arc::clone(&self.client);
+
+		self.requester
+			.pending_traces(Box::new(move || trace_pending::<B, C>(client, extrinsics)))
+			.await
+	}
+
+	/// Fetch the traces of a canonical or pending block.
+	async fn traced_block_traces(&self, block: TracedBlock) -> Result<Vec<TransactionTrace>> {
+		match block {
+			TracedBlock::Hash(block_hash) => self.requester.get_traces(block_hash).await,
+			TracedBlock::Pending => self.pending_traces().await,
+		}
+	}
+
+	/// `trace_block` endpoint (wrapped in the trait implementation with futures compatibilty)
+	async fn block(self, block: RequestBlockId) -> Result<Vec<TransactionTrace>> {
+		let block_height = self.block_id(Some(block))?;
+		if block_height == 0 {
+			return Ok(vec![]); // no traces for genesis block.
+		}
+
+		match self.traced_block(block_height)? {
+			TracedBlock::Hash(block_hash) => self.block_traces(block_hash).await,
+			TracedBlock::Pending => self.pending_traces().await,
+		}
+	}
+
+	/// `trace_transaction` endpoint (wrapped in the trait implementation with futures
+	/// compatibilty)
+	async fn transaction(self, transaction_hash: H256) -> Result<Option<Vec<TransactionTrace>>> {
+		let block_hash = match self.transaction_block_hash(transaction_hash)? {
+			Some(block_hash) => block_hash,
+			None => return Ok(None),
+		};
+
+		let traces = self
+			.block_traces(block_hash)
+			.await?
+			.into_iter()
+			.filter(|trace| trace.transaction_hash == transaction_hash)
+			.collect();
+
+		Ok(Some(traces))
+	}
+
+	/// `trace_get` endpoint (wrapped in the trait implementation with futures compatibilty)
+	async fn get(
+		self,
+		transaction_hash: H256,
+		indices: Vec<Index>,
+	) -> Result<Option<TransactionTrace>> {
+		let trace_address: Vec<u32> = indices.iter().map(|index| index.value() as u32).collect();
+
+		Ok(self
+			.transaction(transaction_hash)
+			.await?
+			.and_then(|traces| {
+				traces
+					.into_iter()
+					.find(|trace| trace.trace_address == trace_address)
+			}))
 	}
 
 	/// `trace_filter` endpoint (wrapped in the trait implementation with futures compatibilty)
@@ -125,123 +333,161 @@ where
 			)));
 		}
 
-		// Build a list of all the Substrate block hashes that need to be traced.
-		let mut block_hashes = vec![];
+		// Build a list of all the blocks that need to be traced.
+		let mut blocks = vec![];
 		for block_height in block_heights {
 			if block_height == 0 {
 				continue; // no traces for genesis block.
 			}
 
-			let block_id = BlockId::<B>::Number(block_height);
-			let block_header = self
-				.client
-				.header(block_id)
-				.map_err(|e| {
-					internal_err(format!(
-						"Error when fetching block {} header : {:?}",
-						block_height, e
-					))
-				})?
-				.ok_or_else(|| {
-					internal_err(format!("Block with height {} don't exist", block_height))
-				})?;
-
-			let block_hash = block_header.hash();
-
-			block_hashes.push(block_hash);
+			blocks.push(self.traced_block(block_height)?);
 		}
 
-		// Start a batch with these blocks.
-		let batch_id = self.requester.start_batch(block_hashes.clone()).await?;
+		// Start a batch with the blocks of the canonical chain.
+		let block_hashes = blocks
+			.iter()
+			.filter_map(|block| match block {
+				TracedBlock::Hash(block_hash) => Some(*block_hash),
+				TracedBlock::Pending => None,
+			})
+			.collect();
+		let batch_id = self.requester.start_batch(block_hashes).await?;
 		// Fetch all the traces. It is done in another function to simplify error handling and allow
 		// to call the following `stop_batch` regardless of the result. This is important for the
 		// cache cleanup to work properly.
-		let res = self.fetch_traces(req, &block_hashes, count as usize).await;
+		let res = fetch_traces(&req, &blocks, count as usize, self.max_count, |block| {
+			self.traced_block_traces(block)
+		})
+		.await;
 		// Stop the batch, allowing the cache task to remove useless non-started block traces and
 		// start the expiration delay.
 		self.requester.stop_batch(batch_id).await;
 
 		res
 	}
+}
 
-	async fn fetch_traces(
-		&self,
-		req: FilterRequest,
-		block_hashes: &[H256],
-		count: usize,
-	) -> Result<Vec<TransactionTrace>> {
-		let from_address = req.from_address.unwrap_or_default();
-		let to_address = req.to_address.unwrap_or_default();
-
-		let mut traces_amount: i64 = -(req.after.unwrap_or(0) as i64);
-		let mut traces = vec![];
+/// Whether a trace matches the address filters of a request.
+///
+/// Following OpenEthereum, a call goes from its caller to its callee, a contract creation from
+/// its creator to the created contract (if it succeeded), and a self destruct from the destroyed
+/// contract to its refund address.
+fn trace_matches(
+	trace: &TransactionTrace,
+	from_address: &[H160],
+	to_address: &[H160],
+	mode: FilterMode,
+) -> bool {
+	let (from, to) = match (&trace.action, &trace.output) {
+		(block::TransactionTraceAction::Call { from, to, .. }, _) => (*from, Some(*to)),
+		(
+			block::TransactionTraceAction::Create { from, .. },
+			block::TransactionTraceOutput::Result(block::TransactionTraceResult::Create {
+				address,
+				..
+			}),
+		) => (*from, Some(*address)),
+		(block::TransactionTraceAction::Create { from, .. }, _) => (*from, None),
+		(
+			block::TransactionTraceAction::Suicide {
+				address,
+				refund_address,
+				..
+			},
+			_,
+		) => (*address, Some(*refund_address)),
+	};
+
+	let from_matches = from_address.contains(&from);
+	let to_matches = matches!(to, Some(to) if to_address.contains(&to));
+
+	match (from_address.is_empty(), to_address.is_empty()) {
+		(true, true) => true,
+		(false, true) => from_matches,
+		(true, false) => to_matches,
+		(false, false) => match mode {
+			FilterMode::Union => from_matches || to_matches,
+			FilterMode::Intersection => from_matches && to_matches,
+		},
+	}
+}
 
-		for &block_hash in block_hashes {
-			// Request the traces of this block to the cache service.
-			// This will resolve quickly if the block is already cached, or wait until the block
-			// has finished tracing.
-			let block_traces = self.requester.get_traces(block_hash).await?;
+/// Fetch the traces of the provided blocks using `get_traces`, then filter and paginate them
+/// according to the request.
+async fn fetch_traces<F, Fut>(
+	req: &FilterRequest,
+	blocks: &[TracedBlock],
+	count: usize,
+	max_count: u32,
+	mut get_traces: F,
+) -> Result<Vec<TransactionTrace>>
+where
+	F: FnMut(TracedBlock) -> Fut,
+	Fut: Future<Output = Result<Vec<TransactionTrace>>>,
+{
+	let from_address = req.from_address.clone().unwrap_or_default();
+	let to_address = req.to_address.clone().unwrap_or_default();
+	let mode = req.mode.unwrap_or_default();
+
+	let mut traces_amount: i64 = -(req.after.unwrap_or(0) as i64);
+	let mut traces = vec![];
+
+	for &block in blocks {
+		// Request the traces of this block to the cache service.
+		// This will resolve quickly if the block is already cached, or wait until the block
+		// has finished tracing.
+		let block_traces = get_traces(block).await?;
+
+		// Filter addresses.
+		let mut block_traces: Vec<_> = block_traces
+			.into_iter()
+			.filter(|trace| trace_matches(trace, &from_address, &to_address, mode))
+			.collect();
 
-			// Filter addresses.
-			let mut block_traces: Vec<_> = block_traces
-				.iter()
-				.filter(|trace| match trace.action {
-					block::TransactionTraceAction::Call { from, to, .. } => {
-						(from_address.is_empty() || from_address.contains(&from))
-							&& (to_address.is_empty() || to_address.contains(&to))
-					}
-					block::TransactionTraceAction::Create { from, .. } => {
-						(from_address.is_empty() || from_address.contains(&from))
-							&& to_address.is_empty()
-					}
-					block::TransactionTraceAction::Suicide { address, .. } => {
-						(from_address.is_empty() || from_address.contains(&address))
-							&& to_address.is_empty()
-					}
-				})
-				.cloned()
-				.collect();
+		// Don't insert anything if we're still before "after"
+		traces_amount += block_traces.len() as i64;
+		if traces_amount > 0 {
+			let traces_amount = traces_amount as usize;
+			// If the current Vec of traces is across the "after" marker,
+			// we skip some elements of it.
+			if traces_amount < block_traces.len() {
+				let skip = block_traces.len() - traces_amount;
+				block_traces = block_traces.into_iter().skip(skip).collect();
+			}
 
-			// Don't insert anything if we're still before "after"
-			traces_amount += block_traces.len() as i64;
-			if traces_amount > 0 {
-				let traces_amount = traces_amount as usize;
-				// If the current Vec of traces is across the "after" marker,
-				// we skip some elements of it.
-				if traces_amount < block_traces.len() {
-					let skip = block_traces.len() - traces_amount;
-					block_traces = block_traces.into_iter().skip(skip).collect();
+			traces.append(&mut block_traces);
+
+			// If we go over "count" (the limit), we trim and exit the loop,
+			// unless we used the default maximum, in which case we return an error.
+			if traces_amount >= count {
+				if req.count.is_none() {
+					return Err(internal_err(format!(
+						"the amount of traces goes over the maximum ({}), please use 'after' \
+						and 'count' in your request",
+						max_count
+					)));
 				}
 
-				traces.append(&mut block_traces);
-
-				// If we go over "count" (the limit), we trim and exit the loop,
-				// unless we used the default maximum, in which case we return an error.
-				if traces_amount >= count {
-					if req.count.is_none() {
-						return Err(internal_err(format!(
-							"the amount of traces goes over the maximum ({}), please use 'after' \
-							and 'count' in your request",
-							self.max_count
-						)));
-					}
-
-					traces = traces.into_iter().take(count).collect();
-					break;
-				}
+				traces = traces.into_iter().take(count).collect();
+				break;
 			}
 		}
-
-		Ok(traces)
 	}
+
+	Ok(traces)
 }
 
-impl<B, C> TraceT for Trace<B, C>
+impl<B, C, P> TraceT for Trace<B, C, P>
 where
 	B: BlockT<Hash = H256> + Send + Sync + 'static,
 	B::Header: HeaderT<Number = u32>,
+	C: ProvideRuntimeApi<B>,
 	C: HeaderMetadata<B, Error = BlockChainError> + HeaderBackend<B>,
 	C: Send + Sync + 'static,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+	P: TransactionPool<Block = B> + 'static,
 {
 	fn filter(
 		&self,
@@ -250,6 +496,28 @@ where
 		// Wraps the async function into futures compatibility layer.
 		self.clone().filter(filter).boxed().compat()
 	}
+
+	fn block(
+		&self,
+		block: RequestBlockId,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Vec<TransactionTrace>>>> {
+		self.clone().block(block).boxed().compat()
+	}
+
+	fn transaction(
+		&self,
+		transaction_hash: H256,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Option<Vec<TransactionTrace>>>>> {
+		self.clone().transaction(transaction_hash).boxed().compat()
+	}
+
+	fn get(
+		&self,
+		transaction_hash: H256,
+		indices: Vec<Index>,
+	) -> Compat<BoxFuture<'static, jsonrpc_core::Result<Option<TransactionTrace>>>> {
+		self.clone().get(transaction_hash, indices).boxed().compat()
+	}
 }
 
 /// An opaque batch ID.
@@ -277,8 +545,19 @@ enum CacheRequest {
 	/// Notify the cache that it can stop the batch with that ID. Any block contained only in
 	/// this batch and still not started will be discarded.
 	StopBatch { batch_id: CacheBatchId },
+	/// Run the provided tracing job once a blocking permit is available. Used for the pending
+	/// block, which is never cached.
+	PendingTraces {
+		/// Returns the array of traces or an error.
+		sender: oneshot::Sender<Result<Vec<TransactionTrace>>>,
+		/// Blocking job tracing the pending block.
+		job: PendingTracesJob,
+	},
 }
 
+/// Blocking job tracing the pending block.
+pub type PendingTracesJob = Box<dyn FnOnce() -> Result<Vec<TransactionTrace>> + Send>;
+
 /// Allows to interact with the cache task.
 #[derive(Clone)]
 pub struct CacheRequester(TracingUnboundedSender<CacheRequest>);
@@ -363,6 +642,37 @@ impl CacheRequester {
 				))
 			});
 	}
+
+	/// Run the provided job tracing the pending block in a blocking task, sharing the blocking
+	/// permits with the cached blocks.
+	#[instrument(skip(self, job))]
+	pub async fn pending_traces(&self, job: PendingTracesJob) -> Result<Vec<TransactionTrace>> {
+		let (response_tx, response_rx) = oneshot::channel();
+		let mut sender = self.0.clone();
+
+		sender
+			.send(CacheRequest::PendingTraces {
+				sender: response_tx,
+				job,
+			})
+			.await
+			.map_err(|e| {
+				internal_err(format!(
+					"Failed to send request to the trace cache task. Error : {:?}",
+					e
+				))
+			})?;
+
+		response_rx
+			.await
+			.map_err(|e| {
+				internal_err(format!(
+					"Trace cache task closed the response channel. Error : {:?}",
+					e
+				))
+			})?
+			.map_err(|e| internal_err(format!("Failed to replay pending block. Error : {:?}", e)))
+	}
 }
 
 /// Data stored for each block in the cache.
@@ -419,6 +729,7 @@ enum BlockingTaskMessage {
 pub struct CacheTask<B, C, BE> {
 	client: Arc<C>,
 	backend: Arc<BE>,
+	trace_db: Option<Arc<TraceDb>>,
 	blocking_permits: Arc<Semaphore>,
 	cached_blocks: BTreeMap<H256, CacheBlock>,
 	batches: BTreeMap<u64, Vec<H256>>,
@@ -442,11 +753,14 @@ where
 	///
 	/// Returns a Future that needs to be added to a tokio executor, and an handle allowing to
 	/// send requests to the task.
+	/// If a `TraceDb` is provided, blocks found in it are not replayed, and replayed blocks of
+	/// the canonical chain are stored in it.
 	pub fn create(
 		client: Arc<C>,
 		backend: Arc<BE>,
 		cache_duration: Duration,
 		blocking_permits: Arc<Semaphore>,
+		trace_db: Option<Arc<TraceDb>>,
 	) -> (impl Future<Output = ()>, CacheRequester) {
 		// Communication with the outside world :
 		let (requester_tx, mut requester_rx) =
@@ -467,6 +781,7 @@ where
 			let mut inner = Self {
 				client,
 				backend,
+				trace_db,
 				blocking_permits,
 				cached_blocks: BTreeMap::new(),
 				batches: BTreeMap::new(),
@@ -495,6 +810,8 @@ where
 
 								inner.request_stop_batch(batch_id);
 							},
+							Some(CacheRequest::PendingTraces {sender, job})
+								=> inner.request_pending_traces(sender, job),
 						}
 					},
 					message = blocking_rx.next() => {
@@ -550,6 +867,7 @@ where
 				let (unqueue_sender, unqueue_receiver) = oneshot::channel();
 				let client = Arc::clone(&self.client);
 				let backend = Arc::clone(&self.backend);
+				let trace_db = self.trace_db.clone();
 				let mut blocking_tx = blocking_tx.clone();
 
 				// Spawn all block caching asynchronously.
@@ -558,6 +876,25 @@ where
 				// thought a channel to the main task loop.
 				tokio::spawn(
 					async move {
+						// Blocks stored on disk don't need to be replayed.
+						if let Some(trace_db) = trace_db.clone() {
+							let stored =
+								tokio::task::spawn_blocking(move || trace_db.traces(block)).await;
+							if let Ok(Ok(Some(traces))) = stored {
+								tracing::trace!("Block traces found in the persistent cache.");
+								let _ = blocking_tx
+									.send(BlockingTaskMessage::Started { block_hash: block })
+									.await;
+								let _ = blocking_tx
+									.send(BlockingTaskMessage::Finished {
+										block_hash: block,
+										result: Ok(traces),
+									})
+									.await;
+								return;
+							}
+						}
+
 						tracing::trace!("Waiting for blocking permit or task cancellation");
 						let _permit = select!(
 							_ = unqueue_receiver.fuse() => {
@@ -578,7 +915,7 @@ where
 						// Perform block tracing in a tokio blocking task.
 						let result = async {
 							tokio::task::spawn_blocking(move || {
-								Self::cache_block(client, backend, block)
+								Self::cache_block(client, backend, trace_db, block)
 							})
 							.await
 							.map_err(|e| {
@@ -671,6 +1008,36 @@ where
 		}
 	}
 
+	/// Handle a request to trace the pending block.
+	/// The job waits for a permit like the pooled blocks, but its result is sent directly to the
+	/// requester without being cached, as the pending block changes with the transaction pool.
+	#[instrument(skip(self, sender, job))]
+	fn request_pending_traces(
+		&mut self,
+		sender: oneshot::Sender<Result<Vec<TransactionTrace>>>,
+		job: PendingTracesJob,
+	) {
+		let blocking_permits = Arc::clone(&self.blocking_permits);
+
+		tokio::spawn(
+			async move {
+				tracing::trace!("Waiting for blocking permit");
+				let _permit = blocking_permits.acquire().await;
+
+				tracing::trace!("Start pending block tracing in a blocking task.");
+				let result = async {
+					tokio::task::spawn_blocking(job).await.map_err(|e| {
+						internal_err(format!("Tracing pending block panicked : {:?}", e))
+					})?
+				}
+				.await;
+
+				let _ = sender.send(result);
+			}
+			.instrument(tracing::trace_span!("Pending block tracing")),
+		);
+	}
+
 	/// Handle a request to stop a batch.
 	/// For all blocks that needed to be traced, are only in this batch and not yet started, their
 	/// tracing is cancelled to save CPU-time and avoid attacks requesting large amount of blocks.
@@ -777,10 +1144,13 @@ where
 	}
 
 	/// (In blocking task) Use the Runtime API to trace the block.
-	#[instrument(skip(client, backend))]
+	/// The traces are stored in the `TraceDb` if provided and the block is part of the canonical
+	/// chain.
+	#[instrument(skip(client, backend, trace_db))]
 	fn cache_block(
 		client: Arc<C>,
 		backend: Arc<BE>,
+		trace_db: Option<Arc<TraceDb>>,
 		substrate_hash: H256,
 	) -> Result<Vec<TransactionTrace>> {
 		let substrate_block_id = BlockId::Hash(substrate_hash);
@@ -845,63 +1215,563 @@ where
 				))
 			})?;
 
-		// Trace the block.
-		let f = || -> Result<_> {
-			api.initialize_block(&substrate_parent_id, &block_header)
-				.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
-
-			let _result = api
-				.trace_block(&substrate_parent_id, extrinsics, eth_tx_hashes)
-				.map_err(|e| {
-					internal_err(format!(
-						"Blockchain error when replaying block {} : {:?}",
-						height, e
-					))
-				})?
-				.map_err(|e| {
-					tracing::warn!(
-						"Internal runtime error when replaying block {} : {:?}",
-						height,
-						e
-					);
-					internal_err(format!(
-						"Internal runtime error when replaying block {} : {:?}",
-						height, e
-					))
-				})?;
-			Ok(moonbeam_rpc_primitives_debug::Response::Block)
+		let traces = replay_block(
+			client.as_ref(),
+			substrate_parent_id,
+			&block_header,
+			extrinsics,
+			eth_tx_hashes,
+			eth_block_hash,
+		)?;
+
+		if let Some(trace_db) = trace_db {
+			// Blocks of forks would only be removed from the database when retracted, which
+			// doesn't happen if they never were the best block.
+			if let Ok(Some(canonical_hash)) = client.hash(height) {
+				if canonical_hash == substrate_hash {
+					if let Err(e) = trace_db.insert_traces(substrate_hash, &traces) {
+						tracing::warn!("Failed to store the traces of block {} : {}", height, e);
+					}
+				}
+			}
+		}
+
+		Ok(traces)
+	}
+}
+
+/// (In blocking task) Use the Runtime API to apply the provided extrinsics on top of the parent
+/// block and trace the Ethereum transactions with the provided hashes, in order.
+fn replay_block<B, C>(
+	client: &C,
+	substrate_parent_id: BlockId<B>,
+	block_header: &B::Header,
+	extrinsics: Vec<B::Extrinsic>,
+	eth_tx_hashes: Vec<H256>,
+	eth_block_hash: H256,
+) -> Result<Vec<TransactionTrace>>
+where
+	C: ProvideRuntimeApi<B>,
+	B: BlockT<Hash = H256>,
+	B::Header: HeaderT<Number = u32>,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+{
+	let api = client.runtime_api();
+	let height = *block_header.number();
+
+	// Trace the block.
+	let f = || -> Result<_> {
+		api.initialize_block(&substrate_parent_id, &block_header)
+			.map_err(|e| internal_err(format!("Runtime api access error: {:?}", e)))?;
+
+		let _result = api
+			.trace_block(&substrate_parent_id, extrinsics, eth_tx_hashes.clone())
+			.map_err(|e| {
+				internal_err(format!(
+					"Blockchain error when replaying block {} : {:?}",
+					height, e
+				))
+			})?
+			.map_err(|e| {
+				tracing::warn!(
+					"Internal runtime error when replaying block {} : {:?}",
+					height,
+					e
+				);
+				internal_err(format!(
+					"Internal runtime error when replaying block {} : {:?}",
+					height, e
+				))
+			})?;
+		Ok(moonbeam_rpc_primitives_debug::Response::Block)
+	};
+
+	let mut proxy = moonbeam_client_evm_tracing::listeners::CallList::default();
+	proxy.using(f)?;
+	let mut traces: Vec<_> =
+		moonbeam_client_evm_tracing::formatters::TraceFilter::format(proxy).unwrap();
+	// Fill missing data.
+	for trace in traces.iter_mut() {
+		trace.block_hash = eth_block_hash;
+		trace.block_number = height;
+		trace.transaction_hash = *eth_tx_hashes
+			.get(trace.transaction_position as usize)
+			.ok_or_else(|| {
+				tracing::warn!(
+					"Bug: A transaction has been replayed while it shouldn't (in block {}).",
+					height
+				);
+
+				internal_err(format!(
+					"Bug: A transaction has been replayed while it shouldn't (in block {}).",
+					height
+				))
+			})?;
+
+		// Reformat error messages.
+		if let block::TransactionTraceOutput::Error(ref mut error) = trace.output {
+			if error.as_slice() == b"execution reverted" {
+				*error = b"Reverted".to_vec();
+			}
+		}
+	}
+
+	Ok(traces)
+}
+
+/// (In blocking task) Trace the pending block, made of the provided extrinsics applied on top of
+/// the best block. As it has no Ethereum block hash yet, its traces have a zero block hash.
+fn trace_pending<B, C>(
+	client: Arc<C>,
+	extrinsics: Vec<B::Extrinsic>,
+) -> Result<Vec<TransactionTrace>>
+where
+	C: ProvideRuntimeApi<B> + HeaderBackend<B>,
+	B: BlockT<Hash = H256>,
+	B::Header: HeaderT<Number = u32>,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+{
+	let info = client.info();
+	let best_block_id = BlockId::<B>::Hash(info.best_hash);
+
+	// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
+	let eth_tx_hashes = client
+		.runtime_api()
+		.extrinsic_filter(&best_block_id, extrinsics.clone())
+		.map_err(|e| internal_err(format!("fetch runtime extrinsic filter failed: {:?}", e)))?
+		.iter()
+		.map(|transaction| {
+			H256::from_slice(Keccak256::digest(&rlp::encode(transaction)).as_slice())
+		})
+		.collect();
+
+	let block_header = B::Header::new(
+		info.best_number.saturating_add(1),
+		Default::default(),
+		Default::default(),
+		info.best_hash,
+		Default::default(),
+	);
+
+	replay_block(
+		client.as_ref(),
+		best_block_id,
+		&block_header,
+		extrinsics,
+		eth_tx_hashes,
+		H256::zero(),
+	)
+}
+
+/// Type wrapper for the task keeping a `TraceDb` up to date, generic over the Client, Block and
+/// Backend types.
+pub struct TraceDbTask<B, C, BE>(PhantomData<(B, C, BE)>);
+
+impl<B, C, BE> TraceDbTask<B, C, BE>
+where
+	BE: Backend<B> + 'static,
+	C: ProvideRuntimeApi<B> + BlockchainEvents<B>,
+	C: HeaderMetadata<B, Error = BlockChainError> + HeaderBackend<B>,
+	C: Send + Sync + 'static,
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	B::Header: HeaderT<Number = u32>,
+	C::Api: BlockBuilder<B>,
+	C::Api: DebugRuntimeApi<B>,
+	C::Api: EthereumRuntimeRPCApi<B>,
+{
+	/// Create the tasks keeping the database up to date: one removing the traces of retracted
+	/// blocks and, if `pretrace` is enabled, one tracing the newly finalized blocks. They are
+	/// separate tasks so that a long pre-tracing doesn't delay the removal of retracted blocks.
+	///
+	/// Returns Futures that need to be added to a tokio executor.
+	pub fn create(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		trace_db: Arc<TraceDb>,
+		blocking_permits: Arc<Semaphore>,
+		pretrace: bool,
+	) -> (impl Future<Output = ()>, Option<impl Future<Output = ()>>) {
+		let retracted = client.import_notification_stream().map(|notification| {
+			notification
+				.tree_route
+				.iter()
+				.flat_map(|tree_route| tree_route.retracted().iter())
+				.map(|block| block.hash)
+				.collect::<Vec<_>>()
+		});
+		let prune_task = prune_retracted(Arc::clone(&trace_db), retracted)
+			.instrument(tracing::debug_span!("trace_db"));
+
+		let pretrace_task = if pretrace {
+			let mut finality_notifications = client.finality_notification_stream();
+			Some(
+				async move {
+					// Notifications received while pre-tracing are handled afterwards, and only
+					// trace the blocks finalized in the meantime.
+					while let Some(notification) = finality_notifications.next().await {
+						Self::pretrace(
+							Arc::clone(&client),
+							Arc::clone(&backend),
+							Arc::clone(&trace_db),
+							Arc::clone(&blocking_permits),
+							*notification.header.number(),
+						)
+						.await
+					}
+				}
+				.instrument(tracing::debug_span!("trace_db_pretrace")),
+			)
+		} else {
+			None
 		};
 
-		let mut proxy = moonbeam_client_evm_tracing::listeners::CallList::default();
-		proxy.using(f)?;
-		let mut traces: Vec<_> =
-			moonbeam_client_evm_tracing::formatters::TraceFilter::format(proxy).unwrap();
-		// Fill missing data.
-		for trace in traces.iter_mut() {
-			trace.block_hash = eth_block_hash;
-			trace.block_number = height;
-			trace.transaction_hash = eth_transactions
-				.get(trace.transaction_position as usize)
-				.ok_or_else(|| {
-					tracing::warn!(
-						"Bug: A transaction has been replayed while it shouldn't (in block {}).",
-						height
-					);
+		(prune_task, pretrace_task)
+	}
+
+	/// Trace the finalized blocks up to `finalized_number` which are not already stored.
+	/// Blocks finalized while the pre-tracing was disabled are not traced, but the ones finalized
+	/// while the node was stopped are.
+	/// Pre-tracing stops at the first block that fails to be traced, which is retried from the
+	/// next finalized block.
+	/// Blocks are traced one at a time to use a single permit, leaving the others to the RPC
+	/// requests.
+	async fn pretrace(
+		client: Arc<C>,
+		backend: Arc<BE>,
+		trace_db: Arc<TraceDb>,
+		blocking_permits: Arc<Semaphore>,
+		finalized_number: u32,
+	) {
+		let from = match trace_db.latest_pretraced() {
+			Ok(Some(latest)) => latest.saturating_add(1),
+			Ok(None) => finalized_number,
+			Err(e) => {
+				tracing::warn!("Failed to read the latest pre-traced block : {}", e);
+				finalized_number
+			}
+		};
 
-					internal_err(format!(
-						"Bug: A transaction has been replayed while it shouldn't (in block {}).",
-						height
-					))
-				})?
-				.transaction_hash;
-
-			// Reformat error messages.
-			if let block::TransactionTraceOutput::Error(ref mut error) = trace.output {
-				if error.as_slice() == b"execution reverted" {
-					*error = b"Reverted".to_vec();
+		for block_number in from..=finalized_number {
+			let block_hash = match client.hash(block_number) {
+				Ok(Some(block_hash)) => block_hash,
+				_ => {
+					tracing::warn!("Finalized block {} not found.", block_number);
+					continue;
 				}
+			};
+
+			if let Ok(None) = trace_db.traces(block_hash) {
+				let _permit = blocking_permits.acquire().await;
+
+				let client = Arc::clone(&client);
+				let backend = Arc::clone(&backend);
+				let trace_db = Arc::clone(&trace_db);
+				let result = tokio::task::spawn_blocking(move || {
+					CacheTask::<B, C, BE>::cache_block(client, backend, Some(trace_db), block_hash)
+				})
+				.await;
+
+				// The latest pre-traced block is not advanced past a failed block, so that it is
+				// retried when the next block is finalized.
+				match result {
+					Ok(Ok(_)) => tracing::trace!("Pre-traced block {}.", block_number),
+					Ok(Err(e)) => {
+						tracing::warn!("Failed to pre-trace block {} : {:?}", block_number, e);
+						return;
+					}
+					Err(e) => {
+						tracing::warn!("Pre-tracing block {} panicked : {:?}", block_number, e);
+						return;
+					}
+				}
+			}
+
+			if let Err(e) = trace_db.set_latest_pretraced(block_number) {
+				tracing::warn!("Failed to store the latest pre-traced block : {}", e);
 			}
 		}
-		Ok(traces)
+	}
+}
+
+/// Remove the traces of the blocks retracted by each reorg of `retracted` from the database.
+async fn prune_retracted<S>(trace_db: Arc<TraceDb>, mut retracted: S)
+where
+	S: Stream<Item = Vec<H256>> + Unpin,
+{
+	while let Some(retracted) = retracted.next().await {
+		if retracted.is_empty() {
+			continue;
+		}
+
+		tracing::trace!("Removing the traces of retracted blocks {:?}", retracted);
+		if let Err(e) = trace_db.remove_traces(&retracted) {
+			tracing::warn!("Failed to remove the traces of retracted blocks : {}", e);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use ethereum_types::U256;
+	use futures::executor::block_on;
+	use moonbeam_client_evm_tracing::types::{CallType, CreateType};
+
+	fn address(byte: u8) -> H160 {
+		H160::repeat_byte(byte)
+	}
+
+	fn trace(
+		action: block::TransactionTraceAction,
+		output: block::TransactionTraceOutput,
+		transaction_position: u32,
+	) -> TransactionTrace {
+		TransactionTrace {
+			action,
+			block_hash: H256::default(),
+			block_number: 1,
+			output,
+			subtraces: 0,
+			trace_address: vec![],
+			transaction_hash: H256::default(),
+			transaction_position,
+		}
+	}
+
+	fn call(from: u8, to: u8, transaction_position: u32) -> TransactionTrace {
+		trace(
+			block::TransactionTraceAction::Call {
+				call_type: CallType::Call,
+				from: address(from),
+				gas: U256::zero(),
+				input: vec![],
+				to: address(to),
+				value: U256::zero(),
+			},
+			block::TransactionTraceOutput::Result(block::TransactionTraceResult::Call {
+				gas_used: U256::zero(),
+				output: vec![],
+			}),
+			transaction_position,
+		)
+	}
+
+	fn create(from: u8, created: Option<u8>) -> TransactionTrace {
+		trace(
+			block::TransactionTraceAction::Create {
+				creation_method: CreateType::Create,
+				from: address(from),
+				gas: U256::zero(),
+				init: vec![],
+				value: U256::zero(),
+			},
+			match created {
+				Some(created) => {
+					block::TransactionTraceOutput::Result(block::TransactionTraceResult::Create {
+						address: address(created),
+						code: vec![],
+						gas_used: U256::zero(),
+					})
+				}
+				None => block::TransactionTraceOutput::Error(b"Reverted".to_vec()),
+			},
+			0,
+		)
+	}
+
+	fn suicide(contract: u8, refund: u8) -> TransactionTrace {
+		trace(
+			block::TransactionTraceAction::Suicide {
+				address: address(contract),
+				balance: U256::zero(),
+				refund_address: address(refund),
+			},
+			block::TransactionTraceOutput::Result(block::TransactionTraceResult::Suicide),
+			0,
+		)
+	}
+
+	fn matches(trace: &TransactionTrace, from: &[u8], to: &[u8], mode: FilterMode) -> bool {
+		let from: Vec<_> = from.iter().cloned().map(address).collect();
+		let to: Vec<_> = to.iter().cloned().map(address).collect();
+		trace_matches(trace, &from, &to, mode)
+	}
+
+	/// Fetch the traces of blocks containing the provided number of calls, identified by their
+	/// position in the whole range.
+	fn fetch(req: FilterRequest, blocks_len: &[u32], max_count: u32) -> Result<Vec<u32>> {
+		let blocks: Vec<_> = (0..blocks_len.len())
+			.map(|i| TracedBlock::Hash(H256::from_low_u64_be(i as u64)))
+			.collect();
+		let count = req.count.unwrap_or(max_count) as usize;
+
+		let traces = block_on(fetch_traces(&req, &blocks, count, max_count, |block| {
+			let index = match block {
+				TracedBlock::Hash(hash) => hash.to_low_u64_be() as usize,
+				TracedBlock::Pending => unreachable!(),
+			};
+			let first: u32 = blocks_len[..index].iter().sum();
+			let traces = (first..first + blocks_len[index])
+				.map(|position| call(1, 2, position))
+				.collect();
+			futures::future::ready(Ok(traces))
+		}))?;
+
+		Ok(traces
+			.into_iter()
+			.map(|trace| trace.transaction_position)
+			.collect())
+	}
+
+	#[test]
+	fn prunes_retracted_blocks() {
+		let trace_db = Arc::new(TraceDb::in_memory());
+		let blocks: Vec<_> = (0..4).map(H256::repeat_byte).collect();
+		for (index, block_hash) in blocks.iter().enumerate() {
+			trace_db
+				.insert_traces(*block_hash, &[call(1, 2, index as u32)])
+				.unwrap();
+		}
+
+		// The first import doesn't retract any block, the next ones are reorgs.
+		let reorgs =
+			futures::stream::iter(vec![vec![], vec![blocks[1]], vec![blocks[2], blocks[3]]]);
+		block_on(prune_retracted(Arc::clone(&trace_db), reorgs));
+
+		assert_eq!(trace_db.traces(blocks[0]), Ok(Some(vec![call(1, 2, 0)])));
+		for block_hash in &blocks[1..] {
+			assert_eq!(trace_db.traces(*block_hash), Ok(None));
+		}
+	}
+
+	#[test]
+	fn matches_calls() {
+		let trace = call(1, 2, 0);
+
+		assert!(matches(&trace, &[], &[], FilterMode::Intersection));
+		assert!(matches(&trace, &[1], &[], FilterMode::Intersection));
+		assert!(!matches(&trace, &[2], &[], FilterMode::Intersection));
+		assert!(matches(&trace, &[], &[2], FilterMode::Intersection));
+		assert!(!matches(&trace, &[], &[1], FilterMode::Intersection));
+		assert!(matches(&trace, &[1], &[2], FilterMode::Intersection));
+		assert!(!matches(&trace, &[1], &[3], FilterMode::Intersection));
+		assert!(matches(&trace, &[1], &[3], FilterMode::Union));
+		assert!(matches(&trace, &[3], &[2], FilterMode::Union));
+		assert!(!matches(&trace, &[3], &[3], FilterMode::Union));
+	}
+
+	#[test]
+	fn matches_creates_on_created_address() {
+		let trace = create(1, Some(2));
+		assert!(matches(&trace, &[1], &[], FilterMode::Intersection));
+		assert!(matches(&trace, &[], &[2], FilterMode::Intersection));
+		assert!(matches(&trace, &[1], &[2], FilterMode::Intersection));
+		assert!(!matches(&trace, &[], &[1], FilterMode::Intersection));
+
+		// A failed creation has no created address.
+		let trace = create(1, None);
+		assert!(matches(&trace, &[1], &[], FilterMode::Intersection));
+		assert!(!matches(&trace, &[], &[2], FilterMode::Intersection));
+		assert!(!matches(&trace, &[1], &[2], FilterMode::Intersection));
+		assert!(matches(&trace, &[1], &[2], FilterMode::Union));
+	}
+
+	#[test]
+	fn matches_suicides_on_refund_address() {
+		let trace = suicide(1, 2);
+		assert!(matches(&trace, &[1], &[], FilterMode::Intersection));
+		assert!(matches(&trace, &[], &[2], FilterMode::Intersection));
+		assert!(!matches(&trace, &[2], &[], FilterMode::Intersection));
+		assert!(matches(&trace, &[3], &[2], FilterMode::Union));
+	}
+
+	#[test]
+	fn filters_addresses_of_internal_calls() {
+		let blocks = [TracedBlock::Hash(H256::zero()), TracedBlock::Pending];
+		let req = FilterRequest {
+			to_address: Some(vec![address(3)]),
+			..Default::default()
+		};
+
+		let traces = block_on(fetch_traces(&req, &blocks, 10, 10, |block| {
+			futures::future::ready(Ok(match block {
+				TracedBlock::Hash(_) => vec![call(1, 2, 0), call(2, 3, 0), call(1, 3, 1)],
+				TracedBlock::Pending => vec![call(3, 4, 0), call(4, 3, 0)],
+			}))
+		}))
+		.unwrap();
+
+		let calls: Vec<_> = traces
+			.iter()
+			.map(|trace| match trace.action {
+				block::TransactionTraceAction::Call { from, .. } => from,
+				_ => unreachable!(),
+			})
+			.collect();
+		assert_eq!(calls, vec![address(2), address(1), address(4)]);
+	}
+
+	#[test]
+	fn paginates_with_after_and_count() {
+		let blocks = [3, 0, 4, 2];
+
+		assert_eq!(
+			fetch(FilterRequest::default(), &blocks, 20),
+			Ok((0..9).collect())
+		);
+		assert_eq!(
+			fetch(
+				FilterRequest {
+					after: Some(2),
+					count: Some(4),
+					..Default::default()
+				},
+				&blocks,
+				20
+			),
+			Ok((2..6).collect())
+		);
+		assert_eq!(
+			fetch(
+				FilterRequest {
+					after: Some(7),
+					count: Some(4),
+					..Default::default()
+				},
+				&blocks,
+				20
+			),
+			Ok(vec![7, 8])
+		);
+		assert_eq!(
+			fetch(
+				FilterRequest {
+					after: Some(9),
+					..Default::default()
+				},
+				&blocks,
+				20
+			),
+			Ok(vec![])
+		);
+	}
+
+	#[test]
+	fn errors_when_default_count_is_exceeded() {
+		assert!(fetch(FilterRequest::default(), &[3, 4], 5).is_err());
+		assert_eq!(
+			fetch(
+				FilterRequest {
+					count: Some(5),
+					..Default::default()
+				},
+				&[3, 4],
+				5
+			),
+			Ok((0..5).collect())
+		);
 	}
 }
@@ -14,11 +14,17 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
+mod pubsub;
+
+pub use pubsub::{TxPoolPubSub, TxPoolTracker, TxPoolTrackerTask};
+
+use ethereum::TransactionV0 as EthereumTransaction;
 use ethereum_types::{H160, H256, U256};
 use fc_rpc::{internal_err, public_key};
 use jsonrpc_core::Result as RpcResult;
 pub use moonbeam_rpc_core_txpool::{
-	GetT, Summary, Transaction, TransactionMap, TxPool as TxPoolT, TxPoolResult, TxPoolServer,
+	GetT, Pagination, Summary, Transaction, TransactionEvent, TransactionMap, TxPool as TxPoolT,
+	TxPoolPubSub as TxPoolPubSubT, TxPoolPubSubServer, TxPoolResult, TxPoolServer,
 };
 // TODO @tgmichel It looks like this graph stuff moved to the test-helpers feature.
 // Is it only for tests? Should we use it here?
@@ -29,10 +35,10 @@ use sha3::{Digest, Keccak256};
 use sp_api::{BlockId, ProvideRuntimeApi};
 use sp_blockchain::{Error as BlockChainError, HeaderBackend, HeaderMetadata};
 use sp_runtime::traits::Block as BlockT;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::{marker::PhantomData, sync::Arc};
 
-use moonbeam_rpc_primitives_txpool::{TxPoolResponse, TxPoolRuntimeApi};
+use moonbeam_rpc_primitives_txpool::{tagged_sender, TxPoolResponse, TxPoolRuntimeApi};
 
 pub struct TxPool<B: BlockT, C, A: ChainApi> {
 	client: Arc<C>,
@@ -50,64 +56,126 @@ where
 	C::Api: TxPoolRuntimeApi<B>,
 {
 	/// Use the transaction graph interface to get the extrinsics currently in the ready and future
-	/// queues.
-	fn map_build<T>(&self) -> RpcResult<TxPoolResult<TransactionMap<T>>>
+	/// queues. If an address is provided, only the transactions it sent are kept, and if a
+	/// pagination is provided only the ones of the senders of the requested page.
+	///
+	/// The senders of the ready transactions are read from the tags they provide, so that only
+	/// the selected ones are decoded and have their signature recovered. The future queue is
+	/// bounded by a much lower limit and doesn't expose the tags, so its transactions are always
+	/// decoded.
+	fn map_build<T>(
+		&self,
+		address: Option<H160>,
+		pagination: Option<Pagination>,
+	) -> RpcResult<TxPoolResult<TransactionMap<T>>>
 	where
 		T: GetT + Serialize,
 	{
-		// Collect transactions in the ready validated pool.
-		let txs_ready = self
-			.graph
-			.validated_pool()
-			.ready()
-			.map(|in_pool_tx| in_pool_tx.data().clone())
-			.collect();
+		let best_block: BlockId<B> = BlockId::Hash(self.client.info().best_hash);
 
 		// Collect transactions in the future validated pool.
 		let txs_future = self
 			.graph
 			.validated_pool()
 			.futures()
+			.into_iter()
+			.map(|(_hash, extrinsic)| extrinsic)
+			.collect();
+		let queued_txns: Vec<_> = self
+			.extrinsic_filter(&best_block, vec![], txs_future)?
+			.future
+			.into_iter()
+			.map(|txn| (hash_and_sender(&txn), txn))
+			.collect();
+
+		// Collect transactions in the ready validated pool, with their sender.
+		let ready: Vec<_> = self
+			.graph
+			.validated_pool()
+			.ready()
+			.filter_map(|in_pool_tx| {
+				tagged_sender(in_pool_tx.provides()).map(|sender| (sender, in_pool_tx))
+			})
+			.collect();
+
+		// Select the senders to return.
+		let senders: BTreeSet<H160> = ready
 			.iter()
-			.map(|(_hash, extrinsic)| extrinsic.clone())
+			.map(|(sender, _)| *sender)
+			.chain(queued_txns.iter().map(|((_, sender), _)| *sender))
+			.filter(|sender| address.map_or(true, |address| address == *sender))
 			.collect();
+		let senders = match pagination {
+			Some(pagination) => page(senders, pagination),
+			None => senders,
+		};
+
+		let txs_ready = ready
+			.into_iter()
+			.filter(|(sender, _)| senders.contains(sender))
+			.map(|(_, in_pool_tx)| in_pool_tx.data().clone())
+			.collect();
+		let pending_txns = self.extrinsic_filter(&best_block, txs_ready, vec![])?.ready;
 
-		// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
-		let best_block: BlockId<B> = BlockId::Hash(self.client.info().best_hash);
-		let ethereum_txns: TxPoolResponse = self
-			.client
-			.runtime_api()
-			.extrinsic_filter(&best_block, txs_ready, txs_future)
-			.map_err(|err| {
-				internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
-			})?;
 		// Build the T response.
 		let mut pending = TransactionMap::<T>::new();
-		for txn in ethereum_txns.ready.iter() {
-			let hash = H256::from_slice(Keccak256::digest(&rlp::encode(txn)).as_slice());
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from_slice(Keccak256::digest(&pk).as_slice())),
-				Err(_e) => H160::default(),
-			};
+		for txn in pending_txns.iter() {
+			let (hash, from_address) = hash_and_sender(txn);
+			if !senders.contains(&from_address) {
+				continue;
+			}
 			pending
 				.entry(from_address)
 				.or_insert_with(HashMap::new)
 				.insert(txn.nonce, T::get(hash, from_address, txn));
 		}
 		let mut queued = TransactionMap::<T>::new();
-		for txn in ethereum_txns.future.iter() {
-			let hash = H256::from_slice(Keccak256::digest(&rlp::encode(txn)).as_slice());
-			let from_address = match public_key(txn) {
-				Ok(pk) => H160::from(H256::from_slice(Keccak256::digest(&pk).as_slice())),
-				Err(_e) => H160::default(),
-			};
+		for ((hash, from_address), txn) in queued_txns.iter() {
+			if !senders.contains(from_address) {
+				continue;
+			}
 			queued
-				.entry(from_address)
+				.entry(*from_address)
 				.or_insert_with(HashMap::new)
-				.insert(txn.nonce, T::get(hash, from_address, txn));
+				.insert(txn.nonce, T::get(*hash, *from_address, txn));
 		}
 		Ok(TxPoolResult { pending, queued })
 	}
+
+	/// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
+	fn extrinsic_filter(
+		&self,
+		at: &BlockId<B>,
+		xts_ready: Vec<B::Extrinsic>,
+		xts_future: Vec<B::Extrinsic>,
+	) -> RpcResult<TxPoolResponse> {
+		self.client
+			.runtime_api()
+			.extrinsic_filter(at, xts_ready, xts_future)
+			.map_err(|err| {
+				internal_err(format!("fetch runtime extrinsic filter failed: {:?}", err))
+			})
+	}
+}
+
+/// Hash and sender of an Ethereum transaction. The sender is the zero address if the signature
+/// is invalid.
+fn hash_and_sender(txn: &EthereumTransaction) -> (H256, H160) {
+	let hash = H256::from_slice(Keccak256::digest(&rlp::encode(txn)).as_slice());
+	let from_address = match public_key(txn) {
+		Ok(pk) => H160::from(H256::from_slice(Keccak256::digest(&pk).as_slice())),
+		Err(_e) => H160::default(),
+	};
+	(hash, from_address)
+}
+
+/// Senders of the requested page, ordered by address.
+fn page(senders: BTreeSet<H160>, pagination: Pagination) -> BTreeSet<H160> {
+	senders
+		.into_iter()
+		.filter(|sender| pagination.after.map_or(true, |after| *sender > after))
+		.take(pagination.count.map_or(usize::MAX, |count| count as usize))
+		.collect()
 }
 
 impl<B: BlockT, C, A: ChainApi> TxPool<B, C, A> {
@@ -129,12 +197,27 @@ where
 	A: ChainApi<Block = B> + 'static,
 	C::Api: TxPoolRuntimeApi<B>,
 {
-	fn content(&self) -> RpcResult<TxPoolResult<TransactionMap<Transaction>>> {
-		self.map_build::<Transaction>()
+	fn content(
+		&self,
+		pagination: Option<Pagination>,
+	) -> RpcResult<TxPoolResult<TransactionMap<Transaction>>> {
+		self.map_build::<Transaction>(None, pagination)
+	}
+
+	fn content_from(&self, address: H160) -> RpcResult<TxPoolResult<HashMap<U256, Transaction>>> {
+		let mut result = self.map_build::<Transaction>(Some(address), None)?;
+		Ok(TxPoolResult {
+			pending: result.pending.remove(&address).unwrap_or_default(),
+			queued: result.queued.remove(&address).unwrap_or_default(),
+		})
 	}
 
-	fn inspect(&self) -> RpcResult<TxPoolResult<TransactionMap<Summary>>> {
-		self.map_build::<Summary>()
+	fn inspect(
+		&self,
+		address: Option<H160>,
+		pagination: Option<Pagination>,
+	) -> RpcResult<TxPoolResult<TransactionMap<Summary>>> {
+		self.map_build::<Summary>(address, pagination)
 	}
 
 	fn status(&self) -> RpcResult<TxPoolResult<U256>> {
@@ -0,0 +1,435 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! `txpool_subscribe` handler, streaming the changes of the Ethereum transactions of the pool.
+//!
+//! The transaction pool only notifies the extrinsics imported in its ready queue, so a single
+//! task keeps track of the Ethereum transactions of the pool while there are subscribers, and
+//! sends its events to each of them:
+//! - New transactions are found by scanning the ready and future queues when an extrinsic or a
+//!   block is imported, and at a regular interval as entering the future queue is not notified.
+//!   The runtime calls matching them against Ethereum transactions are run as blocking tasks.
+//! - A watcher is created for each of them, reporting its promotion to the ready queue, its
+//!   replacement or its removal from the pool.
+
+use crate::{hash_and_sender, GetT, Transaction, TransactionEvent, TxPoolPubSubT};
+use ethereum_types::H256;
+use fc_rpc::{internal_err, HexEncodedIdProvider};
+use futures::{
+	channel::mpsc,
+	compat::Sink01CompatExt,
+	future::Future,
+	select,
+	stream::{self, BoxStream, SelectAll},
+	FutureExt, SinkExt, StreamExt, TryFutureExt,
+};
+use jsonrpc_core::Result as RpcResult;
+use jsonrpc_pubsub::{manager::SubscriptionManager, typed::Subscriber, SubscriptionId};
+use sc_client_api::BlockchainEvents;
+use sc_transaction_pool::test_helpers::{ChainApi, Pool};
+use sc_transaction_pool_api::{InPoolTransaction, TransactionStatus};
+use sp_api::{BlockId, ProvideRuntimeApi};
+use sp_blockchain::HeaderBackend;
+use sp_runtime::traits::Block as BlockT;
+use sp_utils::mpsc::{TracingUnboundedReceiver, TracingUnboundedSender};
+use std::collections::{HashMap, HashSet};
+use std::{
+	marker::PhantomData,
+	sync::{
+		atomic::{AtomicUsize, Ordering},
+		Arc,
+	},
+	time::Duration,
+};
+use tokio::time::delay_for;
+
+use moonbeam_rpc_primitives_txpool::TxPoolRuntimeApi;
+
+/// Interval between two scans of the pool, for the transactions entering the future queue.
+const SCAN_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Number of events buffered for a subscriber. A subscriber lagging further behind is dropped.
+const SUBSCRIBER_BUFFER: usize = 1_000;
+
+/// Status update of a watched transaction, with its pool and Ethereum hashes.
+type StatusUpdate = (H256, H256, TransactionStatus<H256, H256>);
+
+/// Allows to subscribe to the events of the pool tracker task.
+#[derive(Clone)]
+pub struct TxPoolTracker {
+	sender: TracingUnboundedSender<mpsc::Sender<TransactionEvent>>,
+	active_subscriptions: Arc<AtomicUsize>,
+	max_subscriptions: usize,
+}
+
+/// Decrements the number of active subscriptions when the subscription ends.
+struct SubscriptionGuard(Arc<AtomicUsize>);
+
+impl Drop for SubscriptionGuard {
+	fn drop(&mut self) {
+		self.0.fetch_sub(1, Ordering::SeqCst);
+	}
+}
+
+pub struct TxPoolPubSub {
+	tracker: TxPoolTracker,
+	subscriptions: SubscriptionManager<HexEncodedIdProvider>,
+}
+
+impl TxPoolPubSub {
+	pub fn new(
+		tracker: TxPoolTracker,
+		subscriptions: SubscriptionManager<HexEncodedIdProvider>,
+	) -> Self {
+		Self {
+			tracker,
+			subscriptions,
+		}
+	}
+}
+
+/// Ethereum transaction of the pool followed by the tracker task.
+struct TrackedTransaction {
+	transaction: Transaction,
+	ready: bool,
+}
+
+impl TrackedTransaction {
+	/// Event reporting the transaction entering its current queue.
+	fn event(&self) -> TransactionEvent {
+		let transaction = self.transaction.clone();
+		if self.ready {
+			TransactionEvent::Pending { transaction }
+		} else {
+			TransactionEvent::Queued { transaction }
+		}
+	}
+}
+
+/// Task following the pool for the `txpool_subscribe` subscribers.
+pub struct TxPoolTrackerTask<B: BlockT, C, A: ChainApi> {
+	client: Arc<C>,
+	graph: Arc<Pool<A>>,
+	/// Event channels of the subscribers.
+	subscribers: Vec<mpsc::Sender<TransactionEvent>>,
+	/// Ethereum transactions of the pool, by pool hash.
+	transactions: HashMap<H256, TrackedTransaction>,
+	/// Pool hashes of the extrinsics which are not Ethereum transactions.
+	ignored: HashSet<H256>,
+	_marker: PhantomData<B>,
+}
+
+impl<B, C, A> TxPoolTrackerTask<B, C, A>
+where
+	C: ProvideRuntimeApi<B> + BlockchainEvents<B>,
+	C: HeaderBackend<B> + 'static,
+	C: Send + Sync + 'static,
+	B: BlockT<Hash = H256> + Send + Sync + 'static,
+	A: ChainApi<Block = B> + 'static,
+	C::Api: TxPoolRuntimeApi<B>,
+{
+	/// Create a new pool tracker task.
+	///
+	/// Returns a Future that needs to be added to a tokio executor, and an handle allowing to
+	/// subscribe to its events. At most `max_subscriptions` subscriptions are accepted at a time.
+	pub fn create(
+		client: Arc<C>,
+		graph: Arc<Pool<A>>,
+		max_subscriptions: usize,
+	) -> (impl Future<Output = ()>, TxPoolTracker) {
+		let (subscriber_tx, subscriber_rx) = sp_utils::mpsc::tracing_unbounded("txpool-tracker");
+
+		let task = Self {
+			client,
+			graph,
+			subscribers: vec![],
+			transactions: HashMap::new(),
+			ignored: HashSet::new(),
+			_marker: PhantomData,
+		}
+		.run(subscriber_rx);
+
+		let tracker = TxPoolTracker {
+			sender: subscriber_tx,
+			active_subscriptions: Arc::new(AtomicUsize::new(0)),
+			max_subscriptions,
+		};
+		(task, tracker)
+	}
+
+	/// Follow the pool while there are subscribers, until all the handles are dropped.
+	async fn run(
+		mut self,
+		mut new_subscribers: TracingUnboundedReceiver<mpsc::Sender<TransactionEvent>>,
+	) {
+		// The pool is not followed while no one is subscribed.
+		while let Some(subscriber) = new_subscribers.next().await {
+			self.subscribers.push(subscriber);
+			if !self.follow(&mut new_subscribers).await {
+				return;
+			}
+		}
+	}
+
+	/// Follow the pool until the subscribers are gone, sending them the events. Returns `false`
+	/// if the handles have been dropped.
+	async fn follow(
+		&mut self,
+		new_subscribers: &mut TracingUnboundedReceiver<mpsc::Sender<TransactionEvent>>,
+	) -> bool {
+		let mut pool_imports = self
+			.graph
+			.validated_pool()
+			.import_notification_stream()
+			.fuse();
+		let mut block_imports = self.client.import_notification_stream().fuse();
+		let mut scan_ticks = stream::unfold((), |()| async {
+			delay_for(SCAN_INTERVAL).await;
+			Some(((), ()))
+		})
+		.boxed()
+		.fuse();
+		let mut watchers = SelectAll::new();
+
+		// The transactions already in the pool are watched but not reported.
+		let _ = self.scan(&mut watchers).await;
+
+		loop {
+			let update = select! {
+				subscriber = new_subscribers.next() => match subscriber {
+					Some(subscriber) => {
+						self.subscribers.push(subscriber);
+						continue;
+					}
+					None => return false,
+				},
+				_ = pool_imports.next() => None,
+				_ = block_imports.next() => None,
+				_ = scan_ticks.next() => None,
+				update = watchers.next() => update,
+			};
+
+			let events = match update {
+				Some(update) => self.status_update(update, &mut watchers).await,
+				None => self.scan(&mut watchers).await,
+			};
+
+			self.broadcast(events);
+			if self.subscribers.is_empty() {
+				// Start from a fresh state for the next subscribers.
+				self.transactions.clear();
+				self.ignored.clear();
+				return true;
+			}
+		}
+	}
+
+	/// Send the events to the subscribers, dropping the ones which are gone or lagging behind.
+	fn broadcast(&mut self, events: Vec<TransactionEvent>) {
+		self.subscribers = std::mem::take(&mut self.subscribers)
+			.into_iter()
+			.filter_map(|mut subscriber| {
+				for event in &events {
+					subscriber.try_send(event.clone()).ok()?;
+				}
+				Some(subscriber).filter(|subscriber| !subscriber.is_closed())
+			})
+			.collect();
+	}
+
+	/// Scan the ready and future queues, reporting the new Ethereum transactions and the ones
+	/// which changed queue, and forgetting the ones which left the pool.
+	async fn scan(
+		&mut self,
+		watchers: &mut SelectAll<BoxStream<'static, StatusUpdate>>,
+	) -> Vec<TransactionEvent> {
+		let validated_pool = self.graph.validated_pool();
+		let pool: Vec<_> = validated_pool
+			.ready()
+			.map(|in_pool_tx| (*in_pool_tx.hash(), in_pool_tx.data().clone(), true))
+			.chain(
+				validated_pool
+					.futures()
+					.into_iter()
+					.map(|(hash, extrinsic)| (hash, extrinsic, false)),
+			)
+			.collect();
+
+		// Transactions which left the pool are reported by their watcher.
+		let in_pool: HashSet<H256> = pool.iter().map(|(hash, _, _)| *hash).collect();
+		self.transactions.retain(|hash, _| in_pool.contains(hash));
+		self.ignored.retain(|hash| in_pool.contains(hash));
+
+		let mut events = vec![];
+		let mut new_extrinsics = vec![];
+		for (hash, extrinsic, ready) in pool {
+			if self.ignored.contains(&hash) {
+				continue;
+			}
+
+			match self.transactions.get_mut(&hash) {
+				Some(tracked) => {
+					if tracked.ready != ready {
+						tracked.ready = ready;
+						events.push(tracked.event());
+					}
+				}
+				None => new_extrinsics.push((hash, extrinsic, ready)),
+			}
+		}
+
+		if new_extrinsics.is_empty() {
+			return events;
+		}
+
+		// Use the runtime to match the (here) opaque extrinsics against ethereum transactions.
+		let client = Arc::clone(&self.client);
+		let matched = tokio::task::spawn_blocking(move || {
+			let best_block: BlockId<B> = BlockId::Hash(client.info().best_hash);
+			let api = client.runtime_api();
+			new_extrinsics
+				.into_iter()
+				.filter_map(|(hash, extrinsic, ready)| {
+					// Errors are retried on the next scan.
+					let txn = api
+						.extrinsic_filter(&best_block, vec![extrinsic], vec![])
+						.ok()?
+						.ready
+						.into_iter()
+						.next()
+						.map(|txn| {
+							let (eth_hash, from_address) = hash_and_sender(&txn);
+							Transaction::get(eth_hash, from_address, &txn)
+						});
+					Some((hash, ready, txn))
+				})
+				.collect::<Vec<_>>()
+		})
+		.await
+		.unwrap_or_default();
+
+		for (hash, ready, txn) in matched {
+			match txn {
+				Some(transaction) => {
+					let eth_hash = transaction.hash;
+					let tracked = TrackedTransaction { transaction, ready };
+					events.push(tracked.event());
+					self.transactions.insert(hash, tracked);
+
+					watchers.push(
+						validated_pool
+							.create_watcher(hash)
+							.into_stream()
+							.map(move |status| (hash, eth_hash, status))
+							.boxed(),
+					);
+				}
+				None => {
+					self.ignored.insert(hash);
+				}
+			}
+		}
+
+		events
+	}
+
+	/// Handle a status update of a watched transaction.
+	async fn status_update(
+		&mut self,
+		(hash, eth_hash, status): StatusUpdate,
+		watchers: &mut SelectAll<BoxStream<'static, StatusUpdate>>,
+	) -> Vec<TransactionEvent> {
+		match status {
+			TransactionStatus::Ready | TransactionStatus::Future => {
+				let ready = matches!(status, TransactionStatus::Ready);
+				match self.transactions.get_mut(&hash) {
+					Some(tracked) if tracked.ready != ready => {
+						tracked.ready = ready;
+						vec![tracked.event()]
+					}
+					_ => vec![],
+				}
+			}
+			TransactionStatus::Usurped(by) => {
+				self.transactions.remove(&hash);
+
+				// The replacing transaction is reported before the replacement.
+				let mut events = self.scan(watchers).await;
+				events.push(match self.transactions.get(&by) {
+					Some(replacement) => TransactionEvent::Replaced {
+						hash: eth_hash,
+						by: replacement.transaction.hash,
+					},
+					None => TransactionEvent::Dropped { hash: eth_hash },
+				});
+				events
+			}
+			TransactionStatus::Dropped | TransactionStatus::Invalid => {
+				self.transactions.remove(&hash);
+				vec![TransactionEvent::Dropped { hash: eth_hash }]
+			}
+			TransactionStatus::InBlock(_) => {
+				// Included transactions are not reported.
+				self.transactions.remove(&hash);
+				vec![]
+			}
+			_ => vec![],
+		}
+	}
+}
+
+impl TxPoolPubSubT for TxPoolPubSub {
+	type Metadata = sc_rpc::Metadata;
+
+	fn subscribe(&self, _metadata: Self::Metadata, subscriber: Subscriber<TransactionEvent>) {
+		let tracker = &self.tracker;
+		if tracker.active_subscriptions.fetch_add(1, Ordering::SeqCst) >= tracker.max_subscriptions
+		{
+			tracker.active_subscriptions.fetch_sub(1, Ordering::SeqCst);
+			let _ = subscriber.reject(internal_err(format!(
+				"txpool subscriptions limit reached ({})",
+				tracker.max_subscriptions
+			)));
+			return;
+		}
+		let guard = SubscriptionGuard(Arc::clone(&tracker.active_subscriptions));
+
+		let (events_tx, events_rx) = mpsc::channel(SUBSCRIBER_BUFFER);
+		if tracker.sender.unbounded_send(events_tx).is_err() {
+			let _ = subscriber.reject(internal_err("txpool tracker task is not running"));
+			return;
+		}
+
+		self.subscriptions.add(subscriber, move |sink| {
+			events_rx
+				.map(|event| Ok::<_, ()>(Ok(event)))
+				.forward(sink.sink_compat().sink_map_err(|_| ()))
+				// The subscription ends when it is cancelled or dropped by the tracker task.
+				.map(move |_| drop(guard))
+				.unit_error()
+				.boxed()
+				.compat()
+		});
+	}
+
+	fn unsubscribe(
+		&self,
+		_metadata: Option<Self::Metadata>,
+		subscription_id: SubscriptionId,
+	) -> RpcResult<bool> {
+		Ok(self.subscriptions.cancel(subscription_id))
+	}
+}
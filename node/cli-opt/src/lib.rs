@@ -74,5 +74,12 @@ pub struct RpcConfig {
 	pub ethapi_max_permits: u32,
 	pub ethapi_trace_max_count: u32,
 	pub ethapi_trace_cache_duration: u64,
+	pub ethapi_trace_persistent_cache: bool,
+	pub ethapi_trace_pretrace: bool,
+	pub ethapi_debug_timeout: u64,
+	pub ethapi_debug_max_steps: u64,
+	pub ethapi_debug_max_response_size: u32,
+	pub ethapi_debug_js_memory_limit: u32,
+	pub ethapi_debug_js_max_stack_size: u32,
 	pub max_past_logs: u32,
 }
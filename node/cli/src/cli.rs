@@ -169,6 +169,40 @@ pub struct RunCmd {
 	#[structopt(long, default_value = "300")]
 	pub ethapi_trace_cache_duration: u64,
 
+	/// Store the traces of `trace_filter` in a database on disk, keyed by block hash, so they
+	/// outlive the in-memory cache and node restarts.
+	#[structopt(long)]
+	pub ethapi_trace_persistent_cache: bool,
+
+	/// Trace the newly finalized blocks in the background and store them in the persistent cache
+	/// of `trace_filter`.
+	#[structopt(long, requires = "ethapi-trace-persistent-cache")]
+	pub ethapi_trace_pretrace: bool,
+
+	/// Duration (in seconds) after which a `debug` tracing request without a `timeout` parameter
+	/// is interrupted. The request is answered with an error right away, and its replay keeps
+	/// using a permit of `--ethapi-max-permits` until its next tracing event, where it stops.
+	#[structopt(long, default_value = "300")]
+	pub ethapi_debug_timeout: u64,
+
+	/// Maximum number of opcode steps collected by a single `debug` tracing request, when using
+	/// the default (raw) or a Javascript tracer. The number of steps used to be unbounded: traces
+	/// of more than 1000000 steps now need a larger value.
+	#[structopt(long, default_value = "1000000")]
+	pub ethapi_debug_max_steps: u64,
+
+	/// Maximum size (in MiB) of the response of a `debug` tracing request.
+	#[structopt(long, default_value = "256")]
+	pub ethapi_debug_max_response_size: u32,
+
+	/// Maximum memory (in MiB) the engine running a Javascript `debug` tracer can allocate.
+	#[structopt(long, default_value = "256")]
+	pub ethapi_debug_js_memory_limit: u32,
+
+	/// Maximum stack size (in KiB) of the engine running a Javascript `debug` tracer.
+	#[structopt(long, default_value = "256")]
+	pub ethapi_debug_js_max_stack_size: u32,
+
 	/// Maximum number of logs in a query.
 	#[structopt(long, default_value = "10000")]
 	pub max_past_logs: u32,
@@ -550,6 +550,13 @@ pub fn run() -> Result<()> {
 					ethapi_max_permits: cli.run.ethapi_max_permits,
 					ethapi_trace_max_count: cli.run.ethapi_trace_max_count,
 					ethapi_trace_cache_duration: cli.run.ethapi_trace_cache_duration,
+					ethapi_trace_persistent_cache: cli.run.ethapi_trace_persistent_cache,
+					ethapi_trace_pretrace: cli.run.ethapi_trace_pretrace,
+					ethapi_debug_timeout: cli.run.ethapi_debug_timeout,
+					ethapi_debug_max_steps: cli.run.ethapi_debug_max_steps,
+					ethapi_debug_max_response_size: cli.run.ethapi_debug_max_response_size,
+					ethapi_debug_js_memory_limit: cli.run.ethapi_debug_js_memory_limit,
+					ethapi_debug_js_max_stack_size: cli.run.ethapi_debug_js_max_stack_size,
 					max_past_logs: cli.run.max_past_logs,
 				};
 
@@ -33,6 +33,7 @@ pub use moonbeam_runtime;
 #[cfg(feature = "moonriver-native")]
 pub use moonriver_runtime;
 use sc_service::BasePath;
+use sp_core::H160;
 use std::{collections::BTreeMap, sync::Mutex, time::Duration};
 mod rpc;
 use cumulus_client_network::build_block_announce_validator;
@@ -156,6 +157,27 @@ pub fn open_frontier_backend(config: &Configuration) -> Result<Arc<fc_db::Backen
 	)?))
 }
 
+/// Opens the persistent cache of `trace_filter`, if enabled.
+pub fn open_trace_database(
+	rpc_config: &RpcConfig,
+	config: &Configuration,
+) -> Result<Option<Arc<moonbeam_rpc_trace::TraceDb>>, String> {
+	if !rpc_config.ethapi_trace_persistent_cache {
+		return Ok(None);
+	}
+
+	let config_dir = config
+		.base_path
+		.as_ref()
+		.map(|base_path| base_path.config_dir(config.chain_spec.id()))
+		.unwrap_or_else(|| {
+			BasePath::from_project("", "", "moonbeam").config_dir(config.chain_spec.id())
+		});
+	Ok(Some(Arc::new(moonbeam_rpc_trace::TraceDb::open(
+		&config_dir.join("traces").join("db"),
+	)?)))
+}
+
 use sp_runtime::traits::BlakeTwo256;
 use sp_trie::PrefixedMemoryDB;
 
@@ -409,6 +431,21 @@ impl fp_rpc::ConvertTransaction<moonbeam_core_primitives::UncheckedExtrinsic>
 	}
 }
 
+/// Returns the function telling whether a precompile of the runtime of the configured chain lives
+/// at an address, for the tracers that treat calls to precompiles differently.
+fn precompile_filter(config: &Configuration) -> fn(&H160) -> bool {
+	match &config.chain_spec {
+		#[cfg(feature = "moonriver-native")]
+		spec if spec.is_moonriver() => moonriver_runtime::Precompiles::is_precompile,
+		#[cfg(feature = "moonbeam-native")]
+		spec if spec.is_moonbeam() => moonbeam_runtime::Precompiles::is_precompile,
+		#[cfg(feature = "moonbase-native")]
+		_ => moonbase_runtime::Precompiles::is_precompile,
+		#[cfg(not(feature = "moonbase-native"))]
+		_ => panic!("invalid chain spec"),
+	}
+}
+
 /// Start a node with the given parachain `Configuration` and relay chain `Configuration`.
 ///
 /// This is the actual implementation that is abstract over the executor and the runtime api.
@@ -485,6 +522,8 @@ where
 		if ethapi_cmd.contains(&EthApiCmd::Debug) || ethapi_cmd.contains(&EthApiCmd::Trace) {
 			rpc::tracing::spawn_tracing_tasks(
 				&rpc_config,
+				open_trace_database(&rpc_config, &parachain_config)?,
+				precompile_filter(&parachain_config),
 				rpc::SpawnTasksParams {
 					task_manager: &task_manager,
 					client: client.clone(),
@@ -499,6 +538,15 @@ where
 				trace: None,
 			}
 		};
+	let txpool_tracker = if ethapi_cmd.contains(&EthApiCmd::Txpool) {
+		Some(rpc::spawn_txpool_tracker(
+			&task_manager,
+			client.clone(),
+			transaction_pool.pool().clone(),
+		))
+	} else {
+		None
+	};
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -536,12 +584,15 @@ where
 				backend: backend.clone(),
 				max_past_logs,
 				transaction_converter,
+				txpool_tracker: txpool_tracker.clone(),
 			};
 			#[allow(unused_mut)]
 			let mut io = rpc::create_full(deps, subscription_task_executor.clone());
 			if ethapi_cmd.contains(&EthApiCmd::Debug) || ethapi_cmd.contains(&EthApiCmd::Trace) {
 				rpc::tracing::extend_with_tracing(
 					client.clone(),
+					frontier_backend.clone(),
+					pool.clone(),
 					tracing_requesters.clone(),
 					rpc_config.ethapi_trace_max_count,
 					&mut io,
@@ -832,6 +883,8 @@ where
 		if ethapi_cmd.contains(&EthApiCmd::Debug) || ethapi_cmd.contains(&EthApiCmd::Trace) {
 			rpc::tracing::spawn_tracing_tasks(
 				&rpc_config,
+				open_trace_database(&rpc_config, &config)?,
+				precompile_filter(&config),
 				rpc::SpawnTasksParams {
 					task_manager: &task_manager,
 					client: client.clone(),
@@ -846,6 +899,15 @@ where
 				trace: None,
 			}
 		};
+	let txpool_tracker = if ethapi_cmd.contains(&EthApiCmd::Txpool) {
+		Some(rpc::spawn_txpool_tracker(
+			&task_manager,
+			client.clone(),
+			transaction_pool.pool().clone(),
+		))
+	} else {
+		None
+	};
 
 	let rpc_extensions_builder = {
 		let client = client.clone();
@@ -881,12 +943,15 @@ where
 				backend: backend.clone(),
 				max_past_logs,
 				transaction_converter,
+				txpool_tracker: txpool_tracker.clone(),
 			};
 			#[allow(unused_mut)]
 			let mut io = rpc::create_full(deps, subscription_task_executor.clone());
 			if ethapi_cmd.contains(&EthApiCmd::Debug) || ethapi_cmd.contains(&EthApiCmd::Trace) {
 				rpc::tracing::extend_with_tracing(
 					client.clone(),
+					frontier_backend.clone(),
+					pool.clone(),
 					tracing_requesters.clone(),
 					rpc_config.ethapi_trace_max_count,
 					&mut io,
@@ -36,7 +36,10 @@ use fc_rpc_core::types::FilterPool;
 use futures::StreamExt;
 use jsonrpc_pubsub::manager::SubscriptionManager;
 use moonbeam_core_primitives::{Block, Hash};
-use moonbeam_rpc_txpool::{TxPool, TxPoolServer};
+use moonbeam_rpc_primitives_txpool::TxPoolRuntimeApi;
+use moonbeam_rpc_txpool::{
+	TxPool, TxPoolPubSub, TxPoolPubSubServer, TxPoolServer, TxPoolTracker, TxPoolTrackerTask,
+};
 use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApi};
 use sc_client_api::{
 	backend::{AuxStore, Backend, StateBackend, StorageProvider},
@@ -87,6 +90,8 @@ pub struct FullDeps<C, P, A: ChainApi, BE> {
 	pub max_past_logs: u32,
 	/// Ethereum transaction to Extrinsic converter.
 	pub transaction_converter: TransactionConverters,
+	/// Handle of the task following the pool for the `txpool_subscribe` subscriptions.
+	pub txpool_tracker: Option<TxPoolTracker>,
 }
 /// Instantiate all Full RPC extensions.
 pub fn create_full<C, P, BE, A>(
@@ -120,6 +125,7 @@ where
 		backend: _,
 		max_past_logs,
 		transaction_converter,
+		txpool_tracker,
 	} = deps;
 
 	io.extend_with(SystemApi::to_delegate(FullSystem::new(
@@ -185,7 +191,7 @@ where
 		network,
 		SubscriptionManager::<HexEncodedIdProvider>::with_id_provider(
 			HexEncodedIdProvider::default(),
-			Arc::new(subscription_task_executor),
+			Arc::new(subscription_task_executor.clone()),
 		),
 		overrides,
 	)));
@@ -195,6 +201,15 @@ where
 			graph,
 		)));
 	}
+	if let Some(txpool_tracker) = txpool_tracker {
+		io.extend_with(TxPoolPubSubServer::to_delegate(TxPoolPubSub::new(
+			txpool_tracker,
+			SubscriptionManager::<HexEncodedIdProvider>::with_id_provider(
+				HexEncodedIdProvider::default(),
+				Arc::new(subscription_task_executor),
+			),
+		)));
+	}
 
 	if let Some(command_sink) = command_sink {
 		io.extend_with(
@@ -267,3 +282,26 @@ where
 		),
 	);
 }
+
+/// Spawn the task following the transaction pool for the `txpool_subscribe` subscriptions, shared
+/// by all of them.
+pub fn spawn_txpool_tracker<C, A>(
+	task_manager: &TaskManager,
+	client: Arc<C>,
+	graph: Arc<Pool<A>>,
+) -> TxPoolTracker
+where
+	C: ProvideRuntimeApi<Block> + BlockchainEvents<Block>,
+	C: HeaderBackend<Block> + 'static,
+	C: Send + Sync + 'static,
+	C::Api: TxPoolRuntimeApi<Block>,
+	A: ChainApi<Block = Block> + 'static,
+{
+	let (txpool_tracker_task, txpool_tracker) = TxPoolTrackerTask::create(
+		client, graph, 100, // max subscriptions
+	);
+	task_manager
+		.spawn_essential_handle()
+		.spawn("txpool-tracker", txpool_tracker_task);
+	txpool_tracker
+}
@@ -17,10 +17,12 @@
 use super::*;
 
 use moonbeam_rpc_debug::DebugHandler;
-use moonbeam_rpc_debug::{Debug, DebugRequester, DebugServer};
+use moonbeam_rpc_debug::{Debug, DebugLimits, DebugRequester, DebugServer};
 use moonbeam_rpc_trace::{
-	CacheRequester as TraceFilterCacheRequester, CacheTask, Trace, TraceServer,
+	CacheRequester as TraceFilterCacheRequester, CacheTask, Trace, TraceDb, TraceDbTask,
+	TraceServer,
 };
+use sp_core::H160;
 use tokio::sync::Semaphore;
 
 #[derive(Clone)]
@@ -29,8 +31,10 @@ pub struct RpcRequesters {
 	pub trace: Option<TraceFilterCacheRequester>,
 }
 
-pub fn extend_with_tracing<C, BE>(
+pub fn extend_with_tracing<C, BE, P>(
 	client: Arc<C>,
+	frontier_backend: Arc<fc_db::Backend<Block>>,
+	pool: Arc<P>,
 	requesters: RpcRequesters,
 	trace_filter_max_count: u32,
 	io: &mut jsonrpc_core::IoHandler<sc_rpc::Metadata>,
@@ -43,10 +47,13 @@ pub fn extend_with_tracing<C, BE>(
 	C: HeaderBackend<Block> + HeaderMetadata<Block, Error = BlockChainError> + 'static,
 	C: Send + Sync + 'static,
 	C::Api: RuntimeApiCollection<StateBackend = BE::State>,
+	P: TransactionPool<Block = Block> + 'static,
 {
 	if let Some(trace_filter_requester) = requesters.trace {
 		io.extend_with(TraceServer::to_delegate(Trace::new(
 			client,
+			frontier_backend,
+			pool,
 			trace_filter_requester,
 			trace_filter_max_count,
 		)));
@@ -58,8 +65,12 @@ pub fn extend_with_tracing<C, BE>(
 }
 
 // Spawn the tasks that are required to run a Moonbeam tracing node.
+// `trace_db` is the persistent cache of `trace_filter`, if enabled.
+// `is_precompile` tells whether a precompile of the runtime lives at a given address.
 pub fn spawn_tracing_tasks<B, C, BE>(
 	rpc_config: &cli_opt::RpcConfig,
+	trace_db: Option<Arc<TraceDb>>,
+	is_precompile: fn(&H160) -> bool,
 	params: SpawnTasksParams<B, C, BE>,
 ) -> RpcRequesters
 where
@@ -83,18 +94,41 @@ where
 				Arc::clone(&params.substrate_backend),
 				Duration::from_secs(rpc_config.ethapi_trace_cache_duration),
 				Arc::clone(&permit_pool),
+				trace_db.clone(),
 			);
 			(Some(trace_filter_task), Some(trace_filter_requester))
 		} else {
 			(None, None)
 		};
 
+	let (trace_db_task, pretrace_task) = match trace_db {
+		Some(trace_db) if rpc_config.ethapi.contains(&EthApiCmd::Trace) => {
+			let (trace_db_task, pretrace_task) = TraceDbTask::create(
+				Arc::clone(&params.client),
+				Arc::clone(&params.substrate_backend),
+				trace_db,
+				Arc::clone(&permit_pool),
+				rpc_config.ethapi_trace_pretrace,
+			);
+			(Some(trace_db_task), pretrace_task)
+		}
+		_ => (None, None),
+	};
+
 	let (debug_task, debug_requester) = if rpc_config.ethapi.contains(&EthApiCmd::Debug) {
 		let (debug_task, debug_requester) = DebugHandler::task(
 			Arc::clone(&params.client),
 			Arc::clone(&params.substrate_backend),
 			Arc::clone(&params.frontier_backend),
 			Arc::clone(&permit_pool),
+			DebugLimits {
+				default_timeout: Duration::from_secs(rpc_config.ethapi_debug_timeout),
+				max_steps: rpc_config.ethapi_debug_max_steps,
+				max_response_size: rpc_config.ethapi_debug_max_response_size as usize * 1024 * 1024,
+				js_memory_limit: rpc_config.ethapi_debug_js_memory_limit as usize * 1024 * 1024,
+				js_max_stack_size: rpc_config.ethapi_debug_js_max_stack_size as usize * 1024,
+			},
+			is_precompile,
 		);
 		(Some(debug_task), Some(debug_requester))
 	} else {
@@ -110,6 +144,22 @@ where
 			.spawn("trace-filter-cache", trace_filter_task);
 	}
 
+	// Persistent `trace_filter` cache task if enabled. Essential.
+	if let Some(trace_db_task) = trace_db_task {
+		params
+			.task_manager
+			.spawn_essential_handle()
+			.spawn("trace-filter-db", trace_db_task);
+	}
+
+	// Pre-tracing of the finalized blocks into the persistent cache if enabled. Essential.
+	if let Some(pretrace_task) = pretrace_task {
+		params
+			.task_manager
+			.spawn_essential_handle()
+			.spawn("trace-filter-pretrace", pretrace_task);
+	}
+
 	// `debug` task if enabled. Essential.
 	// Proxies rpc requests to it's handler.
 	if let Some(debug_task) = debug_task {
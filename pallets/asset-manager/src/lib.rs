@@ -21,13 +21,25 @@
 //! The main goal of this pallet is to allow moonbeam to register XCM assets
 //! The assumption is we work with AssetTypes, which can then be comperted to AssetIds
 //!
-//! This pallet has two storage items: AssetIdType, which holds a mapping from AssetId->AssetType
+//! It also lets users create local assets, issued natively on moonbeam. Their ids are taken from
+//! a counter, skipping those already used by XCM assets so that both kinds never collide.
+//!
+//! This pallet has five storage items: AssetIdType, which holds a mapping from AssetId->AssetType
 //! AssetIdUnitsPerSecond: an AssetId->u128 mapping that holds how much each AssetId should be
 //! charged per unit of second, in the case such an Asset is received as a XCM asset.
+//! AssetIdRelativePrice: an AssetId->RelativePrice mapping that holds the price of an asset
+//! relative to the native token, from which the units per second are derived.
+//! LocalAssetCounter: the counter from which the next local asset id is derived.
+//! LocalAssetCreator: an AssetId->AccountId mapping that holds who created each local asset.
 //!
-//! This pallet has two extrinsics: register_asset, which registers an Asset in this pallet and
+//! This pallet has four extrinsics: register_asset, which registers an Asset in this pallet and
 //! creates the asset as dictated by the AssetRegistrar trait. set_asset_units_per_second: which
 //! sets the unit per second that should be charged for a particular asset.
+//! set_asset_relative_price: which instead prices an asset relative to the native token, so that
+//! its units per second follow what a second of weight costs in native tokens, optionally within
+//! bounds and for a limited number of blocks. An asset is priced by only one of these two.
+//! create_local_asset: which creates a local asset owned by the caller, as dictated by the
+//! AssetRegistrar trait.
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
@@ -44,7 +56,10 @@ pub mod pallet {
 	use frame_support::{pallet_prelude::*, PalletId};
 	use frame_system::pallet_prelude::*;
 	use parity_scale_codec::HasCompact;
-	use sp_runtime::traits::{AccountIdConversion, AtLeast32BitUnsigned};
+	use sp_runtime::{
+		traits::{AccountIdConversion, AtLeast32BitUnsigned, Saturating},
+		FixedPointNumber, FixedU128,
+	};
 
 	#[pallet::pallet]
 	pub struct Pallet<T>(PhantomData<T>);
@@ -52,6 +67,9 @@ pub mod pallet {
 	/// The AssetManagers's pallet id
 	pub const PALLET_ID: PalletId = PalletId(*b"asstmngr");
 
+	/// The most ids `create_local_asset` tries before giving up, which bounds its weight
+	pub const MAX_LOCAL_ASSET_ID_ATTEMPTS: u32 = 4;
+
 	// The registrar trait. We need to comply with this
 	pub trait AssetRegistrar<T: Config> {
 		// How to create an asset
@@ -60,6 +78,18 @@ pub mod pallet {
 			min_balance: T::Balance,
 			metadata: T::AssetRegistrarMetadata,
 		) -> DispatchResult;
+
+		// How to create a local asset, owned by its creator
+		fn create_local_asset(
+			asset: T::AssetId,
+			creator: T::AccountId,
+			min_balance: T::Balance,
+		) -> DispatchResult;
+	}
+
+	// How to derive the id of a local asset from the local asset counter
+	pub trait LocalAssetIdCreator<T: Config> {
+		fn create_asset_id_from_counter(counter: u128) -> T::AssetId;
 	}
 
 	// We implement this trait to be able to get the AssetType and units per second registered
@@ -71,7 +101,50 @@ pub mod pallet {
 
 	impl<T: Config> xcm_primitives::UnitsToWeightRatio<T::AssetId> for Pallet<T> {
 		fn get_units_per_second(asset_id: T::AssetId) -> Option<u128> {
-			AssetIdUnitsPerSecond::<T>::get(asset_id)
+			AssetIdUnitsPerSecond::<T>::get(asset_id).or_else(|| {
+				AssetIdRelativePrice::<T>::get(asset_id).and_then(|relative_price| {
+					relative_price.units_per_second(
+						T::NativeFeePerSecond::get(),
+						frame_system::Pallet::<T>::block_number(),
+					)
+				})
+			})
+		}
+	}
+
+	/// The price of an asset relative to the native token
+	#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+	pub struct RelativePrice<BlockNumber> {
+		/// The amount of asset units worth one native unit
+		pub price: FixedU128,
+		/// The units per second charged when the price gives less
+		pub min_units_per_second: Option<u128>,
+		/// The units per second charged when the price gives more
+		pub max_units_per_second: Option<u128>,
+		/// The block from which the price is stale and the asset can no longer pay for fees
+		pub expires_at: Option<BlockNumber>,
+	}
+
+	impl<BlockNumber: PartialOrd> RelativePrice<BlockNumber> {
+		/// The units per second to charge at block `now`, when a second of weight costs
+		/// `native_fee_per_second` native units
+		pub fn units_per_second(
+			&self,
+			native_fee_per_second: u128,
+			now: BlockNumber,
+		) -> Option<u128> {
+			if matches!(&self.expires_at, Some(expires_at) if now >= *expires_at) {
+				return None;
+			}
+
+			let mut units_per_second = self.price.saturating_mul_int(native_fee_per_second);
+			if let Some(min) = self.min_units_per_second {
+				units_per_second = units_per_second.max(min);
+			}
+			if let Some(max) = self.max_units_per_second {
+				units_per_second = units_per_second.min(max);
+			}
+			Some(units_per_second)
 		}
 	}
 
@@ -96,6 +169,13 @@ pub mod pallet {
 
 		/// Origin that is allowed to create and modify asset information
 		type AssetModifierOrigin: EnsureOrigin<Self::Origin>;
+
+		/// The fee, in native units, charged for a second of weight. Assets priced relative to the
+		/// native token are charged their price times this fee per second.
+		type NativeFeePerSecond: Get<u128>;
+
+		/// How local asset ids are derived from the local asset counter
+		type LocalAssetIdCreator: LocalAssetIdCreator<Self>;
 	}
 
 	/// An error that can occur while executing the mapping pallet's logic.
@@ -104,6 +184,9 @@ pub mod pallet {
 		ErrorCreatingAsset,
 		AssetAlreadyExists,
 		AssetDoesNotExist,
+		InvalidBounds,
+		LocalAssetLimitReached,
+		LocalAssetIdsTaken,
 	}
 
 	#[pallet::event]
@@ -111,6 +194,8 @@ pub mod pallet {
 	pub enum Event<T: Config> {
 		AssetRegistered(T::AssetId, T::AssetType, T::AssetRegistrarMetadata),
 		UnitsPerSecondChanged(T::AssetId, u128),
+		RelativePriceChanged(T::AssetId, RelativePrice<T::BlockNumber>),
+		LocalAssetCreated(T::AssetId, T::AccountId),
 	}
 
 	/// Stores the asset TYPE
@@ -124,6 +209,24 @@ pub mod pallet {
 	#[pallet::getter(fn asset_id_units_per_second)]
 	pub type AssetIdUnitsPerSecond<T: Config> = StorageMap<_, Blake2_128Concat, T::AssetId, u128>;
 
+	// Stores the price relative to the native token, for the assets whose units per second are
+	// derived from the native fee rather than set
+	#[pallet::storage]
+	#[pallet::getter(fn asset_id_relative_price)]
+	pub type AssetIdRelativePrice<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, RelativePrice<T::BlockNumber>>;
+
+	/// Stores the counter from which the next local asset id is derived
+	#[pallet::storage]
+	#[pallet::getter(fn local_asset_counter)]
+	pub type LocalAssetCounter<T: Config> = StorageValue<_, u128, ValueQuery>;
+
+	/// Stores the creator of each local asset
+	#[pallet::storage]
+	#[pallet::getter(fn local_asset_creator)]
+	pub type LocalAssetCreator<T: Config> =
+		StorageMap<_, Blake2_128Concat, T::AssetId, T::AccountId>;
+
 	#[pallet::call]
 	impl<T: Config> Pallet<T> {
 		/// Register new asset with the asset manager
@@ -138,7 +241,8 @@ pub mod pallet {
 
 			let asset_id: T::AssetId = asset.clone().into();
 			ensure!(
-				AssetIdType::<T>::get(&asset_id).is_none(),
+				AssetIdType::<T>::get(&asset_id).is_none()
+					&& LocalAssetCreator::<T>::get(&asset_id).is_none(),
 				Error::<T>::AssetAlreadyExists
 			);
 			T::AssetRegistrar::create_asset(asset_id, min_amount, metadata.clone())
@@ -165,10 +269,95 @@ pub mod pallet {
 			);
 
 			AssetIdUnitsPerSecond::<T>::insert(&asset_id, &units_per_second);
+			AssetIdRelativePrice::<T>::remove(&asset_id);
 
 			Self::deposit_event(Event::UnitsPerSecondChanged(asset_id, units_per_second));
 			Ok(())
 		}
+
+		/// Price a given AssetId relative to the native token, instead of by a fixed amount of
+		/// units per second. `price` is the amount of asset units worth one native unit, and the
+		/// resulting units per second are kept within the given bounds. With `valid_for`, the price
+		/// is only used for that many blocks.
+		#[pallet::weight(0)]
+		pub fn set_asset_relative_price(
+			origin: OriginFor<T>,
+			asset_id: T::AssetId,
+			price: FixedU128,
+			min_units_per_second: Option<u128>,
+			max_units_per_second: Option<u128>,
+			valid_for: Option<T::BlockNumber>,
+		) -> DispatchResult {
+			T::AssetModifierOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				AssetIdType::<T>::get(&asset_id).is_some(),
+				Error::<T>::AssetDoesNotExist
+			);
+			if let (Some(min), Some(max)) = (min_units_per_second, max_units_per_second) {
+				ensure!(min <= max, Error::<T>::InvalidBounds);
+			}
+
+			let relative_price = RelativePrice {
+				price,
+				min_units_per_second,
+				max_units_per_second,
+				expires_at: valid_for.map(|valid_for| {
+					frame_system::Pallet::<T>::block_number().saturating_add(valid_for)
+				}),
+			};
+
+			AssetIdRelativePrice::<T>::insert(&asset_id, &relative_price);
+			AssetIdUnitsPerSecond::<T>::remove(&asset_id);
+
+			Self::deposit_event(Event::RelativePriceChanged(asset_id, relative_price));
+			Ok(())
+		}
+
+		/// Create a local asset owned by the caller, under the next id of the local asset counter
+		/// that no asset uses yet. The asset creation deposit is taken from the caller
+		/// At most MAX_LOCAL_ASSET_ID_ATTEMPTS ids are tried. If they are all taken, the counter
+		/// still moves past them so that the next creation tries the following ones
+		#[pallet::weight({
+			// Each attempt reads whether the id is taken by an XCM or a local asset. Creating
+			// the asset reads and writes the asset and the deposit of the creator
+			let attempts = MAX_LOCAL_ASSET_ID_ATTEMPTS as Weight;
+			T::DbWeight::get().reads_writes(3 + 2 * attempts, 4)
+		})]
+		pub fn create_local_asset(origin: OriginFor<T>, min_balance: T::Balance) -> DispatchResult {
+			let creator = ensure_signed(origin)?;
+
+			let mut counter = LocalAssetCounter::<T>::get();
+			let mut free_asset_id = None;
+			for _ in 0..MAX_LOCAL_ASSET_ID_ATTEMPTS {
+				let asset_id = T::LocalAssetIdCreator::create_asset_id_from_counter(counter);
+				counter = counter
+					.checked_add(1)
+					.ok_or(Error::<T>::LocalAssetLimitReached)?;
+				// Skip the ids already taken by XCM assets
+				if AssetIdType::<T>::get(&asset_id).is_none()
+					&& LocalAssetCreator::<T>::get(&asset_id).is_none()
+				{
+					free_asset_id = Some(asset_id);
+					break;
+				}
+			}
+			let asset_id = match free_asset_id {
+				Some(asset_id) => asset_id,
+				None => {
+					LocalAssetCounter::<T>::put(counter);
+					return Err(Error::<T>::LocalAssetIdsTaken.into());
+				}
+			};
+
+			T::AssetRegistrar::create_local_asset(asset_id, creator.clone(), min_balance)?;
+
+			LocalAssetCounter::<T>::put(counter);
+			LocalAssetCreator::<T>::insert(&asset_id, &creator);
+
+			Self::deposit_event(Event::LocalAssetCreated(asset_id, creator));
+			Ok(())
+		}
 	}
 
 	impl<T: Config> Pallet<T> {
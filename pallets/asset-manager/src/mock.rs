@@ -123,6 +123,26 @@ impl AssetRegistrar<Test> for MockAssetPalletRegistrar {
 	fn create_asset(_asset: u32, _min_balance: u64, _metadata: u32) -> Result<(), DispatchError> {
 		Ok(())
 	}
+
+	fn create_local_asset(
+		_asset: u32,
+		_creator: u64,
+		_min_balance: u64,
+	) -> Result<(), DispatchError> {
+		Ok(())
+	}
+}
+
+pub struct MockLocalAssetIdCreator;
+
+impl LocalAssetIdCreator<Test> for MockLocalAssetIdCreator {
+	fn create_asset_id_from_counter(counter: u128) -> AssetId {
+		counter as AssetId
+	}
+}
+
+parameter_types! {
+	pub const NativeFeePerSecond: u128 = 1_000;
 }
 
 impl Config for Test {
@@ -133,6 +153,8 @@ impl Config for Test {
 	type AssetType = MockAssetType;
 	type AssetRegistrar = MockAssetPalletRegistrar;
 	type AssetModifierOrigin = EnsureRoot<u64>;
+	type NativeFeePerSecond = NativeFeePerSecond;
+	type LocalAssetIdCreator = MockLocalAssetIdCreator;
 }
 
 pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
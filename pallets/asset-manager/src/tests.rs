@@ -19,6 +19,8 @@ use crate::*;
 use mock::*;
 
 use frame_support::{assert_noop, assert_ok};
+use sp_runtime::{FixedPointNumber, FixedU128};
+use xcm_primitives::UnitsToWeightRatio;
 
 #[test]
 fn registering_works() {
@@ -102,3 +104,236 @@ fn test_asset_id_non_existent_error() {
 		);
 	});
 }
+
+#[test]
+fn test_root_can_set_relative_price() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			Origin::root(),
+			MockAssetType::MockAsset(1),
+			0u32.into(),
+			1u32.into(),
+		));
+
+		assert_ok!(AssetManager::set_asset_relative_price(
+			Origin::root(),
+			1,
+			FixedU128::saturating_from_rational(3, 2),
+			None,
+			None,
+			None
+		));
+
+		// 1.5 asset units per native unit, at 1_000 native units per second
+		assert_eq!(AssetManager::get_units_per_second(1), Some(1_500));
+
+		expect_events(vec![
+			crate::Event::AssetRegistered(1, MockAssetType::MockAsset(1), 0),
+			crate::Event::RelativePriceChanged(
+				1,
+				RelativePrice {
+					price: FixedU128::saturating_from_rational(3, 2),
+					min_units_per_second: None,
+					max_units_per_second: None,
+					expires_at: None,
+				},
+			),
+		])
+	});
+}
+
+#[test]
+fn test_relative_price_is_bounded() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			Origin::root(),
+			MockAssetType::MockAsset(1),
+			0u32.into(),
+			1u32.into(),
+		));
+
+		assert_ok!(AssetManager::set_asset_relative_price(
+			Origin::root(),
+			1,
+			FixedU128::saturating_from_integer(2),
+			None,
+			Some(1_200),
+			None
+		));
+		assert_eq!(AssetManager::get_units_per_second(1), Some(1_200));
+
+		assert_ok!(AssetManager::set_asset_relative_price(
+			Origin::root(),
+			1,
+			FixedU128::saturating_from_rational(1, 2),
+			Some(800),
+			None,
+			None
+		));
+		assert_eq!(AssetManager::get_units_per_second(1), Some(800));
+
+		assert_noop!(
+			AssetManager::set_asset_relative_price(
+				Origin::root(),
+				1,
+				FixedU128::saturating_from_integer(1),
+				Some(800),
+				Some(700),
+				None
+			),
+			Error::<Test>::InvalidBounds
+		);
+	});
+}
+
+#[test]
+fn test_relative_price_expires() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			Origin::root(),
+			MockAssetType::MockAsset(1),
+			0u32.into(),
+			1u32.into(),
+		));
+
+		assert_ok!(AssetManager::set_asset_relative_price(
+			Origin::root(),
+			1,
+			FixedU128::saturating_from_integer(1),
+			None,
+			None,
+			Some(10)
+		));
+
+		System::set_block_number(10);
+		assert_eq!(AssetManager::get_units_per_second(1), Some(1_000));
+
+		System::set_block_number(11);
+		assert_eq!(AssetManager::get_units_per_second(1), None);
+	});
+}
+
+#[test]
+fn test_pricing_modes_are_exclusive() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			Origin::root(),
+			MockAssetType::MockAsset(1),
+			0u32.into(),
+			1u32.into(),
+		));
+
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			Origin::root(),
+			1,
+			200u128.into()
+		));
+		assert_ok!(AssetManager::set_asset_relative_price(
+			Origin::root(),
+			1,
+			FixedU128::saturating_from_integer(2),
+			None,
+			None,
+			None
+		));
+
+		assert!(AssetManager::asset_id_units_per_second(1).is_none());
+		assert_eq!(AssetManager::get_units_per_second(1), Some(2_000));
+
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			Origin::root(),
+			1,
+			200u128.into()
+		));
+
+		assert!(AssetManager::asset_id_relative_price(1).is_none());
+		assert_eq!(AssetManager::get_units_per_second(1), Some(200));
+	});
+}
+
+#[test]
+fn test_signed_can_create_local_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetManager::create_local_asset(Origin::signed(1), 1u64));
+		assert_ok!(AssetManager::create_local_asset(Origin::signed(2), 1u64));
+
+		assert_eq!(AssetManager::local_asset_creator(0), Some(1));
+		assert_eq!(AssetManager::local_asset_creator(1), Some(2));
+		assert_eq!(AssetManager::local_asset_counter(), 2);
+
+		expect_events(vec![
+			crate::Event::LocalAssetCreated(0, 1),
+			crate::Event::LocalAssetCreated(1, 2),
+		])
+	});
+}
+
+#[test]
+fn test_local_asset_ids_skip_registered_assets() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			Origin::root(),
+			MockAssetType::MockAsset(0),
+			0u32.into(),
+			1u32.into(),
+		));
+
+		assert_ok!(AssetManager::create_local_asset(Origin::signed(1), 1u64));
+
+		assert_eq!(AssetManager::local_asset_creator(0), None);
+		assert_eq!(AssetManager::local_asset_creator(1), Some(1));
+		assert_eq!(AssetManager::local_asset_counter(), 2);
+
+		// The id of the local asset can no longer be registered
+		assert_noop!(
+			AssetManager::register_asset(
+				Origin::root(),
+				MockAssetType::MockAsset(1),
+				0u32.into(),
+				1u32.into(),
+			),
+			Error::<Test>::AssetAlreadyExists
+		);
+	});
+}
+
+#[test]
+fn test_local_asset_id_search_is_bounded() {
+	new_test_ext().execute_with(|| {
+		for id in 0..crate::MAX_LOCAL_ASSET_ID_ATTEMPTS {
+			assert_ok!(AssetManager::register_asset(
+				Origin::root(),
+				MockAssetType::MockAsset(id),
+				0u32.into(),
+				1u32.into(),
+			));
+		}
+
+		// All the ids tried are taken
+		assert_eq!(
+			AssetManager::create_local_asset(Origin::signed(1), 1u64),
+			Err(Error::<Test>::LocalAssetIdsTaken.into())
+		);
+		assert_eq!(
+			AssetManager::local_asset_counter(),
+			crate::MAX_LOCAL_ASSET_ID_ATTEMPTS as u128
+		);
+
+		// The next creation carries on from there
+		assert_ok!(AssetManager::create_local_asset(Origin::signed(1), 1u64));
+		assert_eq!(
+			AssetManager::local_asset_creator(crate::MAX_LOCAL_ASSET_ID_ATTEMPTS),
+			Some(1)
+		);
+	});
+}
+
+#[test]
+fn test_root_cannot_create_local_assets() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			AssetManager::create_local_asset(Origin::root(), 1u64),
+			sp_runtime::DispatchError::BadOrigin
+		);
+	});
+}
@@ -0,0 +1,357 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Asset Trap Pallet
+//!
+//! The XCM v1 executor drops the assets left in holding at the end of a message, whether it
+//! completed or failed half-way. This pallet traps them instead, so that the origin of the
+//! message can claim them back.
+//!
+//! The executor does not expose its holding, so the pallet tracks it alongside the execution:
+//! - `TrapUnspentAssets` wraps the executor. It tracks the holding of each message it executes,
+//! and traps whatever is left in it once the message has been executed.
+//! - `TrackDeposits` wraps the asset transactor, removing the deposited assets from the holding.
+//! - `TrackFees` wraps the trader, removing the fees charged from the holding and adding back the
+//! refunds.
+//!
+//! All the executors, asset transactors and traders of the runtime must be wrapped, so that
+//! nested executions are tracked separately.
+//!
+//! Only the holdings that can be tracked exactly are trapped: the ones of `WithdrawAsset`,
+//! `ReserveAssetDeposited` and `ReceiveTeleportedAsset` messages whose effects are `Noop`,
+//! `DepositAsset`, `QueryHolding` and `BuyExecution` (with only `Transact` instructions).
+//! Non-fungible assets are not tracked. Assets are only trapped once the effects of the message
+//! started executing, i.e. once the message passed the barrier and the assets were withdrawn or
+//! accepted.
+//!
+//! The other messages are executed as before and what they leave in holding is still dropped:
+//! - `TransferAsset`, `TransferReserveAsset`, `Transact`, `RelayedFrom`, `QueryResponse` and the
+//! HRMP and version notifications, which have no holding or only a nested one.
+//! - Messages with a `DepositReserveAsset`, `ExchangeAsset`, `InitiateReserveWithdraw` or
+//! `InitiateTeleport` effect, or a `BuyExecution` effect with other instructions than `Transact`,
+//! as they move the holding without going through the tracked asset transactor and trader.
+//!
+//! Storing a trap is not weighed by the `Weigher` of the executor: its weight (one read and one
+//! write) is added to the weight of the `Outcome` after the message has been executed, so the
+//! reported weight may exceed the `weight_limit` the message was executed with.
+//!
+//! Like in `pallet_xcm` from XCM v2 onwards, the traps are stored in `AssetTraps` by the hash of
+//! the origin and the trapped assets. They are claimed with `claim_assets`, from an origin
+//! converted to the location the assets were trapped for.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{pallet, traits::Get, weights::Weight};
+use sp_std::{collections::btree_map::BTreeMap, marker::PhantomData, vec::Vec};
+use xcm::v1::{
+	AssetId, Error as XcmError, ExecuteXcm, Fungibility, MultiAsset, MultiAssets, MultiLocation,
+	Order, Outcome, Result as XcmResult, Xcm,
+};
+use xcm_executor::{
+	traits::{TransactAsset, WeightTrader},
+	Assets,
+};
+
+pub use pallet::*;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+#[pallet]
+pub mod pallet {
+	use frame_support::{pallet_prelude::*, transactional};
+	use frame_system::pallet_prelude::*;
+	use sp_core::H256;
+	use sp_runtime::traits::{BlakeTwo256, Hash};
+	use sp_std::boxed::Box;
+	use xcm::v1::{MultiAssets, MultiLocation};
+	use xcm_executor::traits::TransactAsset;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Overarching event type
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// The origin allowed to claim trapped assets, converted to the location they were
+		/// trapped for
+		type ClaimOrigin: EnsureOrigin<Self::Origin, Success = MultiLocation>;
+		/// How to deposit the claimed assets
+		type AssetTransactor: TransactAsset;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event {
+		/// Assets were left in holding by a message and trapped. [hash, origin, assets]
+		AssetsTrapped(H256, MultiLocation, MultiAssets),
+		/// Trapped assets were claimed. [hash, origin, assets]
+		AssetsClaimed(H256, MultiLocation, MultiAssets),
+	}
+
+	/// An error that can occur while executing this pallet's extrinsics.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// No such assets were trapped for the origin
+		UnknownClaim,
+		/// The claimed assets could not be deposited to the beneficiary
+		UnableToDeposit,
+	}
+
+	/// The number of times each set of assets was trapped, by hash of the origin and the assets
+	#[pallet::storage]
+	#[pallet::getter(fn asset_traps)]
+	pub type AssetTraps<T: Config> = StorageMap<_, Identity, H256, u32, ValueQuery>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Claim assets trapped for the origin, depositing them to the beneficiary
+		#[pallet::weight({
+			// Depositing each asset reads and writes the asset and the beneficiary account
+			let deposits = assets.inner().len() as Weight;
+			T::DbWeight::get().reads_writes(1 + 2 * deposits, 1 + 2 * deposits)
+		})]
+		#[transactional]
+		pub fn claim_assets(
+			origin: OriginFor<T>,
+			assets: Box<MultiAssets>,
+			beneficiary: Box<MultiLocation>,
+		) -> DispatchResult {
+			let location = T::ClaimOrigin::ensure_origin(origin)?;
+
+			let hash = Self::trap_hash(&location, &assets);
+			AssetTraps::<T>::try_mutate_exists(hash, |count| -> DispatchResult {
+				let remaining = count
+					.unwrap_or_default()
+					.checked_sub(1)
+					.ok_or(Error::<T>::UnknownClaim)?;
+				*count = Some(remaining).filter(|remaining| *remaining > 0);
+				Ok(())
+			})?;
+
+			for asset in assets.inner() {
+				T::AssetTransactor::deposit_asset(asset, &beneficiary)
+					.map_err(|_| Error::<T>::UnableToDeposit)?;
+			}
+
+			Self::deposit_event(Event::AssetsClaimed(hash, location, *assets));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// The key under which the assets trapped for an origin are stored
+		pub fn trap_hash(origin: &MultiLocation, assets: &MultiAssets) -> H256 {
+			BlakeTwo256::hash_of(&(origin, assets))
+		}
+
+		/// Trap the assets left in holding by a message from `origin`
+		pub(crate) fn trap(origin: MultiLocation, assets: MultiAssets) {
+			let hash = Self::trap_hash(&origin, &assets);
+			AssetTraps::<T>::mutate(hash, |count| *count = count.saturating_add(1));
+
+			Self::deposit_event(Event::AssetsTrapped(hash, origin, assets));
+		}
+	}
+}
+
+/// The holding of the message being executed, as far as it is tracked
+#[derive(Default)]
+struct Holding {
+	/// The fungible assets held, if the holding of the message can be tracked
+	assets: Option<BTreeMap<AssetId, u128>>,
+	/// Whether the effects of the message started executing
+	started: bool,
+}
+
+environmental::environmental!(holding: Holding);
+
+impl Holding {
+	fn new<Call>(message: &Xcm<Call>) -> Self {
+		let tracked_assets = match message {
+			Xcm::WithdrawAsset { assets, effects }
+			| Xcm::ReserveAssetDeposited { assets, effects }
+			| Xcm::ReceiveTeleportedAsset { assets, effects }
+				if effects.iter().all(Self::is_tracked_effect) =>
+			{
+				Some(assets)
+			}
+			_ => None,
+		};
+
+		let mut holding = Holding::default();
+		if let Some(assets) = tracked_assets {
+			holding.assets = Some(BTreeMap::new());
+			for asset in assets.inner() {
+				holding.add(asset);
+			}
+		}
+		holding
+	}
+
+	/// Whether the effect changes the holding only through the tracked asset transactor and
+	/// trader. The instructions of `BuyExecution` share the trader with the message, so only the
+	/// ones not buying weight are allowed.
+	fn is_tracked_effect<Call>(effect: &Order<Call>) -> bool {
+		match effect {
+			Order::Noop | Order::DepositAsset { .. } | Order::QueryHolding { .. } => true,
+			Order::BuyExecution { instructions, .. } => instructions
+				.iter()
+				.all(|instruction| matches!(instruction, Xcm::Transact { .. })),
+			_ => false,
+		}
+	}
+
+	fn add(&mut self, asset: &MultiAsset) {
+		if let (Some(assets), Fungibility::Fungible(amount)) = (&mut self.assets, &asset.fun) {
+			let held = assets.entry(asset.id.clone()).or_insert(0);
+			*held = held.saturating_add(*amount);
+		}
+	}
+
+	fn remove(&mut self, id: &AssetId, amount: u128) {
+		if let Some(held) = self.assets.as_mut().and_then(|assets| assets.get_mut(id)) {
+			*held = held.saturating_sub(amount);
+		}
+	}
+
+	/// The assets left, if any
+	fn unspent(self) -> Option<MultiAssets> {
+		let unspent: Vec<MultiAsset> = self
+			.assets?
+			.into_iter()
+			.filter(|(_, amount)| *amount > 0)
+			.map(|(id, amount)| MultiAsset {
+				id,
+				fun: Fungibility::Fungible(amount),
+			})
+			.collect();
+
+		if unspent.is_empty() {
+			None
+		} else {
+			Some(unspent.into())
+		}
+	}
+}
+
+/// Executes the messages with `Inner`, trapping the assets they leave in holding. The weight of
+/// the trap is added to the weight of the outcome, outside of `weight_limit`.
+pub struct TrapUnspentAssets<T, Inner>(PhantomData<(T, Inner)>);
+impl<T: Config, Call, Inner: ExecuteXcm<Call>> ExecuteXcm<Call> for TrapUnspentAssets<T, Inner> {
+	fn execute_xcm_in_credit(
+		origin: MultiLocation,
+		message: Xcm<Call>,
+		weight_limit: Weight,
+		weight_credit: Weight,
+	) -> Outcome {
+		let mut tracked = Holding::new(&message);
+		let outcome = holding::using(&mut tracked, || {
+			Inner::execute_xcm_in_credit(origin.clone(), message, weight_limit, weight_credit)
+		});
+
+		if !tracked.started {
+			return outcome;
+		}
+		let unspent = match tracked.unspent() {
+			Some(unspent) => unspent,
+			None => return outcome,
+		};
+
+		Pallet::<T>::trap(origin, unspent);
+
+		let trap_weight = T::DbWeight::get().reads_writes(1, 1);
+		match outcome {
+			Outcome::Complete(weight) => Outcome::Complete(weight.saturating_add(trap_weight)),
+			Outcome::Incomplete(weight, error) => {
+				Outcome::Incomplete(weight.saturating_add(trap_weight), error)
+			}
+			Outcome::Error(error) => Outcome::Error(error),
+		}
+	}
+}
+
+/// Asset transactor removing the assets deposited by the message from its holding
+pub struct TrackDeposits<Inner>(PhantomData<Inner>);
+impl<Inner: TransactAsset> TransactAsset for TrackDeposits<Inner> {
+	fn can_check_in(origin: &MultiLocation, what: &MultiAsset) -> XcmResult {
+		Inner::can_check_in(origin, what)
+	}
+
+	fn check_in(origin: &MultiLocation, what: &MultiAsset) {
+		Inner::check_in(origin, what)
+	}
+
+	fn check_out(dest: &MultiLocation, what: &MultiAsset) {
+		Inner::check_out(dest, what)
+	}
+
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> XcmResult {
+		holding::with(|holding| holding.started = true);
+
+		Inner::deposit_asset(what, who)?;
+
+		if let Fungibility::Fungible(amount) = what.fun {
+			holding::with(|holding| holding.remove(&what.id, amount));
+		}
+		Ok(())
+	}
+
+	fn withdraw_asset(what: &MultiAsset, who: &MultiLocation) -> Result<Assets, XcmError> {
+		Inner::withdraw_asset(what, who)
+	}
+
+	fn transfer_asset(
+		asset: &MultiAsset,
+		from: &MultiLocation,
+		to: &MultiLocation,
+	) -> Result<Assets, XcmError> {
+		Inner::transfer_asset(asset, from, to)
+	}
+}
+
+/// Trader removing the fees it charges from the holding of the message, and adding back the
+/// refunds
+pub struct TrackFees<Inner>(Inner);
+impl<Inner: WeightTrader> WeightTrader for TrackFees<Inner> {
+	fn new() -> Self {
+		TrackFees(Inner::new())
+	}
+
+	fn buy_weight(&mut self, weight: Weight, payment: Assets) -> Result<Assets, XcmError> {
+		holding::with(|holding| holding.started = true);
+
+		let paid = payment.fungible.clone();
+		let unused = self.0.buy_weight(weight, payment)?;
+
+		holding::with(|holding| {
+			for (id, amount) in paid.iter() {
+				let left = unused.fungible.get(id).copied().unwrap_or_default();
+				holding.remove(id, amount.saturating_sub(left));
+			}
+		});
+		Ok(unused)
+	}
+
+	fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
+		let refund = self.0.refund_weight(weight)?;
+		holding::with(|holding| holding.add(&refund));
+		Some(refund)
+	}
+}
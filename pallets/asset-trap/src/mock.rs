@@ -0,0 +1,244 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A minimal runtime including the asset trap pallet
+use super::*;
+use crate as pallet_asset_trap;
+use frame_support::{
+	construct_runtime, parameter_types,
+	traits::{EnsureOrigin, Everything},
+};
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, IdentityLookup},
+};
+use sp_std::cell::RefCell;
+use xcm::v1::{Junction, Junctions::X1, MultiAssetFilter, NetworkId};
+
+pub type AccountId = u64;
+pub type BlockNumber = u64;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the pallet.
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		AssetTrap: pallet_asset_trap::{Pallet, Call, Storage, Event},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = ();
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+/// The location of a local account
+pub(crate) fn account_location(who: AccountId) -> MultiLocation {
+	MultiLocation::new(
+		0,
+		X1(Junction::AccountIndex64 {
+			network: NetworkId::Any,
+			index: who,
+		}),
+	)
+}
+
+/// Signed origins claim the assets trapped for the location of their account
+pub struct EnsureSignedLocation;
+impl EnsureOrigin<Origin> for EnsureSignedLocation {
+	type Success = MultiLocation;
+
+	fn try_origin(o: Origin) -> Result<MultiLocation, Origin> {
+		Into::<Result<frame_system::RawOrigin<AccountId>, Origin>>::into(o).and_then(|o| match o {
+			frame_system::RawOrigin::Signed(who) => Ok(account_location(who)),
+			o => Err(Origin::from(o)),
+		})
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn successful_origin() -> Origin {
+		Origin::signed(0)
+	}
+}
+
+thread_local! {
+	pub static DEPOSITED: RefCell<Vec<(MultiAsset, MultiLocation)>> = RefCell::new(Vec::new());
+}
+
+/// Deposits to this location fail
+pub(crate) fn failing_location() -> MultiLocation {
+	MultiLocation::parent()
+}
+
+/// Asset transactor storing the deposits, which only fail for `failing_location`
+pub struct MockTransactor;
+impl TransactAsset for MockTransactor {
+	fn deposit_asset(what: &MultiAsset, who: &MultiLocation) -> XcmResult {
+		if *who == failing_location() {
+			return Err(XcmError::Unimplemented);
+		}
+		DEPOSITED.with(|q| q.borrow_mut().push((what.clone(), who.clone())));
+		Ok(())
+	}
+}
+
+pub(crate) fn deposited() -> Vec<(MultiAsset, MultiLocation)> {
+	DEPOSITED.with(|q| (*q.borrow()).clone())
+}
+
+/// Trader charging one unit of the first asset paid with per weight, and refunding in the
+/// same asset
+pub struct MockTrader(Option<AssetId>);
+impl WeightTrader for MockTrader {
+	fn new() -> Self {
+		MockTrader(None)
+	}
+
+	fn buy_weight(&mut self, weight: Weight, mut payment: Assets) -> Result<Assets, XcmError> {
+		let (id, amount) = payment
+			.fungible
+			.iter_mut()
+			.next()
+			.ok_or(XcmError::TooExpensive)?;
+		*amount = amount
+			.checked_sub(weight as u128)
+			.ok_or(XcmError::TooExpensive)?;
+		self.0 = Some(id.clone());
+		Ok(payment)
+	}
+
+	fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
+		Some(MultiAsset {
+			id: self.0.clone()?,
+			fun: Fungibility::Fungible(weight as u128),
+		})
+	}
+}
+
+/// Executor following the effects of `ReserveAssetDeposited` messages through the tracked
+/// transactor and trader, without keeping a holding itself:
+/// - `BuyExecution` buys `weight + debt` with the fees and refunds the `debt`
+/// - `DepositAsset` deposits the definite assets to the beneficiary
+pub struct MockExecutor;
+impl ExecuteXcm<Call> for MockExecutor {
+	fn execute_xcm_in_credit(
+		_origin: MultiLocation,
+		message: Xcm<Call>,
+		_weight_limit: Weight,
+		_weight_credit: Weight,
+	) -> Outcome {
+		let effects = match message {
+			Xcm::ReserveAssetDeposited { effects, .. } => effects,
+			_ => return Outcome::Error(XcmError::UnhandledXcmMessage),
+		};
+
+		let mut trader = TrackFees::<MockTrader>::new();
+		for effect in effects {
+			let result = match effect {
+				Order::BuyExecution {
+					fees, weight, debt, ..
+				} => trader
+					.buy_weight(weight + debt, fees.into())
+					.map(|_| trader.refund_weight(debt))
+					.map(|_| ()),
+				Order::DepositAsset {
+					assets: MultiAssetFilter::Definite(assets),
+					beneficiary,
+					..
+				} => assets.inner().iter().try_for_each(|asset| {
+					TrackDeposits::<MockTransactor>::deposit_asset(asset, &beneficiary)
+				}),
+				_ => Ok(()),
+			};
+			if let Err(error) = result {
+				return Outcome::Incomplete(0, error);
+			}
+		}
+		Outcome::Complete(0)
+	}
+}
+
+impl Config for Test {
+	type Event = Event;
+	type ClaimOrigin = EnsureSignedLocation;
+	type AssetTransactor = MockTransactor;
+}
+
+/// Externality builder for pallet asset trap's mock runtime
+pub(crate) struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> ExtBuilder {
+		ExtBuilder
+	}
+}
+
+impl ExtBuilder {
+	pub(crate) fn build(self) -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.expect("Frame system builds valid default genesis config");
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
+
+pub(crate) fn events() -> Vec<pallet_asset_trap::Event> {
+	System::events()
+		.into_iter()
+		.map(|r| r.event)
+		.filter_map(|e| {
+			if let Event::AssetTrap(inner) = e {
+				Some(inner)
+			} else {
+				None
+			}
+		})
+		.collect::<Vec<_>>()
+}
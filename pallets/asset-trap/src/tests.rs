@@ -0,0 +1,312 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit testing
+use crate::mock::{
+	account_location, deposited, events, failing_location, AssetTrap, Call, ExtBuilder,
+	MockExecutor, Origin, Test,
+};
+use crate::{AssetTraps, Error, Event, TrapUnspentAssets};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::DispatchError;
+use xcm::v1::{
+	AssetId::Concrete, Error as XcmError, ExecuteXcm, Fungibility::Fungible, Junction::Parachain,
+	Junctions::X1, MultiAsset, MultiAssetFilter, MultiAssets, MultiLocation, Order, Outcome, Xcm,
+};
+
+fn asset(para_id: u32, amount: u128) -> MultiAsset {
+	MultiAsset {
+		id: Concrete(MultiLocation::new(1, X1(Parachain(para_id)))),
+		fun: Fungible(amount),
+	}
+}
+
+fn buy_execution(fees: MultiAsset, weight: u64, debt: u64) -> Order<Call> {
+	Order::BuyExecution {
+		fees,
+		weight,
+		debt,
+		halt_on_error: false,
+		instructions: vec![],
+	}
+}
+
+fn deposit(assets: Vec<MultiAsset>, beneficiary: MultiLocation) -> Order<Call> {
+	Order::DepositAsset {
+		assets: MultiAssetFilter::Definite(assets.into()),
+		max_assets: 1,
+		beneficiary,
+	}
+}
+
+fn execute(assets: Vec<MultiAsset>, effects: Vec<Order<Call>>) -> Outcome {
+	TrapUnspentAssets::<Test, MockExecutor>::execute_xcm_in_credit(
+		account_location(1),
+		Xcm::ReserveAssetDeposited {
+			assets: assets.into(),
+			effects,
+		},
+		1_000,
+		0,
+	)
+}
+
+#[test]
+fn assets_left_in_holding_are_trapped() {
+	ExtBuilder::default().build().execute_with(|| {
+		let outcome = execute(
+			vec![asset(1, 100), asset(2, 50)],
+			vec![
+				buy_execution(asset(1, 100), 10, 5),
+				deposit(vec![asset(1, 80)], account_location(2)),
+			],
+		);
+
+		assert_eq!(outcome, Outcome::Complete(0));
+		assert_eq!(deposited(), vec![(asset(1, 80), account_location(2))]);
+
+		// 15 were charged, 5 of which were refunded
+		let trapped: MultiAssets = vec![asset(1, 10), asset(2, 50)].into();
+		let hash = AssetTrap::trap_hash(&account_location(1), &trapped);
+		assert_eq!(AssetTrap::asset_traps(hash), 1);
+		assert_eq!(
+			events(),
+			vec![Event::AssetsTrapped(hash, account_location(1), trapped)]
+		);
+	})
+}
+
+#[test]
+fn assets_are_trapped_when_buying_execution_fails() {
+	ExtBuilder::default().build().execute_with(|| {
+		let outcome = execute(
+			vec![asset(1, 100)],
+			vec![
+				buy_execution(asset(1, 100), 200, 0),
+				deposit(vec![asset(1, 100)], account_location(2)),
+			],
+		);
+
+		assert_eq!(outcome, Outcome::Incomplete(0, XcmError::TooExpensive));
+		assert!(deposited().is_empty());
+
+		let trapped: MultiAssets = vec![asset(1, 100)].into();
+		let hash = AssetTrap::trap_hash(&account_location(1), &trapped);
+		assert_eq!(AssetTrap::asset_traps(hash), 1);
+	})
+}
+
+#[test]
+fn assets_are_trapped_when_a_deposit_fails() {
+	ExtBuilder::default().build().execute_with(|| {
+		let outcome = execute(
+			vec![asset(1, 100), asset(2, 50)],
+			vec![
+				deposit(vec![asset(2, 50)], account_location(2)),
+				deposit(vec![asset(1, 100)], failing_location()),
+			],
+		);
+
+		assert_eq!(outcome, Outcome::Incomplete(0, XcmError::Unimplemented));
+		assert_eq!(deposited(), vec![(asset(2, 50), account_location(2))]);
+
+		let trapped: MultiAssets = vec![asset(1, 100)].into();
+		let hash = AssetTrap::trap_hash(&account_location(1), &trapped);
+		assert_eq!(AssetTrap::asset_traps(hash), 1);
+	})
+}
+
+#[test]
+fn the_same_assets_can_be_trapped_several_times() {
+	ExtBuilder::default().build().execute_with(|| {
+		for _ in 0..2 {
+			execute(
+				vec![asset(1, 100)],
+				vec![deposit(vec![asset(1, 100)], failing_location())],
+			);
+		}
+
+		let trapped: MultiAssets = vec![asset(1, 100)].into();
+		let hash = AssetTrap::trap_hash(&account_location(1), &trapped);
+		assert_eq!(AssetTrap::asset_traps(hash), 2);
+	})
+}
+
+#[test]
+fn nothing_is_trapped_when_the_holding_is_spent() {
+	ExtBuilder::default().build().execute_with(|| {
+		let outcome = execute(
+			vec![asset(1, 100)],
+			vec![
+				buy_execution(asset(1, 100), 10, 0),
+				deposit(vec![asset(1, 90)], account_location(2)),
+			],
+		);
+
+		assert_eq!(outcome, Outcome::Complete(0));
+		assert_eq!(AssetTraps::<Test>::iter().count(), 0);
+		assert!(events().is_empty());
+	})
+}
+
+#[test]
+fn nothing_is_trapped_before_the_effects_start() {
+	ExtBuilder::default().build().execute_with(|| {
+		let outcome = execute(vec![asset(1, 100)], vec![]);
+
+		assert_eq!(outcome, Outcome::Complete(0));
+		assert_eq!(AssetTraps::<Test>::iter().count(), 0);
+	})
+}
+
+#[test]
+fn untracked_messages_are_not_trapped() {
+	ExtBuilder::default().build().execute_with(|| {
+		execute(
+			vec![asset(1, 100)],
+			vec![
+				deposit(vec![asset(1, 50)], account_location(2)),
+				Order::InitiateReserveWithdraw {
+					assets: MultiAssetFilter::Definite(vec![asset(1, 50)].into()),
+					reserve: MultiLocation::parent(),
+					effects: vec![],
+				},
+			],
+		);
+		execute(
+			vec![asset(1, 100)],
+			vec![Order::BuyExecution {
+				fees: asset(1, 100),
+				weight: 10,
+				debt: 0,
+				halt_on_error: false,
+				instructions: vec![Xcm::WithdrawAsset {
+					assets: vec![asset(1, 90)].into(),
+					effects: vec![],
+				}],
+			}],
+		);
+
+		assert_eq!(AssetTraps::<Test>::iter().count(), 0);
+		assert!(events().is_empty());
+	})
+}
+
+#[test]
+fn claiming_requires_the_claim_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			AssetTrap::claim_assets(
+				Origin::root(),
+				Box::new(vec![asset(1, 100)].into()),
+				Box::new(account_location(2))
+			),
+			DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn claiming_unknown_assets_fails() {
+	ExtBuilder::default().build().execute_with(|| {
+		AssetTrap::trap(account_location(1), vec![asset(1, 100)].into());
+
+		// Not trapped for this origin
+		assert_noop!(
+			AssetTrap::claim_assets(
+				Origin::signed(2),
+				Box::new(vec![asset(1, 100)].into()),
+				Box::new(account_location(2))
+			),
+			Error::<Test>::UnknownClaim
+		);
+		// Not these assets
+		assert_noop!(
+			AssetTrap::claim_assets(
+				Origin::signed(1),
+				Box::new(vec![asset(1, 99)].into()),
+				Box::new(account_location(2))
+			),
+			Error::<Test>::UnknownClaim
+		);
+	})
+}
+
+#[test]
+fn claiming_deposits_the_trapped_assets() {
+	ExtBuilder::default().build().execute_with(|| {
+		let trapped: MultiAssets = vec![asset(1, 100), asset(2, 50)].into();
+		let hash = AssetTrap::trap_hash(&account_location(1), &trapped);
+		AssetTrap::trap(account_location(1), trapped.clone());
+		AssetTrap::trap(account_location(1), trapped.clone());
+
+		assert_ok!(AssetTrap::claim_assets(
+			Origin::signed(1),
+			Box::new(trapped.clone()),
+			Box::new(account_location(2))
+		));
+
+		assert_eq!(
+			deposited(),
+			vec![
+				(asset(1, 100), account_location(2)),
+				(asset(2, 50), account_location(2))
+			]
+		);
+		assert_eq!(AssetTrap::asset_traps(hash), 1);
+		assert_eq!(
+			events().last(),
+			Some(&Event::AssetsClaimed(
+				hash,
+				account_location(1),
+				trapped.clone()
+			))
+		);
+
+		assert_ok!(AssetTrap::claim_assets(
+			Origin::signed(1),
+			Box::new(trapped.clone()),
+			Box::new(account_location(2))
+		));
+		assert!(!AssetTraps::<Test>::contains_key(hash));
+
+		assert_noop!(
+			AssetTrap::claim_assets(
+				Origin::signed(1),
+				Box::new(trapped),
+				Box::new(account_location(2))
+			),
+			Error::<Test>::UnknownClaim
+		);
+	})
+}
+
+#[test]
+fn claims_failing_to_deposit_are_reverted() {
+	ExtBuilder::default().build().execute_with(|| {
+		let trapped: MultiAssets = vec![asset(1, 100)].into();
+		AssetTrap::trap(account_location(1), trapped.clone());
+
+		assert_noop!(
+			AssetTrap::claim_assets(
+				Origin::signed(1),
+				Box::new(trapped),
+				Box::new(failing_location())
+			),
+			Error::<Test>::UnableToDeposit
+		);
+	})
+}
@@ -0,0 +1,35 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API to inspect the rules of the xcm-barrier pallet
+
+#![cfg_attr(not(feature = "std"), no_std)]
+// These clippy lints are disabled because the macro-generated code triggers them.
+#![allow(clippy::unnecessary_mut_passed)]
+#![allow(clippy::too_many_arguments)]
+
+pub use pallet_xcm_barrier::BarrierRule;
+use sp_std::vec::Vec;
+use xcm::v1::MultiLocation;
+
+sp_api::decl_runtime_apis! {
+	pub trait XcmBarrierApi {
+		/// The origins allowed by a rule, along with whether their descendants are allowed too
+		fn allowed_origins(rule: BarrierRule) -> Vec<(MultiLocation, bool)>;
+		/// Whether an origin is allowed by a rule
+		fn is_allowed(rule: BarrierRule, origin: MultiLocation) -> bool;
+	}
+}
@@ -0,0 +1,277 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Xcm Barrier Pallet
+//!
+//! An XCM barrier whose rules are maintained on-chain. For each kind of execution (paid, unpaid or
+//! version subscriptions) governance maintains a list of the origins allowed to do it. Each entry
+//! can optionally allow the descendants of the location too, e.g. allowing the parent and its
+//! descendants allows the relay chain and every sibling parachain.
+//!
+//! The pallet implements `ShouldExecute` and is meant to be used as the last element of the
+//! runtime barrier tuple. It emits an event when it rejects a message, at most once per origin per
+//! block and for at most `MaxRejectionEvents` origins per block, as anyone able to send messages
+//! could otherwise fill the block events. The other rejections are only logged.
+//!
+//! The rules in `InitialRules` are inserted at genesis, or in the first runtime upgrade including
+//! this pallet, so that adding the pallet does not cut the chain from XCM.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use frame_support::pallet;
+
+pub use pallet::*;
+
+#[pallet]
+pub mod pallet {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+	use sp_std::{boxed::Box, vec::Vec};
+	use xcm::v1::{MultiLocation, Order, Xcm};
+	use xcm_executor::traits::ShouldExecute;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	/// The kinds of execution the barrier can allow
+	#[derive(Clone, Copy, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+	pub enum BarrierRule {
+		/// Top level messages paying for their execution
+		PaidExecution,
+		/// Top level messages of any kind, without paying for their execution
+		UnpaidExecution,
+		/// Top level version subscription messages
+		VersionSubscription,
+	}
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// Overarching event type
+		type Event: From<Event> + IsType<<Self as frame_system::Config>::Event>;
+		/// The origin allowed to change the rules of the barrier
+		type BarrierOrigin: EnsureOrigin<Self::Origin>;
+		/// The rules the barrier starts with, as (rule, origin, whether descendants are allowed)
+		type InitialRules: Get<Vec<(BarrierRule, MultiLocation, bool)>>;
+		/// The maximum number of origins whose rejection is reported by an event in a block
+		type MaxRejectionEvents: Get<u32>;
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event {
+		/// An origin was allowed by a rule. [rule, origin, descendants allowed]
+		OriginAllowed(BarrierRule, MultiLocation, bool),
+		/// An origin is no longer allowed by a rule. [rule, origin]
+		OriginDisallowed(BarrierRule, MultiLocation),
+		/// A message was rejected by the barrier. Only the first rejection of an origin in a block
+		/// is reported. [origin]
+		ExecutionRejected(MultiLocation),
+	}
+
+	/// An error that can occur while executing this pallet's extrinsics.
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The origin is not in the list of the rule
+		OriginNotAllowed,
+	}
+
+	/// The origins allowed by each rule, along with whether their descendants are allowed too
+	#[pallet::storage]
+	#[pallet::getter(fn allowed_origin)]
+	pub type AllowedOrigins<T: Config> = StorageDoubleMap<
+		_,
+		Twox64Concat,
+		BarrierRule,
+		Blake2_128Concat,
+		MultiLocation,
+		bool,
+		OptionQuery,
+	>;
+
+	/// Whether the initial rules were already inserted
+	#[pallet::storage]
+	pub type Initialized<T: Config> = StorageValue<_, bool, ValueQuery>;
+
+	/// The block of the last reported rejection, and the origins whose rejection was reported in
+	/// that block
+	#[pallet::storage]
+	pub type ReportedRejections<T: Config> =
+		StorageValue<_, (T::BlockNumber, Vec<MultiLocation>), ValueQuery>;
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn on_runtime_upgrade() -> Weight {
+			if Initialized::<T>::get() {
+				return T::DbWeight::get().reads(1);
+			}
+			let inserted = Self::initialize();
+			T::DbWeight::get().reads_writes(1, inserted + 1)
+		}
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Allow an origin under a rule, optionally allowing its descendants too
+		/// Allowing an already allowed origin overwrites whether descendants are allowed
+		#[pallet::weight(T::DbWeight::get().writes(1))]
+		pub fn allow_origin(
+			origin: OriginFor<T>,
+			rule: BarrierRule,
+			location: Box<MultiLocation>,
+			include_descendants: bool,
+		) -> DispatchResult {
+			T::BarrierOrigin::ensure_origin(origin)?;
+
+			AllowedOrigins::<T>::insert(rule, &*location, include_descendants);
+
+			Self::deposit_event(Event::OriginAllowed(rule, *location, include_descendants));
+			Ok(())
+		}
+
+		/// Remove an origin from the list of a rule
+		#[pallet::weight(T::DbWeight::get().reads_writes(1, 1))]
+		pub fn disallow_origin(
+			origin: OriginFor<T>,
+			rule: BarrierRule,
+			location: Box<MultiLocation>,
+		) -> DispatchResult {
+			T::BarrierOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				AllowedOrigins::<T>::contains_key(rule, &*location),
+				Error::<T>::OriginNotAllowed
+			);
+			AllowedOrigins::<T>::remove(rule, &*location);
+
+			Self::deposit_event(Event::OriginDisallowed(rule, *location));
+			Ok(())
+		}
+	}
+
+	#[derive(Default)]
+	#[pallet::genesis_config]
+	/// Genesis config for the xcm barrier pallet. The rules come from `InitialRules`.
+	pub struct GenesisConfig {}
+
+	#[pallet::genesis_build]
+	impl<T: Config> GenesisBuild<T> for GenesisConfig {
+		fn build(&self) {
+			Pallet::<T>::initialize();
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		/// Insert the initial rules, returning how many were inserted
+		fn initialize() -> u64 {
+			let rules = T::InitialRules::get();
+			let inserted = rules.len() as u64;
+			for (rule, location, include_descendants) in rules {
+				AllowedOrigins::<T>::insert(rule, location, include_descendants);
+			}
+			Initialized::<T>::put(true);
+			inserted
+		}
+
+		/// Whether the origin, or one of its ancestors allowing descendants, is allowed by the rule
+		pub fn is_allowed(rule: BarrierRule, origin: &MultiLocation) -> bool {
+			if AllowedOrigins::<T>::contains_key(rule, origin) {
+				return true;
+			}
+
+			let mut ancestor = origin.clone();
+			while ancestor.interior.take_last().is_some() {
+				if AllowedOrigins::<T>::get(rule, &ancestor) == Some(true) {
+					return true;
+				}
+			}
+			false
+		}
+
+		/// The origins allowed by the rule, along with whether their descendants are allowed too
+		pub fn allowed_origins(rule: BarrierRule) -> Vec<(MultiLocation, bool)> {
+			AllowedOrigins::<T>::iter_prefix(rule).collect()
+		}
+
+		/// Emit an event for the rejection of a message from `origin`, unless it was already
+		/// reported in this block or too many rejections were reported in this block
+		fn report_rejection(origin: &MultiLocation) {
+			let now = frame_system::Pallet::<T>::block_number();
+			let (block, mut reported) = ReportedRejections::<T>::get();
+			if block != now {
+				reported.clear();
+			}
+
+			if reported.contains(origin) || reported.len() >= T::MaxRejectionEvents::get() as usize
+			{
+				return;
+			}
+
+			reported.push(origin.clone());
+			ReportedRejections::<T>::put((now, reported));
+			Self::deposit_event(Event::ExecutionRejected(origin.clone()));
+		}
+
+		/// Whether the message pays for its execution, i.e. its first effect buys enough weight
+		fn is_paid<Call>(message: &Xcm<Call>, shallow_weight: Weight) -> bool {
+			match message {
+				Xcm::ReceiveTeleportedAsset { effects, .. }
+				| Xcm::WithdrawAsset { effects, .. }
+				| Xcm::ReserveAssetDeposited { effects, .. } => matches!(
+					effects.first(),
+					Some(Order::BuyExecution { debt, .. }) if *debt >= shallow_weight
+				),
+				_ => false,
+			}
+		}
+	}
+
+	impl<T: Config> ShouldExecute for Pallet<T> {
+		fn should_execute<Call>(
+			origin: &MultiLocation,
+			top_level: bool,
+			message: &Xcm<Call>,
+			shallow_weight: Weight,
+			_weight_credit: &mut Weight,
+		) -> Result<(), ()> {
+			let allowed = top_level
+				&& (Self::is_allowed(BarrierRule::UnpaidExecution, origin)
+					|| (Self::is_paid(message, shallow_weight)
+						&& Self::is_allowed(BarrierRule::PaidExecution, origin))
+					|| (matches!(
+						message,
+						Xcm::SubscribeVersion { .. } | Xcm::UnsubscribeVersion
+					) && Self::is_allowed(BarrierRule::VersionSubscription, origin)));
+
+			if allowed {
+				Ok(())
+			} else {
+				log::debug!(
+					target: "xcm-barrier",
+					"Rejected message from {:?} (top level: {})",
+					origin,
+					top_level
+				);
+				Self::report_rejection(origin);
+				Err(())
+			}
+		}
+	}
+}
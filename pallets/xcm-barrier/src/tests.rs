@@ -0,0 +1,335 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit testing
+use crate::mock::{events, ExtBuilder, Origin, System, Test, XcmBarrier};
+use crate::{AllowedOrigins, BarrierRule, Error, Event, Initialized};
+use frame_support::{assert_noop, assert_ok, traits::OnRuntimeUpgrade};
+use sp_runtime::DispatchError;
+use xcm::v1::{
+	AssetId::Concrete,
+	Fungibility::Fungible,
+	Junction::{AccountKey20, Parachain},
+	Junctions::{X1, X2},
+	MultiAsset, MultiAssets, MultiLocation, NetworkId, Order, Xcm,
+};
+use xcm_executor::traits::ShouldExecute;
+
+fn sibling(para_id: u32) -> MultiLocation {
+	MultiLocation::new(1, X1(Parachain(para_id)))
+}
+
+fn paid_message(debt: u64) -> Xcm<()> {
+	Xcm::WithdrawAsset {
+		assets: MultiAssets::new(),
+		effects: vec![Order::BuyExecution {
+			fees: MultiAsset {
+				id: Concrete(MultiLocation::parent()),
+				fun: Fungible(1),
+			},
+			weight: 0,
+			debt,
+			halt_on_error: false,
+			instructions: vec![],
+		}],
+	}
+}
+
+fn unpaid_message() -> Xcm<()> {
+	Xcm::WithdrawAsset {
+		assets: MultiAssets::new(),
+		effects: vec![],
+	}
+}
+
+fn should_execute(origin: MultiLocation, top_level: bool, message: Xcm<()>) -> Result<(), ()> {
+	XcmBarrier::should_execute(&origin, top_level, &message, 10, &mut 0)
+}
+
+#[test]
+fn initial_rules_are_set_at_genesis() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(Initialized::<Test>::get());
+		assert_eq!(
+			XcmBarrier::allowed_origins(BarrierRule::PaidExecution),
+			vec![(MultiLocation::parent(), true)]
+		);
+		assert_eq!(
+			XcmBarrier::allowed_origins(BarrierRule::UnpaidExecution),
+			vec![(MultiLocation::parent(), false)]
+		);
+		assert!(XcmBarrier::allowed_origins(BarrierRule::VersionSubscription).is_empty());
+	})
+}
+
+#[test]
+fn initial_rules_are_set_on_first_runtime_upgrade() {
+	ExtBuilder::default().build().execute_with(|| {
+		AllowedOrigins::<Test>::remove_all(None);
+		Initialized::<Test>::kill();
+
+		XcmBarrier::on_runtime_upgrade();
+		assert_eq!(
+			XcmBarrier::allowed_origin(BarrierRule::PaidExecution, MultiLocation::parent()),
+			Some(true)
+		);
+
+		// Later upgrades do not restore removed rules
+		assert_ok!(XcmBarrier::disallow_origin(
+			Origin::root(),
+			BarrierRule::PaidExecution,
+			Box::new(MultiLocation::parent())
+		));
+		XcmBarrier::on_runtime_upgrade();
+		assert_eq!(
+			XcmBarrier::allowed_origin(BarrierRule::PaidExecution, MultiLocation::parent()),
+			None
+		);
+	})
+}
+
+#[test]
+fn allow_and_disallow_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_ok!(XcmBarrier::allow_origin(
+			Origin::root(),
+			BarrierRule::VersionSubscription,
+			Box::new(sibling(2000)),
+			false
+		));
+		assert_eq!(
+			XcmBarrier::allowed_origins(BarrierRule::VersionSubscription),
+			vec![(sibling(2000), false)]
+		);
+
+		assert_ok!(XcmBarrier::disallow_origin(
+			Origin::root(),
+			BarrierRule::VersionSubscription,
+			Box::new(sibling(2000))
+		));
+		assert!(XcmBarrier::allowed_origins(BarrierRule::VersionSubscription).is_empty());
+
+		assert_eq!(
+			events(),
+			vec![
+				Event::OriginAllowed(BarrierRule::VersionSubscription, sibling(2000), false),
+				Event::OriginDisallowed(BarrierRule::VersionSubscription, sibling(2000)),
+			]
+		);
+	})
+}
+
+#[test]
+fn cannot_change_rules_from_wrong_origin() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			XcmBarrier::allow_origin(
+				Origin::signed(1),
+				BarrierRule::UnpaidExecution,
+				Box::new(sibling(2000)),
+				true
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			XcmBarrier::disallow_origin(
+				Origin::signed(1),
+				BarrierRule::PaidExecution,
+				Box::new(MultiLocation::parent())
+			),
+			DispatchError::BadOrigin
+		);
+	})
+}
+
+#[test]
+fn cannot_disallow_origin_not_allowed() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_noop!(
+			XcmBarrier::disallow_origin(
+				Origin::root(),
+				BarrierRule::PaidExecution,
+				Box::new(sibling(2000))
+			),
+			Error::<Test>::OriginNotAllowed
+		);
+	})
+}
+
+#[test]
+fn descendants_are_allowed_only_if_requested() {
+	ExtBuilder::default().build().execute_with(|| {
+		let sibling_account = MultiLocation::new(
+			1,
+			X2(
+				Parachain(2000),
+				AccountKey20 {
+					network: NetworkId::Any,
+					key: [1u8; 20],
+				},
+			),
+		);
+
+		// Paid execution is allowed for the relay descendants, unpaid only for the relay
+		assert!(XcmBarrier::is_allowed(
+			BarrierRule::PaidExecution,
+			&sibling_account
+		));
+		assert!(XcmBarrier::is_allowed(
+			BarrierRule::UnpaidExecution,
+			&MultiLocation::parent()
+		));
+		assert!(!XcmBarrier::is_allowed(
+			BarrierRule::UnpaidExecution,
+			&sibling(2000)
+		));
+		assert!(!XcmBarrier::is_allowed(
+			BarrierRule::PaidExecution,
+			&MultiLocation::here()
+		));
+	})
+}
+
+#[test]
+fn paid_execution_is_allowed_from_allowed_origins() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			should_execute(sibling(2000), true, paid_message(10)),
+			Ok(())
+		);
+		// Not enough weight bought
+		assert_eq!(
+			should_execute(sibling(2000), true, paid_message(9)),
+			Err(())
+		);
+		// Not top level
+		assert_eq!(
+			should_execute(sibling(2000), false, paid_message(10)),
+			Err(())
+		);
+		// Not allowed origin
+		assert_eq!(
+			should_execute(MultiLocation::here(), true, paid_message(10)),
+			Err(())
+		);
+	})
+}
+
+#[test]
+fn unpaid_execution_is_allowed_from_allowed_origins() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			should_execute(MultiLocation::parent(), true, unpaid_message()),
+			Ok(())
+		);
+		assert_eq!(
+			should_execute(sibling(2000), true, unpaid_message()),
+			Err(())
+		);
+	})
+}
+
+#[test]
+fn version_subscriptions_are_allowed_from_allowed_origins() {
+	ExtBuilder::default().build().execute_with(|| {
+		let subscribe = || Xcm::SubscribeVersion {
+			query_id: 0,
+			max_response_weight: 0,
+		};
+		assert_eq!(should_execute(sibling(2000), true, subscribe()), Err(()));
+
+		assert_ok!(XcmBarrier::allow_origin(
+			Origin::root(),
+			BarrierRule::VersionSubscription,
+			Box::new(sibling(2000)),
+			false
+		));
+		assert_eq!(should_execute(sibling(2000), true, subscribe()), Ok(()));
+		assert_eq!(
+			should_execute(sibling(2000), true, Xcm::UnsubscribeVersion),
+			Ok(())
+		);
+		// Only subscription messages are allowed
+		assert_eq!(
+			should_execute(sibling(2000), true, unpaid_message()),
+			Err(())
+		);
+	})
+}
+
+#[test]
+fn rejections_emit_events() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert_eq!(
+			should_execute(sibling(2000), true, unpaid_message()),
+			Err(())
+		);
+		assert_eq!(
+			should_execute(sibling(2000), true, paid_message(10)),
+			Ok(())
+		);
+
+		assert_eq!(events(), vec![Event::ExecutionRejected(sibling(2000))]);
+	})
+}
+
+#[test]
+fn rejections_are_reported_once_per_origin_per_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		for _ in 0..2 {
+			assert_eq!(
+				should_execute(sibling(2000), true, unpaid_message()),
+				Err(())
+			);
+		}
+		assert_eq!(events(), vec![Event::ExecutionRejected(sibling(2000))]);
+
+		// The origin is reported again in the next block
+		System::set_block_number(2);
+		assert_eq!(
+			should_execute(sibling(2000), true, unpaid_message()),
+			Err(())
+		);
+		assert_eq!(
+			events(),
+			vec![
+				Event::ExecutionRejected(sibling(2000)),
+				Event::ExecutionRejected(sibling(2000)),
+			]
+		);
+	})
+}
+
+#[test]
+fn rejection_events_are_bounded_per_block() {
+	ExtBuilder::default().build().execute_with(|| {
+		// MaxRejectionEvents is 2 in the mock
+		for para_id in 2000..2003 {
+			assert_eq!(
+				should_execute(sibling(para_id), true, unpaid_message()),
+				Err(())
+			);
+		}
+
+		assert_eq!(
+			events(),
+			vec![
+				Event::ExecutionRejected(sibling(2000)),
+				Event::ExecutionRejected(sibling(2001)),
+			]
+		);
+	})
+}
@@ -0,0 +1,171 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking
+//!
+//! The transacts are benchmarked end to end, with the asset transactor and the XCM sender of the
+//! runtime, so the withdrawal of the fee and the sending of the message are accounted for.
+use crate::{Call, IndexToAccount, Pallet, RemoteTransactInfo, TransactInfo};
+use frame_benchmarking::{account, benchmarks, impl_benchmark_test_suite};
+use frame_support::{
+	traits::EnsureOrigin,
+	weights::{constants::WEIGHT_PER_SECOND, Weight},
+};
+use sp_runtime::traits::Convert;
+use sp_std::{boxed::Box, vec};
+use xcm::v1::{AssetId, Fungibility, MultiAsset, MultiLocation};
+use xcm_executor::traits::TransactAsset;
+use xcm_primitives::XcmTransact;
+
+/// The derivative index used by the benchmarks
+const INDEX: u16 = 0;
+
+/// The weight of the call dispatched in the destination
+const DEST_WEIGHT: Weight = 1_000_000_000;
+
+/// The amount of the fee asset given to the caller, enough to pay any benchmarked transact
+const AMOUNT: u128 = 1_000_000_000_000_000;
+
+/// The size of the call dispatched in the destination
+const CALL_SIZE: usize = 256;
+
+/// What the runtime provides to benchmark the transacts
+pub trait Config: crate::Config {
+	/// A destination the XCM sender can send messages to
+	fn transactor() -> Self::Transactor;
+	/// A currency reserved in the destination of `transactor`, which the asset transactor can
+	/// deposit
+	fn currency_id() -> Self::CurrencyId;
+}
+
+fn destination<T: Config>() -> MultiLocation {
+	T::transactor().destination()
+}
+
+fn fee_location<T: Config>() -> MultiLocation {
+	T::CurrencyIdToMultiLocation::convert(T::currency_id())
+		.expect("the benchmarked currency must be transferable")
+}
+
+/// Set the transact info of the destination, charging one unit of the fee asset per weight
+fn set_transact_info<T: Config>() {
+	TransactInfo::<T>::insert(
+		destination::<T>(),
+		RemoteTransactInfo {
+			transact_extra_weight: DEST_WEIGHT,
+			fee_per_second: WEIGHT_PER_SECOND as u128,
+			max_weight: DEST_WEIGHT.saturating_mul(10),
+		},
+	);
+}
+
+/// Create an account holding `AMOUNT` of the fee asset
+fn create_funded_user<T: Config>() -> T::AccountId {
+	let user: T::AccountId = account("account id", 0u32, 0u32);
+	let asset = MultiAsset {
+		id: AssetId::Concrete(fee_location::<T>()),
+		fun: Fungibility::Fungible(AMOUNT),
+	};
+	assert!(T::AssetTransactor::deposit_asset(
+		&asset,
+		&T::AccountIdToMultiLocation::convert(user.clone())
+	)
+	.is_ok());
+	user
+}
+
+benchmarks! {
+	register {
+		let user: T::AccountId = account("account id", 0u32, 0u32);
+		let origin = T::DerivativeAddressRegistrationOrigin::successful_origin();
+	}: _(origin, user.clone(), INDEX)
+	verify {
+		assert_eq!(Pallet::<T>::index_to_account(INDEX), Some(user));
+	}
+
+	deregister {
+		let user: T::AccountId = account("account id", 0u32, 0u32);
+		IndexToAccount::<T>::insert(INDEX, user);
+		let origin = T::DerivativeAddressRegistrationOrigin::successful_origin();
+	}: _(origin, INDEX)
+	verify {
+		assert_eq!(Pallet::<T>::index_to_account(INDEX), None);
+	}
+
+	transact_through_derivative_multilocation {
+		let user = create_funded_user::<T>();
+		IndexToAccount::<T>::insert(INDEX, user.clone());
+		set_transact_info::<T>();
+	}: _(
+		frame_system::RawOrigin::Signed(user),
+		T::transactor(),
+		INDEX,
+		Box::new(fee_location::<T>()),
+		DEST_WEIGHT,
+		vec![1u8; CALL_SIZE]
+	)
+
+	transact_through_derivative {
+		let user = create_funded_user::<T>();
+		IndexToAccount::<T>::insert(INDEX, user.clone());
+		set_transact_info::<T>();
+	}: _(
+		frame_system::RawOrigin::Signed(user),
+		T::transactor(),
+		INDEX,
+		T::currency_id(),
+		DEST_WEIGHT,
+		vec![1u8; CALL_SIZE]
+	)
+
+	transact_through_sovereign {
+		let user = create_funded_user::<T>();
+		set_transact_info::<T>();
+		let origin = T::SovereignAccountDispatcherOrigin::successful_origin();
+	}: _(
+		origin,
+		Box::new(destination::<T>()),
+		user,
+		Box::new(fee_location::<T>()),
+		DEST_WEIGHT,
+		vec![1u8; CALL_SIZE]
+	)
+
+	set_transact_info {
+		let origin = T::DerivativeAddressRegistrationOrigin::successful_origin();
+	}: _(
+		origin,
+		Box::new(destination::<T>()),
+		DEST_WEIGHT,
+		WEIGHT_PER_SECOND as u128,
+		DEST_WEIGHT.saturating_mul(10)
+	)
+	verify {
+		assert!(Pallet::<T>::transact_info(destination::<T>()).is_some());
+	}
+
+	remove_transact_info {
+		set_transact_info::<T>();
+		let origin = T::DerivativeAddressRegistrationOrigin::successful_origin();
+	}: _(origin, Box::new(destination::<T>()))
+	verify {
+		assert!(Pallet::<T>::transact_info(destination::<T>()).is_none());
+	}
+}
+
+impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
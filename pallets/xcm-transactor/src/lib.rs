@@ -0,0 +1,426 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Xcm Transactor Pallet
+//!
+//! This pallet allows to dispatch calls in other chains through XCM `Transact`. The message sent
+//! is `WithdrawAsset + BuyExecution + Transact`, where the fee is paid in the destination by our
+//! sovereign account. The same amount of the fee asset is burnt locally from the caller.
+//!
+//! Calls can be dispatched in two flavors:
+//! - Through a derivative account of our sovereign account in the destination. Each index of
+//! derivative account (as in `utility.as_derivative`) is registered to a local account, which
+//! is the only one able to transact through it.
+//! - Directly through our sovereign account in the destination. This is only allowed for
+//! `SovereignAccountDispatcherOrigin`.
+//!
+//! Derivative indices are registered and deregistered by `DerivativeAddressRegistrationOrigin`.
+//!
+//! This pallet has two storage items: IndexToAccount, which holds the local account owning each
+//! derivative index, and TransactInfo, which holds for each destination the information needed
+//! to compute the fee of a transact (extra weight, fee per second and maximum weight allowed).
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::pallet;
+pub use pallet::*;
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub mod benchmarks;
+#[cfg(test)]
+pub mod mock;
+#[cfg(test)]
+pub mod tests;
+pub mod weights;
+
+#[pallet]
+pub mod pallet {
+
+	use crate::weights::WeightInfo;
+	use frame_support::{pallet_prelude::*, transactional, weights::constants::WEIGHT_PER_SECOND};
+	use frame_system::{ensure_signed, pallet_prelude::*};
+	use sp_runtime::traits::Convert;
+	use sp_std::{boxed::Box, vec, vec::Vec};
+	use xcm::v1::{
+		AssetId as XcmAssetId, Fungibility, MultiAsset, MultiAssetFilter, MultiLocation, Order,
+		OriginKind, SendXcm, WildMultiAsset, Xcm,
+	};
+	use xcm_executor::traits::{InvertLocation, TransactAsset};
+	use xcm_primitives::{Reserve, UtilityAvailableCalls, UtilityEncodeCall, XcmTransact};
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(PhantomData<T>);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		type Event: From<Event<Self>> + IsType<<Self as frame_system::Config>::Event>;
+
+		/// The destinations we know how to encode utility calls for
+		type Transactor: Parameter + Member + Clone + XcmTransact + UtilityEncodeCall;
+
+		/// Origin that is allowed to register derivative indices and to set the transact info
+		type DerivativeAddressRegistrationOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Origin that is allowed to transact through our sovereign account
+		type SovereignAccountDispatcherOrigin: EnsureOrigin<Self::Origin>;
+
+		/// Currency Id, used to select the asset paying the fee
+		type CurrencyId: Parameter + Member + Clone;
+
+		/// Convert `T::CurrencyId` to `MultiLocation`.
+		type CurrencyIdToMultiLocation: Convert<Self::CurrencyId, Option<MultiLocation>>;
+
+		/// Convert `T::AccountId` to `MultiLocation`.
+		type AccountIdToMultiLocation: Convert<Self::AccountId, MultiLocation>;
+
+		/// Means of inverting a location, used to express assets from the destination point of view
+		type LocationInverter: InvertLocation;
+
+		/// How to send the XCM messages
+		type XcmSender: SendXcm;
+
+		/// How to withdraw the fee asset from the caller
+		type AssetTransactor: TransactAsset;
+
+		/// Weight information for the extrinsics of this pallet
+		type WeightInfo: WeightInfo;
+	}
+
+	/// Information needed to transact in a destination chain
+	#[derive(Default, Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq)]
+	pub struct RemoteTransactInfo {
+		/// Extra weight that transacting a call in the destination chain adds, i.e. the weight of
+		/// the XCM instructions themselves
+		pub transact_extra_weight: Weight,
+		/// Fee per second charged in the destination chain, in units of its native asset
+		pub fee_per_second: u128,
+		/// Maximum weight the destination chain allows for a transact
+		pub max_weight: Weight,
+	}
+
+	/// An error that can occur while executing the xcm transactor pallet's logic.
+	#[pallet::error]
+	pub enum Error<T> {
+		IndexAlreadyClaimed,
+		UnclaimedIndex,
+		NotOwner,
+		CannotReanchor,
+		AssetIsNotReserveInDestination,
+		NotCrossChainTransferableCurrency,
+		UnableToWithdrawAsset,
+		ErrorSending,
+		WeightOverflow,
+		AmountOverflow,
+		TransactorInfoNotSet,
+		MaxWeightTransactReached,
+	}
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(crate) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// Transacted the inner call through a derivative account in a destination chain.
+		TransactedDerivative(T::AccountId, MultiLocation, Vec<u8>, u16),
+		/// Transacted the call through the sovereign account in a destination chain.
+		TransactedSovereign(T::AccountId, MultiLocation, Vec<u8>),
+		/// Registered a derivative index for an account id.
+		RegisteredDerivative(T::AccountId, u16),
+		/// Deregistered a derivative index, which belonged to the account id.
+		DeRegisteredDerivative(T::AccountId, u16),
+		/// Changed the transact info of a location
+		TransactInfoChanged(MultiLocation, RemoteTransactInfo),
+		/// Removed the transact info of a location
+		TransactInfoRemoved(MultiLocation),
+	}
+
+	/// Stores the local account owning each derivative index
+	#[pallet::storage]
+	#[pallet::getter(fn index_to_account)]
+	pub type IndexToAccount<T: Config> = StorageMap<_, Blake2_128Concat, u16, T::AccountId>;
+
+	/// Stores the transact info of each destination. Transacting into a destination without
+	/// transact info is not allowed
+	#[pallet::storage]
+	#[pallet::getter(fn transact_info)]
+	pub type TransactInfo<T: Config> =
+		StorageMap<_, Blake2_128Concat, MultiLocation, RemoteTransactInfo>;
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Register a derivative index for an account id
+		#[pallet::weight(<T as Config>::WeightInfo::register())]
+		pub fn register(origin: OriginFor<T>, who: T::AccountId, index: u16) -> DispatchResult {
+			T::DerivativeAddressRegistrationOrigin::ensure_origin(origin)?;
+
+			ensure!(
+				IndexToAccount::<T>::get(&index).is_none(),
+				Error::<T>::IndexAlreadyClaimed
+			);
+
+			IndexToAccount::<T>::insert(&index, who.clone());
+
+			Self::deposit_event(Event::RegisteredDerivative(who, index));
+			Ok(())
+		}
+
+		/// Transact the inner call through a derivative account in a destination chain,
+		/// paying the fee with the asset at `fee_location`
+		#[pallet::weight(<T as Config>::WeightInfo::transact_through_derivative_multilocation())]
+		#[transactional]
+		pub fn transact_through_derivative_multilocation(
+			origin: OriginFor<T>,
+			dest: T::Transactor,
+			index: u16,
+			fee_location: Box<MultiLocation>,
+			dest_weight: Weight,
+			inner_call: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			Self::do_transact_through_derivative(
+				who,
+				dest,
+				index,
+				*fee_location,
+				dest_weight,
+				inner_call,
+			)
+		}
+
+		/// Transact the inner call through a derivative account in a destination chain,
+		/// paying the fee with the given currency
+		#[pallet::weight(<T as Config>::WeightInfo::transact_through_derivative())]
+		#[transactional]
+		pub fn transact_through_derivative(
+			origin: OriginFor<T>,
+			dest: T::Transactor,
+			index: u16,
+			currency_id: T::CurrencyId,
+			dest_weight: Weight,
+			inner_call: Vec<u8>,
+		) -> DispatchResult {
+			let who = ensure_signed(origin)?;
+
+			let fee_location = T::CurrencyIdToMultiLocation::convert(currency_id)
+				.ok_or(Error::<T>::NotCrossChainTransferableCurrency)?;
+
+			Self::do_transact_through_derivative(
+				who,
+				dest,
+				index,
+				fee_location,
+				dest_weight,
+				inner_call,
+			)
+		}
+
+		/// Transact the call through our sovereign account in a destination chain,
+		/// the fee being paid by `fee_payer`
+		#[pallet::weight(<T as Config>::WeightInfo::transact_through_sovereign())]
+		#[transactional]
+		pub fn transact_through_sovereign(
+			origin: OriginFor<T>,
+			dest: Box<MultiLocation>,
+			fee_payer: T::AccountId,
+			fee_location: Box<MultiLocation>,
+			dest_weight: Weight,
+			call: Vec<u8>,
+		) -> DispatchResult {
+			T::SovereignAccountDispatcherOrigin::ensure_origin(origin)?;
+
+			Self::transact_in_dest_chain_asset(
+				*dest.clone(),
+				fee_payer.clone(),
+				*fee_location,
+				dest_weight,
+				call.clone(),
+			)?;
+
+			Self::deposit_event(Event::TransactedSovereign(fee_payer, *dest, call));
+			Ok(())
+		}
+
+		/// Change the transact info of a destination
+		#[pallet::weight(<T as Config>::WeightInfo::set_transact_info())]
+		pub fn set_transact_info(
+			origin: OriginFor<T>,
+			location: Box<MultiLocation>,
+			transact_extra_weight: Weight,
+			fee_per_second: u128,
+			max_weight: Weight,
+		) -> DispatchResult {
+			T::DerivativeAddressRegistrationOrigin::ensure_origin(origin)?;
+
+			let remote_info = RemoteTransactInfo {
+				transact_extra_weight,
+				fee_per_second,
+				max_weight,
+			};
+
+			TransactInfo::<T>::insert(&*location, &remote_info);
+
+			Self::deposit_event(Event::TransactInfoChanged(*location, remote_info));
+			Ok(())
+		}
+
+		/// Remove the transact info of a destination
+		#[pallet::weight(<T as Config>::WeightInfo::remove_transact_info())]
+		pub fn remove_transact_info(
+			origin: OriginFor<T>,
+			location: Box<MultiLocation>,
+		) -> DispatchResult {
+			T::DerivativeAddressRegistrationOrigin::ensure_origin(origin)?;
+
+			TransactInfo::<T>::remove(&*location);
+
+			Self::deposit_event(Event::TransactInfoRemoved(*location));
+			Ok(())
+		}
+
+		/// Deregister a derivative index, which can then be registered for another account id
+		#[pallet::weight(<T as Config>::WeightInfo::deregister())]
+		pub fn deregister(origin: OriginFor<T>, index: u16) -> DispatchResult {
+			T::DerivativeAddressRegistrationOrigin::ensure_origin(origin)?;
+
+			let account = IndexToAccount::<T>::take(&index).ok_or(Error::<T>::UnclaimedIndex)?;
+
+			Self::deposit_event(Event::DeRegisteredDerivative(account, index));
+			Ok(())
+		}
+	}
+
+	impl<T: Config> Pallet<T> {
+		fn do_transact_through_derivative(
+			who: T::AccountId,
+			dest: T::Transactor,
+			index: u16,
+			fee_location: MultiLocation,
+			dest_weight: Weight,
+			inner_call: Vec<u8>,
+		) -> DispatchResult {
+			let account = IndexToAccount::<T>::get(index).ok_or(Error::<T>::UnclaimedIndex)?;
+			ensure!(account == who, Error::<T>::NotOwner);
+
+			// Wrap the inner call in a utility.as_derivative call of the destination
+			let call_bytes = dest
+				.clone()
+				.encode_call(UtilityAvailableCalls::AsDerivative(
+					index,
+					inner_call.clone(),
+				));
+			let dest = dest.destination();
+
+			Self::transact_in_dest_chain_asset(
+				dest.clone(),
+				who.clone(),
+				fee_location,
+				dest_weight,
+				call_bytes,
+			)?;
+
+			Self::deposit_event(Event::TransactedDerivative(who, dest, inner_call, index));
+			Ok(())
+		}
+
+		fn transact_in_dest_chain_asset(
+			dest: MultiLocation,
+			fee_payer: T::AccountId,
+			fee_location: MultiLocation,
+			dest_weight: Weight,
+			call: Vec<u8>,
+		) -> DispatchResult {
+			let transact_info =
+				TransactInfo::<T>::get(&dest).ok_or(Error::<T>::TransactorInfoNotSet)?;
+
+			let total_weight = dest_weight
+				.checked_add(transact_info.transact_extra_weight)
+				.ok_or(Error::<T>::WeightOverflow)?;
+			ensure!(
+				total_weight <= transact_info.max_weight,
+				Error::<T>::MaxWeightTransactReached
+			);
+
+			let amount = Self::calculate_fee(total_weight, transact_info.fee_per_second)?;
+			let fee = MultiAsset {
+				id: XcmAssetId::Concrete(fee_location),
+				fun: Fungibility::Fungible(amount),
+			};
+
+			// The fee is paid in the destination by our sovereign account, so the fee asset needs
+			// to be reserved in the destination
+			ensure!(
+				fee.reserve() == Some(dest.clone()),
+				Error::<T>::AssetIsNotReserveInDestination
+			);
+
+			// Burn the fee locally, as the destination charges it to our sovereign account
+			let fee_payer_location = T::AccountIdToMultiLocation::convert(fee_payer);
+			T::AssetTransactor::withdraw_asset(&fee, &fee_payer_location)
+				.map_err(|_| Error::<T>::UnableToWithdrawAsset)?;
+
+			let message =
+				Self::transact_message(dest.clone(), fee, total_weight, call, dest_weight)?;
+
+			T::XcmSender::send_xcm(dest, message).map_err(|_| Error::<T>::ErrorSending)?;
+
+			Ok(())
+		}
+
+		/// Build the `WithdrawAsset + BuyExecution + Transact` message. The unspent fee is
+		/// deposited back in our sovereign account.
+		fn transact_message(
+			dest: MultiLocation,
+			fee: MultiAsset,
+			total_weight: Weight,
+			call: Vec<u8>,
+			dispatch_weight: Weight,
+		) -> Result<Xcm<()>, DispatchError> {
+			let self_location = T::LocationInverter::invert_location(&dest);
+			let fees = fee
+				.reanchored(&self_location)
+				.map_err(|_| Error::<T>::CannotReanchor)?;
+
+			Ok(Xcm::WithdrawAsset {
+				assets: vec![fees.clone()].into(),
+				effects: vec![
+					Order::BuyExecution {
+						fees,
+						weight: dispatch_weight,
+						debt: total_weight.saturating_sub(dispatch_weight),
+						halt_on_error: false,
+						instructions: vec![Xcm::Transact {
+							origin_type: OriginKind::SovereignAccount,
+							require_weight_at_most: dispatch_weight,
+							call: call.into(),
+						}],
+					},
+					Order::DepositAsset {
+						assets: MultiAssetFilter::Wild(WildMultiAsset::All),
+						max_assets: 1,
+						beneficiary: self_location,
+					},
+				],
+			})
+		}
+
+		/// Returns the fee for a given weight, in units of the destination native asset
+		pub fn calculate_fee(weight: Weight, fee_per_second: u128) -> Result<u128, DispatchError> {
+			let amount = fee_per_second
+				.checked_mul(weight as u128)
+				.ok_or(Error::<T>::AmountOverflow)?;
+
+			Ok(amount / (WEIGHT_PER_SECOND as u128))
+		}
+	}
+}
@@ -0,0 +1,249 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use super::*;
+use crate as pallet_xcm_transactor;
+use parity_scale_codec::{Decode, Encode};
+
+use frame_support::{construct_runtime, parameter_types, RuntimeDebug};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::{
+	testing::Header,
+	traits::{BlakeTwo256, Convert, IdentityLookup},
+};
+use sp_std::cell::RefCell;
+use xcm::v1::{
+	Error as XcmError, Junction, Junctions, MultiAsset, MultiLocation, NetworkId,
+	Result as XcmResult, SendXcm, Xcm,
+};
+use xcm_builder::LocationInverter;
+use xcm_executor::{traits::TransactAsset, Assets};
+use xcm_primitives::{UtilityAvailableCalls, UtilityEncodeCall, XcmTransact};
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		XcmTransactor: pallet_xcm_transactor::{Pallet, Call, Storage, Event<T>},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type Origin = Origin;
+	type Call = Call;
+	type Index = u64;
+	type BlockNumber = u64;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = u64;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type DbWeight = ();
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<u64>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type SS58Prefix = ();
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: u64 = 1;
+}
+
+impl pallet_balances::Config for Test {
+	type Balance = u64;
+	type DustRemoval = ();
+	type Event = Event;
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+	type MaxLocks = ();
+	type MaxReserves = ();
+	type ReserveIdentifier = [u8; 8];
+}
+
+thread_local! {
+	pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+	pub static WITHDRAWN: RefCell<Vec<(MultiAsset, MultiLocation)>> = RefCell::new(Vec::new());
+}
+
+/// Sender that never returns error, always sends and stores the messages
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	fn send_xcm(dest: MultiLocation, msg: Xcm<()>) -> XcmResult {
+		SENT_XCM.with(|q| q.borrow_mut().push((dest, msg)));
+		Ok(())
+	}
+}
+
+pub(crate) fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+	SENT_XCM.with(|q| (*q.borrow()).clone())
+}
+
+/// Asset transactor that always succeeds and stores the withdrawals
+pub struct DummyAssetTransactor;
+impl TransactAsset for DummyAssetTransactor {
+	fn withdraw_asset(what: &MultiAsset, who: &MultiLocation) -> Result<Assets, XcmError> {
+		WITHDRAWN.with(|q| q.borrow_mut().push((what.clone(), who.clone())));
+		Ok(what.clone().into())
+	}
+
+	fn deposit_asset(_what: &MultiAsset, _who: &MultiLocation) -> XcmResult {
+		Ok(())
+	}
+}
+
+pub(crate) fn withdrawn() -> Vec<(MultiAsset, MultiLocation)> {
+	WITHDRAWN.with(|q| (*q.borrow()).clone())
+}
+
+/// The index of the utility pallet in the mocked relay chain
+pub const RELAY_UTILITY_INDEX: u8 = 1;
+
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum Transactors {
+	Relay,
+}
+
+impl XcmTransact for Transactors {
+	fn destination(self) -> MultiLocation {
+		match self {
+			Transactors::Relay => MultiLocation::parent(),
+		}
+	}
+}
+
+impl UtilityEncodeCall for Transactors {
+	fn encode_call(self, call: UtilityAvailableCalls) -> Vec<u8> {
+		match self {
+			Transactors::Relay => match call {
+				UtilityAvailableCalls::AsDerivative(index, inner_call) => {
+					// pallet index, as_derivative call index, derivative index, inner call
+					let mut call = vec![RELAY_UTILITY_INDEX, 1u8];
+					index.using_encoded(|bytes| call.extend_from_slice(bytes));
+					call.extend_from_slice(&inner_call);
+					call
+				}
+			},
+		}
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Encode, Decode, RuntimeDebug)]
+pub enum CurrencyId {
+	SelfReserve,
+	OtherReserve(MultiLocation),
+}
+
+parameter_types! {
+	pub Ancestry: MultiLocation = Junction::Parachain(100).into();
+	pub SelfReserve: MultiLocation = MultiLocation::new(1, Junctions::X1(Junction::Parachain(100)));
+}
+
+pub struct CurrencyIdToMultiLocation;
+impl Convert<CurrencyId, Option<MultiLocation>> for CurrencyIdToMultiLocation {
+	fn convert(currency: CurrencyId) -> Option<MultiLocation> {
+		match currency {
+			CurrencyId::SelfReserve => Some(SelfReserve::get()),
+			CurrencyId::OtherReserve(location) => Some(location),
+		}
+	}
+}
+
+pub struct AccountIdToMultiLocation;
+impl Convert<u64, MultiLocation> for AccountIdToMultiLocation {
+	fn convert(account: u64) -> MultiLocation {
+		let mut key = [0u8; 20];
+		key[..8].copy_from_slice(&account.to_le_bytes());
+		Junction::AccountKey20 {
+			network: NetworkId::Any,
+			key,
+		}
+		.into()
+	}
+}
+
+impl Config for Test {
+	type Event = Event;
+	type Transactor = Transactors;
+	type DerivativeAddressRegistrationOrigin = EnsureRoot<u64>;
+	type SovereignAccountDispatcherOrigin = EnsureRoot<u64>;
+	type CurrencyId = CurrencyId;
+	type CurrencyIdToMultiLocation = CurrencyIdToMultiLocation;
+	type AccountIdToMultiLocation = AccountIdToMultiLocation;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type XcmSender = TestSendXcm;
+	type AssetTransactor = DummyAssetTransactor;
+	type WeightInfo = ();
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::benchmarks::Config for Test {
+	fn transactor() -> Transactors {
+		Transactors::Relay
+	}
+
+	fn currency_id() -> CurrencyId {
+		CurrencyId::OtherReserve(MultiLocation::parent())
+	}
+}
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let t = frame_system::GenesisConfig::default()
+		.build_storage::<Test>()
+		.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
+
+pub(crate) fn events() -> Vec<super::Event<Test>> {
+	System::events()
+		.into_iter()
+		.map(|r| r.event)
+		.filter_map(|e| {
+			if let Event::XcmTransactor(inner) = e {
+				Some(inner)
+			} else {
+				None
+			}
+		})
+		.collect::<Vec<_>>()
+}
+
+pub fn expect_events(e: Vec<super::Event<Test>>) {
+	assert_eq!(events(), e);
+}
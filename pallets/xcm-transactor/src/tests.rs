@@ -0,0 +1,397 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+// Tests for XcmTransactor Pallet
+use crate::*;
+use mock::*;
+
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::{traits::Convert, DispatchError};
+use xcm::v1::{
+	AssetId as XcmAssetId, Fungibility, Junction, Junctions, MultiAsset, MultiAssetFilter,
+	MultiLocation, Order, OriginKind, WildMultiAsset, Xcm,
+};
+
+fn set_relay_transact_info() {
+	assert_ok!(XcmTransactor::set_transact_info(
+		Origin::root(),
+		Box::new(MultiLocation::parent()),
+		100,
+		// 1 unit per weight
+		1_000_000_000_000,
+		10_000
+	));
+}
+
+fn relay_fee(amount: u128) -> MultiAsset {
+	MultiAsset {
+		id: XcmAssetId::Concrete(MultiLocation::parent()),
+		fun: Fungibility::Fungible(amount),
+	}
+}
+
+#[test]
+fn test_register_address() {
+	new_test_ext().execute_with(|| {
+		// Only root can do this, as specified in the mock
+		assert_noop!(
+			XcmTransactor::register(Origin::signed(1u64), 1u64, 1),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		assert_eq!(XcmTransactor::index_to_account(&1).unwrap(), 1u64);
+
+		expect_events(vec![crate::Event::RegisteredDerivative(1u64, 1)])
+	});
+}
+
+#[test]
+fn test_register_address_already_claimed() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		assert_noop!(
+			XcmTransactor::register(Origin::root(), 2u64, 1),
+			Error::<Test>::IndexAlreadyClaimed
+		);
+	});
+}
+
+#[test]
+fn test_deregister_address() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		// Only root can do this, as specified in the mock
+		assert_noop!(
+			XcmTransactor::deregister(Origin::signed(1u64), 1),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(XcmTransactor::deregister(Origin::root(), 1));
+
+		assert_eq!(XcmTransactor::index_to_account(&1), None);
+
+		// The index can be registered again, for another account
+		assert_ok!(XcmTransactor::register(Origin::root(), 2u64, 1));
+
+		expect_events(vec![
+			crate::Event::RegisteredDerivative(1u64, 1),
+			crate::Event::DeRegisteredDerivative(1u64, 1),
+			crate::Event::RegisteredDerivative(2u64, 1),
+		])
+	});
+}
+
+#[test]
+fn test_deregister_unclaimed_index() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmTransactor::deregister(Origin::root(), 1),
+			Error::<Test>::UnclaimedIndex
+		);
+	});
+}
+
+#[test]
+fn test_set_and_remove_transact_info() {
+	new_test_ext().execute_with(|| {
+		assert_noop!(
+			XcmTransactor::set_transact_info(
+				Origin::signed(1u64),
+				Box::new(MultiLocation::parent()),
+				100,
+				1_000_000_000_000,
+				10_000
+			),
+			DispatchError::BadOrigin
+		);
+
+		set_relay_transact_info();
+
+		let expected_info = RemoteTransactInfo {
+			transact_extra_weight: 100,
+			fee_per_second: 1_000_000_000_000,
+			max_weight: 10_000,
+		};
+		assert_eq!(
+			XcmTransactor::transact_info(MultiLocation::parent()).unwrap(),
+			expected_info
+		);
+
+		assert_ok!(XcmTransactor::remove_transact_info(
+			Origin::root(),
+			Box::new(MultiLocation::parent())
+		));
+		assert!(XcmTransactor::transact_info(MultiLocation::parent()).is_none());
+
+		expect_events(vec![
+			crate::Event::TransactInfoChanged(MultiLocation::parent(), expected_info),
+			crate::Event::TransactInfoRemoved(MultiLocation::parent()),
+		])
+	});
+}
+
+#[test]
+fn test_transact_through_derivative_errors() {
+	new_test_ext().execute_with(|| {
+		// Index not registered
+		assert_noop!(
+			XcmTransactor::transact_through_derivative_multilocation(
+				Origin::signed(1u64),
+				Transactors::Relay,
+				1,
+				Box::new(MultiLocation::parent()),
+				100,
+				vec![0u8]
+			),
+			Error::<Test>::UnclaimedIndex
+		);
+
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+
+		// Not the owner of the index
+		assert_noop!(
+			XcmTransactor::transact_through_derivative_multilocation(
+				Origin::signed(2u64),
+				Transactors::Relay,
+				1,
+				Box::new(MultiLocation::parent()),
+				100,
+				vec![0u8]
+			),
+			Error::<Test>::NotOwner
+		);
+
+		// No transact info for the relay
+		assert_noop!(
+			XcmTransactor::transact_through_derivative_multilocation(
+				Origin::signed(1u64),
+				Transactors::Relay,
+				1,
+				Box::new(MultiLocation::parent()),
+				100,
+				vec![0u8]
+			),
+			Error::<Test>::TransactorInfoNotSet
+		);
+
+		set_relay_transact_info();
+
+		// Too much weight, the extra weight counts towards the maximum
+		assert_noop!(
+			XcmTransactor::transact_through_derivative_multilocation(
+				Origin::signed(1u64),
+				Transactors::Relay,
+				1,
+				Box::new(MultiLocation::parent()),
+				9_901,
+				vec![0u8]
+			),
+			Error::<Test>::MaxWeightTransactReached
+		);
+
+		// Our own asset cannot pay for fees in the relay
+		assert_noop!(
+			XcmTransactor::transact_through_derivative(
+				Origin::signed(1u64),
+				Transactors::Relay,
+				1,
+				CurrencyId::SelfReserve,
+				100,
+				vec![0u8]
+			),
+			Error::<Test>::AssetIsNotReserveInDestination
+		);
+
+		assert!(sent_xcm().is_empty());
+	});
+}
+
+#[test]
+fn test_transact_through_derivative_with_currency_id() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+		set_relay_transact_info();
+
+		assert_ok!(XcmTransactor::transact_through_derivative(
+			Origin::signed(1u64),
+			Transactors::Relay,
+			1,
+			CurrencyId::OtherReserve(MultiLocation::parent()),
+			9_900,
+			vec![1u8, 2u8]
+		));
+
+		// The extra weight is also paid
+		assert_eq!(
+			withdrawn(),
+			vec![(relay_fee(10_000), AccountIdToMultiLocation::convert(1u64))]
+		);
+		assert_eq!(sent_xcm().len(), 1);
+	});
+}
+
+#[test]
+fn test_transact_through_derivative_multilocation_message() {
+	new_test_ext().execute_with(|| {
+		assert_ok!(XcmTransactor::register(Origin::root(), 1u64, 1));
+		assert_ok!(XcmTransactor::set_transact_info(
+			Origin::root(),
+			Box::new(MultiLocation::parent()),
+			1_000,
+			// 1 unit per 1_000 weight
+			1_000_000_000,
+			1_000_000
+		));
+
+		assert_ok!(XcmTransactor::transact_through_derivative_multilocation(
+			Origin::signed(1u64),
+			Transactors::Relay,
+			1,
+			Box::new(MultiLocation::parent()),
+			4_000,
+			vec![1u8, 2u8]
+		));
+
+		// 5000 weight is 5 units of the relay token, burnt from the caller
+		assert_eq!(
+			withdrawn(),
+			vec![(relay_fee(5), AccountIdToMultiLocation::convert(1u64))]
+		);
+
+		// The fee is expressed from the relay point of view, and the call is wrapped in
+		// utility.as_derivative
+		let expected_call = vec![RELAY_UTILITY_INDEX, 1u8, 1u8, 0u8, 1u8, 2u8];
+		let fee = MultiAsset {
+			id: XcmAssetId::Concrete(MultiLocation::here()),
+			fun: Fungibility::Fungible(5),
+		};
+		assert_eq!(
+			sent_xcm(),
+			vec![(
+				MultiLocation::parent(),
+				Xcm::WithdrawAsset {
+					assets: vec![fee.clone()].into(),
+					effects: vec![
+						Order::BuyExecution {
+							fees: fee,
+							weight: 4_000,
+							debt: 1_000,
+							halt_on_error: false,
+							instructions: vec![Xcm::Transact {
+								origin_type: OriginKind::SovereignAccount,
+								require_weight_at_most: 4_000,
+								call: expected_call.into(),
+							}],
+						},
+						Order::DepositAsset {
+							assets: MultiAssetFilter::Wild(WildMultiAsset::All),
+							max_assets: 1,
+							beneficiary: MultiLocation::new(
+								0,
+								Junctions::X1(Junction::Parachain(100))
+							),
+						},
+					],
+				}
+			)]
+		);
+
+		expect_events(vec![
+			crate::Event::RegisteredDerivative(1u64, 1),
+			crate::Event::TransactInfoChanged(
+				MultiLocation::parent(),
+				RemoteTransactInfo {
+					transact_extra_weight: 1_000,
+					fee_per_second: 1_000_000_000,
+					max_weight: 1_000_000,
+				},
+			),
+			crate::Event::TransactedDerivative(1u64, MultiLocation::parent(), vec![1u8, 2u8], 1),
+		])
+	});
+}
+
+#[test]
+fn test_transact_through_sovereign() {
+	new_test_ext().execute_with(|| {
+		set_relay_transact_info();
+
+		// Only root can do this, as specified in the mock
+		assert_noop!(
+			XcmTransactor::transact_through_sovereign(
+				Origin::signed(1u64),
+				Box::new(MultiLocation::parent()),
+				1u64,
+				Box::new(MultiLocation::parent()),
+				100,
+				vec![1u8]
+			),
+			DispatchError::BadOrigin
+		);
+
+		assert_ok!(XcmTransactor::transact_through_sovereign(
+			Origin::root(),
+			Box::new(MultiLocation::parent()),
+			1u64,
+			Box::new(MultiLocation::parent()),
+			100,
+			vec![1u8]
+		));
+
+		// The call is transacted as is, without being wrapped
+		match sent_xcm().as_slice() {
+			[(dest, Xcm::WithdrawAsset { effects, .. })] => {
+				assert_eq!(dest, &MultiLocation::parent());
+				assert!(matches!(
+					effects.first(),
+					Some(Order::BuyExecution { instructions, .. })
+						if instructions == &vec![Xcm::Transact {
+							origin_type: OriginKind::SovereignAccount,
+							require_weight_at_most: 100,
+							call: vec![1u8].into(),
+						}]
+				));
+			}
+			sent => panic!("unexpected messages sent: {:?}", sent),
+		}
+		assert_eq!(withdrawn().len(), 1);
+
+		expect_events(vec![
+			crate::Event::TransactInfoChanged(
+				MultiLocation::parent(),
+				RemoteTransactInfo {
+					transact_extra_weight: 100,
+					fee_per_second: 1_000_000_000_000,
+					max_weight: 10_000,
+				},
+			),
+			crate::Event::TransactedSovereign(1u64, MultiLocation::parent(), vec![1u8]),
+		])
+	});
+}
+
+#[test]
+fn test_calculate_fee() {
+	assert_eq!(XcmTransactor::calculate_fee(1_000, 1_000_000_000), Ok(1));
+	assert_eq!(
+		XcmTransactor::calculate_fee(u64::MAX, u128::MAX),
+		Err(Error::<Test>::AmountOverflow.into())
+	);
+}
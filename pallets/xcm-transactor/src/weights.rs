@@ -0,0 +1,113 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for pallet_xcm_transactor
+//!
+//! PLACEHOLDER ESTIMATES, NOT BENCHMARK RESULTS. They account for the storage accessed by each
+//! extrinsic, including the withdrawal of the fee asset and the sending of the XCM message, and
+//! must be replaced by the output of `benchmark --pallet pallet_xcm_transactor` (see
+//! `benchmarks.rs`) on the reference hardware.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_xcm_transactor.
+pub trait WeightInfo {
+	fn register() -> Weight;
+	fn deregister() -> Weight;
+	fn transact_through_derivative_multilocation() -> Weight;
+	fn transact_through_derivative() -> Weight;
+	fn transact_through_sovereign() -> Weight;
+	fn set_transact_info() -> Weight;
+	fn remove_transact_info() -> Weight;
+}
+
+/// Weights for pallet_xcm_transactor using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn register() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn deregister() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn transact_through_derivative_multilocation() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(7 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn transact_through_derivative() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(8 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn transact_through_sovereign() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(4 as Weight))
+	}
+	fn set_transact_info() -> Weight {
+		(15_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn remove_transact_info() -> Weight {
+		(15_000_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn register() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn deregister() -> Weight {
+		(20_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn transact_through_derivative_multilocation() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(7 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn transact_through_derivative() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(8 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn transact_through_sovereign() -> Weight {
+		(150_000_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(4 as Weight))
+	}
+	fn set_transact_info() -> Weight {
+		(15_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn remove_transact_info() -> Weight {
+		(15_000_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
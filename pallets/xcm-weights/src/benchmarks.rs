@@ -0,0 +1,220 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+//! Benchmarking
+//!
+//! Each instruction is benchmarked by executing a message made of it with the executor of the
+//! runtime. Orders are benchmarked as the effects of a `ReserveAssetDeposited`, whose own cost is
+//! only checking the reserve.
+use frame_benchmarking::{benchmarks, impl_benchmark_test_suite};
+use frame_support::weights::{GetDispatchInfo, Weight};
+use parity_scale_codec::Encode;
+use sp_std::{boxed::Box, marker::PhantomData, vec, vec::Vec};
+use xcm::v1::{
+	ExecuteXcm, Junctions, MultiAsset, MultiAssetFilter, MultiAssets, MultiLocation, Order,
+	OriginKind, Outcome, Response, WildMultiAsset, Xcm,
+};
+use xcm_executor::{traits::TransactAsset, XcmExecutor};
+
+/// The benchmarked instructions handle up to this number of assets
+const MAX_ASSETS: u32 = 10;
+
+/// The amount of each asset used in the benchmarks
+const AMOUNT: u128 = 1_000_000_000_000_000;
+
+pub struct Pallet<T: Config>(PhantomData<T>);
+
+/// What the runtime provides to benchmark its XCM instructions
+pub trait Config: frame_system::Config {
+	/// The XCM configuration whose instructions are benchmarked
+	type XcmConfig: xcm_executor::Config<Call = <Self as frame_system::Config>::Call>;
+
+	/// The location of a local account, for each index
+	fn account(index: u32) -> MultiLocation;
+	/// A location the router can send messages to
+	fn destination() -> MultiLocation;
+	/// The location of the reserve of the assets returned by `reserve_asset`
+	fn reserve_location() -> MultiLocation;
+	/// A distinct fungible asset for each index, which the asset transactor can deposit and the
+	/// trader accepts as fees
+	fn reserve_asset(index: u32, amount: u128) -> MultiAsset;
+}
+
+type XcmOf<T> = Xcm<<T as frame_system::Config>::Call>;
+
+fn assets<T: Config>(count: u32) -> MultiAssets {
+	(0..count)
+		.map(|index| T::reserve_asset(index, AMOUNT))
+		.collect::<Vec<_>>()
+		.into()
+}
+
+/// Deposit the assets in the account of `who`
+fn fund<T: Config>(assets: &MultiAssets, who: &MultiLocation) {
+	for asset in assets.inner() {
+		assert!(
+			<T::XcmConfig as xcm_executor::Config>::AssetTransactor::deposit_asset(asset, who)
+				.is_ok()
+		);
+	}
+}
+
+/// Execute the message, which must complete
+fn execute<T: Config>(origin: MultiLocation, message: XcmOf<T>) {
+	let outcome = XcmExecutor::<T::XcmConfig>::execute_xcm_in_credit(
+		origin,
+		message,
+		Weight::max_value(),
+		Weight::max_value(),
+	);
+	assert!(matches!(outcome, Outcome::Complete(_)), "{:?}", outcome);
+}
+
+/// Execute the order as the effect of a reserve deposit of `count` assets
+fn execute_order<T: Config>(count: u32, order: Order<<T as frame_system::Config>::Call>) {
+	execute::<T>(
+		T::reserve_location(),
+		Xcm::ReserveAssetDeposited {
+			assets: assets::<T>(count),
+			effects: vec![order],
+		},
+	);
+}
+
+fn all() -> MultiAssetFilter {
+	MultiAssetFilter::Wild(WildMultiAsset::All)
+}
+
+benchmarks! {
+	where_clause {
+		where <T as frame_system::Config>::Call:
+			From<frame_system::Call<T>> + Encode + GetDispatchInfo
+	}
+
+	withdraw_asset {
+		let a in 1 .. MAX_ASSETS;
+		let who = T::account(0);
+		let assets = assets::<T>(a);
+		fund::<T>(&assets, &who);
+		let message = Xcm::WithdrawAsset { assets, effects: vec![] };
+	}: { execute::<T>(who, message) }
+
+	reserve_asset_deposited {
+		let a in 1 .. MAX_ASSETS;
+		let message = Xcm::ReserveAssetDeposited { assets: assets::<T>(a), effects: vec![] };
+	}: { execute::<T>(T::reserve_location(), message) }
+
+	transfer_asset {
+		let a in 1 .. MAX_ASSETS;
+		let who = T::account(0);
+		let assets = assets::<T>(a);
+		fund::<T>(&assets, &who);
+		let message = Xcm::TransferAsset { assets, beneficiary: T::account(1) };
+	}: { execute::<T>(who, message) }
+
+	transfer_reserve_asset {
+		let a in 1 .. MAX_ASSETS;
+		let who = T::account(0);
+		let assets = assets::<T>(a);
+		fund::<T>(&assets, &who);
+		let message = Xcm::TransferReserveAsset {
+			assets,
+			dest: T::destination(),
+			effects: vec![],
+		};
+	}: { execute::<T>(who, message) }
+
+	transact {
+		let call: <T as frame_system::Config>::Call = frame_system::Call::<T>::remark(vec![]).into();
+		let message = Xcm::Transact {
+			origin_type: OriginKind::SovereignAccount,
+			require_weight_at_most: call.get_dispatch_info().weight,
+			call: call.encode().into(),
+		};
+	}: { execute::<T>(T::account(0), message) }
+
+	query_response {
+		let message = Xcm::QueryResponse {
+			query_id: 0,
+			response: Response::Assets(MultiAssets::new()),
+		};
+	}: { execute::<T>(T::destination(), message) }
+
+	relayed_from {
+		let message = Xcm::RelayedFrom {
+			who: Junctions::Here,
+			message: Box::new(Xcm::WithdrawAsset { assets: MultiAssets::new(), effects: vec![] }),
+		};
+	}: { execute::<T>(T::account(0), message) }
+
+	subscribe_version {
+		let message = Xcm::SubscribeVersion { query_id: 0, max_response_weight: 0 };
+	}: { execute::<T>(T::destination(), message) }
+
+	unsubscribe_version {
+		execute::<T>(
+			T::destination(),
+			Xcm::SubscribeVersion { query_id: 0, max_response_weight: 0 },
+		);
+	}: { execute::<T>(T::destination(), Xcm::UnsubscribeVersion) }
+
+	deposit_asset {
+		let a in 1 .. MAX_ASSETS;
+		let order = Order::DepositAsset { assets: all(), max_assets: a, beneficiary: T::account(1) };
+	}: { execute_order::<T>(a, order) }
+
+	deposit_reserve_asset {
+		let a in 1 .. MAX_ASSETS;
+		let order = Order::DepositReserveAsset {
+			assets: all(),
+			max_assets: a,
+			dest: T::destination(),
+			effects: vec![],
+		};
+	}: { execute_order::<T>(a, order) }
+
+	initiate_reserve_withdraw {
+		let a in 1 .. MAX_ASSETS;
+		let order = Order::InitiateReserveWithdraw {
+			assets: all(),
+			reserve: T::destination(),
+			effects: vec![],
+		};
+	}: { execute_order::<T>(a, order) }
+
+	query_holding {
+		let a in 1 .. MAX_ASSETS;
+		let order = Order::QueryHolding { query_id: 0, dest: T::destination(), assets: all() };
+	}: { execute_order::<T>(a, order) }
+
+	buy_execution {
+		let order = Order::BuyExecution {
+			fees: T::reserve_asset(0, AMOUNT),
+			weight: 1_000_000,
+			debt: 0,
+			halt_on_error: true,
+			instructions: vec![],
+		};
+	}: { execute_order::<T>(1, order) }
+}
+
+impl_benchmark_test_suite!(
+	Pallet,
+	crate::mock::ExtBuilder::default().build(),
+	crate::mock::Test
+);
@@ -0,0 +1,157 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! # Xcm Weights
+//!
+//! An XCM weigher pricing each instruction with its benchmarked weight, instead of the single
+//! weight per instruction `FixedWeightBounds` uses. Since the executor passes this weight to the
+//! trader, what is charged for a message follows what its execution actually costs.
+//!
+//! Instructions handling assets are weighed according to the number of assets they handle, which
+//! for orders is bounded by the number of assets the enclosing message put in holding. `Transact`
+//! is weighed as its benchmarked overhead plus the weight of the dispatched call.
+//!
+//! The weights in `weights.rs` are placeholder estimates until the benchmarks are run.
+//!
+//! Instructions the executor does not handle in a parachain (the Hrmp notifications and
+//! `ExchangeAsset`) cannot be weighed, so messages containing them are rejected.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+#[cfg(any(test, feature = "runtime-benchmarks"))]
+pub mod benchmarks;
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+pub mod weights;
+
+pub use weights::WeightInfo;
+
+use frame_support::weights::{GetDispatchInfo, Weight};
+use parity_scale_codec::Decode;
+use sp_std::marker::PhantomData;
+use xcm::v1::{MultiAssetFilter, MultiAssets, Order, Xcm};
+use xcm_executor::traits::WeightBounds;
+
+/// Weighs XCM messages with the weights `W` of each instruction
+pub struct WeightInfoBounds<W, Call>(PhantomData<(W, Call)>);
+
+impl<W: WeightInfo, Call> WeightInfoBounds<W, Call> {
+	/// The number of assets in `assets`, when `holding` assets are in holding
+	fn assets_count(assets: &MultiAssetFilter, holding: u32) -> u32 {
+		match assets {
+			MultiAssetFilter::Definite(assets) => (assets.len() as u32).min(holding),
+			MultiAssetFilter::Wild(_) => holding,
+		}
+	}
+
+	/// The weight of an order, when `holding` assets are in holding
+	fn order_weight(order: &Order<Call>, holding: u32) -> Result<Weight, ()> {
+		Ok(match order {
+			Order::Noop => 0,
+			Order::DepositAsset {
+				assets, max_assets, ..
+			} => W::deposit_asset(Self::assets_count(assets, holding).min(*max_assets)),
+			Order::DepositReserveAsset {
+				assets, max_assets, ..
+			} => W::deposit_reserve_asset(Self::assets_count(assets, holding).min(*max_assets)),
+			Order::ExchangeAsset { .. } => return Err(()),
+			// Teleports take the same path as reserve withdrawals: the assets are taken from
+			// holding and a message is sent
+			Order::InitiateReserveWithdraw { assets, .. }
+			| Order::InitiateTeleport { assets, .. } => {
+				W::initiate_reserve_withdraw(Self::assets_count(assets, holding))
+			}
+			Order::QueryHolding { assets, .. } => {
+				W::query_holding(Self::assets_count(assets, holding))
+			}
+			// The weight bought is weighed by `deep`
+			Order::BuyExecution { .. } => W::buy_execution(),
+		})
+	}
+
+	/// The weight of an instruction putting `assets` in holding, plus the weight of its effects
+	fn with_effects(
+		weight: Weight,
+		assets: &MultiAssets,
+		effects: &[Order<Call>],
+	) -> Result<Weight, ()> {
+		let holding = assets.len() as u32;
+		effects.iter().try_fold(weight, |weight, order| {
+			Ok(weight.saturating_add(Self::order_weight(order, holding)?))
+		})
+	}
+}
+
+impl<W: WeightInfo, Call: Decode + GetDispatchInfo> WeightBounds<Call>
+	for WeightInfoBounds<W, Call>
+{
+	fn shallow(message: &mut Xcm<Call>) -> Result<Weight, ()> {
+		match message {
+			Xcm::WithdrawAsset { assets, effects } => {
+				Self::with_effects(W::withdraw_asset(assets.len() as u32), assets, effects)
+			}
+			// Teleported assets are handled like reserve deposited ones, as both are only
+			// checked and put in holding
+			Xcm::ReserveAssetDeposited { assets, effects }
+			| Xcm::ReceiveTeleportedAsset { assets, effects } => Self::with_effects(
+				W::reserve_asset_deposited(assets.len() as u32),
+				assets,
+				effects,
+			),
+			Xcm::QueryResponse { .. } => Ok(W::query_response()),
+			Xcm::TransferAsset { assets, .. } => Ok(W::transfer_asset(assets.len() as u32)),
+			// The effects are executed by the destination
+			Xcm::TransferReserveAsset { assets, .. } => {
+				Ok(W::transfer_reserve_asset(assets.len() as u32))
+			}
+			Xcm::Transact { call, .. } => {
+				Ok(W::transact().saturating_add(call.ensure_decoded()?.get_dispatch_info().weight))
+			}
+			Xcm::RelayedFrom { message, .. } => {
+				Ok(W::relayed_from().saturating_add(Self::shallow(message.as_mut())?))
+			}
+			Xcm::SubscribeVersion { .. } => Ok(W::subscribe_version()),
+			Xcm::UnsubscribeVersion => Ok(W::unsubscribe_version()),
+			Xcm::HrmpNewChannelOpenRequest { .. }
+			| Xcm::HrmpChannelAccepted { .. }
+			| Xcm::HrmpChannelClosing { .. } => Err(()),
+		}
+	}
+
+	fn deep(message: &mut Xcm<Call>) -> Result<Weight, ()> {
+		match message {
+			Xcm::RelayedFrom { message, .. } => Self::deep(message.as_mut()),
+			Xcm::WithdrawAsset { effects, .. }
+			| Xcm::ReserveAssetDeposited { effects, .. }
+			| Xcm::ReceiveTeleportedAsset { effects, .. } => {
+				let mut extra: Weight = 0;
+				for effect in effects.iter_mut() {
+					if let Order::BuyExecution { instructions, .. } = effect {
+						for message in instructions.iter_mut() {
+							extra = extra
+								.saturating_add(Self::shallow(message)?)
+								.saturating_add(Self::deep(message)?);
+						}
+					}
+				}
+				Ok(extra)
+			}
+			_ => Ok(0),
+		}
+	}
+}
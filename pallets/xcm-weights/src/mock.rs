@@ -0,0 +1,261 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A minimal runtime executing XCM, weighed with the weights of this crate
+use crate::WeightInfoBounds;
+use frame_support::{construct_runtime, parameter_types, traits::Everything};
+use frame_system::EnsureRoot;
+use sp_core::H256;
+use sp_runtime::traits::{BlakeTwo256, IdentityLookup};
+use sp_std::cell::RefCell;
+use xcm::v1::{
+	AssetId::Concrete,
+	Junction::{AccountIndex64, GeneralIndex},
+	Junctions::{Here, X1},
+	MultiLocation, Result as XcmResult, SendXcm, Xcm,
+};
+use xcm_builder::{
+	AsPrefixedGeneralIndex, ConvertedConcreteAssetId, EnsureXcmOrigin, FixedRateOfFungible,
+	FungiblesAdapter, LocationInverter, SovereignSignedViaLocation, TakeWeightCredit,
+};
+use xcm_executor::traits::{Convert, JustTry};
+
+pub type AccountId = u64;
+pub type Balance = u128;
+pub type AssetId = u128;
+pub type BlockNumber = u64;
+
+type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Test>;
+type Block = frame_system::mocking::MockBlock<Test>;
+
+// Configure a mock runtime to test the weigher.
+construct_runtime!(
+	pub enum Test where
+		Block = Block,
+		NodeBlock = Block,
+		UncheckedExtrinsic = UncheckedExtrinsic,
+	{
+		System: frame_system::{Pallet, Call, Config, Storage, Event<T>},
+		Balances: pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>},
+		PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin},
+	}
+);
+
+parameter_types! {
+	pub const BlockHashCount: u64 = 250;
+	pub const SS58Prefix: u8 = 42;
+}
+impl frame_system::Config for Test {
+	type BaseCallFilter = Everything;
+	type DbWeight = ();
+	type Origin = Origin;
+	type Index = u64;
+	type BlockNumber = BlockNumber;
+	type Call = Call;
+	type Hash = H256;
+	type Hashing = BlakeTwo256;
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Header = sp_runtime::testing::Header;
+	type Event = Event;
+	type BlockHashCount = BlockHashCount;
+	type Version = ();
+	type PalletInfo = PalletInfo;
+	type AccountData = pallet_balances::AccountData<Balance>;
+	type OnNewAccount = ();
+	type OnKilledAccount = ();
+	type SystemWeightInfo = ();
+	type BlockWeights = ();
+	type BlockLength = ();
+	type SS58Prefix = SS58Prefix;
+	type OnSetCode = ();
+}
+
+parameter_types! {
+	pub const ExistentialDeposit: Balance = 1;
+}
+impl pallet_balances::Config for Test {
+	type MaxReserves = ();
+	type ReserveIdentifier = ();
+	type MaxLocks = ();
+	type Balance = Balance;
+	type Event = Event;
+	type DustRemoval = ();
+	type ExistentialDeposit = ExistentialDeposit;
+	type AccountStore = System;
+	type WeightInfo = ();
+}
+
+parameter_types! {
+	pub const AssetDeposit: Balance = 0;
+	pub const ApprovalDeposit: Balance = 0;
+	pub const AssetsStringLimit: u32 = 50;
+	pub const MetadataDepositBase: Balance = 0;
+	pub const MetadataDepositPerByte: Balance = 0;
+}
+impl pallet_assets::Config for Test {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = ();
+}
+
+thread_local! {
+	pub static SENT_XCM: RefCell<Vec<(MultiLocation, Xcm<()>)>> = RefCell::new(Vec::new());
+}
+
+/// Sender that never returns error, always sends and stores the messages
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	fn send_xcm(dest: MultiLocation, msg: Xcm<()>) -> XcmResult {
+		SENT_XCM.with(|q| q.borrow_mut().push((dest, msg)));
+		Ok(())
+	}
+}
+
+pub(crate) fn sent_xcm() -> Vec<(MultiLocation, Xcm<()>)> {
+	SENT_XCM.with(|q| (*q.borrow()).clone())
+}
+
+/// Local accounts are `AccountIndex64` junctions, the parent has the default account
+pub struct LocationToAccountId;
+impl Convert<MultiLocation, AccountId> for LocationToAccountId {
+	fn convert_ref(location: impl core::borrow::Borrow<MultiLocation>) -> Result<AccountId, ()> {
+		match location.borrow() {
+			MultiLocation {
+				parents: 0,
+				interior: X1(AccountIndex64 { index, .. }),
+			} => Ok(*index),
+			MultiLocation {
+				parents: 1,
+				interior: Here,
+			} => Ok(AccountId::default()),
+			_ => Err(()),
+		}
+	}
+}
+
+parameter_types! {
+	pub Ancestry: MultiLocation = MultiLocation::new(1, X1(xcm::v1::Junction::Parachain(1000)));
+	// The assets are reserve-backed by the parent, with their id as general index
+	pub ReservePrefix: MultiLocation = MultiLocation::parent();
+	pub FeePerSecond: (xcm::v1::AssetId, u128) = (Concrete(reserve_asset_location(0)), 1);
+}
+
+pub type AssetTransactor = FungiblesAdapter<
+	Assets,
+	ConvertedConcreteAssetId<
+		AssetId,
+		Balance,
+		AsPrefixedGeneralIndex<ReservePrefix, AssetId, JustTry>,
+		JustTry,
+	>,
+	LocationToAccountId,
+	AccountId,
+	(),
+	(),
+>;
+
+pub struct XcmConfig;
+impl xcm_executor::Config for XcmConfig {
+	type Call = Call;
+	type XcmSender = TestSendXcm;
+	type AssetTransactor = AssetTransactor;
+	type OriginConverter = SovereignSignedViaLocation<LocationToAccountId, Origin>;
+	type IsReserve = xcm_primitives::MultiNativeAsset;
+	type IsTeleporter = ();
+	type LocationInverter = LocationInverter<Ancestry>;
+	type Barrier = TakeWeightCredit;
+	type Weigher = WeightInfoBounds<(), Call>;
+	type Trader = FixedRateOfFungible<FeePerSecond, ()>;
+	type ResponseHandler = PolkadotXcm;
+	type SubscriptionService = PolkadotXcm;
+}
+
+impl pallet_xcm::Config for Test {
+	type Event = Event;
+	type SendXcmOrigin = EnsureXcmOrigin<Origin, ()>;
+	type XcmRouter = TestSendXcm;
+	type ExecuteXcmOrigin = EnsureXcmOrigin<Origin, ()>;
+	type XcmExecuteFilter = Everything;
+	type XcmExecutor = xcm_executor::XcmExecutor<XcmConfig>;
+	type XcmTeleportFilter = Everything;
+	type XcmReserveTransferFilter = Everything;
+	type Weigher = WeightInfoBounds<(), Call>;
+	type LocationInverter = LocationInverter<Ancestry>;
+}
+
+pub(crate) fn reserve_asset_location(index: u32) -> MultiLocation {
+	MultiLocation::new(1, X1(GeneralIndex(index as u128)))
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl crate::benchmarks::Config for Test {
+	type XcmConfig = XcmConfig;
+
+	fn account(index: u32) -> MultiLocation {
+		X1(AccountIndex64 {
+			network: xcm::v1::NetworkId::Any,
+			index: index as u64 + 1,
+		})
+		.into()
+	}
+
+	fn destination() -> MultiLocation {
+		MultiLocation::parent()
+	}
+
+	fn reserve_location() -> MultiLocation {
+		ReservePrefix::get()
+	}
+
+	fn reserve_asset(index: u32, amount: u128) -> xcm::v1::MultiAsset {
+		// Fails when the asset already exists
+		let _ = Assets::force_create(Origin::root(), index as AssetId, 0, true, 1);
+		(reserve_asset_location(index), amount).into()
+	}
+}
+
+/// Externality builder for pallet xcm weights' mock runtime
+pub(crate) struct ExtBuilder;
+
+impl Default for ExtBuilder {
+	fn default() -> ExtBuilder {
+		ExtBuilder
+	}
+}
+
+impl ExtBuilder {
+	pub(crate) fn build(self) -> sp_io::TestExternalities {
+		let t = frame_system::GenesisConfig::default()
+			.build_storage::<Test>()
+			.expect("Frame system builds valid default genesis config");
+
+		let mut ext = sp_io::TestExternalities::new(t);
+		ext.execute_with(|| System::set_block_number(1));
+		ext
+	}
+}
@@ -0,0 +1,191 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Unit testing
+use crate::mock::{reserve_asset_location, sent_xcm, Assets, Call, ExtBuilder, Origin};
+use crate::{WeightInfo, WeightInfoBounds};
+use frame_support::weights::{GetDispatchInfo, Weight};
+use parity_scale_codec::Encode;
+use xcm::v1::{
+	ExecuteXcm, Junction::AccountIndex64, Junctions::X1, MultiAsset, MultiAssetFilter, MultiAssets,
+	MultiLocation, NetworkId, Order, OriginKind, Outcome, WildMultiAsset, Xcm,
+};
+use xcm_executor::{traits::WeightBounds, XcmExecutor};
+
+type Weigher = WeightInfoBounds<(), Call>;
+
+fn account(index: u64) -> MultiLocation {
+	X1(AccountIndex64 {
+		network: NetworkId::Any,
+		index,
+	})
+	.into()
+}
+
+fn assets(count: u32) -> MultiAssets {
+	(0..count)
+		.map(|index| (reserve_asset_location(index), 1_000u128).into())
+		.collect::<Vec<MultiAsset>>()
+		.into()
+}
+
+fn deposit(max_assets: u32) -> Order<Call> {
+	Order::DepositAsset {
+		assets: MultiAssetFilter::Wild(WildMultiAsset::All),
+		max_assets,
+		beneficiary: account(1),
+	}
+}
+
+fn buy_execution(instructions: Vec<Xcm<Call>>) -> Order<Call> {
+	Order::BuyExecution {
+		fees: (reserve_asset_location(0), 1_000u128).into(),
+		weight: 0,
+		debt: 0,
+		halt_on_error: true,
+		instructions,
+	}
+}
+
+fn remark() -> Xcm<Call> {
+	let call: Call = frame_system::Call::remark(vec![]).into();
+	Xcm::Transact {
+		origin_type: OriginKind::SovereignAccount,
+		require_weight_at_most: call.get_dispatch_info().weight,
+		call: call.encode().into(),
+	}
+}
+
+#[test]
+fn asset_instructions_are_weighed_by_number_of_assets() {
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::WithdrawAsset {
+			assets: assets(3),
+			effects: vec![],
+		}),
+		Ok(<() as WeightInfo>::withdraw_asset(3))
+	);
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::TransferAsset {
+			assets: assets(2),
+			beneficiary: account(1),
+		}),
+		Ok(<() as WeightInfo>::transfer_asset(2))
+	);
+	assert!(<() as WeightInfo>::withdraw_asset(3) > <() as WeightInfo>::withdraw_asset(1));
+}
+
+#[test]
+fn orders_are_bounded_by_the_assets_in_holding() {
+	// At most one asset is deposited
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::ReserveAssetDeposited {
+			assets: assets(2),
+			effects: vec![deposit(1)],
+		}),
+		Ok(<() as WeightInfo>::reserve_asset_deposited(2) + <() as WeightInfo>::deposit_asset(1))
+	);
+	// There are only two assets to deposit
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::ReserveAssetDeposited {
+			assets: assets(2),
+			effects: vec![deposit(10)],
+		}),
+		Ok(<() as WeightInfo>::reserve_asset_deposited(2) + <() as WeightInfo>::deposit_asset(2))
+	);
+}
+
+#[test]
+fn transact_is_weighed_with_its_call() {
+	let call: Call = frame_system::Call::remark(vec![]).into();
+	assert_eq!(
+		Weigher::shallow(&mut remark()),
+		Ok(<() as WeightInfo>::transact() + call.get_dispatch_info().weight)
+	);
+}
+
+#[test]
+fn relayed_messages_are_weighed_with_the_inner_message() {
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::RelayedFrom {
+			who: X1(AccountIndex64 {
+				network: NetworkId::Any,
+				index: 1,
+			}),
+			message: Box::new(Xcm::UnsubscribeVersion),
+		}),
+		Ok(<() as WeightInfo>::relayed_from() + <() as WeightInfo>::unsubscribe_version())
+	);
+}
+
+#[test]
+fn bought_instructions_are_weighed_deep() {
+	let mut message = Xcm::WithdrawAsset {
+		assets: assets(1),
+		effects: vec![buy_execution(vec![remark()])],
+	};
+
+	assert_eq!(
+		Weigher::shallow(&mut message),
+		Ok(<() as WeightInfo>::withdraw_asset(1) + <() as WeightInfo>::buy_execution())
+	);
+	assert_eq!(Weigher::deep(&mut message), Weigher::shallow(&mut remark()));
+}
+
+#[test]
+fn unhandled_instructions_are_not_weighable() {
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::HrmpChannelAccepted { recipient: 1 }),
+		Err(())
+	);
+	assert_eq!(
+		Weigher::shallow(&mut Xcm::WithdrawAsset {
+			assets: assets(1),
+			effects: vec![Order::ExchangeAsset {
+				give: MultiAssetFilter::Wild(WildMultiAsset::All),
+				receive: assets(1),
+			}],
+		}),
+		Err(())
+	);
+}
+
+#[test]
+fn executed_messages_use_the_weighed_weight() {
+	ExtBuilder::default().build().execute_with(|| {
+		assert!(Assets::force_create(Origin::root(), 0, 0, true, 1).is_ok());
+		assert!(Assets::force_create(Origin::root(), 1, 0, true, 1).is_ok());
+
+		let message = || Xcm::ReserveAssetDeposited {
+			assets: assets(2),
+			effects: vec![deposit(2)],
+		};
+		let weight: Weight = Weigher::shallow(&mut message()).expect("message is weighable");
+
+		assert_eq!(
+			XcmExecutor::<crate::mock::XcmConfig>::execute_xcm_in_credit(
+				MultiLocation::parent(),
+				message(),
+				weight,
+				weight
+			),
+			Outcome::Complete(weight)
+		);
+		assert_eq!(Assets::balance(0, 1), 1_000);
+		assert_eq!(Assets::balance(1, 1), 1_000);
+		assert!(sent_xcm().is_empty());
+	})
+}
@@ -0,0 +1,200 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for pallet_xcm_weights
+//!
+//! These are placeholder estimates and were not produced by the benchmark CLI. Each instruction
+//! is given a base cost and a per-asset cost, plus the storage the runtime asset transactors and
+//! traders access for it. Run `benchmark --pallet pallet_xcm_weights --extrinsic '*'` with the
+//! benchmarks of `benchmarks.rs` and replace this file with its output before relying on them.
+
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+
+use frame_support::{
+	traits::Get,
+	weights::{constants::RocksDbWeight, Weight},
+};
+use sp_std::marker::PhantomData;
+
+/// Weight functions needed for pallet_xcm_weights.
+pub trait WeightInfo {
+	fn withdraw_asset(a: u32) -> Weight;
+	fn reserve_asset_deposited(a: u32) -> Weight;
+	fn transfer_asset(a: u32) -> Weight;
+	fn transfer_reserve_asset(a: u32) -> Weight;
+	fn transact() -> Weight;
+	fn query_response() -> Weight;
+	fn relayed_from() -> Weight;
+	fn subscribe_version() -> Weight;
+	fn unsubscribe_version() -> Weight;
+	fn deposit_asset(a: u32) -> Weight;
+	fn deposit_reserve_asset(a: u32) -> Weight;
+	fn initiate_reserve_withdraw(a: u32) -> Weight;
+	fn query_holding(a: u32) -> Weight;
+	fn buy_execution() -> Weight;
+}
+
+/// Weights for pallet_xcm_weights using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: frame_system::Config> WeightInfo for SubstrateWeight<T> {
+	fn withdraw_asset(a: u32) -> Weight {
+		(8_912_000 as Weight)
+			.saturating_add((27_134_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads((2 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
+	}
+	fn reserve_asset_deposited(a: u32) -> Weight {
+		(7_846_000 as Weight).saturating_add((1_209_000 as Weight).saturating_mul(a as Weight))
+	}
+	fn transfer_asset(a: u32) -> Weight {
+		(10_118_000 as Weight)
+			.saturating_add((48_763_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn transfer_reserve_asset(a: u32) -> Weight {
+		(31_402_000 as Weight)
+			.saturating_add((50_317_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn transact() -> Weight {
+		(20_954_000 as Weight).saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn query_response() -> Weight {
+		(14_531_000 as Weight).saturating_add(T::DbWeight::get().reads(1 as Weight))
+	}
+	fn relayed_from() -> Weight {
+		(6_275_000 as Weight)
+	}
+	fn subscribe_version() -> Weight {
+		(35_727_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(6 as Weight))
+			.saturating_add(T::DbWeight::get().writes(2 as Weight))
+	}
+	fn unsubscribe_version() -> Weight {
+		(15_183_000 as Weight).saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn deposit_asset(a: u32) -> Weight {
+		(12_606_000 as Weight)
+			.saturating_add((30_921_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn deposit_reserve_asset(a: u32) -> Weight {
+		(34_005_000 as Weight)
+			.saturating_add((31_480_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+			.saturating_add(T::DbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn initiate_reserve_withdraw(a: u32) -> Weight {
+		(29_876_000 as Weight)
+			.saturating_add((1_873_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn query_holding(a: u32) -> Weight {
+		(27_560_000 as Weight)
+			.saturating_add((1_402_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(T::DbWeight::get().reads(4 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+	fn buy_execution() -> Weight {
+		(22_348_000 as Weight)
+			.saturating_add(T::DbWeight::get().reads(3 as Weight))
+			.saturating_add(T::DbWeight::get().writes(1 as Weight))
+	}
+}
+
+// For backwards compatibility and tests
+impl WeightInfo for () {
+	fn withdraw_asset(a: u32) -> Weight {
+		(8_912_000 as Weight)
+			.saturating_add((27_134_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads((2 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((2 as Weight).saturating_mul(a as Weight)))
+	}
+	fn reserve_asset_deposited(a: u32) -> Weight {
+		(7_846_000 as Weight).saturating_add((1_209_000 as Weight).saturating_mul(a as Weight))
+	}
+	fn transfer_asset(a: u32) -> Weight {
+		(10_118_000 as Weight)
+			.saturating_add((48_763_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn transfer_reserve_asset(a: u32) -> Weight {
+		(31_402_000 as Weight)
+			.saturating_add((50_317_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn transact() -> Weight {
+		(20_954_000 as Weight).saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn query_response() -> Weight {
+		(14_531_000 as Weight).saturating_add(RocksDbWeight::get().reads(1 as Weight))
+	}
+	fn relayed_from() -> Weight {
+		(6_275_000 as Weight)
+	}
+	fn subscribe_version() -> Weight {
+		(35_727_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(6 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(2 as Weight))
+	}
+	fn unsubscribe_version() -> Weight {
+		(15_183_000 as Weight).saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn deposit_asset(a: u32) -> Weight {
+		(12_606_000 as Weight)
+			.saturating_add((30_921_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn deposit_reserve_asset(a: u32) -> Weight {
+		(34_005_000 as Weight)
+			.saturating_add((31_480_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().reads((3 as Weight).saturating_mul(a as Weight)))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+			.saturating_add(RocksDbWeight::get().writes((3 as Weight).saturating_mul(a as Weight)))
+	}
+	fn initiate_reserve_withdraw(a: u32) -> Weight {
+		(29_876_000 as Weight)
+			.saturating_add((1_873_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn query_holding(a: u32) -> Weight {
+		(27_560_000 as Weight)
+			.saturating_add((1_402_000 as Weight).saturating_mul(a as Weight))
+			.saturating_add(RocksDbWeight::get().reads(4 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+	fn buy_execution() -> Weight {
+		(22_348_000 as Weight)
+			.saturating_add(RocksDbWeight::get().reads(3 as Weight))
+			.saturating_add(RocksDbWeight::get().writes(1 as Weight))
+	}
+}
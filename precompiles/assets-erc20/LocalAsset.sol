@@ -0,0 +1,66 @@
+pragma solidity ^0.4.24;
+
+import "./ERC20.sol";
+
+/**
+ * @title Extension of the ERC20 interface for local assets
+ * @dev Only available at the precompile addresses of the local assets. Each function is
+ * dispatched with the caller as origin, and thus requires the caller to hold the asset role
+ * (owner, issuer, admin or freezer) mentioned in its description.
+ */
+interface LocalAssetExtendedErc20 is IERC20 {
+  /**
+   * @dev Mint tokens to an address. Requires the issuer role.
+   * Selector: 40c10f19
+   * @param to address The address to which the minted tokens are sent.
+   * @param value uint256 The amount of tokens to be minted.
+   */
+  function mint(address to, uint256 value) external returns (bool);
+
+  /**
+   * @dev Burn tokens from an address. Requires the admin role.
+   * Selector: 9dc29fac
+   * @param from address The address from which the tokens are burnt.
+   * @param value uint256 The amount of tokens to be burnt.
+   */
+  function burn(address from, uint256 value) external returns (bool);
+
+  /**
+   * @dev Freeze an account, preventing it from transferring the asset. Requires the freezer role.
+   * Selector: 8d1fdf2f
+   * @param account address The address to be frozen.
+   */
+  function freeze(address account) external returns (bool);
+
+  /**
+   * @dev Thaw a frozen account. Requires the admin role.
+   * Selector: 5ea20216
+   * @param account address The address to be thawed.
+   */
+  function thaw(address account) external returns (bool);
+
+  /**
+   * @dev Transfer the ownership of the asset. Requires the owner role.
+   * Selector: f2fde38b
+   * @param owner address The new owner of the asset.
+   */
+  function transferOwnership(address owner) external returns (bool);
+
+  /**
+   * @dev Set the issuer, admin and freezer of the asset. Requires the owner role.
+   * Selector: c7d93c59
+   * @param issuer address The new issuer of the asset.
+   * @param admin address The new admin of the asset.
+   * @param freezer address The new freezer of the asset.
+   */
+  function setTeam(address issuer, address admin, address freezer) external returns (bool);
+
+  /**
+   * @dev Set the metadata of the asset. Requires the owner role, who pays the metadata deposit.
+   * Selector: 37d2c2f4
+   * @param name string The name of the asset.
+   * @param symbol string The symbol of the asset.
+   * @param decimals uint8 The number of decimals of the asset.
+   */
+  function setMetadata(string name, string symbol, uint8 decimals) external returns (bool);
+}
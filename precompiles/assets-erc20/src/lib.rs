@@ -19,21 +19,21 @@
 
 use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::traits::fungibles::Inspect;
-use frame_support::traits::OriginTrait;
+use frame_support::traits::{Get, OriginTrait};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
 	sp_runtime::traits::StaticLookup,
 };
-use sp_runtime::traits::Zero;
+use sp_runtime::traits::{Saturating, Zero};
 
 use pallet_evm::{AddressMapping, Precompile, PrecompileSet};
 use precompile_utils::{
-	error, keccak256, Address, EvmData, EvmDataReader, EvmDataWriter, EvmResult, Gasometer,
+	error, keccak256, Address, Bytes, EvmData, EvmDataReader, EvmDataWriter, EvmResult, Gasometer,
 	LogsBuilder, RuntimeHelper,
 };
 
 use sp_core::{H160, U256};
-use sp_std::{convert::TryFrom, marker::PhantomData, vec};
+use sp_std::{convert::TryFrom, marker::PhantomData, vec, vec::Vec};
 
 #[cfg(test)]
 mod mock;
@@ -61,16 +61,24 @@ pub enum Action {
 	Transfer = "transfer(address,uint256)",
 	Approve = "approve(address,uint256)",
 	TransferFrom = "transferFrom(address,address,uint256)",
+	// Asset management, only available for local assets
+	Mint = "mint(address,uint256)",
+	Burn = "burn(address,uint256)",
+	Freeze = "freeze(address)",
+	Thaw = "thaw(address)",
+	TransferOwnership = "transferOwnership(address)",
+	SetTeam = "setTeam(address,address,address)",
+	SetMetadata = "setMetadata(string,string,uint8)",
 }
 
 /// This trait ensure we can convert AccountIds to AssetIds
 /// We will require Runtime to have this trait implemented
 pub trait AccountIdAssetIdConversion<Account, AssetId> {
-	// Get assetId from account
-	fn account_to_asset_id(account: Account) -> Option<AssetId>;
+	// Get assetId and the address prefix it was found under from account
+	fn account_to_asset_id(account: Account) -> Option<([u8; 4], AssetId)>;
 
-	// Get AccountId from AssetId
-	fn asset_id_to_account(asset_id: AssetId) -> Account;
+	// Get AccountId from AssetId and address prefix
+	fn asset_id_to_account(prefix: [u8; 4], asset_id: AssetId) -> Account;
 }
 
 /// The following distribution has been decided for the precompiles
@@ -82,19 +90,25 @@ pub trait AccountIdAssetIdConversion<Account, AssetId> {
 /// The precompile for AssetId X, where X is a u128 (i.e.16 bytes), if 0XFFFFFFFF + Bytes(AssetId)
 /// In order to route the address to Erc20AssetsPrecompile<R>, we first check whether the AssetId
 /// exists in pallet-assets
-/// We cannot do this right now, so instead we check whether the total supply is zero. If so, we
-/// do not route to the precompiles
+/// We cannot do this right now, so instead we check whether the minimum balance is zero. Since
+/// pallet-assets does not allow to create assets with a zero minimum balance, this only happens
+/// when the asset does not exist, in which case we do not route to the precompiles
 
 /// This means that every address that starts with 0xFFFFFFFF will go through an additional db read,
 /// but the probability for this to happen is 2^-32 for random addresses
-pub struct Erc20AssetsPrecompileSet<Runtime, Instance: 'static = ()>(
-	PhantomData<(Runtime, Instance)>,
+///
+/// If IsLocal is true, the precompiles of the set also expose the asset management functions
+/// (mint, burn, freeze, thaw, transferOwnership, setTeam and setMetadata), which are dispatched
+/// with the caller as origin, and thus only succeed for the owner/admin/issuer/freezer of the asset
+pub struct Erc20AssetsPrecompileSet<Runtime, Instance: 'static = (), IsLocal = ()>(
+	PhantomData<(Runtime, Instance, IsLocal)>,
 );
 
-impl<Runtime, Instance> PrecompileSet for Erc20AssetsPrecompileSet<Runtime, Instance>
+impl<Runtime, Instance, IsLocal> PrecompileSet
+	for Erc20AssetsPrecompileSet<Runtime, Instance, IsLocal>
 where
 	Instance: 'static,
-	Erc20AssetsPrecompile<Runtime, Instance>: Precompile,
+	Erc20AssetsPrecompile<Runtime, Instance, IsLocal>: Precompile,
 	Runtime: pallet_assets::Config<Instance> + pallet_evm::Config,
 	Runtime: AccountIdAssetIdConversion<Runtime::AccountId, AssetIdOf<Runtime, Instance>>,
 {
@@ -104,19 +118,20 @@ where
 		target_gas: Option<u64>,
 		context: &Context,
 	) -> Option<Result<PrecompileOutput, ExitError>> {
-		if let Some(asset_id) =
+		if let Some((_, asset_id)) =
 			Runtime::account_to_asset_id(Runtime::AddressMapping::into_account_id(address))
 		{
-			// If the assetId has non-zero supply
-			// "total_supply" returns both 0 if the assetId does not exist or if the supply is 0
-			// The assumption I am making here is that a 0 supply asset is not interesting from
-			// the perspective of the precompiles. Once pallet-assets has more publicly accesible
-			// storage we can use another function for this, like check_asset_existence.
+			// If the assetId has non-zero minimum balance
+			// "minimum_balance" returns 0 only if the assetId does not exist, as pallet-assets
+			// rejects the creation of assets with a 0 minimum balance. We cannot rely on the
+			// supply, as local assets are created without supply and still need to be minted
+			// through the precompiles. Once pallet-assets has more publicly accesible storage we
+			// can use another function for this, like check_asset_existence.
 			// The other options is to check the asset existence in pallet-asset-manager, but
 			// this makes the precompiles dependent on such a pallet, which is not ideal
-			if !pallet_assets::Pallet::<Runtime, Instance>::total_supply(asset_id).is_zero() {
+			if !pallet_assets::Pallet::<Runtime, Instance>::minimum_balance(asset_id).is_zero() {
 				return Some(
-					<Erc20AssetsPrecompile<Runtime, Instance> as Precompile>::execute(
+					<Erc20AssetsPrecompile<Runtime, Instance, IsLocal> as Precompile>::execute(
 						input, target_gas, context,
 					),
 				);
@@ -126,11 +141,14 @@ where
 	}
 }
 
-pub struct Erc20AssetsPrecompile<Runtime, Instance: 'static = ()>(PhantomData<(Runtime, Instance)>);
+pub struct Erc20AssetsPrecompile<Runtime, Instance: 'static = (), IsLocal = ()>(
+	PhantomData<(Runtime, Instance, IsLocal)>,
+);
 
-impl<Runtime, Instance> Precompile for Erc20AssetsPrecompile<Runtime, Instance>
+impl<Runtime, Instance, IsLocal> Precompile for Erc20AssetsPrecompile<Runtime, Instance, IsLocal>
 where
 	Instance: 'static,
+	IsLocal: Get<bool>,
 	Runtime: pallet_assets::Config<Instance> + pallet_evm::Config,
 	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
 	Runtime::Call: From<pallet_assets::Call<Runtime, Instance>>,
@@ -153,11 +171,30 @@ where
 			Action::Approve => Self::approve(input, target_gas, context),
 			Action::Transfer => Self::transfer(input, target_gas, context),
 			Action::TransferFrom => Self::transfer_from(input, target_gas, context),
+			// The asset management functions are not exposed for non-local assets
+			Action::Mint
+			| Action::Burn
+			| Action::Freeze
+			| Action::Thaw
+			| Action::TransferOwnership
+			| Action::SetTeam
+			| Action::SetMetadata
+				if !IsLocal::get() =>
+			{
+				Err(error("unknown selector"))
+			}
+			Action::Mint => Self::mint(input, target_gas, context),
+			Action::Burn => Self::burn(input, target_gas, context),
+			Action::Freeze => Self::freeze(input, target_gas, context),
+			Action::Thaw => Self::thaw(input, target_gas, context),
+			Action::TransferOwnership => Self::transfer_ownership(input, target_gas, context),
+			Action::SetTeam => Self::set_team(input, target_gas, context),
+			Action::SetMetadata => Self::set_metadata(input, target_gas, context),
 		}
 	}
 }
 
-impl<Runtime, Instance> Erc20AssetsPrecompile<Runtime, Instance>
+impl<Runtime, Instance, IsLocal> Erc20AssetsPrecompile<Runtime, Instance, IsLocal>
 where
 	Instance: 'static,
 	Runtime: pallet_assets::Config<Instance> + pallet_evm::Config + frame_system::Config,
@@ -181,7 +218,7 @@ where
 		// Parse input.
 		input.expect_arguments(0)?;
 
-		let asset_id: AssetIdOf<Runtime, Instance> =
+		let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
 			Runtime::account_to_asset_id(execution_address).ok_or(error("non-assetId address"))?;
 
 		// Fetch info.
@@ -214,7 +251,7 @@ where
 		let amount: U256 = {
 			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
 
-			let asset_id: AssetIdOf<Runtime, Instance> =
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
 				Runtime::account_to_asset_id(execution_address)
 					.ok_or(error("non-assetId address"))?;
 
@@ -250,7 +287,7 @@ where
 		// Fetch info.
 		let amount: U256 = {
 			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
-			let asset_id: AssetIdOf<Runtime, Instance> =
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
 				Runtime::account_to_asset_id(execution_address)
 					.ok_or(error("non-assetId address"))?;
 
@@ -287,7 +324,7 @@ where
 
 		{
 			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
-			let asset_id: AssetIdOf<Runtime, Instance> =
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
 				Runtime::account_to_asset_id(execution_address)
 					.ok_or(error("non-assetId address"))?;
 
@@ -371,7 +408,7 @@ where
 		// Build call with origin.
 		{
 			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
-			let asset_id: AssetIdOf<Runtime, Instance> =
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
 				Runtime::account_to_asset_id(execution_address)
 					.ok_or(error("non-assetId address"))?;
 
@@ -425,7 +462,7 @@ where
 
 		{
 			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
-			let asset_id: AssetIdOf<Runtime, Instance> =
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
 				Runtime::account_to_asset_id(execution_address)
 					.ok_or(error("non-assetId address"))?;
 			let caller: Runtime::AccountId =
@@ -476,4 +513,351 @@ where
 				.build(),
 		})
 	}
+
+	fn mint(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+		gasometer.record_log_costs_manual(3, 32)?;
+
+		// Parse input.
+		input.expect_arguments(2)?;
+
+		let to: H160 = input.read::<Address>()?.into();
+		let amount = input.read::<BalanceOf<Runtime, Instance>>()?;
+
+		// Build call with origin.
+		{
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let to = Runtime::AddressMapping::into_account_id(to);
+
+			// Dispatch call (if enough gas).
+			// Only the issuer of the asset can mint
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::mint(
+					asset_id,
+					Runtime::Lookup::unlookup(to),
+					amount,
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: LogsBuilder::new(context.address)
+				.log3(
+					SELECTOR_LOG_TRANSFER,
+					H160::default(),
+					to,
+					EvmDataWriter::new().write(amount).build(),
+				)
+				.build(),
+		})
+	}
+
+	fn burn(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+		gasometer.record_log_costs_manual(3, 32)?;
+
+		// Parse input.
+		input.expect_arguments(2)?;
+
+		let from: H160 = input.read::<Address>()?.into();
+		let amount = input.read::<BalanceOf<Runtime, Instance>>()?;
+
+		// Build call with origin.
+		// pallet-assets burns at most the balance of the account, and also the dust left behind,
+		// so the burnt amount is taken from the balance difference rather than from the input.
+		let burnt = {
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let from = Runtime::AddressMapping::into_account_id(from);
+
+			gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+			let balance_before =
+				pallet_assets::Pallet::<Runtime, Instance>::balance(asset_id, &from);
+
+			// Dispatch call (if enough gas).
+			// Only the admin of the asset can burn
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::burn(
+					asset_id,
+					Runtime::Lookup::unlookup(from.clone()),
+					amount,
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+
+			let balance_after =
+				pallet_assets::Pallet::<Runtime, Instance>::balance(asset_id, &from);
+			balance_before.saturating_sub(balance_after)
+		};
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: LogsBuilder::new(context.address)
+				.log3(
+					SELECTOR_LOG_TRANSFER,
+					from,
+					H160::default(),
+					EvmDataWriter::new().write(burnt).build(),
+				)
+				.build(),
+		})
+	}
+
+	fn freeze(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Parse input.
+		input.expect_arguments(1)?;
+
+		let who: H160 = input.read::<Address>()?.into();
+
+		// Build call with origin.
+		{
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let who = Runtime::AddressMapping::into_account_id(who);
+
+			// Dispatch call (if enough gas).
+			// Only the freezer of the asset can freeze
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::freeze(
+					asset_id,
+					Runtime::Lookup::unlookup(who),
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: vec![],
+		})
+	}
+
+	fn thaw(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Parse input.
+		input.expect_arguments(1)?;
+
+		let who: H160 = input.read::<Address>()?.into();
+
+		// Build call with origin.
+		{
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let who = Runtime::AddressMapping::into_account_id(who);
+
+			// Dispatch call (if enough gas).
+			// Only the admin of the asset can thaw
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::thaw(
+					asset_id,
+					Runtime::Lookup::unlookup(who),
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: vec![],
+		})
+	}
+
+	fn transfer_ownership(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Parse input.
+		input.expect_arguments(1)?;
+
+		let owner: H160 = input.read::<Address>()?.into();
+
+		// Build call with origin.
+		{
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let owner = Runtime::AddressMapping::into_account_id(owner);
+
+			// Dispatch call (if enough gas).
+			// Only the owner of the asset can transfer its ownership
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::transfer_ownership(
+					asset_id,
+					Runtime::Lookup::unlookup(owner),
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: vec![],
+		})
+	}
+
+	fn set_team(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Parse input.
+		input.expect_arguments(3)?;
+
+		let issuer: H160 = input.read::<Address>()?.into();
+		let admin: H160 = input.read::<Address>()?.into();
+		let freezer: H160 = input.read::<Address>()?.into();
+
+		// Build call with origin.
+		{
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let issuer = Runtime::AddressMapping::into_account_id(issuer);
+			let admin = Runtime::AddressMapping::into_account_id(admin);
+			let freezer = Runtime::AddressMapping::into_account_id(freezer);
+
+			// Dispatch call (if enough gas).
+			// Only the owner of the asset can set its team
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::set_team(
+					asset_id,
+					Runtime::Lookup::unlookup(issuer),
+					Runtime::Lookup::unlookup(admin),
+					Runtime::Lookup::unlookup(freezer),
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: vec![],
+		})
+	}
+
+	fn set_metadata(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Parse input.
+		input.expect_arguments(3)?;
+
+		let name = input.read::<Bytes>()?;
+		let symbol = input.read::<Bytes>()?;
+		let decimals = input.read::<u8>()?;
+
+		// Build call with origin.
+		{
+			let execution_address = Runtime::AddressMapping::into_account_id(context.address);
+			let (_, asset_id): (_, AssetIdOf<Runtime, Instance>) =
+				Runtime::account_to_asset_id(execution_address)
+					.ok_or(error("non-assetId address"))?;
+
+			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+
+			// Dispatch call (if enough gas).
+			// Only the owner of the asset can set its metadata, and it pays the deposit for it
+			let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+				Some(origin).into(),
+				pallet_assets::Call::<Runtime, Instance>::set_metadata(
+					asset_id,
+					name.as_bytes().to_vec(),
+					symbol.as_bytes().to_vec(),
+					decimals,
+				),
+				gasometer.remaining_gas()?,
+			)?;
+			gasometer.record_cost(used_gas)?;
+		}
+
+		// Build output.
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: vec![],
+			logs: vec![],
+		})
+	}
 }
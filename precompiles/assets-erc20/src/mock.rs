@@ -90,14 +90,14 @@ impl AddressMapping<Account> for Account {
 impl AccountIdAssetIdConversion<AccountId, AssetId> for Runtime {
 	/// The way to convert an account to assetId is by ensuring that the prefix is 0XFFFFFFFF
 	/// and by taking the lowest 128 bits as the assetId
-	fn account_to_asset_id(account: AccountId) -> Option<AssetId> {
+	fn account_to_asset_id(account: AccountId) -> Option<([u8; 4], AssetId)> {
 		match account {
-			Account::AssetId(asset_id) => Some(asset_id),
+			Account::AssetId(asset_id) => Some(([255u8; 4], asset_id)),
 			_ => None,
 		}
 	}
 
-	fn asset_id_to_account(asset_id: AssetId) -> AccountId {
+	fn asset_id_to_account(_prefix: [u8; 4], asset_id: AssetId) -> AccountId {
 		Account::AssetId(asset_id)
 	}
 }
@@ -127,6 +127,13 @@ impl From<Account> for H256 {
 	}
 }
 
+parameter_types! {
+	pub const IsLocal: bool = true;
+}
+
+// The same assets, exposed through the precompiles with the asset management functions
+pub type LocalAssetsPrecompileSet = Erc20AssetsPrecompileSet<Runtime, (), IsLocal>;
+
 parameter_types! {
 	pub const BlockHashCount: u64 = 250;
 	pub const SS58Prefix: u8 = 42;
@@ -101,6 +101,13 @@ fn selectors() {
 	//assert_eq!(u32::from(Action::Allowance), 0xdd62ed3e);
 	assert_eq!(u32::from(Action::Transfer), 0xa9059cbb);
 	assert_eq!(u32::from(Action::TransferFrom), 0x23b872dd);
+	assert_eq!(u32::from(Action::Mint), 0x40c10f19);
+	assert_eq!(u32::from(Action::Burn), 0x9dc29fac);
+	assert_eq!(u32::from(Action::Freeze), 0x8d1fdf2f);
+	assert_eq!(u32::from(Action::Thaw), 0x5ea20216);
+	assert_eq!(u32::from(Action::TransferOwnership), 0xf2fde38b);
+	assert_eq!(u32::from(Action::SetTeam), 0xc7d93c59);
+	assert_eq!(u32::from(Action::SetMetadata), 0x37d2c2f4);
 
 	assert_eq!(
 		crate::SELECTOR_LOG_TRANSFER,
@@ -941,3 +948,509 @@ fn transfer_from_self() {
 			);
 		});
 }
+
+#[test]
+fn local_functions_cannot_be_accessed_by_non_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+
+			assert_eq!(
+				Erc20AssetsPrecompileSet::<Runtime>::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Mint)
+						.write(Address(Account::Bob.into()))
+						.write(U256::from(400))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Err(error("unknown selector")))
+			);
+		});
+}
+
+#[test]
+fn mint_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+
+			// The asset has no supply yet, but is still reachable
+			let output = LocalAssetsPrecompileSet::execute(
+				Account::AssetId(0u128).into(),
+				&EvmDataWriter::new()
+					.write_selector(Action::Mint)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(400))
+					.build(),
+				None,
+				&evm::Context {
+					address: Account::AssetId(0u128).into(),
+					caller: Account::Alice.into(),
+					apparent_value: From::from(0),
+				},
+			)
+			.expect("the asset exists")
+			.expect("the issuer can mint");
+
+			assert_eq!(output.exit_status, ExitSucceed::Returned);
+			assert_eq!(
+				output.logs,
+				LogsBuilder::new(Account::AssetId(0u128).into())
+					.log3(
+						SELECTOR_LOG_TRANSFER,
+						H160::default(),
+						Account::Bob,
+						EvmDataWriter::new().write(U256::from(400)).build(),
+					)
+					.build()
+			);
+			assert_eq!(Assets::balance(0u128, Account::Bob), 400);
+		});
+}
+
+#[test]
+fn mint_local_assets_requires_issuer() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Mint)
+						.write(Address(Account::Bob.into()))
+						.write(U256::from(400))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Bob.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Err(ExitError::Other(str)))
+					if str.contains("Dispatched call failed with error: DispatchErrorWithPostInfo")
+					&& str.contains("NoPermission")
+			);
+		});
+}
+
+#[test]
+fn burn_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+			assert_ok!(Assets::mint(
+				Origin::signed(Account::Alice),
+				0u128,
+				Account::Bob.into(),
+				1000
+			));
+
+			let output = LocalAssetsPrecompileSet::execute(
+				Account::AssetId(0u128).into(),
+				&EvmDataWriter::new()
+					.write_selector(Action::Burn)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(400))
+					.build(),
+				None,
+				&evm::Context {
+					address: Account::AssetId(0u128).into(),
+					caller: Account::Alice.into(),
+					apparent_value: From::from(0),
+				},
+			)
+			.expect("the asset exists")
+			.expect("the admin can burn");
+
+			assert_eq!(
+				output.logs,
+				LogsBuilder::new(Account::AssetId(0u128).into())
+					.log3(
+						SELECTOR_LOG_TRANSFER,
+						Account::Bob,
+						H160::default(),
+						EvmDataWriter::new().write(U256::from(400)).build(),
+					)
+					.build()
+			);
+			assert_eq!(Assets::balance(0u128, Account::Bob), 600);
+		});
+}
+
+#[test]
+fn burn_local_assets_logs_the_burnt_amount() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+			assert_ok!(Assets::mint(
+				Origin::signed(Account::Alice),
+				0u128,
+				Account::Bob.into(),
+				1000
+			));
+
+			// Burning more than the balance only burns the balance
+			let output = LocalAssetsPrecompileSet::execute(
+				Account::AssetId(0u128).into(),
+				&EvmDataWriter::new()
+					.write_selector(Action::Burn)
+					.write(Address(Account::Bob.into()))
+					.write(U256::from(2000))
+					.build(),
+				None,
+				&evm::Context {
+					address: Account::AssetId(0u128).into(),
+					caller: Account::Alice.into(),
+					apparent_value: From::from(0),
+				},
+			)
+			.expect("the asset exists")
+			.expect("the admin can burn");
+
+			assert_eq!(
+				output.logs,
+				LogsBuilder::new(Account::AssetId(0u128).into())
+					.log3(
+						SELECTOR_LOG_TRANSFER,
+						Account::Bob,
+						H160::default(),
+						EvmDataWriter::new().write(U256::from(1000)).build(),
+					)
+					.build()
+			);
+			assert_eq!(Assets::balance(0u128, Account::Bob), 0);
+		});
+}
+
+#[test]
+fn freeze_and_thaw_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+			assert_ok!(Assets::mint(
+				Origin::signed(Account::Alice),
+				0u128,
+				Account::Bob.into(),
+				1000
+			));
+
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Freeze)
+						.write(Address(Account::Bob.into()))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+
+			// Bob cannot transfer while frozen
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Transfer)
+						.write(Address(Account::Charlie.into()))
+						.write(U256::from(400))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Bob.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Err(ExitError::Other(str)))
+					if str.contains("Dispatched call failed with error: DispatchErrorWithPostInfo")
+					&& str.contains("Frozen")
+			);
+
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Thaw)
+						.write(Address(Account::Bob.into()))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+
+			// Bob can transfer again
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Transfer)
+						.write(Address(Account::Charlie.into()))
+						.write(U256::from(400))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Bob.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+			assert_eq!(Assets::balance(0u128, Account::Charlie), 400);
+		});
+}
+
+#[test]
+fn transfer_ownership_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::TransferOwnership)
+						.write(Address(Account::Bob.into()))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+
+			// Alice is no longer the owner
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::TransferOwnership)
+						.write(Address(Account::Alice.into()))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Err(ExitError::Other(str)))
+					if str.contains("Dispatched call failed with error: DispatchErrorWithPostInfo")
+					&& str.contains("NoPermission")
+			);
+		});
+}
+
+#[test]
+fn set_team_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::SetTeam)
+						.write(Address(Account::Bob.into()))
+						.write(Address(Account::Bob.into()))
+						.write(Address(Account::Bob.into()))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+
+			// Bob is now the issuer, and can mint
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Mint)
+						.write(Address(Account::Bob.into()))
+						.write(U256::from(400))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Bob.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+			assert_eq!(Assets::balance(0u128, Account::Bob), 400);
+		});
+}
+
+#[test]
+fn set_metadata_local_assets() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(Assets::force_create(
+				Origin::root(),
+				0u128,
+				Account::Alice.into(),
+				true,
+				1
+			));
+
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::SetMetadata)
+						.write::<Bytes>("Local Token".into())
+						.write::<Bytes>("LOC".into())
+						.write(12u8)
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					..
+				}))
+			);
+
+			// The name is above the string limit of the mock
+			assert_matches!(
+				LocalAssetsPrecompileSet::execute(
+					Account::AssetId(0u128).into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::SetMetadata)
+						.write::<Bytes>([b'a'; 51][..].into())
+						.write::<Bytes>("LOC".into())
+						.write(12u8)
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::AssetId(0u128).into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Err(ExitError::Other(str)))
+					if str.contains("Dispatched call failed with error: DispatchErrorWithPostInfo")
+					&& str.contains("BadMetadata")
+			);
+		});
+}
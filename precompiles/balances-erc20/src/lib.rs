@@ -19,7 +19,6 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 #![cfg_attr(test, feature(assert_matches))]
 
-use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
 	sp_runtime::traits::{CheckedSub, StaticLookup},
@@ -31,16 +30,15 @@ use pallet_balances::pallet::{
 	Instance1, Instance10, Instance11, Instance12, Instance13, Instance14, Instance15, Instance16,
 	Instance2, Instance3, Instance4, Instance5, Instance6, Instance7, Instance8, Instance9,
 };
-use pallet_evm::{AddressMapping, Precompile};
+use pallet_evm::AddressMapping;
 use precompile_utils::{
-	error, keccak256, Address, Bytes, EvmDataReader, EvmDataWriter, EvmResult, Gasometer,
-	LogsBuilder, RuntimeHelper,
+	error, keccak256, Address, Bytes, EvmDataWriter, EvmResult, LogsBuilder, PrecompileHandle,
+	RuntimeHelper,
 };
 use sp_core::{H160, U256};
 use sp_std::{
 	convert::{TryFrom, TryInto},
 	marker::PhantomData,
-	vec,
 };
 
 #[cfg(test)]
@@ -114,20 +112,6 @@ pub type ApprovesStorage<Runtime, Instance> = StorageDoubleMap<
 	BalanceOf<Runtime, Instance>,
 >;
 
-#[precompile_utils::generate_function_selector]
-#[derive(Debug, PartialEq, num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
-pub enum Action {
-	TotalSupply = "totalSupply()",
-	BalanceOf = "balanceOf(address)",
-	Allowance = "allowance(address,address)",
-	Transfer = "transfer(address,uint256)",
-	Approve = "approve(address,uint256)",
-	TransferFrom = "transferFrom(address,address,uint256)",
-	Name = "name()",
-	Symbol = "symbol()",
-	Decimals = "decimals()",
-}
-
 /// Metadata of an ERC20 token.
 pub trait Erc20Metadata {
 	/// Returns the name of the token.
@@ -147,38 +131,7 @@ pub struct Erc20BalancesPrecompile<Runtime, Metadata: Erc20Metadata, Instance: '
 	PhantomData<(Runtime, Metadata, Instance)>,
 );
 
-impl<Runtime, Metadata, Instance> Precompile
-	for Erc20BalancesPrecompile<Runtime, Metadata, Instance>
-where
-	Metadata: Erc20Metadata,
-	Instance: InstanceToPrefix + 'static,
-	Runtime: pallet_balances::Config<Instance> + pallet_evm::Config,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
-	Runtime::Call: From<pallet_balances::Call<Runtime, Instance>>,
-	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
-	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
-{
-	fn execute(
-		input: &[u8], //Reminder this is big-endian
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut input = EvmDataReader::new(input);
-
-		match &input.read_selector()? {
-			Action::TotalSupply => Self::total_supply(input, target_gas),
-			Action::BalanceOf => Self::balance_of(input, target_gas),
-			Action::Allowance => Self::allowance(input, target_gas),
-			Action::Approve => Self::approve(input, target_gas, context),
-			Action::Transfer => Self::transfer(input, target_gas, context),
-			Action::TransferFrom => Self::transfer_from(input, target_gas, context),
-			Action::Name => Self::name(input, target_gas, context),
-			Action::Symbol => Self::symbol(input, target_gas, context),
-			Action::Decimals => Self::decimals(input, target_gas, context),
-		}
-	}
-}
-
+#[precompile_utils::precompile]
 impl<Runtime, Metadata, Instance> Erc20BalancesPrecompile<Runtime, Metadata, Instance>
 where
 	Metadata: Erc20Metadata,
@@ -189,139 +142,79 @@ where
 	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
 	BalanceOf<Runtime, Instance>: TryFrom<U256> + Into<U256>,
 {
-	fn total_supply(input: EvmDataReader, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-
-		// Parse input.
-		input.expect_arguments(0)?;
-
+	#[precompile::public("totalSupply()")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn total_supply() -> EvmResult<U256> {
 		// Fetch info.
-		let amount: U256 = pallet_balances::Pallet::<Runtime, Instance>::total_issuance().into();
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(amount).build(),
-			logs: vec![],
-		})
+		Ok(pallet_balances::Pallet::<Runtime, Instance>::total_issuance().into())
 	}
 
-	fn balance_of(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-
-		// Read input.
-		input.expect_arguments(1)?;
-
-		let owner: H160 = input.read::<Address>()?.into();
-
+	#[precompile::public("balanceOf(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn balance_of(owner: Address) -> EvmResult<U256> {
 		// Fetch info.
-		let amount: U256 = {
-			let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
-			pallet_balances::Pallet::<Runtime, Instance>::usable_balance(&owner).into()
-		};
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(amount).build(),
-			logs: vec![],
-		})
-	}
-
-	fn allowance(mut input: EvmDataReader, target_gas: Option<u64>) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner.into());
 
-		// Read input.
-		input.expect_arguments(2)?;
-
-		let owner: H160 = input.read::<Address>()?.into();
-		let spender: H160 = input.read::<Address>()?.into();
+		Ok(pallet_balances::Pallet::<Runtime, Instance>::usable_balance(&owner).into())
+	}
 
+	#[precompile::public("allowance(address,address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn allowance(owner: Address, spender: Address) -> EvmResult<U256> {
 		// Fetch info.
-		let amount: U256 = {
-			let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner);
-			let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
+		let owner: Runtime::AccountId = Runtime::AddressMapping::into_account_id(owner.into());
+		let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender.into());
 
-			ApprovesStorage::<Runtime, Instance>::get(owner, spender)
-				.unwrap_or_default()
-				.into()
-		};
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(amount).build(),
-			logs: vec![],
-		})
+		Ok(ApprovesStorage::<Runtime, Instance>::get(owner, spender)
+			.unwrap_or_default()
+			.into())
 	}
 
-	fn approve(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
-		gasometer.record_log_costs_manual(3, 32)?;
-
-		// Parse input.
-		input.expect_arguments(2)?;
+	#[precompile::public("approve(address,uint256)")]
+	#[precompile::gas(writes = 1)]
+	fn approve(handle: &mut PrecompileHandle, spender: Address, amount: U256) -> EvmResult<bool> {
+		handle.gasometer().record_log_costs_manual(3, 32)?;
 
-		let spender: H160 = input.read::<Address>()?.into();
-		let amount: U256 = input.read()?;
+		let caller = handle.context().caller;
+		let spender: H160 = spender.into();
 
 		// Write into storage.
 		{
-			let caller: Runtime::AccountId =
-				Runtime::AddressMapping::into_account_id(context.caller);
+			let caller: Runtime::AccountId = Runtime::AddressMapping::into_account_id(caller);
 			let spender: Runtime::AccountId = Runtime::AddressMapping::into_account_id(spender);
 			let amount = Self::u256_to_amount(amount)?;
 
 			ApprovesStorage::<Runtime, Instance>::insert(caller, spender, amount);
 		}
 
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(true).build(),
-			logs: LogsBuilder::new(context.address)
+		// Emit log.
+		handle.add_logs(
+			LogsBuilder::new(handle.context().address)
 				.log3(
 					SELECTOR_LOG_APPROVAL,
-					context.caller,
+					caller,
 					spender,
 					EvmDataWriter::new().write(amount).build(),
 				)
 				.build(),
-		})
-	}
+		);
 
-	fn transfer(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_log_costs_manual(3, 32)?;
+		Ok(true)
+	}
 
-		// Parse input.
-		input.expect_arguments(2)?;
+	#[precompile::public("transfer(address,uint256)")]
+	fn transfer(handle: &mut PrecompileHandle, to: Address, amount: U256) -> EvmResult<bool> {
+		handle.gasometer().record_log_costs_manual(3, 32)?;
 
-		let to: H160 = input.read::<Address>()?.into();
-		let amount: U256 = input.read()?;
+		let caller = handle.context().caller;
+		let to: H160 = to.into();
 
 		// Build call with origin.
 		{
-			let origin = Runtime::AddressMapping::into_account_id(context.caller);
+			let origin = Runtime::AddressMapping::into_account_id(caller);
 			let to = Runtime::AddressMapping::into_account_id(to);
 			let amount = Self::u256_to_amount(amount)?;
 
@@ -332,46 +225,42 @@ where
 					Runtime::Lookup::unlookup(to),
 					amount,
 				),
-				gasometer.remaining_gas()?,
+				handle.remaining_gas()?,
 			)?;
-			gasometer.record_cost(used_gas)?;
+			handle.record_cost(used_gas)?;
 		}
 
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(true).build(),
-			logs: LogsBuilder::new(context.address)
+		// Emit log.
+		handle.add_logs(
+			LogsBuilder::new(handle.context().address)
 				.log3(
 					SELECTOR_LOG_TRANSFER,
-					context.caller,
+					caller,
 					to,
 					EvmDataWriter::new().write(amount).build(),
 				)
 				.build(),
-		})
+		);
+
+		Ok(true)
 	}
 
+	#[precompile::public("transferFrom(address,address,uint256)")]
+	#[precompile::gas(reads = 1, writes = 1)]
 	fn transfer_from(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_write_gas_cost())?;
-		gasometer.record_log_costs_manual(3, 32)?;
-
-		// Parse input.
-		input.expect_arguments(3)?;
-		let from: H160 = input.read::<Address>()?.into();
-		let to: H160 = input.read::<Address>()?.into();
-		let amount: U256 = input.read()?;
+		handle: &mut PrecompileHandle,
+		from: Address,
+		to: Address,
+		amount: U256,
+	) -> EvmResult<bool> {
+		handle.gasometer().record_log_costs_manual(3, 32)?;
+
+		let from: H160 = from.into();
+		let to: H160 = to.into();
 
 		{
 			let caller: Runtime::AccountId =
-				Runtime::AddressMapping::into_account_id(context.caller);
+				Runtime::AddressMapping::into_account_id(handle.context().caller);
 			let from: Runtime::AccountId = Runtime::AddressMapping::into_account_id(from);
 			let to: Runtime::AccountId = Runtime::AddressMapping::into_account_id(to);
 			let amount = Self::u256_to_amount(amount)?;
@@ -402,17 +291,14 @@ where
 					Runtime::Lookup::unlookup(to),
 					amount,
 				),
-				gasometer.remaining_gas()?,
+				handle.remaining_gas()?,
 			)?;
-			gasometer.record_cost(used_gas)?;
+			handle.record_cost(used_gas)?;
 		}
 
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(true).build(),
-			logs: LogsBuilder::new(context.address)
+		// Emit log.
+		handle.add_logs(
+			LogsBuilder::new(handle.context().address)
 				.log3(
 					SELECTOR_LOG_TRANSFER,
 					from,
@@ -420,41 +306,27 @@ where
 					EvmDataWriter::new().write(amount).build(),
 				)
 				.build(),
-		})
+		);
+
+		Ok(true)
 	}
 
-	fn name(_: EvmDataReader, _: Option<u64>, _: &Context) -> EvmResult<PrecompileOutput> {
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: 0,
-			output: EvmDataWriter::new()
-				.write::<Bytes>(Metadata::name().into())
-				.build(),
-			logs: Default::default(),
-		})
+	#[precompile::public("name()")]
+	#[precompile::view]
+	fn name() -> EvmResult<Bytes> {
+		Ok(Metadata::name().into())
 	}
 
-	fn symbol(_: EvmDataReader, _: Option<u64>, _: &Context) -> EvmResult<PrecompileOutput> {
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: 0,
-			output: EvmDataWriter::new()
-				.write::<Bytes>(Metadata::symbol().into())
-				.build(),
-			logs: Default::default(),
-		})
+	#[precompile::public("symbol()")]
+	#[precompile::view]
+	fn symbol() -> EvmResult<Bytes> {
+		Ok(Metadata::symbol().into())
 	}
 
-	fn decimals(_: EvmDataReader, _: Option<u64>, _: &Context) -> EvmResult<PrecompileOutput> {
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: 0,
-			output: EvmDataWriter::new().write(Metadata::decimals()).build(),
-			logs: Default::default(),
-		})
+	#[precompile::public("decimals()")]
+	#[precompile::view]
+	fn decimals() -> EvmResult<u8> {
+		Ok(Metadata::decimals())
 	}
 
 	fn u256_to_amount(value: U256) -> EvmResult<BalanceOf<Runtime, Instance>> {
@@ -19,8 +19,11 @@
 use super::*;
 
 use codec::{Decode, Encode, MaxEncodedLen};
+use evm::{executor::PrecompileOutput, Context, ExitError};
 use frame_support::{construct_runtime, parameter_types, traits::Everything};
-use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot, PrecompileSet};
+use pallet_evm::{
+	AddressMapping, EnsureAddressNever, EnsureAddressRoot, Precompile, PrecompileSet,
+};
 use serde::{Deserialize, Serialize};
 use sp_core::{H160, H256};
 use sp_runtime::{
@@ -19,6 +19,7 @@ use std::assert_matches::assert_matches;
 use crate::mock::*;
 use crate::*;
 
+use evm::{executor::PrecompileOutput, ExitSucceed};
 use pallet_evm::PrecompileSet;
 use precompile_utils::{error, Bytes, EvmDataWriter, LogsBuilder};
 use sha3::{Digest, Keccak256};
@@ -89,6 +90,44 @@ fn selectors() {
 	);
 }
 
+#[test]
+fn solidity_interface() {
+	assert!(Action::SOLIDITY_INTERFACE.starts_with("interface Erc20BalancesPrecompile {"));
+	assert!(Action::SOLIDITY_INTERFACE
+		.contains("function balanceOf(address) external view returns (uint256);"));
+	assert!(Action::SOLIDITY_INTERFACE
+		.contains("function transfer(address,uint256) external returns (bool);"));
+	assert!(Action::SOLIDITY_INTERFACE
+		.contains("function decimals() external view returns (uint8);"));
+	assert_eq!(Action::TransferFrom.signature(), "transferFrom(address,address,uint256)");
+}
+
+#[test]
+fn non_payable_function_with_value() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::Transfer)
+						.write(Address(Account::Bob.into()))
+						.write(U256::from(400))
+						.build(),
+					None,
+					&evm::Context {
+						address: Account::Precompile.into(),
+						caller: Account::Alice.into(),
+						apparent_value: From::from(1),
+					},
+				),
+				Some(Err(error("function is not payable")))
+			);
+		});
+}
+
 #[test]
 fn get_total_supply() {
 	ExtBuilder::default()
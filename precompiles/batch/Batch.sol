@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/**
+ * @title Batch precompile
+ *
+ * Allows to perform multiple calls in a single transaction, keeping the caller of the precompile
+ * as `msg.sender` of each subcall.
+ * Address : 0x0000000000000000000000000000000000000805
+ *
+ * For each subcall, `value`, `callData` and `gasLimit` are read at the same index as `to`.
+ * Missing values are defaulted to 0 value, empty call data, and all the remaining gas.
+ *
+ * Each subcall is executed as a transaction by the runtime and thus costs the 21000 intrinsic gas
+ * of a transaction (plus the cost of its call data) on top of the gas used by the called address,
+ * which a CALL opcode doesn't. The gas limit of a subcall must account for it.
+ */
+interface Batch {
+    /**
+     * Batch multiple calls. A failing subcall doesn't prevent the next ones to be executed.
+     * Selector: 79df4b9c
+     *
+     * @param to List of addresses to call.
+     * @param value List of values to transfer to each address.
+     * @param callData List of call data to provide to each address.
+     * @param gasLimit List of gas limits for each call, 0 to forward all the remaining gas.
+     */
+    function batchSome(
+        address[] memory to,
+        uint256[] memory value,
+        bytes[] memory callData,
+        uint64[] memory gasLimit
+    ) external;
+
+    /**
+     * Batch multiple calls. The first failing subcall stops the execution of the batch,
+     * but the previous subcalls are kept.
+     * Selector: cf0491c7
+     *
+     * @param to List of addresses to call.
+     * @param value List of values to transfer to each address.
+     * @param callData List of call data to provide to each address.
+     * @param gasLimit List of gas limits for each call, 0 to forward all the remaining gas.
+     */
+    function batchSomeUntilFailure(
+        address[] memory to,
+        uint256[] memory value,
+        bytes[] memory callData,
+        uint64[] memory gasLimit
+    ) external;
+
+    /**
+     * Batch multiple calls atomically. If a subcall fails, the whole batch is reverted.
+     * Selector: 96e292b8
+     *
+     * @param to List of addresses to call.
+     * @param value List of values to transfer to each address.
+     * @param callData List of call data to provide to each address.
+     * @param gasLimit List of gas limits for each call, 0 to forward all the remaining gas.
+     */
+    function batchAll(
+        address[] memory to,
+        uint256[] memory value,
+        bytes[] memory callData,
+        uint64[] memory gasLimit
+    ) external;
+
+    /**
+     * Emitted when a subcall succeeds.
+     * Selector: bf855484
+     */
+    event SubcallSucceeded(uint256 index);
+
+    /**
+     * Emitted when a subcall fails.
+     * Selector: dbc5d06f
+     */
+    event SubcallFailed(uint256 index);
+}
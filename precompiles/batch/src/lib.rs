@@ -0,0 +1,246 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to batch multiple EVM calls in a single transaction.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+use evm::{executor::PrecompileOutput, Context, ExitError};
+use frame_support::{sp_runtime::TransactionOutcome, storage::with_transaction};
+use pallet_evm::Precompile;
+use precompile_utils::{
+	error, keccak256, Address, Bytes, EvmDataWriter, EvmResult, LogsBuilder, PrecompileHandle,
+	RuntimeHelper, SubcallOutcome,
+};
+use sp_core::{H160, U256};
+use sp_std::{marker::PhantomData, vec, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+/// Solidity selector of the SubcallSucceeded log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SUBCALL_SUCCEEDED: [u8; 32] = keccak256!("SubcallSucceeded(uint256)");
+
+/// Solidity selector of the SubcallFailed log, which is the Keccak of the Log signature.
+pub const SELECTOR_LOG_SUBCALL_FAILED: [u8; 32] = keccak256!("SubcallFailed(uint256)");
+
+/// How the batch reacts to a failing subcall.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+	/// Continue with the next subcalls.
+	BatchSome,
+	/// Stop the batch, keeping the effects of the previous subcalls.
+	BatchSomeUntilFailure,
+	/// Revert the whole batch.
+	BatchAll,
+}
+
+// Set while a batch is executed. Subcalls are performed by a new EVM executor which doesn't know
+// about the call depth of the batch, so nested batches are forbidden to bound the recursion.
+environmental::environmental!(in_batch: bool);
+
+/// Precompile allowing to perform multiple calls in a single transaction.
+///
+/// Each subcall is executed through the runtime `Runner` with the caller of the precompile as
+/// source, which means it is seen as `msg.sender` by the called contract. The subcalls don't pay
+/// fees (the gas they use is recorded as the cost of the precompile) and don't increase the nonce
+/// of the caller.
+///
+/// As the subcalls are not performed by the executor running the precompile (see
+/// `RuntimeHelper::evm_subcall`):
+/// - The batch runs in a storage transaction, which is reverted if the batch fails.
+/// - A subcall cannot perform another batch.
+/// - The precompile must be executed with `execute_at`, which rejects DELEGATECALL and CALLCODE
+/// as they would allow a contract to perform calls on behalf of its own caller.
+pub struct BatchPrecompile<Runtime>(PhantomData<Runtime>);
+
+impl<Runtime> BatchPrecompile<Runtime>
+where
+	Self: Precompile,
+{
+	/// Execute the batch precompile deployed at `address`.
+	pub fn execute_at(
+		address: H160,
+		input: &[u8],
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> Result<PrecompileOutput, ExitError> {
+		// With DELEGATECALL and CALLCODE the context is the one of the calling contract.
+		if context.address != address {
+			return Err(error("cannot be called with DELEGATECALL or CALLCODE"));
+		}
+
+		<Self as Precompile>::execute(input, target_gas, context)
+	}
+}
+
+#[precompile_utils::precompile]
+impl<Runtime> BatchPrecompile<Runtime>
+where
+	Runtime: pallet_evm::Config,
+{
+	#[precompile::public("batchSome(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some(
+		handle: &mut PrecompileHandle,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(handle, Mode::BatchSome, to, value, call_data, gas_limit)
+	}
+
+	#[precompile::public("batchSomeUntilFailure(address[],uint256[],bytes[],uint64[])")]
+	fn batch_some_until_failure(
+		handle: &mut PrecompileHandle,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(
+			handle,
+			Mode::BatchSomeUntilFailure,
+			to,
+			value,
+			call_data,
+			gas_limit,
+		)
+	}
+
+	#[precompile::public("batchAll(address[],uint256[],bytes[],uint64[])")]
+	fn batch_all(
+		handle: &mut PrecompileHandle,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		Self::batch(handle, Mode::BatchAll, to, value, call_data, gas_limit)
+	}
+
+	fn batch(
+		handle: &mut PrecompileHandle,
+		mode: Mode,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		if in_batch::with(|_| ()).is_some() {
+			return Err(error("cannot perform a batch inside a batch"));
+		}
+
+		in_batch::using(&mut true, || {
+			with_transaction(|| {
+				let result = Self::batch_inner(handle, mode, to, value, call_data, gas_limit);
+
+				if result.is_ok() {
+					TransactionOutcome::Commit(result)
+				} else {
+					TransactionOutcome::Rollback(result)
+				}
+			})
+		})
+	}
+
+	fn batch_inner(
+		handle: &mut PrecompileHandle,
+		mode: Mode,
+		to: Vec<Address>,
+		value: Vec<U256>,
+		call_data: Vec<Bytes>,
+		gas_limit: Vec<u64>,
+	) -> EvmResult {
+		let caller = handle.context().caller;
+		let address = handle.context().address;
+
+		let mut value = value.into_iter();
+		let mut call_data = call_data.into_iter();
+		let mut gas_limit = gas_limit.into_iter();
+
+		for (index, to) in to.into_iter().enumerate() {
+			// Each subcall emits a log with the index as data.
+			handle.gasometer().record_log_costs_manual(1, 32)?;
+
+			let value = value.next().unwrap_or_default();
+			let call_data = call_data.next().map(|data| data.0).unwrap_or_default();
+			let gas_limit = gas_limit.next().unwrap_or_default();
+
+			// A gas limit of 0 forwards all the remaining gas.
+			let remaining_gas = handle.remaining_gas()?.unwrap_or(u64::MAX);
+			let gas_limit = match gas_limit {
+				0 => Some(remaining_gas),
+				gas_limit if gas_limit <= remaining_gas => Some(gas_limit),
+				_ if mode == Mode::BatchAll => return Err(ExitError::OutOfGas),
+				_ => None,
+			};
+
+			let outcome = match gas_limit {
+				Some(gas_limit) => RuntimeHelper::<Runtime>::evm_subcall(
+					caller,
+					to.into(),
+					value,
+					call_data,
+					gas_limit,
+				),
+				None => SubcallOutcome {
+					succeeded: false,
+					used_gas: 0,
+					logs: vec![],
+				},
+			};
+
+			handle.record_cost(outcome.used_gas)?;
+
+			let index = U256::from(index);
+			if outcome.succeeded {
+				handle.add_logs(outcome.logs);
+				handle.add_logs(
+					LogsBuilder::new(address)
+						.log1(
+							SELECTOR_LOG_SUBCALL_SUCCEEDED,
+							EvmDataWriter::new().write(index).build(),
+						)
+						.build(),
+				);
+			} else {
+				if mode == Mode::BatchAll {
+					return Err(error(alloc::format!("subcall {} failed", index)));
+				}
+
+				handle.add_logs(
+					LogsBuilder::new(address)
+						.log1(
+							SELECTOR_LOG_SUBCALL_FAILED,
+							EvmDataWriter::new().write(index).build(),
+						)
+						.build(),
+				);
+
+				if mode == Mode::BatchSomeUntilFailure {
+					break;
+				}
+			}
+		}
+
+		Ok(())
+	}
+}
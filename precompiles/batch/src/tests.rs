@@ -0,0 +1,346 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::{executor::PrecompileOutput, ExitSucceed};
+use frame_support::{assert_ok, dispatch::Dispatchable};
+use pallet_evm::{Call as EvmCall, PrecompileSet};
+use precompile_utils::{error, Address, Bytes, EvmDataWriter, LogsBuilder};
+use sha3::{Digest, Keccak256};
+
+fn evm_test_context() -> evm::Context {
+	evm::Context {
+		address: Account::Precompile.into(),
+		caller: Account::Alice.into(),
+		apparent_value: From::from(0),
+	}
+}
+
+fn batch_input(action: Action, transfers: Vec<(Account, u128)>) -> Vec<u8> {
+	let to: Vec<Address> = transfers
+		.iter()
+		.map(|(to, _)| Address(to.clone().into()))
+		.collect();
+	let value: Vec<U256> = transfers
+		.iter()
+		.map(|(_, value)| U256::from(*value))
+		.collect();
+
+	EvmDataWriter::new()
+		.write_selector(action)
+		.write(to)
+		.write(value)
+		.write(Vec::<Bytes>::new())
+		.write(Vec::<u64>::new())
+		.build()
+}
+
+fn subcall_log(selector: [u8; 32], index: u64) -> LogsBuilder {
+	LogsBuilder::new(Account::Precompile.into()).log1(
+		selector,
+		EvmDataWriter::new().write(U256::from(index)).build(),
+	)
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::BatchSome as u32, 0x79df4b9c);
+	assert_eq!(Action::BatchSomeUntilFailure as u32, 0xcf0491c7);
+	assert_eq!(Action::BatchAll as u32, 0x96e292b8);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_SUBCALL_SUCCEEDED,
+		&Keccak256::digest(b"SubcallSucceeded(uint256)")[..]
+	);
+
+	assert_eq!(
+		crate::SELECTOR_LOG_SUBCALL_FAILED,
+		&Keccak256::digest(b"SubcallFailed(uint256)")[..]
+	);
+}
+
+#[test]
+fn batch_some_continues_after_failure() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let result = Precompiles::<Runtime>::execute(
+				Account::Precompile.into(),
+				&batch_input(
+					Action::BatchSome,
+					vec![
+						(Account::Bob, 100),
+						(Account::Charlie, 2000),
+						(Account::Charlie, 200),
+					],
+				),
+				None,
+				&evm_test_context(),
+			)
+			.expect("precompile exists")
+			.expect("batch succeeds");
+
+			assert_eq!(result.exit_status, ExitSucceed::Returned);
+			assert_eq!(
+				result.logs,
+				vec![
+					subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0).build(),
+					subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 1).build(),
+					subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 2).build(),
+				]
+				.concat()
+			);
+
+			assert_eq!(Balances::free_balance(Account::Alice), 700);
+			assert_eq!(Balances::free_balance(Account::Bob), 100);
+			assert_eq!(Balances::free_balance(Account::Charlie), 200);
+			assert_eq!(System::account_nonce(Account::Alice), 0);
+		});
+}
+
+#[test]
+fn batch_some_until_failure_stops_at_failure() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let result = Precompiles::<Runtime>::execute(
+				Account::Precompile.into(),
+				&batch_input(
+					Action::BatchSomeUntilFailure,
+					vec![
+						(Account::Bob, 100),
+						(Account::Charlie, 2000),
+						(Account::Charlie, 200),
+					],
+				),
+				None,
+				&evm_test_context(),
+			)
+			.expect("precompile exists")
+			.expect("batch succeeds");
+
+			assert_eq!(
+				result.logs,
+				vec![
+					subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0).build(),
+					subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 1).build(),
+				]
+				.concat()
+			);
+
+			assert_eq!(Balances::free_balance(Account::Alice), 900);
+			assert_eq!(Balances::free_balance(Account::Bob), 100);
+			assert_eq!(Balances::free_balance(Account::Charlie), 0);
+		});
+}
+
+#[test]
+fn batch_all_fails_on_failure() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&batch_input(
+						Action::BatchAll,
+						vec![(Account::Bob, 100), (Account::Charlie, 2000)],
+					),
+					None,
+					&evm_test_context(),
+				),
+				Some(Err(error("subcall 1 failed")))
+			);
+
+			// The transfer of the first subcall is reverted along with the batch.
+			assert_eq!(Balances::free_balance(Account::Alice), 1000);
+			assert_eq!(Balances::free_balance(Account::Bob), 0);
+			assert_eq!(Balances::free_balance(Account::Charlie), 0);
+		});
+}
+
+#[test]
+fn batch_all_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let result = Precompiles::<Runtime>::execute(
+				Account::Precompile.into(),
+				&batch_input(
+					Action::BatchAll,
+					vec![(Account::Bob, 100), (Account::Charlie, 200)],
+				),
+				None,
+				&evm_test_context(),
+			)
+			.expect("precompile exists")
+			.expect("batch succeeds");
+
+			assert_eq!(
+				result,
+				PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: result.cost,
+					output: vec![],
+					logs: vec![
+						subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0).build(),
+						subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 1).build(),
+					]
+					.concat(),
+				}
+			);
+
+			assert_eq!(Balances::free_balance(Account::Alice), 700);
+			assert_eq!(Balances::free_balance(Account::Bob), 100);
+			assert_eq!(Balances::free_balance(Account::Charlie), 200);
+		});
+}
+
+#[test]
+fn subcall_logs_are_deposited_once() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// PUSH1 0x00 PUSH1 0x00 LOG0 STOP: emits an empty log.
+			pallet_evm::AccountCodes::<Runtime>::insert(
+				H160::from(Account::Bob),
+				vec![0x60, 0x00, 0x60, 0x00, 0xa0, 0x00],
+			);
+
+			assert_ok!(Call::Evm(EvmCall::call(
+				Account::Alice.into(),
+				Account::Precompile.into(),
+				batch_input(Action::BatchAll, vec![(Account::Bob, 0)]),
+				U256::zero(),
+				1_000_000,
+				U256::zero(),
+				None,
+			))
+			.dispatch(Origin::root()));
+
+			let deposited_logs: Vec<_> = System::events()
+				.into_iter()
+				.filter_map(|record| match record.event {
+					Event::Evm(pallet_evm::Event::Log(log)) => Some(log),
+					_ => None,
+				})
+				.collect();
+
+			assert_eq!(
+				deposited_logs,
+				vec![
+					LogsBuilder::new(Account::Bob.into()).log0(vec![]).build(),
+					subcall_log(SELECTOR_LOG_SUBCALL_SUCCEEDED, 0).build(),
+				]
+				.concat()
+			);
+		});
+}
+
+#[test]
+fn batch_all_out_of_gas() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let input = EvmDataWriter::new()
+				.write_selector(Action::BatchAll)
+				.write(vec![Address(Account::Bob.into())])
+				.write(vec![U256::from(100)])
+				.write(Vec::<Bytes>::new())
+				.write(vec![100_000u64])
+				.build();
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&input,
+					Some(50_000),
+					&evm_test_context(),
+				),
+				Some(Err(ExitError::OutOfGas))
+			);
+		});
+}
+
+#[test]
+fn batch_cannot_be_nested() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let nested_batch = batch_input(Action::BatchAll, vec![(Account::Bob, 100)]);
+
+			let input = EvmDataWriter::new()
+				.write_selector(Action::BatchSome)
+				.write(vec![Address(Account::Precompile.into())])
+				.write(vec![U256::zero()])
+				.write(vec![Bytes(nested_batch)])
+				.write(vec![100_000u64])
+				.build();
+
+			let result = Precompiles::<Runtime>::execute(
+				Account::Precompile.into(),
+				&input,
+				Some(1_000_000),
+				&evm_test_context(),
+			)
+			.expect("precompile exists")
+			.expect("batch succeeds");
+
+			assert_eq!(
+				result.logs,
+				subcall_log(SELECTOR_LOG_SUBCALL_FAILED, 0).build()
+			);
+			assert_eq!(Balances::free_balance(Account::Alice), 1000);
+			assert_eq!(Balances::free_balance(Account::Bob), 0);
+		});
+}
+
+#[test]
+fn batch_rejects_delegate_call() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			// With DELEGATECALL the context address is the one of the calling contract.
+			let context = evm::Context {
+				address: Account::Charlie.into(),
+				caller: Account::Alice.into(),
+				apparent_value: From::from(0),
+			};
+
+			assert_eq!(
+				Precompiles::<Runtime>::execute(
+					Account::Precompile.into(),
+					&batch_input(Action::BatchAll, vec![(Account::Bob, 100)]),
+					None,
+					&context,
+				),
+				Some(Err(error("cannot be called with DELEGATECALL or CALLCODE")))
+			);
+
+			assert_eq!(Balances::free_balance(Account::Alice), 1000);
+			assert_eq!(Balances::free_balance(Account::Bob), 0);
+		});
+}
@@ -0,0 +1,94 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/**
+ * @title Collective precompile
+ *
+ * Allows the members of a collective (council, technical committee) to make and vote on
+ * proposals. Each collective is exposed at its own address:
+ * Council : 0x0000000000000000000000000000000000000808
+ * Technical committee : 0x0000000000000000000000000000000000000809
+ *
+ * Proposals are SCALE encoded runtime calls.
+ */
+interface Collective {
+    /**
+     * Execute a proposal as a single member of the collective.
+     * Selector: 09c5eabe
+     *
+     * @param proposal The SCALE encoded proposal.
+     */
+    function execute(bytes memory proposal) external;
+
+    /**
+     * Make a proposal for a call. If the threshold is less than 2 the proposal is directly
+     * executed instead.
+     * Selector: c57f3260
+     *
+     * @param threshold The number of members required to approve the proposal.
+     * @param proposal The SCALE encoded proposal.
+     * @return index The index of the new proposal.
+     */
+    function propose(uint32 threshold, bytes memory proposal)
+        external
+        returns (uint32 index);
+
+    /**
+     * Vote on a proposal.
+     * Selector: 73e37688
+     *
+     * @param proposalHash The hash of the proposal.
+     * @param proposalIndex The index of the proposal.
+     * @param approve Whether to vote for or against the proposal.
+     */
+    function vote(
+        bytes32 proposalHash,
+        uint32 proposalIndex,
+        bool approve
+    ) external;
+
+    /**
+     * Close a proposal, executing it if it was approved.
+     * Can be called by anyone once the proposal reached the voting threshold or its end.
+     * Selector: 638d9d47
+     *
+     * @param proposalHash The hash of the proposal.
+     * @param proposalIndex The index of the proposal.
+     * @param proposalWeightBound The maximum weight the proposal execution can use.
+     * @param lengthBound The maximum length of the SCALE encoded proposal.
+     */
+    function close(
+        bytes32 proposalHash,
+        uint32 proposalIndex,
+        uint64 proposalWeightBound,
+        uint32 lengthBound
+    ) external;
+
+    /**
+     * Compute the hash of a proposal.
+     * Selector: fc379417
+     *
+     * @param proposal The SCALE encoded proposal.
+     * @return The hash of the proposal.
+     */
+    function proposalHash(bytes memory proposal)
+        external
+        view
+        returns (bytes32);
+
+    /**
+     * Retrieve the hashes of the active proposals.
+     * Selector: 55ef20e6
+     *
+     * @return The hashes of the active proposals.
+     */
+    function proposals() external view returns (bytes32[] memory);
+
+    /**
+     * Retrieve the members of the collective.
+     * Selector: bdd4d18d
+     *
+     * @return The members of the collective.
+     */
+    function members() external view returns (address[] memory);
+}
@@ -0,0 +1,172 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to interact with a pallet_collective instance through an evm precompile.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Decode;
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::traits::Hash,
+};
+use pallet_evm::AddressMapping;
+use precompile_utils::{error, Address, Bytes, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_core::{H160, H256};
+use sp_std::{boxed::Box, marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type RuntimeCall<Runtime> = <Runtime as frame_system::Config>::Call;
+
+type ProposalOf<Runtime, Instance> = <Runtime as pallet_collective::Config<Instance>>::Proposal;
+
+/// A precompile to wrap the functionality from a pallet_collective instance.
+///
+/// Each instance (council, technical committee) is exposed at its own address.
+pub struct CollectivePrecompile<Runtime, Instance: 'static>(PhantomData<(Runtime, Instance)>);
+
+#[precompile_utils::precompile]
+impl<Runtime, Instance> CollectivePrecompile<Runtime, Instance>
+where
+	Instance: 'static,
+	Runtime: pallet_collective::Config<Instance> + pallet_evm::Config,
+	Runtime::AccountId: Into<H160>,
+	Runtime::Hash: From<H256> + Into<H256>,
+	RuntimeCall<Runtime>: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<RuntimeCall<Runtime> as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	RuntimeCall<Runtime>: From<pallet_collective::Call<Runtime, Instance>>,
+{
+	// Not named `execute` to not shadow `Precompile::execute`.
+	#[precompile::public("execute(bytes)")]
+	fn execute_proposal(handle: &mut PrecompileHandle, proposal: Bytes) -> EvmResult {
+		let length_bound = proposal.0.len() as u32;
+		let proposal = Self::decode_proposal(proposal)?;
+
+		let call =
+			pallet_collective::Call::<Runtime, Instance>::execute(Box::new(proposal), length_bound);
+
+		Self::dispatch(handle, call)
+	}
+
+	/// Returns the index the proposal will have, which is only meaningful if the threshold is
+	/// at least 2 (otherwise the proposal is directly executed).
+	#[precompile::public("propose(uint32,bytes)")]
+	#[precompile::gas(reads = 1)]
+	fn propose(handle: &mut PrecompileHandle, threshold: u32, proposal: Bytes) -> EvmResult<u32> {
+		let length_bound = proposal.0.len() as u32;
+		let proposal = Self::decode_proposal(proposal)?;
+		let proposal_index = pallet_collective::Pallet::<Runtime, Instance>::proposal_count();
+
+		let call = pallet_collective::Call::<Runtime, Instance>::propose(
+			threshold,
+			Box::new(proposal),
+			length_bound,
+		);
+		Self::dispatch(handle, call)?;
+
+		Ok(proposal_index)
+	}
+
+	#[precompile::public("vote(bytes32,uint32,bool)")]
+	fn vote(
+		handle: &mut PrecompileHandle,
+		proposal_hash: H256,
+		proposal_index: u32,
+		approve: bool,
+	) -> EvmResult {
+		let call = pallet_collective::Call::<Runtime, Instance>::vote(
+			proposal_hash.into(),
+			proposal_index,
+			approve,
+		);
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("close(bytes32,uint32,uint64,uint32)")]
+	fn close(
+		handle: &mut PrecompileHandle,
+		proposal_hash: H256,
+		proposal_index: u32,
+		proposal_weight_bound: u64,
+		length_bound: u32,
+	) -> EvmResult {
+		let call = pallet_collective::Call::<Runtime, Instance>::close(
+			proposal_hash.into(),
+			proposal_index,
+			proposal_weight_bound,
+			length_bound,
+		);
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("proposalHash(bytes)")]
+	#[precompile::view]
+	fn proposal_hash(proposal: Bytes) -> EvmResult<H256> {
+		// The hash is computed on the decoded proposal like in pallet_collective, which ensures
+		// it matches the one of the proposal if it is submitted.
+		let proposal = Self::decode_proposal(proposal)?;
+
+		Ok(Runtime::Hashing::hash_of(&proposal).into())
+	}
+
+	#[precompile::public("proposals()")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn proposals() -> EvmResult<Vec<H256>> {
+		Ok(pallet_collective::Pallet::<Runtime, Instance>::proposals()
+			.iter()
+			.map(|hash| (*hash).into())
+			.collect())
+	}
+
+	#[precompile::public("members()")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn members() -> EvmResult<Vec<Address>> {
+		Ok(pallet_collective::Pallet::<Runtime, Instance>::members()
+			.into_iter()
+			.map(|member| Address(member.into()))
+			.collect())
+	}
+
+	fn decode_proposal(proposal: Bytes) -> EvmResult<ProposalOf<Runtime, Instance>> {
+		ProposalOf::<Runtime, Instance>::decode(&mut &*proposal.0)
+			.map_err(|_| error("Failed decoding proposal"))
+	}
+
+	/// Dispatch the call with the caller as origin (if enough gas).
+	fn dispatch(
+		handle: &mut PrecompileHandle,
+		call: pallet_collective::Call<Runtime, Instance>,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.remaining_gas()?,
+		)?;
+		handle.record_cost(used_gas)?;
+
+		Ok(())
+	}
+}
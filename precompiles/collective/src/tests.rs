@@ -0,0 +1,277 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use codec::Encode;
+use evm::{executor::PrecompileOutput, ExitSucceed};
+use pallet_evm::PrecompileSet;
+use precompile_utils::{error, EvmDataWriter};
+use sp_runtime::traits::BlakeTwo256;
+
+fn evm_test_context(caller: Account) -> evm::Context {
+	evm::Context {
+		address: Account::Precompile.into(),
+		caller: caller.into(),
+		apparent_value: From::from(0),
+	}
+}
+
+fn execute(caller: Account, input: Vec<u8>) -> Option<EvmResult<PrecompileOutput>> {
+	Precompiles::<Runtime>::execute(
+		Account::Precompile.into(),
+		&input,
+		None,
+		&evm_test_context(caller),
+	)
+}
+
+fn remark(data: Vec<u8>) -> Call {
+	Call::System(frame_system::Call::remark(data))
+}
+
+fn propose_input(threshold: u32, proposal: &Call) -> Vec<u8> {
+	EvmDataWriter::new()
+		.write_selector(Action::Propose)
+		.write(threshold)
+		.write(Bytes(proposal.encode()))
+		.build()
+}
+
+fn council_event(event: pallet_collective::Event<Runtime, CouncilInstance>) -> Event {
+	Event::Council(event)
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::ExecuteProposal as u32, 0x09c5eabe);
+	assert_eq!(Action::Propose as u32, 0xc57f3260);
+	assert_eq!(Action::Vote as u32, 0x73e37688);
+	assert_eq!(Action::Close as u32, 0x638d9d47);
+	assert_eq!(Action::ProposalHash as u32, 0xfc379417);
+	assert_eq!(Action::Proposals as u32, 0x55ef20e6);
+	assert_eq!(Action::Members as u32, 0xbdd4d18d);
+}
+
+#[test]
+fn execute_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let proposal = remark(vec![1, 2, 3]);
+		let proposal_hash = BlakeTwo256::hash_of(&proposal);
+
+		let result = execute(
+			Account::Alice,
+			EvmDataWriter::new()
+				.write_selector(Action::ExecuteProposal)
+				.write(Bytes(proposal.encode()))
+				.build(),
+		)
+		.expect("precompile exists")
+		.expect("execute succeeds");
+
+		assert_eq!(result.exit_status, ExitSucceed::Returned);
+		assert!(events().iter().any(|event| matches!(
+			event,
+			Event::Council(pallet_collective::Event::MemberExecuted(hash, _))
+				if *hash == proposal_hash
+		)));
+	});
+}
+
+#[test]
+fn execute_fails_for_non_member() {
+	ExtBuilder::default().build().execute_with(|| {
+		let input = EvmDataWriter::new()
+			.write_selector(Action::ExecuteProposal)
+			.write(Bytes(remark(vec![1, 2, 3]).encode()))
+			.build();
+
+		assert!(matches!(execute(Account::Bogus, input), Some(Err(_))));
+	});
+}
+
+#[test]
+fn execute_fails_with_invalid_proposal() {
+	ExtBuilder::default().build().execute_with(|| {
+		let input = EvmDataWriter::new()
+			.write_selector(Action::ExecuteProposal)
+			.write(Bytes(vec![0xff; 4]))
+			.build();
+
+		assert_eq!(
+			execute(Account::Alice, input),
+			Some(Err(error("Failed decoding proposal")))
+		);
+	});
+}
+
+#[test]
+fn propose_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let first = remark(vec![1]);
+		let second = remark(vec![2]);
+
+		let output = |proposal: &Call| {
+			execute(Account::Alice, propose_input(2, proposal))
+				.expect("precompile exists")
+				.expect("propose succeeds")
+				.output
+		};
+
+		assert_eq!(output(&first), EvmDataWriter::new().write(0u32).build());
+		assert_eq!(output(&second), EvmDataWriter::new().write(1u32).build());
+
+		assert_eq!(
+			Council::proposals().to_vec(),
+			vec![BlakeTwo256::hash_of(&first), BlakeTwo256::hash_of(&second)]
+		);
+		assert!(
+			events().contains(&council_event(pallet_collective::Event::Proposed(
+				Account::Alice,
+				0,
+				BlakeTwo256::hash_of(&first),
+				2
+			)))
+		);
+	});
+}
+
+#[test]
+fn vote_and_close_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let proposal = remark(vec![1, 2, 3]);
+		let proposal_hash = BlakeTwo256::hash_of(&proposal);
+
+		execute(Account::Alice, propose_input(2, &proposal))
+			.expect("precompile exists")
+			.expect("propose succeeds");
+
+		execute(
+			Account::Bob,
+			EvmDataWriter::new()
+				.write_selector(Action::Vote)
+				.write(proposal_hash)
+				.write(0u32)
+				.write(true)
+				.build(),
+		)
+		.expect("precompile exists")
+		.expect("vote succeeds");
+
+		assert!(
+			events().contains(&council_event(pallet_collective::Event::Voted(
+				Account::Bob,
+				proposal_hash,
+				true,
+				2,
+				0
+			)))
+		);
+
+		roll_to(MotionDuration::get() + 1);
+
+		execute(
+			Account::Charlie,
+			EvmDataWriter::new()
+				.write_selector(Action::Close)
+				.write(proposal_hash)
+				.write(0u32)
+				.write(proposal.get_dispatch_info().weight)
+				.write(proposal.encode().len() as u32)
+				.build(),
+		)
+		.expect("precompile exists")
+		.expect("close succeeds");
+
+		assert!(
+			events().contains(&council_event(pallet_collective::Event::Closed(
+				proposal_hash,
+				2,
+				1
+			)))
+		);
+		assert!(
+			events().contains(&council_event(pallet_collective::Event::Approved(
+				proposal_hash
+			)))
+		);
+		assert!(Council::proposals().is_empty());
+	});
+}
+
+#[test]
+fn proposal_hash_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let proposal = remark(vec![1, 2, 3]);
+
+		let output = execute(
+			Account::Alice,
+			EvmDataWriter::new()
+				.write_selector(Action::ProposalHash)
+				.write(Bytes(proposal.encode()))
+				.build(),
+		)
+		.expect("precompile exists")
+		.expect("proposal_hash succeeds")
+		.output;
+
+		assert_eq!(
+			output,
+			EvmDataWriter::new()
+				.write(BlakeTwo256::hash_of(&proposal))
+				.build()
+		);
+	});
+}
+
+#[test]
+fn proposals_and_members_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let proposal = remark(vec![1, 2, 3]);
+
+		execute(Account::Alice, propose_input(2, &proposal))
+			.expect("precompile exists")
+			.expect("propose succeeds");
+
+		let output = |action: Action| {
+			execute(
+				Account::Bogus,
+				EvmDataWriter::new().write_selector(action).build(),
+			)
+			.expect("precompile exists")
+			.expect("view succeeds")
+			.output
+		};
+
+		assert_eq!(
+			output(Action::Proposals),
+			EvmDataWriter::new()
+				.write(vec![BlakeTwo256::hash_of(&proposal)])
+				.build()
+		);
+		assert_eq!(
+			output(Action::Members),
+			EvmDataWriter::new()
+				.write(vec![
+					Address(Account::Alice.into()),
+					Address(Account::Bob.into()),
+					Address(Account::Charlie.into()),
+				])
+				.build()
+		);
+	});
+}
@@ -18,15 +18,12 @@
 
 #![cfg_attr(not(feature = "std"), no_std)]
 
-use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
 	traits::Currency,
 };
-use pallet_evm::{AddressMapping, Precompile};
-use precompile_utils::{
-	error, Address, EvmDataReader, EvmDataWriter, EvmResult, Gasometer, RuntimeHelper,
-};
+use pallet_evm::AddressMapping;
+use precompile_utils::{error, Address, EvmResult, PrecompileHandle, RuntimeHelper};
 
 use sp_core::{H160, U256};
 use sp_std::{
@@ -45,43 +42,10 @@ pub type BalanceOf<Runtime> =
 		<Runtime as frame_system::Config>::AccountId,
 	>>::Balance;
 
-#[precompile_utils::generate_function_selector]
-#[derive(Debug, PartialEq, num_enum::TryFromPrimitive)]
-enum Action {
-	IsContributor = "is_contributor(address)",
-	RewardInfo = "reward_info(address)",
-	Claim = "claim()",
-	UpdateRewardAddress = "update_reward_address(address)",
-}
-
 /// A precompile to wrap the functionality from pallet_crowdloan_rewards.
 pub struct CrowdloanRewardsWrapper<Runtime>(PhantomData<Runtime>);
 
-impl<Runtime> Precompile for CrowdloanRewardsWrapper<Runtime>
-where
-	Runtime: pallet_crowdloan_rewards::Config + pallet_evm::Config,
-	BalanceOf<Runtime>: TryFrom<U256> + Debug,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
-	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
-	Runtime::Call: From<pallet_crowdloan_rewards::Call<Runtime>>,
-{
-	fn execute(
-		input: &[u8], //Reminder this is big-endian
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut input = EvmDataReader::new(input);
-
-		match &input.read_selector()? {
-			// Check for accessor methods first. These return results immediately
-			Action::IsContributor => Self::is_contributor(input, target_gas),
-			Action::RewardInfo => Self::reward_info(input, target_gas),
-			Action::Claim => Self::claim(target_gas, context),
-			Action::UpdateRewardAddress => Self::update_reward_address(input, target_gas, context),
-		}
-	}
-}
-
+#[precompile_utils::precompile]
 impl<Runtime> CrowdloanRewardsWrapper<Runtime>
 where
 	Runtime: pallet_crowdloan_rewards::Config + pallet_evm::Config + frame_system::Config,
@@ -91,18 +55,11 @@ where
 	Runtime::Call: From<pallet_crowdloan_rewards::Call<Runtime>>,
 {
 	// The accessors are first. They directly return their result.
-	fn is_contributor(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?; // accounts_payable
-
-		// Bound check
-		input.expect_arguments(1)?;
-
-		// parse the address
-		let contributor: H160 = input.read::<Address>()?.into();
+	#[precompile::public("is_contributor(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)] // accounts_payable
+	fn is_contributor(contributor: Address) -> EvmResult<bool> {
+		let contributor: H160 = contributor.into();
 
 		let account = Runtime::AddressMapping::into_account_id(contributor);
 
@@ -118,26 +75,14 @@ where
 
 		log::trace!(target: "crowldoan-rewards-precompile", "Result from pallet is {:?}", is_contributor);
 
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(is_contributor).build(),
-			logs: Default::default(),
-		})
+		Ok(is_contributor)
 	}
 
-	fn reward_info(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?; // accounts_payable
-
-		// Bound check
-		input.expect_arguments(1)?;
-
-		// parse the address
-		let contributor: H160 = input.read::<Address>()?.into();
+	#[precompile::public("reward_info(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)] // accounts_payable
+	fn reward_info(contributor: Address) -> EvmResult<(U256, U256)> {
+		let contributor: H160 = contributor.into();
 
 		let account = Runtime::AddressMapping::into_account_id(contributor);
 
@@ -170,75 +115,50 @@ where
 			total, claimed
 		);
 
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(total).write(claimed).build(),
-			logs: Default::default(),
-		})
+		Ok((total, claimed))
 	}
 
-	fn claim(target_gas: Option<u64>, context: &Context) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+	#[precompile::public("claim()")]
+	fn claim(handle: &mut PrecompileHandle) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
 		let call = pallet_crowdloan_rewards::Call::<Runtime>::claim();
 
 		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
 			Some(origin).into(),
 			call,
-			gasometer.remaining_gas()?,
+			handle.remaining_gas()?,
 		)?;
 
-		gasometer.record_cost(used_gas)?;
+		handle.record_cost(used_gas)?;
 
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Stopped,
-			cost: gasometer.used_gas(),
-			output: Default::default(),
-			logs: Default::default(),
-		})
+		Ok(())
 	}
 
-	fn update_reward_address(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> EvmResult<PrecompileOutput> {
-		let mut gasometer = Gasometer::new(target_gas);
-
+	#[precompile::public("update_reward_address(address)")]
+	fn update_reward_address(handle: &mut PrecompileHandle, new_address: Address) -> EvmResult {
 		log::trace!(
 			target: "crowdloan-rewards-precompile",
 			"In update_reward_address dispatchable wrapper"
 		);
 
-		// Bound check
-		input.expect_arguments(1)?;
-
-		// parse the address
-		let new_address: H160 = input.read::<Address>()?.into();
+		let new_address: H160 = new_address.into();
 
 		let new_address_account = Runtime::AddressMapping::into_account_id(new_address);
 
 		log::trace!(target: "crowdloan-rewards-precompile", "New account is {:?}", new_address);
 
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
 		let call =
 			pallet_crowdloan_rewards::Call::<Runtime>::update_reward_address(new_address_account);
 
 		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
 			Some(origin).into(),
 			call,
-			gasometer.remaining_gas()?,
+			handle.remaining_gas()?,
 		)?;
 
-		gasometer.record_cost(used_gas)?;
+		handle.record_cost(used_gas)?;
 
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Stopped,
-			cost: gasometer.used_gas(),
-			output: Default::default(),
-			logs: Default::default(),
-		})
+		Ok(())
 	}
 }
@@ -17,6 +17,7 @@
 //! Test utilities
 use super::*;
 use codec::{Decode, Encode, MaxEncodedLen};
+use evm::{executor::PrecompileOutput, Context, ExitError};
 use cumulus_primitives_core::{
 	relay_chain::BlockNumber as RelayChainBlockNumber, PersistedValidationData,
 };
@@ -30,7 +31,9 @@ use frame_support::{
 	traits::{Everything, GenesisBuild, OnFinalize, OnInitialize},
 };
 use frame_system::{EnsureSigned, RawOrigin};
-use pallet_evm::{AddressMapping, EnsureAddressNever, EnsureAddressRoot, PrecompileSet};
+use pallet_evm::{
+	AddressMapping, EnsureAddressNever, EnsureAddressRoot, Precompile, PrecompileSet,
+};
 use serde::{Deserialize, Serialize};
 use sp_core::H256;
 use sp_io;
@@ -18,7 +18,8 @@ use crate::mock::{
 	events, evm_test_context, precompile_address, roll_to, Call, Crowdloan, ExtBuilder, Origin,
 	Precompiles, TestAccount::Alice, TestAccount::Bob, TestAccount::Charlie,
 };
-use crate::{Action, PrecompileOutput};
+use crate::Action;
+use evm::executor::PrecompileOutput;
 use frame_support::{assert_ok, dispatch::Dispatchable};
 use num_enum::TryFromPrimitive;
 use pallet_crowdloan_rewards::{Call as CrowdloanCall, Event as CrowdloanEvent};
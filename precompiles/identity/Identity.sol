@@ -0,0 +1,149 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/**
+ * @title Identity precompile
+ *
+ * Allows to manage the on-chain identity of the caller and to read the identities, judgements
+ * and registrars of pallet_identity.
+ * Address : 0x0000000000000000000000000000000000000807
+ */
+interface Identity {
+    /**
+     * Data stored in an identity field.
+     *
+     * kind: 0: None, 1: Raw, 2: BlakeTwo256, 3: Sha256, 4: Keccak256, 5: ShaThree256
+     * Raw data is at most 32 bytes long, hashes are 32 bytes long.
+     */
+    struct Data {
+        uint8 kind;
+        bytes value;
+    }
+
+    /// An additional field of an identity.
+    struct Additional {
+        Data key;
+        Data value;
+    }
+
+    /// The information of an identity. The fingerprint is 20 bytes long if present.
+    struct IdentityInfo {
+        Additional[] additional;
+        Data display;
+        Data legal;
+        Data web;
+        Data riot;
+        Data email;
+        bool hasPgpFingerprint;
+        bytes pgpFingerprint;
+        Data image;
+        Data twitter;
+    }
+
+    /**
+     * A judgement provided by a registrar.
+     *
+     * kind: 0: Unknown, 1: FeePaid, 2: Reasonable, 3: KnownGood, 4: OutOfDate,
+     * 5: LowQuality, 6: Erroneous
+     * fee is only non-zero for FeePaid.
+     */
+    struct Judgement {
+        uint32 registrarIndex;
+        uint8 kind;
+        uint256 fee;
+    }
+
+    /// The registration of an identity. isValid is false if the account has no identity.
+    struct Registration {
+        bool isValid;
+        Judgement[] judgements;
+        uint256 deposit;
+        IdentityInfo info;
+    }
+
+    /// A registrar able to provide judgements.
+    struct Registrar {
+        uint32 index;
+        address account;
+        uint256 fee;
+    }
+
+    /// The super identity of a sub account. isValid is false if the account is not a sub account.
+    struct SuperOf {
+        bool isValid;
+        address account;
+        Data data;
+    }
+
+    /// The sub accounts of an account.
+    struct SubsOf {
+        uint256 deposit;
+        address[] accounts;
+    }
+
+    /**
+     * Set the identity of the caller, reserving the corresponding deposit.
+     * Selector: 7e1d58e7
+     *
+     * @param info The identity information.
+     */
+    function setIdentity(IdentityInfo memory info) external;
+
+    /**
+     * Clear the identity of the caller, unreserving the deposit.
+     * Selector: 7a6a10c7
+     */
+    function clearIdentity() external;
+
+    /**
+     * Request a judgement from a registrar, reserving its fee.
+     * Selector: d523ceb9
+     *
+     * @param registrarIndex The index of the registrar.
+     * @param maxFee The maximum fee the caller is willing to pay.
+     */
+    function requestJudgement(uint32 registrarIndex, uint256 maxFee) external;
+
+    /**
+     * Cancel a judgement request which has not been provided yet.
+     * Selector: c79934a5
+     *
+     * @param registrarIndex The index of the registrar.
+     */
+    function cancelRequest(uint32 registrarIndex) external;
+
+    /**
+     * Retrieve the identity registration of an account.
+     * Selector: f0eb5e54
+     *
+     * @param who The account to query.
+     * @return The registration of the account.
+     */
+    function identity(address who) external view returns (Registration memory);
+
+    /**
+     * Retrieve the super identity of a sub account.
+     * Selector: c18110d6
+     *
+     * @param who The sub account to query.
+     * @return The super identity of the account.
+     */
+    function superOf(address who) external view returns (SuperOf memory);
+
+    /**
+     * Retrieve the sub accounts of an account.
+     * Selector: 3f08986b
+     *
+     * @param who The account to query.
+     * @return The sub accounts of the account.
+     */
+    function subsOf(address who) external view returns (SubsOf memory);
+
+    /**
+     * Retrieve the list of registrars.
+     * Selector: e88e512e
+     *
+     * @return The registrars.
+     */
+    function registrars() external view returns (Registrar[] memory);
+}
@@ -0,0 +1,399 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to interact with pallet_identity through an evm precompile.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	traits::Currency,
+};
+use pallet_evm::AddressMapping;
+use pallet_identity::Judgement as PalletJudgement;
+use precompile_utils::{
+	error, Address, Bytes, EvmData, EvmResult, PrecompileHandle, RuntimeHelper,
+};
+use sp_core::{H160, U256};
+use sp_std::{
+	boxed::Box,
+	convert::{TryFrom, TryInto},
+	marker::PhantomData,
+	vec::Vec,
+};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type RuntimeCall<Runtime> = <Runtime as frame_system::Config>::Call;
+
+type BalanceOf<Runtime> = <<Runtime as pallet_identity::Config>::Currency as Currency<
+	<Runtime as frame_system::Config>::AccountId,
+>>::Balance;
+
+/// Solidity representation of `pallet_identity::Data`.
+///
+/// `kind` is the index of the variant (0: None, 1: Raw, 2: BlakeTwo256, 3: Sha256,
+/// 4: Keccak256, 5: ShaThree256). Raw data is at most 32 bytes long while hashes are
+/// exactly 32 bytes long.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct Data {
+	pub kind: u8,
+	pub value: Bytes,
+}
+
+/// An additional field of an identity, as a key/value pair.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct Additional {
+	pub key: Data,
+	pub value: Data,
+}
+
+/// Solidity representation of `pallet_identity::IdentityInfo`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct IdentityInfo {
+	pub additional: Vec<Additional>,
+	pub display: Data,
+	pub legal: Data,
+	pub web: Data,
+	pub riot: Data,
+	pub email: Data,
+	pub has_pgp_fingerprint: bool,
+	pub pgp_fingerprint: Bytes,
+	pub image: Data,
+	pub twitter: Data,
+}
+
+/// A judgement provided by a registrar.
+///
+/// `kind` is the index of the variant of `pallet_identity::Judgement` (0: Unknown, 1: FeePaid,
+/// 2: Reasonable, 3: KnownGood, 4: OutOfDate, 5: LowQuality, 6: Erroneous). `fee` is only
+/// non-zero for `FeePaid`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct Judgement {
+	pub registrar_index: u32,
+	pub kind: u8,
+	pub fee: U256,
+}
+
+/// The identity registration of an account. `is_valid` is false if the account has no identity.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct Registration {
+	pub is_valid: bool,
+	pub judgements: Vec<Judgement>,
+	pub deposit: U256,
+	pub info: IdentityInfo,
+}
+
+/// A registrar able to provide judgements.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct Registrar {
+	pub index: u32,
+	pub account: Address,
+	pub fee: U256,
+}
+
+/// The super identity of a sub account. `is_valid` is false if the account is not a sub account.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct SuperOf {
+	pub is_valid: bool,
+	pub account: Address,
+	pub data: Data,
+}
+
+/// The sub accounts of an account, along with the deposit reserved for them.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct SubsOf {
+	pub deposit: U256,
+	pub accounts: Vec<Address>,
+}
+
+/// A precompile to wrap the functionality from pallet_identity.
+pub struct IdentityWrapper<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> IdentityWrapper<Runtime>
+where
+	Runtime: pallet_identity::Config + pallet_evm::Config,
+	Runtime::AccountId: Into<H160>,
+	BalanceOf<Runtime>: TryFrom<U256> + Into<U256>,
+	RuntimeCall<Runtime>: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<RuntimeCall<Runtime> as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	RuntimeCall<Runtime>: From<pallet_identity::Call<Runtime>>,
+{
+	#[precompile::public(
+		"setIdentity(\
+		(((uint8,bytes),(uint8,bytes))[],\
+		(uint8,bytes),(uint8,bytes),(uint8,bytes),(uint8,bytes),(uint8,bytes),\
+		bool,bytes,(uint8,bytes),(uint8,bytes)))"
+	)]
+	fn set_identity(handle: &mut PrecompileHandle, info: IdentityInfo) -> EvmResult {
+		let info = Self::identity_info_to_pallet(info)?;
+		let call = pallet_identity::Call::<Runtime>::set_identity(Box::new(info));
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("clearIdentity()")]
+	fn clear_identity(handle: &mut PrecompileHandle) -> EvmResult {
+		let call = pallet_identity::Call::<Runtime>::clear_identity();
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("requestJudgement(uint32,uint256)")]
+	fn request_judgement(
+		handle: &mut PrecompileHandle,
+		registrar_index: u32,
+		max_fee: U256,
+	) -> EvmResult {
+		let max_fee = max_fee
+			.try_into()
+			.map_err(|_| error("max fee is too large"))?;
+		let call = pallet_identity::Call::<Runtime>::request_judgement(registrar_index, max_fee);
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("cancelRequest(uint32)")]
+	fn cancel_request(handle: &mut PrecompileHandle, registrar_index: u32) -> EvmResult {
+		let call = pallet_identity::Call::<Runtime>::cancel_request(registrar_index);
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("identity(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn identity(who: Address) -> EvmResult<Registration> {
+		let who = Runtime::AddressMapping::into_account_id(who.0);
+
+		let registration = match pallet_identity::Pallet::<Runtime>::identity(who) {
+			Some(registration) => registration,
+			None => return Ok(Registration::default()),
+		};
+
+		Ok(Registration {
+			is_valid: true,
+			judgements: registration
+				.judgements
+				.iter()
+				.map(|(index, judgement)| Self::judgement_from_pallet(*index, judgement))
+				.collect(),
+			deposit: registration.deposit.into(),
+			info: Self::identity_info_from_pallet(registration.info),
+		})
+	}
+
+	#[precompile::public("superOf(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn super_of(who: Address) -> EvmResult<SuperOf> {
+		let who = Runtime::AddressMapping::into_account_id(who.0);
+
+		Ok(match pallet_identity::Pallet::<Runtime>::super_of(who) {
+			Some((account, data)) => SuperOf {
+				is_valid: true,
+				account: Address(account.into()),
+				data: Self::data_from_pallet(data),
+			},
+			None => SuperOf::default(),
+		})
+	}
+
+	#[precompile::public("subsOf(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn subs_of(who: Address) -> EvmResult<SubsOf> {
+		let who = Runtime::AddressMapping::into_account_id(who.0);
+		let (deposit, accounts) = pallet_identity::Pallet::<Runtime>::subs_of(who);
+
+		Ok(SubsOf {
+			deposit: deposit.into(),
+			accounts: accounts
+				.iter()
+				.map(|account| Address(account.clone().into()))
+				.collect(),
+		})
+	}
+
+	#[precompile::public("registrars()")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn registrars() -> EvmResult<Vec<Registrar>> {
+		// Removed registrars are `None` and skipped, the index of the other ones being kept.
+		Ok(pallet_identity::Pallet::<Runtime>::registrars()
+			.iter()
+			.enumerate()
+			.filter_map(|(index, registrar)| {
+				registrar.as_ref().map(|registrar| Registrar {
+					index: index as u32,
+					account: Address(registrar.account.clone().into()),
+					fee: registrar.fee.into(),
+				})
+			})
+			.collect())
+	}
+
+	fn data_to_pallet(data: Data) -> EvmResult<pallet_identity::Data> {
+		let value = data.value.0;
+
+		Ok(match data.kind {
+			0 => pallet_identity::Data::None,
+			1 => {
+				if value.len() > 32 {
+					return Err(error("raw data must be at most 32 bytes long"));
+				}
+
+				pallet_identity::Data::Raw(
+					value
+						.try_into()
+						.map_err(|_| error("raw data must be at most 32 bytes long"))?,
+				)
+			}
+			2 => pallet_identity::Data::BlakeTwo256(Self::read_hash(&value)?),
+			3 => pallet_identity::Data::Sha256(Self::read_hash(&value)?),
+			4 => pallet_identity::Data::Keccak256(Self::read_hash(&value)?),
+			5 => pallet_identity::Data::ShaThree256(Self::read_hash(&value)?),
+			_ => return Err(error("unknown data kind")),
+		})
+	}
+
+	fn read_hash(value: &[u8]) -> EvmResult<[u8; 32]> {
+		value
+			.try_into()
+			.map_err(|_| error("hash must be 32 bytes long"))
+	}
+
+	fn data_from_pallet(data: pallet_identity::Data) -> Data {
+		let (kind, value) = match data {
+			pallet_identity::Data::None => (0, Vec::new()),
+			pallet_identity::Data::Raw(value) => (1, value.to_vec()),
+			pallet_identity::Data::BlakeTwo256(hash) => (2, hash.to_vec()),
+			pallet_identity::Data::Sha256(hash) => (3, hash.to_vec()),
+			pallet_identity::Data::Keccak256(hash) => (4, hash.to_vec()),
+			pallet_identity::Data::ShaThree256(hash) => (5, hash.to_vec()),
+		};
+
+		Data {
+			kind,
+			value: Bytes(value),
+		}
+	}
+
+	fn identity_info_to_pallet(
+		info: IdentityInfo,
+	) -> EvmResult<pallet_identity::IdentityInfo<Runtime::MaxAdditionalFields>> {
+		let additional = info
+			.additional
+			.into_iter()
+			.map(|Additional { key, value }| {
+				Ok((Self::data_to_pallet(key)?, Self::data_to_pallet(value)?))
+			})
+			.collect::<EvmResult<Vec<_>>>()?
+			.try_into()
+			.map_err(|_| error("too many additional fields"))?;
+
+		let pgp_fingerprint = if info.has_pgp_fingerprint {
+			let fingerprint: [u8; 20] = info
+				.pgp_fingerprint
+				.0
+				.as_slice()
+				.try_into()
+				.map_err(|_| error("pgp fingerprint must be 20 bytes long"))?;
+			Some(fingerprint)
+		} else {
+			None
+		};
+
+		Ok(pallet_identity::IdentityInfo {
+			additional,
+			display: Self::data_to_pallet(info.display)?,
+			legal: Self::data_to_pallet(info.legal)?,
+			web: Self::data_to_pallet(info.web)?,
+			riot: Self::data_to_pallet(info.riot)?,
+			email: Self::data_to_pallet(info.email)?,
+			pgp_fingerprint,
+			image: Self::data_to_pallet(info.image)?,
+			twitter: Self::data_to_pallet(info.twitter)?,
+		})
+	}
+
+	fn identity_info_from_pallet(
+		info: pallet_identity::IdentityInfo<Runtime::MaxAdditionalFields>,
+	) -> IdentityInfo {
+		IdentityInfo {
+			additional: info
+				.additional
+				.iter()
+				.map(|(key, value)| Additional {
+					key: Self::data_from_pallet(key.clone()),
+					value: Self::data_from_pallet(value.clone()),
+				})
+				.collect(),
+			display: Self::data_from_pallet(info.display),
+			legal: Self::data_from_pallet(info.legal),
+			web: Self::data_from_pallet(info.web),
+			riot: Self::data_from_pallet(info.riot),
+			email: Self::data_from_pallet(info.email),
+			has_pgp_fingerprint: info.pgp_fingerprint.is_some(),
+			pgp_fingerprint: Bytes(
+				info.pgp_fingerprint
+					.map(|fingerprint| fingerprint.to_vec())
+					.unwrap_or_default(),
+			),
+			image: Self::data_from_pallet(info.image),
+			twitter: Self::data_from_pallet(info.twitter),
+		}
+	}
+
+	fn judgement_from_pallet(
+		registrar_index: u32,
+		judgement: &PalletJudgement<BalanceOf<Runtime>>,
+	) -> Judgement {
+		let (kind, fee) = match judgement {
+			PalletJudgement::Unknown => (0, U256::zero()),
+			PalletJudgement::FeePaid(fee) => (1, (*fee).into()),
+			PalletJudgement::Reasonable => (2, U256::zero()),
+			PalletJudgement::KnownGood => (3, U256::zero()),
+			PalletJudgement::OutOfDate => (4, U256::zero()),
+			PalletJudgement::LowQuality => (5, U256::zero()),
+			PalletJudgement::Erroneous => (6, U256::zero()),
+		};
+
+		Judgement {
+			registrar_index,
+			kind,
+			fee,
+		}
+	}
+
+	/// Dispatch the call with the caller as origin (if enough gas).
+	fn dispatch(handle: &mut PrecompileHandle, call: pallet_identity::Call<Runtime>) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.remaining_gas()?,
+		)?;
+		handle.record_cost(used_gas)?;
+
+		Ok(())
+	}
+}
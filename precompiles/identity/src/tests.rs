@@ -0,0 +1,397 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::{executor::PrecompileOutput, ExitSucceed};
+use pallet_evm::PrecompileSet;
+use precompile_utils::{error, EvmDataReader, EvmDataWriter};
+
+fn evm_test_context(caller: Account) -> evm::Context {
+	evm::Context {
+		address: Account::Precompile.into(),
+		caller: caller.into(),
+		apparent_value: From::from(0),
+	}
+}
+
+fn execute(caller: Account, input: Vec<u8>) -> Option<EvmResult<PrecompileOutput>> {
+	Precompiles::<Runtime>::execute(
+		Account::Precompile.into(),
+		&input,
+		None,
+		&evm_test_context(caller),
+	)
+}
+
+fn read_output<T: EvmData>(caller: Account, input: Vec<u8>) -> T {
+	let output = execute(caller, input)
+		.expect("precompile exists")
+		.expect("call succeeds")
+		.output;
+
+	EvmDataReader::new(&output).read().expect("output is valid")
+}
+
+fn raw(value: &str) -> Data {
+	Data {
+		kind: 1,
+		value: value.into(),
+	}
+}
+
+fn pallet_raw(value: &str) -> pallet_identity::Data {
+	pallet_identity::Data::Raw(value.as_bytes().to_vec().try_into().unwrap())
+}
+
+fn alice_identity() -> IdentityInfo {
+	IdentityInfo {
+		additional: vec![Additional {
+			key: raw("discord"),
+			value: raw("alice#1234"),
+		}],
+		display: raw("Alice"),
+		email: Data {
+			kind: 4,
+			value: Bytes(vec![0x42; 32]),
+		},
+		has_pgp_fingerprint: true,
+		pgp_fingerprint: Bytes(vec![0x11; 20]),
+		..Default::default()
+	}
+}
+
+fn set_identity_input(info: IdentityInfo) -> Vec<u8> {
+	EvmDataWriter::new()
+		.write_selector(Action::SetIdentity)
+		.write(info)
+		.build()
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::SetIdentity as u32, 0x7e1d58e7);
+	assert_eq!(Action::ClearIdentity as u32, 0x7a6a10c7);
+	assert_eq!(Action::RequestJudgement as u32, 0xd523ceb9);
+	assert_eq!(Action::CancelRequest as u32, 0xc79934a5);
+	assert_eq!(Action::Identity as u32, 0xf0eb5e54);
+	assert_eq!(Action::SuperOf as u32, 0xc18110d6);
+	assert_eq!(Action::SubsOf as u32, 0x3f08986b);
+	assert_eq!(Action::Registrars as u32, 0xe88e512e);
+}
+
+#[test]
+fn set_identity_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let result = execute(Account::Alice, set_identity_input(alice_identity()))
+				.expect("precompile exists")
+				.expect("set_identity succeeds");
+
+			assert_eq!(result.exit_status, ExitSucceed::Returned);
+
+			let registration = Identity::identity(Account::Alice).expect("identity is set");
+			assert_eq!(registration.info.display, pallet_raw("Alice"));
+			assert_eq!(
+				registration.info.email,
+				pallet_identity::Data::Keccak256([0x42; 32])
+			);
+			assert_eq!(registration.info.pgp_fingerprint, Some([0x11; 20]));
+			assert_eq!(
+				registration.info.additional.to_vec(),
+				vec![(pallet_raw("discord"), pallet_raw("alice#1234"))]
+			);
+			// Basic deposit and one additional field.
+			assert_eq!(registration.deposit, 11);
+			assert_eq!(Balances::reserved_balance(Account::Alice), 11);
+		});
+}
+
+#[test]
+fn set_identity_fails_with_invalid_data() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let too_long = IdentityInfo {
+				display: Data {
+					kind: 1,
+					value: Bytes(vec![0x42; 33]),
+				},
+				..Default::default()
+			};
+			assert_eq!(
+				execute(Account::Alice, set_identity_input(too_long)),
+				Some(Err(error("raw data must be at most 32 bytes long")))
+			);
+
+			let bad_hash = IdentityInfo {
+				display: Data {
+					kind: 2,
+					value: Bytes(vec![0x42; 20]),
+				},
+				..Default::default()
+			};
+			assert_eq!(
+				execute(Account::Alice, set_identity_input(bad_hash)),
+				Some(Err(error("hash must be 32 bytes long")))
+			);
+
+			let unknown_kind = IdentityInfo {
+				display: Data {
+					kind: 6,
+					value: Bytes(vec![]),
+				},
+				..Default::default()
+			};
+			assert_eq!(
+				execute(Account::Alice, set_identity_input(unknown_kind)),
+				Some(Err(error("unknown data kind")))
+			);
+
+			let bad_fingerprint = IdentityInfo {
+				has_pgp_fingerprint: true,
+				pgp_fingerprint: Bytes(vec![0x11; 19]),
+				..Default::default()
+			};
+			assert_eq!(
+				execute(Account::Alice, set_identity_input(bad_fingerprint)),
+				Some(Err(error("pgp fingerprint must be 20 bytes long")))
+			);
+
+			assert!(Identity::identity(Account::Alice).is_none());
+		});
+}
+
+#[test]
+fn clear_identity_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			execute(Account::Alice, set_identity_input(alice_identity()))
+				.expect("precompile exists")
+				.expect("set_identity succeeds");
+
+			execute(
+				Account::Alice,
+				EvmDataWriter::new()
+					.write_selector(Action::ClearIdentity)
+					.build(),
+			)
+			.expect("precompile exists")
+			.expect("clear_identity succeeds");
+
+			assert!(Identity::identity(Account::Alice).is_none());
+			assert_eq!(Balances::reserved_balance(Account::Alice), 0);
+		});
+}
+
+#[test]
+fn identity_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let input = || {
+				EvmDataWriter::new()
+					.write_selector(Action::Identity)
+					.write(Address(Account::Alice.into()))
+					.build()
+			};
+
+			assert_eq!(
+				read_output::<Registration>(Account::Bob, input()),
+				Registration::default()
+			);
+
+			execute(Account::Alice, set_identity_input(alice_identity()))
+				.expect("precompile exists")
+				.expect("set_identity succeeds");
+
+			assert_eq!(
+				read_output::<Registration>(Account::Bob, input()),
+				Registration {
+					is_valid: true,
+					judgements: vec![],
+					deposit: 11.into(),
+					info: alice_identity(),
+				}
+			);
+		});
+}
+
+#[test]
+fn request_and_cancel_judgement_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Identity::add_registrar(Origin::root(), Account::Bob).is_ok());
+			assert!(Identity::set_fee(Origin::signed(Account::Bob), 0, 5).is_ok());
+
+			execute(Account::Alice, set_identity_input(alice_identity()))
+				.expect("precompile exists")
+				.expect("set_identity succeeds");
+
+			execute(
+				Account::Alice,
+				EvmDataWriter::new()
+					.write_selector(Action::RequestJudgement)
+					.write(0u32)
+					.write(U256::from(10))
+					.build(),
+			)
+			.expect("precompile exists")
+			.expect("request_judgement succeeds");
+
+			let registration: Registration = read_output(
+				Account::Bob,
+				EvmDataWriter::new()
+					.write_selector(Action::Identity)
+					.write(Address(Account::Alice.into()))
+					.build(),
+			);
+			assert_eq!(
+				registration.judgements,
+				vec![Judgement {
+					registrar_index: 0,
+					kind: 1,
+					fee: 5.into(),
+				}]
+			);
+			assert_eq!(Balances::reserved_balance(Account::Alice), 16);
+
+			execute(
+				Account::Alice,
+				EvmDataWriter::new()
+					.write_selector(Action::CancelRequest)
+					.write(0u32)
+					.build(),
+			)
+			.expect("precompile exists")
+			.expect("cancel_request succeeds");
+
+			let registration = Identity::identity(Account::Alice).expect("identity is set");
+			assert!(registration.judgements.is_empty());
+			assert_eq!(Balances::reserved_balance(Account::Alice), 11);
+		});
+}
+
+#[test]
+fn request_judgement_fails_with_too_large_fee() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let input = EvmDataWriter::new()
+				.write_selector(Action::RequestJudgement)
+				.write(0u32)
+				.write(U256::MAX)
+				.build();
+
+			assert_eq!(
+				execute(Account::Alice, input),
+				Some(Err(error("max fee is too large")))
+			);
+		});
+}
+
+#[test]
+fn registrars_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Identity::add_registrar(Origin::root(), Account::Bob).is_ok());
+			assert!(Identity::add_registrar(Origin::root(), Account::Charlie).is_ok());
+			assert!(Identity::set_fee(Origin::signed(Account::Charlie), 1, 7).is_ok());
+
+			assert_eq!(
+				read_output::<Vec<Registrar>>(
+					Account::Alice,
+					EvmDataWriter::new()
+						.write_selector(Action::Registrars)
+						.build(),
+				),
+				vec![
+					Registrar {
+						index: 0,
+						account: Address(Account::Bob.into()),
+						fee: 0.into(),
+					},
+					Registrar {
+						index: 1,
+						account: Address(Account::Charlie.into()),
+						fee: 7.into(),
+					},
+				]
+			);
+		});
+}
+
+#[test]
+fn super_of_and_subs_of_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			execute(Account::Alice, set_identity_input(alice_identity()))
+				.expect("precompile exists")
+				.expect("set_identity succeeds");
+			assert!(Identity::set_subs(
+				Origin::signed(Account::Alice),
+				vec![(Account::Bob, pallet_raw("bob"))]
+			)
+			.is_ok());
+
+			let super_of = |who: Account| {
+				read_output::<SuperOf>(
+					Account::Charlie,
+					EvmDataWriter::new()
+						.write_selector(Action::SuperOf)
+						.write(Address(who.into()))
+						.build(),
+				)
+			};
+			assert_eq!(
+				super_of(Account::Bob),
+				SuperOf {
+					is_valid: true,
+					account: Address(Account::Alice.into()),
+					data: raw("bob"),
+				}
+			);
+			assert_eq!(super_of(Account::Charlie), SuperOf::default());
+
+			assert_eq!(
+				read_output::<SubsOf>(
+					Account::Charlie,
+					EvmDataWriter::new()
+						.write_selector(Action::SubsOf)
+						.write(Address(Account::Alice.into()))
+						.build(),
+				),
+				SubsOf {
+					deposit: 5.into(),
+					accounts: vec![Address(Account::Bob.into())],
+				}
+			);
+		});
+}
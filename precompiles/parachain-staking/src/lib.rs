@@ -23,47 +23,17 @@ mod mock;
 #[cfg(test)]
 mod tests;
 
-use evm::{executor::PrecompileOutput, Context, ExitError, ExitSucceed};
 use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
 use frame_support::traits::{Currency, Get};
 use pallet_evm::AddressMapping;
-use pallet_evm::Precompile;
-use precompile_utils::{
-	error, Address, EvmData, EvmDataReader, EvmDataWriter, Gasometer, RuntimeHelper,
-};
+use precompile_utils::{error, Address, EvmData, EvmResult, PrecompileHandle, RuntimeHelper};
 use sp_std::convert::TryInto;
-use sp_std::fmt::Debug;
 use sp_std::marker::PhantomData;
-use sp_std::vec;
 
 type BalanceOf<Runtime> = <<Runtime as parachain_staking::Config>::Currency as Currency<
 	<Runtime as frame_system::Config>::AccountId,
 >>::Balance;
 
-#[precompile_utils::generate_function_selector]
-#[derive(Debug, PartialEq, num_enum::TryFromPrimitive)]
-enum Action {
-	MinNomination = "min_nomination()",
-	Points = "points(uint256)",
-	CandidateCount = "candidate_count()",
-	CollatorNominationCount = "collator_nomination_count(address)",
-	NominatorNominationCount = "nominator_nomination_count(address)",
-	IsNominator = "is_nominator(address)",
-	IsCandidate = "is_candidate(address)",
-	IsSelectedCandidate = "is_selected_candidate(address)",
-	JoinCandidates = "join_candidates(uint256,uint256)",
-	LeaveCandidates = "leave_candidates(uint256)",
-	GoOffline = "go_offline()",
-	GoOnline = "go_online()",
-	CandidateBondLess = "candidate_bond_less(uint256)",
-	CandidateBondMore = "candidate_bond_more(uint256)",
-	Nominate = "nominate(address,uint256,uint256,uint256)",
-	LeaveNominators = "leave_nominators(uint256)",
-	RevokeNomination = "revoke_nomination(address)",
-	NominatorBondLess = "nominator_bond_less(address,uint256)",
-	NominatorBondMore = "nominator_bond_more(address,uint256)",
-}
-
 /// A precompile to wrap the functionality from parachain_staking.
 ///
 /// EXAMPLE USECASE:
@@ -73,65 +43,7 @@ enum Action {
 /// supporters who want to donate toward a perpetual nomination fund.
 pub struct ParachainStakingWrapper<Runtime>(PhantomData<Runtime>);
 
-impl<Runtime> Precompile for ParachainStakingWrapper<Runtime>
-where
-	Runtime: parachain_staking::Config + pallet_evm::Config,
-	BalanceOf<Runtime>: EvmData,
-	Runtime::Call: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
-	<Runtime::Call as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
-	Runtime::Call: From<parachain_staking::Call<Runtime>>,
-{
-	fn execute(
-		input: &[u8], //Reminder this is big-endian
-		target_gas: Option<u64>,
-		context: &Context,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut input = EvmDataReader::new(input);
-		// Return early if storage getter; return (origin, call) if dispatchable
-		let (origin, call) = match &input.read_selector()? {
-			// constants
-			Action::MinNomination => return Self::min_nomination(target_gas),
-			// storage getters
-			Action::Points => return Self::points(input, target_gas),
-			Action::CandidateCount => return Self::candidate_count(target_gas),
-			Action::CollatorNominationCount => {
-				return Self::collator_nomination_count(input, target_gas)
-			}
-			Action::NominatorNominationCount => {
-				return Self::nominator_nomination_count(input, target_gas)
-			}
-			// role verifiers
-			Action::IsNominator => return Self::is_nominator(input, target_gas),
-			Action::IsCandidate => return Self::is_candidate(input, target_gas),
-			Action::IsSelectedCandidate => return Self::is_selected_candidate(input, target_gas),
-			// runtime methods (dispatchables)
-			Action::JoinCandidates => Self::join_candidates(input, context)?,
-			Action::LeaveCandidates => Self::leave_candidates(input, context)?,
-			Action::GoOffline => Self::go_offline(context)?,
-			Action::GoOnline => Self::go_online(context)?,
-			Action::CandidateBondLess => Self::candidate_bond_less(input, context)?,
-			Action::CandidateBondMore => Self::candidate_bond_more(input, context)?,
-			Action::Nominate => Self::nominate(input, context)?,
-			Action::LeaveNominators => Self::leave_nominators(input, context)?,
-			Action::RevokeNomination => Self::revoke_nomination(input, context)?,
-			Action::NominatorBondLess => Self::nominator_bond_less(input, context)?,
-			Action::NominatorBondMore => Self::nominator_bond_more(input, context)?,
-		};
-		// Initialize gasometer
-		let mut gasometer = Gasometer::new(target_gas);
-		// Dispatch call (if enough gas).
-		let used_gas =
-			RuntimeHelper::<Runtime>::try_dispatch(origin, call, gasometer.remaining_gas()?)?;
-		gasometer.record_cost(used_gas)?;
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: vec![],
-			logs: vec![],
-		})
-	}
-}
-
+#[precompile_utils::precompile]
 impl<Runtime> ParachainStakingWrapper<Runtime>
 where
 	Runtime: parachain_staking::Config + pallet_evm::Config,
@@ -142,81 +54,43 @@ where
 {
 	// Constants
 
-	fn min_nomination(target_gas: Option<u64>) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
+	#[precompile::public("min_nomination()")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn min_nomination() -> EvmResult<u128> {
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let min_nomination: u128 = <<Runtime as parachain_staking::Config>::MinNomination as Get<
-			BalanceOf<Runtime>,
-		>>::get()
-		.try_into()
-		.map_err(|_| error("Amount is too large for provided balance type"))?;
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(min_nomination).build(),
-			logs: vec![],
-		})
+		<<Runtime as parachain_staking::Config>::MinNomination as Get<BalanceOf<Runtime>>>::get()
+			.try_into()
+			.map_err(|_| error("Amount is too large for provided balance type"))
 	}
 
 	// Storage Getters
 
-	fn points(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		// Read input.
-		input.expect_arguments(1)?;
-		let round = input.read::<u32>()?;
-
+	#[precompile::public("points(uint256)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn points(round: u32) -> EvmResult<u32> {
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let points: u32 = parachain_staking::Pallet::<Runtime>::points(round);
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(points).build(),
-			logs: vec![],
-		})
+		Ok(parachain_staking::Pallet::<Runtime>::points(round))
 	}
 
-	fn candidate_count(target_gas: Option<u64>) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
+	#[precompile::public("candidate_count()")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn candidate_count() -> EvmResult<u32> {
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let candidate_count: u32 = <parachain_staking::Pallet<Runtime>>::candidate_pool()
+		Ok(<parachain_staking::Pallet<Runtime>>::candidate_pool()
 			.0
-			.len() as u32;
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(candidate_count).build(),
-			logs: vec![],
-		})
+			.len() as u32)
 	}
 
-	fn collator_nomination_count(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		// Read input.
-		input.expect_arguments(1)?;
-		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
+	#[precompile::public("collator_nomination_count(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn collator_nomination_count(collator: Address) -> EvmResult<u32> {
+		let address = Runtime::AddressMapping::into_account_id(collator.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 		let result =
 			if let Some(state) = <parachain_staking::Pallet<Runtime>>::collator_state2(&address) {
 				let collator_nomination_count: u32 = state.nominators.0.len() as u32;
@@ -236,27 +110,16 @@ where
 				0u32
 			};
 
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(result).build(),
-			logs: vec![],
-		})
+		Ok(result)
 	}
 
-	fn nominator_nomination_count(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		// Read input.
-		input.expect_arguments(1)?;
-		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
+	#[precompile::public("nominator_nomination_count(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn nominator_nomination_count(nominator: Address) -> EvmResult<u32> {
+		let address = Runtime::AddressMapping::into_account_id(nominator.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
 		let result =
 			if let Some(state) = <parachain_staking::Pallet<Runtime>>::nominator_state2(&address) {
 				let nominator_nomination_count: u32 = state.nominations.0.len() as u32;
@@ -277,231 +140,99 @@ where
 				0u32
 			};
 
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(result).build(),
-			logs: vec![],
-		})
+		Ok(result)
 	}
 
 	// Role Verifiers
 
-	fn is_nominator(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		// Read input.
-		input.expect_arguments(1)?;
-		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
+	#[precompile::public("is_nominator(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn is_nominator(nominator: Address) -> EvmResult<bool> {
+		let address = Runtime::AddressMapping::into_account_id(nominator.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let is_nominator = parachain_staking::Pallet::<Runtime>::is_nominator(&address);
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(is_nominator).build(),
-			logs: vec![],
-		})
+		Ok(parachain_staking::Pallet::<Runtime>::is_nominator(&address))
 	}
 
-	fn is_candidate(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		// Read input.
-		input.expect_arguments(1)?;
-		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
+	#[precompile::public("is_candidate(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn is_candidate(candidate: Address) -> EvmResult<bool> {
+		let address = Runtime::AddressMapping::into_account_id(candidate.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let is_candidate = parachain_staking::Pallet::<Runtime>::is_candidate(&address);
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(is_candidate).build(),
-			logs: vec![],
-		})
+		Ok(parachain_staking::Pallet::<Runtime>::is_candidate(&address))
 	}
 
-	fn is_selected_candidate(
-		mut input: EvmDataReader,
-		target_gas: Option<u64>,
-	) -> Result<PrecompileOutput, ExitError> {
-		let mut gasometer = Gasometer::new(target_gas);
-
-		// Read input.
-		input.expect_arguments(1)?;
-		let address = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
+	#[precompile::public("is_selected_candidate(address)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn is_selected_candidate(candidate: Address) -> EvmResult<bool> {
+		let address = Runtime::AddressMapping::into_account_id(candidate.0);
 
 		// Fetch info.
-		gasometer.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
-		let is_selected = parachain_staking::Pallet::<Runtime>::is_selected_candidate(&address);
-
-		// Build output.
-		Ok(PrecompileOutput {
-			exit_status: ExitSucceed::Returned,
-			cost: gasometer.used_gas(),
-			output: EvmDataWriter::new().write(is_selected).build(),
-			logs: vec![],
-		})
+		Ok(parachain_staking::Pallet::<Runtime>::is_selected_candidate(&address))
 	}
 
 	// Runtime Methods (dispatchables)
 
+	#[precompile::public("join_candidates(uint256,uint256)")]
 	fn join_candidates(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(2)?;
-		let amount: BalanceOf<Runtime> = input.read()?;
-		let collator_candidate_count = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+		handle: &mut PrecompileHandle,
+		amount: BalanceOf<Runtime>,
+		collator_candidate_count: u32,
+	) -> EvmResult {
 		let call =
 			parachain_staking::Call::<Runtime>::join_candidates(amount, collator_candidate_count);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn leave_candidates(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(1)?;
-		let collator_candidate_count = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+	#[precompile::public("leave_candidates(uint256)")]
+	fn leave_candidates(handle: &mut PrecompileHandle, collator_candidate_count: u32) -> EvmResult {
 		let call = parachain_staking::Call::<Runtime>::leave_candidates(collator_candidate_count);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn go_offline(
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+	#[precompile::public("go_offline()")]
+	fn go_offline(handle: &mut PrecompileHandle) -> EvmResult {
 		let call = parachain_staking::Call::<Runtime>::go_offline();
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn go_online(
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+	#[precompile::public("go_online()")]
+	fn go_online(handle: &mut PrecompileHandle) -> EvmResult {
 		let call = parachain_staking::Call::<Runtime>::go_online();
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn candidate_bond_more(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(1)?;
-		let amount: BalanceOf<Runtime> = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
-		let call = parachain_staking::Call::<Runtime>::candidate_bond_more(amount);
+	#[precompile::public("candidate_bond_less(uint256)")]
+	fn candidate_bond_less(handle: &mut PrecompileHandle, amount: BalanceOf<Runtime>) -> EvmResult {
+		let call = parachain_staking::Call::<Runtime>::candidate_bond_less(amount);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn candidate_bond_less(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(1)?;
-		let amount: BalanceOf<Runtime> = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
-		let call = parachain_staking::Call::<Runtime>::candidate_bond_less(amount);
+	#[precompile::public("candidate_bond_more(uint256)")]
+	fn candidate_bond_more(handle: &mut PrecompileHandle, amount: BalanceOf<Runtime>) -> EvmResult {
+		let call = parachain_staking::Call::<Runtime>::candidate_bond_more(amount);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
+	#[precompile::public("nominate(address,uint256,uint256,uint256)")]
 	fn nominate(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(4)?;
-		let collator = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
-		let amount: BalanceOf<Runtime> = input.read()?;
-		let collator_nomination_count = input.read()?;
-		let nominator_nomination_count = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+		handle: &mut PrecompileHandle,
+		collator: Address,
+		amount: BalanceOf<Runtime>,
+		collator_nomination_count: u32,
+		nominator_nomination_count: u32,
+	) -> EvmResult {
+		let collator = Runtime::AddressMapping::into_account_id(collator.0);
 		let call = parachain_staking::Call::<Runtime>::nominate(
 			collator,
 			amount,
@@ -509,97 +240,59 @@ where
 			nominator_nomination_count,
 		);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn leave_nominators(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(1)?;
-		let nomination_count = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+	#[precompile::public("leave_nominators(uint256)")]
+	fn leave_nominators(handle: &mut PrecompileHandle, nomination_count: u32) -> EvmResult {
 		let call = parachain_staking::Call::<Runtime>::leave_nominators(nomination_count);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn revoke_nomination(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(1)?;
-		let collator = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+	#[precompile::public("revoke_nomination(address)")]
+	fn revoke_nomination(handle: &mut PrecompileHandle, collator: Address) -> EvmResult {
+		let collator = Runtime::AddressMapping::into_account_id(collator.0);
 		let call = parachain_staking::Call::<Runtime>::revoke_nomination(collator);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
+	#[precompile::public("nominator_bond_less(address,uint256)")]
+	fn nominator_bond_less(
+		handle: &mut PrecompileHandle,
+		collator: Address,
+		amount: BalanceOf<Runtime>,
+	) -> EvmResult {
+		let collator = Runtime::AddressMapping::into_account_id(collator.0);
+		let call = parachain_staking::Call::<Runtime>::nominator_bond_less(collator, amount);
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("nominator_bond_more(address,uint256)")]
 	fn nominator_bond_more(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(2)?;
-		let collator = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
-		let amount: BalanceOf<Runtime> = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+		handle: &mut PrecompileHandle,
+		collator: Address,
+		amount: BalanceOf<Runtime>,
+	) -> EvmResult {
+		let collator = Runtime::AddressMapping::into_account_id(collator.0);
 		let call = parachain_staking::Call::<Runtime>::nominator_bond_more(collator, amount);
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Self::dispatch(handle, call)
 	}
 
-	fn nominator_bond_less(
-		mut input: EvmDataReader,
-		context: &Context,
-	) -> Result<
-		(
-			<Runtime::Call as Dispatchable>::Origin,
-			parachain_staking::Call<Runtime>,
-		),
-		ExitError,
-	> {
-		// Read input.
-		input.expect_arguments(2)?;
-		let collator = Runtime::AddressMapping::into_account_id(input.read::<Address>()?.0);
-		let amount: BalanceOf<Runtime> = input.read()?;
-
-		// Build call with origin.
-		let origin = Runtime::AddressMapping::into_account_id(context.caller);
-		let call = parachain_staking::Call::<Runtime>::nominator_bond_less(collator, amount);
+	/// Dispatch the call with the caller as origin (if enough gas).
+	fn dispatch(
+		handle: &mut PrecompileHandle,
+		call: parachain_staking::Call<Runtime>,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let used_gas =
+			RuntimeHelper::<Runtime>::try_dispatch(Some(origin).into(), call, handle.remaining_gas()?)?;
+		handle.record_cost(used_gas)?;
 
-		// Return call information
-		Ok((Some(origin).into(), call))
+		Ok(())
 	}
 }
@@ -18,7 +18,7 @@ use crate::mock::{
 	events, evm_test_context, precompile_address, set_points, Call, ExtBuilder, Origin,
 	ParachainStaking, Precompiles, TestAccount,
 };
-use crate::PrecompileOutput;
+use evm::executor::PrecompileOutput;
 use frame_support::{assert_ok, dispatch::Dispatchable};
 use pallet_evm::Call as EvmCall;
 use pallet_evm::{ExitSucceed, PrecompileSet};
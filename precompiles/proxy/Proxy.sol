@@ -0,0 +1,82 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/**
+ * @title Proxy precompile
+ *
+ * Allows to manage the proxies of the caller, and to perform calls as a proxied account.
+ * Address : 0x0000000000000000000000000000000000000806
+ *
+ * Proxy types are the SCALE index of the runtime `ProxyType`:
+ * 0: Any, 1: NonTransfer, 2: Governance, 3: Staking, 4: CancelProxy
+ */
+interface Proxy {
+    /**
+     * Register a proxy account for the caller.
+     * Selector: 74a34dd3
+     *
+     * @param delegate The account that the caller would like to make a proxy.
+     * @param proxyType The permissions allowed for this proxy account.
+     * @param delay The announcement period required of the initial proxy, in blocks.
+     */
+    function addProxy(
+        address delegate,
+        uint8 proxyType,
+        uint32 delay
+    ) external;
+
+    /**
+     * Unregister a proxy account for the caller.
+     * Selector: fef3f708
+     *
+     * @param delegate The account that the caller would like to remove as a proxy.
+     * @param proxyType The permissions currently enabled for the removed proxy account.
+     * @param delay The announcement period of the removed proxy, in blocks.
+     */
+    function removeProxy(
+        address delegate,
+        uint8 proxyType,
+        uint32 delay
+    ) external;
+
+    /**
+     * Unregister all proxy accounts for the caller.
+     * Selector: 14a5b5fa
+     */
+    function removeProxies() external;
+
+    /**
+     * Check if an account is a proxy of another account with the given type and delay.
+     * Selector: e26d38ed
+     *
+     * @param real The account that has the proxy.
+     * @param delegate The proxy account.
+     * @param proxyType The permissions of the proxy account.
+     * @param delay The announcement period of the proxy account, in blocks.
+     * @return exists True if the proxy exists.
+     */
+    function isProxy(
+        address real,
+        address delegate,
+        uint8 proxyType,
+        uint32 delay
+    ) external view returns (bool exists);
+
+    /**
+     * Perform a call as `real`, if the caller is a proxy of `real` without delay and
+     * with a proxy type allowing this call. The proxied call cannot perform another proxied call,
+     * and is reverted along with the changes it made if it fails.
+     * Selector: 9ddba085
+     *
+     * @param real The account that the proxy will make a call on behalf of.
+     * @param callTo The address to call.
+     * @param value The amount to transfer from `real` to `callTo`.
+     * @param callData The call data to provide to `callTo`.
+     */
+    function proxy(
+        address real,
+        address callTo,
+        uint256 value,
+        bytes memory callData
+    ) external;
+}
@@ -0,0 +1,233 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to manage proxies and perform proxied calls via the EVM.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Decode;
+use frame_support::{
+	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
+	sp_runtime::{traits::Zero, TransactionOutcome},
+	storage::with_transaction,
+};
+use pallet_evm::AddressMapping;
+use pallet_proxy::ProxyDefinition;
+use precompile_utils::{error, Address, Bytes, EvmResult, PrecompileHandle, RuntimeHelper};
+use sp_core::{H160, U256};
+use sp_std::{marker::PhantomData, vec::Vec};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type RuntimeCall<Runtime> = <Runtime as frame_system::Config>::Call;
+
+// Set while a proxied call is executed. Subcalls are performed by a new EVM executor which doesn't
+// know about the call depth of the precompile, so nested proxied calls are forbidden to bound the
+// recursion.
+environmental::environmental!(in_proxy: bool);
+
+/// An EVM call performed through the `proxy` function of the precompile.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EvmSubCall {
+	/// Address being called.
+	pub to: H160,
+	/// Value transfered from the proxied account.
+	pub value: U256,
+	/// Call data provided to the called address.
+	pub call_data: Vec<u8>,
+}
+
+/// Filter of the EVM calls a proxy type allows to perform on behalf of the proxied account.
+///
+/// This is the EVM counterpart of the `InstanceFilter<Call>` implemented by the runtime
+/// `ProxyType`, and should be kept consistent with it: proxies with a type other than `Any` should
+/// only be allowed to call the precompiles matching their Substrate filter. The proxy and batch
+/// precompiles should not be allowed either, as they would allow to bypass the filter.
+pub trait EvmProxyCallFilter {
+	/// Returns whether the proxy type allows to perform the provided call.
+	fn is_evm_proxy_call_allowed(&self, _call: &EvmSubCall) -> bool {
+		false
+	}
+}
+
+/// A precompile to wrap the functionality from pallet_proxy.
+pub struct ProxyWrapper<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> ProxyWrapper<Runtime>
+where
+	Runtime: pallet_proxy::Config + pallet_evm::Config,
+	Runtime::ProxyType: EvmProxyCallFilter,
+	RuntimeCall<Runtime>: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	<RuntimeCall<Runtime> as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	RuntimeCall<Runtime>: From<pallet_proxy::Call<Runtime>>,
+{
+	#[precompile::public("addProxy(address,uint8,uint32)")]
+	fn add_proxy(
+		handle: &mut PrecompileHandle,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult {
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.0);
+		let proxy_type = Self::decode_proxy_type(proxy_type)?;
+		let call = pallet_proxy::Call::<Runtime>::add_proxy(delegate, proxy_type, delay.into());
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("removeProxy(address,uint8,uint32)")]
+	fn remove_proxy(
+		handle: &mut PrecompileHandle,
+		delegate: Address,
+		proxy_type: u8,
+		delay: u32,
+	) -> EvmResult {
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.0);
+		let proxy_type = Self::decode_proxy_type(proxy_type)?;
+		let call = pallet_proxy::Call::<Runtime>::remove_proxy(delegate, proxy_type, delay.into());
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("removeProxies()")]
+	fn remove_proxies(handle: &mut PrecompileHandle) -> EvmResult {
+		let call = pallet_proxy::Call::<Runtime>::remove_proxies();
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("isProxy(address,address,uint8,uint32)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn is_proxy(real: Address, delegate: Address, proxy_type: u8, delay: u32) -> EvmResult<bool> {
+		let real = Runtime::AddressMapping::into_account_id(real.0);
+		let delegate = Runtime::AddressMapping::into_account_id(delegate.0);
+		let proxy_type = Self::decode_proxy_type(proxy_type)?;
+
+		let definition = ProxyDefinition {
+			delegate,
+			proxy_type,
+			delay: delay.into(),
+		};
+
+		Ok(pallet_proxy::Pallet::<Runtime>::proxies(real)
+			.0
+			.contains(&definition))
+	}
+
+	#[precompile::public("proxy(address,address,uint256,bytes)")]
+	fn proxy(
+		handle: &mut PrecompileHandle,
+		real: Address,
+		call_to: Address,
+		value: U256,
+		call_data: Bytes,
+	) -> EvmResult {
+		if in_proxy::with(|_| ()).is_some() {
+			return Err(error("cannot perform a proxied call inside a proxied call"));
+		}
+
+		// Read the proxies of `real`.
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+
+		let real_account = Runtime::AddressMapping::into_account_id(real.0);
+		let delegate = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let call = EvmSubCall {
+			to: call_to.0,
+			value,
+			call_data: call_data.0,
+		};
+
+		// Only proxies without delay can perform calls directly, the ones with a delay must
+		// announce them through Substrate.
+		let allowed = pallet_proxy::Pallet::<Runtime>::proxies(real_account)
+			.0
+			.iter()
+			.any(|definition| {
+				definition.delegate == delegate
+					&& definition.delay.is_zero()
+					&& definition.proxy_type.is_evm_proxy_call_allowed(&call)
+			});
+
+		if !allowed {
+			return Err(error("Not a proxy allowed to perform this call"));
+		}
+
+		log::trace!(
+			target: "proxy-precompile",
+			"Proxied call from {:?} to {:?} with value {:?}",
+			real.0,
+			call.to,
+			call.value
+		);
+
+		in_proxy::using(&mut true, || {
+			with_transaction(|| {
+				let result = Self::proxied_call(handle, real.0, call);
+
+				if result.is_ok() {
+					TransactionOutcome::Commit(result)
+				} else {
+					TransactionOutcome::Rollback(result)
+				}
+			})
+		})
+	}
+
+	fn proxied_call(handle: &mut PrecompileHandle, real: H160, call: EvmSubCall) -> EvmResult {
+		let remaining_gas = handle.remaining_gas()?.unwrap_or(u64::MAX);
+		let outcome = RuntimeHelper::<Runtime>::evm_subcall(
+			real,
+			call.to,
+			call.value,
+			call.call_data,
+			remaining_gas,
+		);
+		handle.record_cost(outcome.used_gas)?;
+
+		if !outcome.succeeded {
+			return Err(error("Proxied call failed"));
+		}
+
+		handle.add_logs(outcome.logs);
+
+		Ok(())
+	}
+
+	fn decode_proxy_type(proxy_type: u8) -> EvmResult<Runtime::ProxyType> {
+		Runtime::ProxyType::decode(&mut &[proxy_type][..])
+			.map_err(|_| error("Failed decoding value to ProxyType"))
+	}
+
+	/// Dispatch the call with the caller as origin (if enough gas).
+	fn dispatch(handle: &mut PrecompileHandle, call: pallet_proxy::Call<Runtime>) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.remaining_gas()?,
+		)?;
+		handle.record_cost(used_gas)?;
+
+		Ok(())
+	}
+}
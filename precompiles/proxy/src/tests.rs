@@ -0,0 +1,354 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::{executor::PrecompileOutput, ExitSucceed};
+use pallet_evm::PrecompileSet;
+use precompile_utils::{error, Address, Bytes, EvmDataWriter};
+
+fn evm_test_context(caller: Account) -> evm::Context {
+	evm::Context {
+		address: Account::Precompile.into(),
+		caller: caller.into(),
+		apparent_value: From::from(0),
+	}
+}
+
+fn execute(caller: Account, input: Vec<u8>) -> Option<EvmResult<PrecompileOutput>> {
+	Precompiles::<Runtime>::execute(
+		Account::Precompile.into(),
+		&input,
+		None,
+		&evm_test_context(caller),
+	)
+}
+
+fn proxy_input(action: Action, delegate: Account, proxy_type: ProxyType, delay: u32) -> Vec<u8> {
+	EvmDataWriter::new()
+		.write_selector(action)
+		.write(Address(delegate.into()))
+		.write(proxy_type as u8)
+		.write(delay)
+		.build()
+}
+
+fn definition(
+	delegate: Account,
+	proxy_type: ProxyType,
+	delay: u64,
+) -> ProxyDefinition<Account, ProxyType, u64> {
+	ProxyDefinition {
+		delegate,
+		proxy_type,
+		delay,
+	}
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::AddProxy as u32, 0x74a34dd3);
+	assert_eq!(Action::RemoveProxy as u32, 0xfef3f708);
+	assert_eq!(Action::RemoveProxies as u32, 0x14a5b5fa);
+	assert_eq!(Action::IsProxy as u32, 0xe26d38ed);
+	assert_eq!(Action::Proxy as u32, 0x9ddba085);
+}
+
+#[test]
+fn add_proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let result = execute(
+				Account::Alice,
+				proxy_input(Action::AddProxy, Account::Bob, ProxyType::Any, 0),
+			)
+			.expect("precompile exists")
+			.expect("add_proxy succeeds");
+
+			assert_eq!(result.exit_status, ExitSucceed::Returned);
+			assert_eq!(
+				Proxy::proxies(Account::Alice).0.into_inner(),
+				vec![definition(Account::Bob, ProxyType::Any, 0)]
+			);
+		});
+}
+
+#[test]
+fn add_proxy_fails_with_unknown_proxy_type() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let input = EvmDataWriter::new()
+				.write_selector(Action::AddProxy)
+				.write(Address(Account::Bob.into()))
+				.write(5u8)
+				.write(0u32)
+				.build();
+
+			assert_eq!(
+				execute(Account::Alice, input),
+				Some(Err(error("Failed decoding value to ProxyType")))
+			);
+		});
+}
+
+#[test]
+fn remove_proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::Any,
+				0
+			)
+			.is_ok());
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Charlie,
+				ProxyType::NonTransfer,
+				0
+			)
+			.is_ok());
+
+			execute(
+				Account::Alice,
+				proxy_input(Action::RemoveProxy, Account::Bob, ProxyType::Any, 0),
+			)
+			.expect("precompile exists")
+			.expect("remove_proxy succeeds");
+
+			assert_eq!(
+				Proxy::proxies(Account::Alice).0.into_inner(),
+				vec![definition(Account::Charlie, ProxyType::NonTransfer, 0)]
+			);
+		});
+}
+
+#[test]
+fn remove_proxies_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::Any,
+				0
+			)
+			.is_ok());
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Charlie,
+				ProxyType::NonTransfer,
+				0
+			)
+			.is_ok());
+
+			execute(
+				Account::Alice,
+				EvmDataWriter::new()
+					.write_selector(Action::RemoveProxies)
+					.build(),
+			)
+			.expect("precompile exists")
+			.expect("remove_proxies succeeds");
+
+			assert!(Proxy::proxies(Account::Alice).0.is_empty());
+			assert_eq!(Balances::reserved_balance(Account::Alice), 0);
+		});
+}
+
+#[test]
+fn is_proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::Any,
+				0
+			)
+			.is_ok());
+
+			let is_proxy = |delegate: Account, proxy_type: ProxyType, delay: u32| {
+				let input = EvmDataWriter::new()
+					.write_selector(Action::IsProxy)
+					.write(Address(Account::Alice.into()))
+					.write(Address(delegate.into()))
+					.write(proxy_type as u8)
+					.write(delay)
+					.build();
+
+				execute(Account::Charlie, input)
+					.expect("precompile exists")
+					.expect("is_proxy succeeds")
+					.output
+			};
+
+			let output = |exists: bool| EvmDataWriter::new().write(exists).build();
+
+			assert_eq!(is_proxy(Account::Bob, ProxyType::Any, 0), output(true));
+			assert_eq!(
+				is_proxy(Account::Bob, ProxyType::NonTransfer, 0),
+				output(false)
+			);
+			assert_eq!(is_proxy(Account::Bob, ProxyType::Any, 1), output(false));
+			assert_eq!(is_proxy(Account::Charlie, ProxyType::Any, 0), output(false));
+		});
+}
+
+fn proxy_call_input(real: Account, call_to: Account, value: u128) -> Vec<u8> {
+	EvmDataWriter::new()
+		.write_selector(Action::Proxy)
+		.write(Address(real.into()))
+		.write(Address(call_to.into()))
+		.write(U256::from(value))
+		.write(Bytes(vec![]))
+		.build()
+}
+
+#[test]
+fn proxy_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::Any,
+				0
+			)
+			.is_ok());
+
+			let result = execute(
+				Account::Bob,
+				proxy_call_input(Account::Alice, Account::Charlie, 100),
+			)
+			.expect("precompile exists")
+			.expect("proxy succeeds");
+
+			assert_eq!(result.exit_status, ExitSucceed::Returned);
+			assert_eq!(Balances::free_balance(Account::Charlie), 100);
+			assert_eq!(System::account_nonce(Account::Alice), 0);
+		});
+}
+
+#[test]
+fn proxy_fails_if_not_proxy() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert_eq!(
+				execute(
+					Account::Bob,
+					proxy_call_input(Account::Alice, Account::Charlie, 100),
+				),
+				Some(Err(error("Not a proxy allowed to perform this call")))
+			);
+		});
+}
+
+#[test]
+fn proxy_respects_proxy_type_filter() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::NonTransfer,
+				0
+			)
+			.is_ok());
+
+			assert_eq!(
+				execute(
+					Account::Bob,
+					proxy_call_input(Account::Alice, Account::Charlie, 100),
+				),
+				Some(Err(error("Not a proxy allowed to perform this call")))
+			);
+			assert_eq!(Balances::free_balance(Account::Charlie), 0);
+		});
+}
+
+#[test]
+fn proxy_fails_with_delay() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::Any,
+				1
+			)
+			.is_ok());
+
+			assert_eq!(
+				execute(
+					Account::Bob,
+					proxy_call_input(Account::Alice, Account::Charlie, 100),
+				),
+				Some(Err(error("Not a proxy allowed to perform this call")))
+			);
+		});
+}
+
+#[test]
+fn proxy_cannot_be_nested() {
+	ExtBuilder::default()
+		.with_balances(vec![(Account::Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			assert!(Proxy::add_proxy(
+				Origin::signed(Account::Alice),
+				Account::Bob,
+				ProxyType::Any,
+				0
+			)
+			.is_ok());
+
+			let nested_proxy = proxy_call_input(Account::Alice, Account::Charlie, 100);
+			let input = EvmDataWriter::new()
+				.write_selector(Action::Proxy)
+				.write(Address(Account::Alice.into()))
+				.write(Address(Account::Precompile.into()))
+				.write(U256::zero())
+				.write(Bytes(nested_proxy))
+				.build();
+
+			assert_eq!(
+				execute(Account::Bob, input),
+				Some(Err(error("Proxied call failed")))
+			);
+			assert_eq!(Balances::free_balance(Account::Charlie), 0);
+		});
+}
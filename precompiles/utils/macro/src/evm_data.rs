@@ -0,0 +1,98 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+//! Implementation of `#[derive(EvmData)]` for structs.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+	parse_quote, spanned::Spanned, Data, DataStruct, DeriveInput, Error, Fields, WhereClause,
+};
+
+pub fn main(input: TokenStream) -> Result<TokenStream, Error> {
+	let DeriveInput {
+		ident,
+		generics,
+		data,
+		..
+	} = syn::parse2(input)?;
+
+	let fields = match data {
+		Data::Struct(DataStruct {
+			fields: Fields::Named(fields),
+			..
+		}) => fields.named,
+		_ => {
+			return Err(Error::new(
+				ident.span(),
+				"EvmData can only be derived for structs with named fields",
+			))
+		}
+	};
+
+	if fields.is_empty() {
+		return Err(Error::new(
+			fields.span(),
+			"EvmData cannot be derived for structs without fields",
+		));
+	}
+
+	let idents: Vec<_> = fields.iter().map(|field| &field.ident).collect();
+	let types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+
+	let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+	let mut where_clause: WhereClause = where_clause
+		.cloned()
+		.unwrap_or_else(|| parse_quote!(where));
+	for ty in &types {
+		where_clause
+			.predicates
+			.push(parse_quote!(#ty: ::precompile_utils::EvmData));
+	}
+
+	// A struct is encoded like a tuple of its fields. If one of the fields is dynamic, this
+	// tuple is encoded behind a pointer.
+	Ok(quote! {
+		impl #impl_generics ::precompile_utils::EvmData for #ident #ty_generics #where_clause {
+			fn read(
+				reader: &mut ::precompile_utils::EvmDataReader,
+			) -> ::precompile_utils::EvmResult<Self> {
+				let mut inner_reader;
+				let reader = if <Self as ::precompile_utils::EvmData>::has_static_size() {
+					reader
+				} else {
+					inner_reader = reader.read_pointer()?;
+					&mut inner_reader
+				};
+
+				Ok(Self {
+					#(#idents: reader.read()?,)*
+				})
+			}
+
+			fn write(writer: &mut ::precompile_utils::EvmDataWriter, value: Self) {
+				if <Self as ::precompile_utils::EvmData>::has_static_size() {
+					#(::precompile_utils::EvmData::write(writer, value.#idents);)*
+				} else {
+					let inner_writer = ::precompile_utils::EvmDataWriter::new()
+						#(.write(value.#idents))*;
+					writer.write_pointer(inner_writer.build());
+				}
+			}
+
+			fn has_static_size() -> bool {
+				true #(&& <#types as ::precompile_utils::EvmData>::has_static_size())*
+			}
+		}
+	})
+}
@@ -21,6 +21,9 @@ use sha3::{Digest, Keccak256};
 use std::convert::TryInto;
 use syn::{parse_macro_input, spanned::Spanned, Expr, ExprLit, Ident, ItemEnum, Lit, LitStr};
 
+mod evm_data;
+mod precompile;
+
 struct Bytes(Vec<u8>);
 
 impl ::std::fmt::Debug for Bytes {
@@ -141,3 +144,82 @@ pub fn generate_function_selector(_: TokenStream, input: TokenStream) -> TokenSt
 	})
 	.into()
 }
+
+/// This macro generates the dispatching logic of a precompile from an impl block in which the
+/// functions exposed to Solidity are written as normal Rust functions with typed arguments.
+///
+/// Exposed functions are annotated with `#[precompile::public("signature")]`, and can additionaly
+/// be annotated with:
+/// - `#[precompile::view]` or `#[precompile::payable]`. Functions that are not payable return an
+///   error if called with some value.
+/// - `#[precompile::gas(reads = N, writes = M)]` to record the cost of DB reads/writes before the
+///   arguments are parsed. This requires the impl block to have a `Runtime` type parameter.
+///
+/// If the first argument of a function is a `&mut` reference, it is given the
+/// `PrecompileHandle` which allows to record gas costs, emit logs and access the call context.
+/// The other arguments are parsed from the input using `EvmData`, and so is the output of the
+/// function, which must be an `EvmResult`.
+///
+/// Structs deriving `EvmData` can be used as arguments or output, and are referred to by their
+/// name in the generated Solidity interface.
+///
+/// The macro also generates an enum (named `Action` unless provided as argument of the macro)
+/// containing the selector of each function, along with the Solidity interface of the precompile.
+///
+/// Usage:
+///
+/// ```ignore
+/// #[precompile_utils::precompile]
+/// impl<Runtime> MyPrecompile<Runtime> {
+/// 	#[precompile::public("balanceOf(address)")]
+/// 	#[precompile::view]
+/// 	#[precompile::gas(reads = 1)]
+/// 	fn balance_of(owner: Address) -> EvmResult<U256> {
+/// 		// ...
+/// 	}
+///
+/// 	#[precompile::public("transfer(address,uint256)")]
+/// 	fn transfer(handle: &mut PrecompileHandle, to: Address, value: U256) -> EvmResult<bool> {
+/// 		// ...
+/// 	}
+/// }
+/// ```
+///
+/// Generates the `Action` enum with variants `BalanceOf` and `Transfer`, and an implementation
+/// of `pallet_evm::Precompile` for `MyPrecompile<Runtime>`.
+#[proc_macro_attribute]
+pub fn precompile(attr: TokenStream, input: TokenStream) -> TokenStream {
+	match precompile::main(attr.into(), input.into()) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
+
+/// Derives `EvmData` for a struct with named fields, which is encoded like a Solidity struct
+/// with the same fields in the same order. All fields must implement `EvmData`.
+///
+/// Usage:
+///
+/// ```ignore
+/// #[derive(EvmData)]
+/// pub struct Data {
+/// 	kind: u8,
+/// 	value: Bytes,
+/// }
+/// ```
+///
+/// matches the Solidity struct
+///
+/// ```solidity
+/// struct Data {
+/// 	uint8 kind;
+/// 	bytes value;
+/// }
+/// ```
+#[proc_macro_derive(EvmData)]
+pub fn derive_evm_data(input: TokenStream) -> TokenStream {
+	match evm_data::main(input.into()) {
+		Ok(tokens) => tokens.into(),
+		Err(err) => err.to_compile_error().into(),
+	}
+}
@@ -0,0 +1,493 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+//! Implementation of the `#[precompile]` attribute macro.
+
+use proc_macro2::{Literal, Span, TokenStream};
+use quote::{format_ident, quote};
+use sha3::{Digest, Keccak256};
+use std::convert::TryInto;
+use syn::{
+	spanned::Spanned, Attribute, Error, FnArg, GenericArgument, Ident, ImplItem, ItemImpl, Lit,
+	LitStr, Meta, NestedMeta, PathArguments, ReturnType, Type,
+};
+
+/// Modifier of a public function, which controls if it accepts value.
+#[derive(Clone, Copy, PartialEq)]
+enum Modifier {
+	NonPayable,
+	View,
+	Payable,
+}
+
+/// Description of a function exposed by the precompile.
+struct Function {
+	ident: Ident,
+	variant: Ident,
+	signature: LitStr,
+	selector: u32,
+	modifier: Modifier,
+	reads: u64,
+	writes: u64,
+	takes_handle: bool,
+	arguments: Vec<Type>,
+	returns: Option<String>,
+}
+
+pub fn main(attr: TokenStream, item: TokenStream) -> Result<TokenStream, Error> {
+	let enum_ident: Ident = if attr.is_empty() {
+		Ident::new("Action", Span::call_site())
+	} else {
+		syn::parse2(attr)?
+	};
+
+	let mut item: ItemImpl = syn::parse2(item)?;
+
+	if let Some((_, path, _)) = &item.trait_ {
+		return Err(Error::new(
+			path.span(),
+			"#[precompile] must be used on an inherent impl block",
+		));
+	}
+
+	let interface_name = match &*item.self_ty {
+		Type::Path(type_path) => match type_path.path.segments.last() {
+			Some(segment) => segment.ident.to_string(),
+			None => return Err(Error::new(item.self_ty.span(), "expected a type path")),
+		},
+		other => return Err(Error::new(other.span(), "expected a type path")),
+	};
+
+	let mut functions = vec![];
+	for impl_item in item.items.iter_mut() {
+		if let ImplItem::Method(method) = impl_item {
+			if let Some(function) = parse_function(method)? {
+				functions.push(function);
+			}
+		}
+	}
+
+	if functions.is_empty() {
+		return Err(Error::new(
+			item.span(),
+			"#[precompile] requires at least one #[precompile::public(...)] function",
+		));
+	}
+
+	for (i, function) in functions.iter().enumerate() {
+		if let Some(other) = functions[..i]
+			.iter()
+			.find(|other| other.selector == function.selector)
+		{
+			return Err(Error::new(
+				function.signature.span(),
+				format!(
+					"selector of \"{}\" collides with \"{}\"",
+					function.signature.value(),
+					other.signature.value()
+				),
+			));
+		}
+	}
+
+	let variants: Vec<_> = functions.iter().map(|f| &f.variant).collect();
+	let selectors: Vec<_> = functions
+		.iter()
+		.map(|f| Literal::u32_suffixed(f.selector))
+		.collect();
+	let signatures: Vec<_> = functions.iter().map(|f| &f.signature).collect();
+	let interface = solidity_interface(&interface_name, &functions);
+
+	let arms = functions.iter().map(|function| {
+		let variant = &function.variant;
+		let ident = &function.ident;
+
+		let payable_check = if function.modifier == Modifier::Payable {
+			quote!()
+		} else {
+			quote! {
+				if !context.apparent_value.is_zero() {
+					return Err(::precompile_utils::error("function is not payable"));
+				}
+			}
+		};
+
+		let reads = function.reads;
+		let read_cost = if reads > 0 {
+			quote! {
+				handle.record_cost(
+					::precompile_utils::RuntimeHelper::<Runtime>::db_read_gas_cost()
+						.saturating_mul(#reads),
+				)?;
+			}
+		} else {
+			quote!()
+		};
+
+		let writes = function.writes;
+		let write_cost = if writes > 0 {
+			quote! {
+				handle.record_cost(
+					::precompile_utils::RuntimeHelper::<Runtime>::db_write_gas_cost()
+						.saturating_mul(#writes),
+				)?;
+			}
+		} else {
+			quote!()
+		};
+
+		let arguments_count = function.arguments.len();
+		let argument_idents: Vec<_> = (0..arguments_count)
+			.map(|i| format_ident!("arg{}", i))
+			.collect();
+		let argument_types = &function.arguments;
+
+		let handle_argument = if function.takes_handle {
+			quote!(&mut handle,)
+		} else {
+			quote!()
+		};
+
+		quote! {
+			#enum_ident::#variant => {
+				#payable_check
+				#read_cost
+				#write_cost
+
+				input.expect_arguments(#arguments_count)?;
+				#(
+					let #argument_idents = input.read::<#argument_types>()?;
+				)*
+
+				let output = Self::#ident(#handle_argument #(#argument_idents),*)?;
+
+				Ok(handle.build(::precompile_utils::EvmDataWriter::new().write(output).build()))
+			}
+		}
+	});
+
+	let (impl_generics, _, where_clause) = item.generics.split_for_impl();
+	let self_ty = &item.self_ty;
+
+	Ok(quote! {
+		#item
+
+		#[derive(
+			Clone,
+			Copy,
+			Debug,
+			PartialEq,
+			Eq,
+			::num_enum::TryFromPrimitive,
+			::num_enum::IntoPrimitive,
+		)]
+		#[repr(u32)]
+		pub enum #enum_ident {
+			#(
+				#[doc = #signatures]
+				#variants = #selectors,
+			)*
+		}
+
+		impl #enum_ident {
+			/// Solidity interface of the precompile.
+			pub const SOLIDITY_INTERFACE: &'static str = #interface;
+
+			/// Solidity signature of the function.
+			pub fn signature(&self) -> &'static str {
+				match self {
+					#(
+						Self::#variants => #signatures,
+					)*
+				}
+			}
+		}
+
+		impl #impl_generics ::pallet_evm::Precompile for #self_ty #where_clause {
+			fn execute(
+				input: &[u8], //Reminder this is big-endian
+				target_gas: Option<u64>,
+				context: &::evm::Context,
+			) -> Result<::evm::executor::PrecompileOutput, ::evm::ExitError> {
+				let mut input = ::precompile_utils::EvmDataReader::new(input);
+				let selector = input.read_selector::<#enum_ident>()?;
+				#[allow(unused_mut)]
+				let mut handle = ::precompile_utils::PrecompileHandle::new(target_gas, context);
+
+				match selector {
+					#(#arms)*
+				}
+			}
+		}
+	})
+}
+
+/// Parse a method of the impl block, removing the `precompile::*` attributes from it.
+/// Returns `None` if the method is not exposed by the precompile.
+fn parse_function(method: &mut syn::ImplItemMethod) -> Result<Option<Function>, Error> {
+	let mut signature = None;
+	let mut modifier = Modifier::NonPayable;
+	let mut reads = 0;
+	let mut writes = 0;
+
+	let mut attrs: Vec<Attribute> = vec![];
+	for attr in method.attrs.drain(..) {
+		let path = &attr.path;
+		let is_precompile_attr = path.segments.len() == 2 && path.segments[0].ident == "precompile";
+
+		if !is_precompile_attr {
+			attrs.push(attr);
+			continue;
+		}
+
+		let name = path.segments[1].ident.to_string();
+		match name.as_str() {
+			"public" => {
+				if signature.is_some() {
+					return Err(Error::new(attr.span(), "duplicated #[precompile::public]"));
+				}
+				signature = Some(attr.parse_args::<LitStr>()?);
+			}
+			"view" | "payable" => {
+				if modifier != Modifier::NonPayable {
+					return Err(Error::new(
+						attr.span(),
+						"a function can only be one of view or payable",
+					));
+				}
+				modifier = if name == "view" {
+					Modifier::View
+				} else {
+					Modifier::Payable
+				};
+			}
+			"gas" => {
+				let list = match attr.parse_meta()? {
+					Meta::List(list) => list,
+					other => {
+						return Err(Error::new(
+							other.span(),
+							"expected #[precompile::gas(reads = N, writes = N)]",
+						))
+					}
+				};
+
+				for nested in list.nested {
+					let (ident, value) = match &nested {
+						NestedMeta::Meta(Meta::NameValue(nv)) => match (&nv.lit, nv.path.get_ident())
+						{
+							(Lit::Int(value), Some(ident)) => (ident, value.base10_parse::<u64>()?),
+							_ => return Err(Error::new(nested.span(), "expected `name = integer`")),
+						},
+						_ => return Err(Error::new(nested.span(), "expected `name = integer`")),
+					};
+
+					if ident == "reads" {
+						reads = value;
+					} else if ident == "writes" {
+						writes = value;
+					} else {
+						return Err(Error::new(ident.span(), "expected `reads` or `writes`"));
+					}
+				}
+			}
+			_ => {
+				return Err(Error::new(
+					path.span(),
+					"unknown attribute, expected one of public, view, payable or gas",
+				))
+			}
+		}
+	}
+	method.attrs = attrs;
+
+	let signature = match signature {
+		Some(signature) => signature,
+		None if modifier != Modifier::NonPayable || reads > 0 || writes > 0 => {
+			return Err(Error::new(
+				method.sig.ident.span(),
+				"#[precompile::public(...)] is required to use other precompile attributes",
+			))
+		}
+		None => return Ok(None),
+	};
+
+	let selector = u32::from_be_bytes(
+		Keccak256::digest(signature.value().as_ref())[..4]
+			.try_into()
+			.unwrap(),
+	);
+
+	let mut takes_handle = false;
+	let mut arguments = vec![];
+	for (i, input) in method.sig.inputs.iter().enumerate() {
+		match input {
+			FnArg::Receiver(receiver) => {
+				return Err(Error::new(
+					receiver.span(),
+					"precompile functions cannot take self",
+				))
+			}
+			FnArg::Typed(typed) => match &*typed.ty {
+				Type::Reference(reference) if i == 0 && reference.mutability.is_some() => {
+					takes_handle = true;
+				}
+				ty => arguments.push(ty.clone()),
+			},
+		}
+	}
+
+	let returns = match &method.sig.output {
+		ReturnType::Default => {
+			return Err(Error::new(
+				method.sig.span(),
+				"precompile functions must return an EvmResult",
+			))
+		}
+		ReturnType::Type(_, ty) => match result_ok_type(ty) {
+			None => None,
+			Some(ty) => Some(solidity_type(&ty)?),
+		},
+	};
+
+	Ok(Some(Function {
+		variant: Ident::new(&upper_camel_case(&method.sig.ident.to_string()), method.sig.ident.span()),
+		ident: method.sig.ident.clone(),
+		signature,
+		selector,
+		modifier,
+		reads,
+		writes,
+		takes_handle,
+		arguments,
+		returns,
+	}))
+}
+
+/// Extract `T` from `EvmResult<T>`/`Result<T, _>`. Returns `None` for `EvmResult`, `()` and
+/// `EvmResult<()>` since the function doesn't return anything.
+fn result_ok_type(ty: &Type) -> Option<Type> {
+	let segment = match ty {
+		Type::Path(type_path) => type_path.path.segments.last()?,
+		_ => return None,
+	};
+
+	let ok_type = match &segment.arguments {
+		PathArguments::AngleBracketed(args) => match args.args.first()? {
+			GenericArgument::Type(ty) => ty.clone(),
+			_ => return None,
+		},
+		_ => return None,
+	};
+
+	match &ok_type {
+		Type::Tuple(tuple) if tuple.elems.is_empty() => None,
+		_ => Some(ok_type),
+	}
+}
+
+/// Name of the Solidity type(s) matching the provided Rust type, as expected in the `returns`
+/// clause of a function.
+fn solidity_type(ty: &Type) -> Result<String, Error> {
+	let unsupported = || {
+		Error::new(
+			ty.span(),
+			"unsupported output type, expected a type implementing EvmData with a known \
+			Solidity equivalent or a struct deriving EvmData",
+		)
+	};
+
+	match ty {
+		Type::Tuple(tuple) => {
+			let types = tuple
+				.elems
+				.iter()
+				.map(solidity_type)
+				.collect::<Result<Vec<_>, _>>()?;
+			Ok(types.join(", "))
+		}
+		Type::Path(type_path) => {
+			let segment = type_path.path.segments.last().ok_or_else(unsupported)?;
+			let name = segment.ident.to_string();
+
+			Ok(match name.as_str() {
+				"bool" => "bool".into(),
+				"u8" => "uint8".into(),
+				"u16" => "uint16".into(),
+				"u32" => "uint32".into(),
+				"u64" => "uint64".into(),
+				"u128" => "uint128".into(),
+				"U256" => "uint256".into(),
+				"H256" => "bytes32".into(),
+				"Address" => "address".into(),
+				"Bytes" => "bytes".into(),
+				"Vec" => match &segment.arguments {
+					PathArguments::AngleBracketed(args) => match args.args.first() {
+						Some(GenericArgument::Type(inner)) => {
+							format!("{}[]", solidity_type(inner)?)
+						}
+						_ => return Err(unsupported()),
+					},
+					_ => return Err(unsupported()),
+				},
+				// Other types without generics are expected to be structs deriving `EvmData`,
+				// declared with the same name in the Solidity interface.
+				_ if segment.arguments.is_empty() => name,
+				_ => return Err(unsupported()),
+			})
+		}
+		_ => Err(unsupported()),
+	}
+}
+
+/// Build the Solidity interface of the precompile.
+fn solidity_interface(name: &str, functions: &[Function]) -> String {
+	let mut interface = format!("interface {} {{\n", name);
+
+	for function in functions {
+		let modifier = match function.modifier {
+			Modifier::NonPayable => "",
+			Modifier::View => " view",
+			Modifier::Payable => " payable",
+		};
+
+		let returns = match &function.returns {
+			Some(returns) => format!(" returns ({})", returns),
+			None => String::new(),
+		};
+
+		interface.push_str(&format!(
+			"\tfunction {} external{}{};\n",
+			function.signature.value(),
+			modifier,
+			returns
+		));
+	}
+
+	interface.push('}');
+	interface
+}
+
+/// Convert a `snake_case` function name into an `UpperCamelCase` variant name.
+fn upper_camel_case(name: &str) -> String {
+	name.split('_')
+		.filter(|part| !part.is_empty())
+		.map(|part| {
+			let mut chars = part.chars();
+			match chars.next() {
+				Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+				None => String::new(),
+			}
+		})
+		.collect()
+}
@@ -23,7 +23,7 @@ use sp_std::{convert::TryInto, vec, vec::Vec};
 /// The `address` type of Solidity.
 /// H160 could represent 2 types of data (bytes20 and address) that are not encoded the same way.
 /// To avoid issues writing H160 is thus not supported.
-#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
 pub struct Address(pub H160);
 
 impl From<H160> for Address {
@@ -41,7 +41,7 @@ impl From<Address> for H160 {
 /// The `bytes`/`string` type of Solidity.
 /// It is different from `Vec<u8>` which will be serialized with padding for each `u8` element
 /// of the array, while `Bytes` is tightly packed.
-#[derive(Clone, Debug, Eq, PartialEq)]
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
 pub struct Bytes(pub Vec<u8>);
 
 impl Bytes {
@@ -125,6 +125,7 @@ impl<'a> EvmDataReader<'a> {
 
 	/// Parse (4 bytes) selector.
 	/// Returns an error if trying to parse out of bounds.
+	/// Must be called before reading the arguments.
 	pub fn read_selector<T>(&mut self) -> EvmResult<T>
 	where
 		T: num_enum::TryFromPrimitive<Primitive = u32>,
@@ -134,13 +135,40 @@ impl<'a> EvmDataReader<'a> {
 			self.read_raw_bytes(4)
 				.map_err(|_| error("tried to parse selector out of bounds"))?,
 		);
-		T::try_from_primitive(u32::from_be_bytes(buffer)).map_err(|_| {
+		let selector = T::try_from_primitive(u32::from_be_bytes(buffer)).map_err(|_| {
 			log::trace!(
 				target: "precompile-utils",
 				"Failed to match function selector for {}",
 				type_name::<T>()
 			);
 			error("unknown selector")
+		})?;
+
+		// Offsets of dynamic arguments are relative to the start of the arguments, which are
+		// thus read as a new input.
+		self.input = &self.input[self.cursor..];
+		self.cursor = 0;
+
+		Ok(selector)
+	}
+
+	/// Read a pointer (offset) to some dynamic data and returns a reader of this data.
+	/// Offsets are relative to the start of the current data, which can be the arguments of a
+	/// function, the elements of an array or the fields of a struct.
+	pub fn read_pointer(&mut self) -> EvmResult<Self> {
+		let offset: usize = self
+			.read::<U256>()
+			.map_err(|_| error("tried to parse pointer out of bounds"))?
+			.try_into()
+			.map_err(|_| error("pointer is too large"))?;
+
+		if offset >= self.input.len() {
+			return Err(error("pointer points out of bounds"));
+		}
+
+		Ok(Self {
+			input: &self.input[offset..],
+			cursor: 0,
 		})
 	}
 
@@ -170,13 +198,13 @@ impl<'a> EvmDataReader<'a> {
 pub struct EvmDataWriter {
 	pub(crate) data: Vec<u8>,
 	offset_data: Vec<OffsetDatum>,
+	selector: Option<u32>,
 }
 
 #[derive(Clone, Debug)]
 struct OffsetDatum {
 	offset_position: usize,
 	data: Vec<u8>,
-	inner_offset_data: Vec<OffsetDatum>,
 }
 
 impl EvmDataWriter {
@@ -185,38 +213,57 @@ impl EvmDataWriter {
 		Self {
 			data: vec![],
 			offset_data: vec![],
+			selector: None,
 		}
 	}
 
 	/// Return the built data.
 	pub fn build(mut self) -> Vec<u8> {
-		Self::compute_offsets(&mut self.data, self.offset_data);
-
-		self.data
+		Self::bake_offsets(&mut self.data, self.offset_data);
+
+		match self.selector {
+			None => self.data,
+			Some(selector) => {
+				let mut output = selector.to_be_bytes().to_vec();
+				output.append(&mut self.data);
+				output
+			}
+		}
 	}
 
-	/// Build the array into data.
-	fn compute_offsets(output: &mut Vec<u8>, arrays: Vec<OffsetDatum>) {
-		for mut array in arrays {
-			let offset_position = array.offset_position;
+	/// Append the dynamic data at the end of the output, and write their offsets.
+	fn bake_offsets(output: &mut Vec<u8>, offset_data: Vec<OffsetDatum>) {
+		for mut offset_datum in offset_data {
+			let offset_position = offset_datum.offset_position;
 			let offset_position_end = offset_position + 32;
 
-			// The offset is the distance between the start of the offset location and the
-			// start of the array length.
-			let free_space_offset = output.len() - offset_position;
+			// The offset is the distance between the start of the output (arguments, array
+			// elements or struct fields) and the start of the dynamic data.
+			let free_space_offset = output.len();
 
 			// Override dummy offset to the offset it will be in the final output.
 			U256::from(free_space_offset)
 				.to_big_endian(&mut output[offset_position..offset_position_end]);
 
-			// Build inner arrays if any.
-			Self::compute_offsets(&mut array.data, array.inner_offset_data);
-
 			// Append this data at the end of the current output.
-			output.append(&mut array.data);
+			output.append(&mut offset_datum.data);
 		}
 	}
 
+	/// Write a pointer to some dynamic data, which will be appended at the end of the output
+	/// when building it. The provided data must already be built.
+	/// Should only be used when implementing `EvmData` for a dynamic type.
+	pub fn write_pointer(&mut self, data: Vec<u8>) {
+		let offset_position = self.data.len();
+		H256::write(self, H256::repeat_byte(0xff));
+		// 0xff = When debugging it makes spoting offset values easier.
+
+		self.offset_data.push(OffsetDatum {
+			offset_position,
+			data,
+		});
+	}
+
 	/// Write arbitrary bytes.
 	/// Doesn't handle any alignement checks, prefer using `write` instead if possible.
 	pub fn write_raw_bytes(mut self, value: &[u8]) -> Self {
@@ -226,10 +273,11 @@ impl EvmDataWriter {
 
 	/// Write a selector.
 	/// The provided type must impl `Into<u32>`.
-	/// Doesn't handle any alignement checks, should be used only when adding the initial
-	/// selector of a Solidity call data.
-	pub fn write_selector<T: Into<u32>>(self, value: T) -> Self {
-		self.write_raw_bytes(&value.into().to_be_bytes())
+	/// The selector is always put at the start of the built data, which allows offsets of the
+	/// arguments to be relative to the start of the arguments.
+	pub fn write_selector<T: Into<u32>>(mut self, value: T) -> Self {
+		self.selector = Some(value.into());
+		self
 	}
 
 	/// Write data of requested type.
@@ -249,6 +297,9 @@ impl Default for EvmDataWriter {
 pub trait EvmData: Sized {
 	fn read(reader: &mut EvmDataReader) -> EvmResult<Self>;
 	fn write(writer: &mut EvmDataWriter, value: Self);
+	/// Whether the type has a static size. Dynamic types are encoded behind a pointer when
+	/// part of a struct or array.
+	fn has_static_size() -> bool;
 }
 
 impl EvmData for H256 {
@@ -266,6 +317,10 @@ impl EvmData for H256 {
 	fn write(writer: &mut EvmDataWriter, value: Self) {
 		writer.data.extend_from_slice(value.as_bytes());
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 impl EvmData for Address {
@@ -283,6 +338,10 @@ impl EvmData for Address {
 	fn write(writer: &mut EvmDataWriter, value: Self) {
 		H256::write(writer, value.0.into());
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 impl EvmData for U256 {
@@ -302,6 +361,10 @@ impl EvmData for U256 {
 		value.to_big_endian(&mut buffer);
 		writer.data.extend_from_slice(&buffer);
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 macro_rules! impl_evmdata_for_uints {
@@ -328,6 +391,10 @@ macro_rules! impl_evmdata_for_uints {
 					buffer[32 - core::mem::size_of::<Self>()..].copy_from_slice(&value.to_be_bytes());
 					writer.data.extend_from_slice(&buffer);
 				}
+
+				fn has_static_size() -> bool {
+					true
+				}
 			}
 		)*
 	};
@@ -354,6 +421,10 @@ impl EvmData for u8 {
 
 		writer.data.extend_from_slice(&buffer);
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
 impl EvmData for bool {
@@ -371,130 +442,132 @@ impl EvmData for bool {
 
 		writer.data.extend_from_slice(&buffer);
 	}
+
+	fn has_static_size() -> bool {
+		true
+	}
 }
 
+// Tuples are encoded as consecutive values, which is the encoding of function arguments and
+// return values. `()` is used for functions that don't return anything.
+// As they are never encoded behind a pointer, Solidity structs containing dynamic data must be
+// represented by a struct deriving `EvmData` instead.
+macro_rules! impl_evmdata_for_tuples {
+	($(($($ident:ident),*),)*) => {
+		$(
+			impl<$($ident: EvmData),*> EvmData for ($($ident,)*) {
+				#[allow(unused_variables)]
+				fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
+					Ok(($($ident::read(reader)?,)*))
+				}
+
+				#[allow(non_snake_case, unused_variables)]
+				fn write(writer: &mut EvmDataWriter, value: Self) {
+					let ($($ident,)*) = value;
+					$($ident::write(writer, $ident);)*
+				}
+
+				fn has_static_size() -> bool {
+					true $(&& $ident::has_static_size())*
+				}
+			}
+		)*
+	};
+}
+
+impl_evmdata_for_tuples!(
+	(),
+	(A),
+	(A, B),
+	(A, B, C),
+	(A, B, C, D),
+	(A, B, C, D, E),
+	(A, B, C, D, E, F),
+);
+
 impl<T: EvmData> EvmData for Vec<T> {
 	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
-		let offset_reference = reader.cursor;
-		let array_offset: usize = reader
-			.read::<U256>()
-			.map_err(|_| error("tried to parse array offset out of bounds"))?
-			.try_into()
-			.map_err(|_| error("array offset is too large"))?;
-
-		// We temporarily move the cursor to the offset, we'll set it back afterward.
-		let original_cursor = reader.cursor;
-		reader.cursor = offset_reference + array_offset;
+		let mut inner_reader = reader.read_pointer()?;
 
-		let array_size: usize = reader
+		let array_size: usize = inner_reader
 			.read::<U256>()
 			.map_err(|_| error("tried to parse array length out of bounds"))?
 			.try_into()
 			.map_err(|_| error("array length is too large"))?;
 
+		// Offsets of dynamic elements are relative to the start of the elements.
+		let mut inner_reader = EvmDataReader::new(&inner_reader.input[inner_reader.cursor..]);
+
 		let mut array = vec![];
 
 		for _ in 0..array_size {
-			array.push(reader.read()?);
+			array.push(inner_reader.read()?);
 		}
 
-		// We set back the cursor to its original location.
-		reader.cursor = original_cursor;
-
 		Ok(array)
 	}
 
 	fn write(writer: &mut EvmDataWriter, value: Self) {
-		let offset_position = writer.data.len();
-		H256::write(writer, H256::repeat_byte(0xff));
-		// 0xff = When debugging it makes spoting offset values easier.
-
-		let mut inner_writer = EvmDataWriter::new();
+		let mut inner_writer = EvmDataWriter::new().write(U256::from(value.len()));
 
-		// Write length.
-		inner_writer = inner_writer.write(U256::from(value.len()));
-
-		// Write elements of array.
+		// Write elements of array, which are built separately as offsets of dynamic elements
+		// are relative to the start of the elements.
+		let mut elements_writer = EvmDataWriter::new();
 		for inner in value {
-			inner_writer = inner_writer.write(inner);
+			elements_writer = elements_writer.write(inner);
 		}
+		inner_writer.data.append(&mut elements_writer.build());
 
-		let array = OffsetDatum {
-			offset_position,
-			data: inner_writer.data,
-			inner_offset_data: inner_writer.offset_data,
-		};
+		writer.write_pointer(inner_writer.build());
+	}
 
-		writer.offset_data.push(array);
+	fn has_static_size() -> bool {
+		false
 	}
 }
 
 impl EvmData for Bytes {
 	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
-		let offset_reference = reader.cursor;
-		let array_offset: usize = reader
-			.read::<U256>()
-			.map_err(|_| error("tried to parse array offset out of bounds"))?
-			.try_into()
-			.map_err(|_| error("array offset is too large"))?;
-
-		// We temporarily move the cursor to the offset, we'll set it back afterward.
-		let original_cursor = reader.cursor;
-		reader.cursor = offset_reference + array_offset;
+		let mut inner_reader = reader.read_pointer()?;
 
 		// Read bytes/string size.
-		let array_size: usize = reader
+		let array_size: usize = inner_reader
 			.read::<U256>()
 			.map_err(|_| error("tried to parse bytes/string length out of bounds"))?
 			.try_into()
 			.map_err(|_| error("bytes/string length is too large"))?;
 
-		let range = reader.move_cursor(array_size)?;
-
-		let data = reader
-			.input
-			.get(range)
-			.ok_or_else(|| error("tried to parse bytes/string out of bounds"))?;
+		let data = inner_reader
+			.read_raw_bytes(array_size)
+			.map_err(|_| error("tried to parse bytes/string out of bounds"))?;
 
-		let bytes = Self(data.to_owned());
-
-		// We set back the cursor to its original location.
-		reader.cursor = original_cursor;
-
-		Ok(bytes)
+		Ok(Self(data.to_owned()))
 	}
 
 	fn write(writer: &mut EvmDataWriter, value: Self) {
-		let offset_position = writer.data.len();
-		H256::write(writer, H256::repeat_byte(0xff));
-		// 0xff = When debugging it makes spoting offset values easier.
-
-		let mut inner_writer = EvmDataWriter::new();
-
-		// Write length.
-		inner_writer = inner_writer.write(value.0.len() as u64);
+		let length = value.0.len();
 
 		// Pad the data.
 		// Leave it as is if a multiple of 32, otherwise pad to next
 		// multiple or 32.
-		let chunks = value.0.len() / 32;
-		let padded_size = match value.0.len() % 32 {
+		let chunks = length / 32;
+		let padded_size = match length % 32 {
 			0 => chunks * 32,
 			_ => (chunks + 1) * 32,
 		};
 
-		let mut value = value.0.to_vec();
+		let mut value = value.0;
 		value.resize(padded_size, 0);
 
-		// Write bytes data.
-		inner_writer = inner_writer.write_raw_bytes(&value);
+		// Write length and bytes data.
+		let inner_writer = EvmDataWriter::new()
+			.write(U256::from(length))
+			.write_raw_bytes(&value);
 
-		let array = OffsetDatum {
-			offset_position,
-			data: inner_writer.data,
-			inner_offset_data: inner_writer.offset_data,
-		};
+		writer.write_pointer(inner_writer.build());
+	}
 
-		writer.offset_data.push(array);
+	fn has_static_size() -> bool {
+		false
 	}
 }
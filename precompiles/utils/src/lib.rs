@@ -18,19 +18,24 @@
 
 extern crate alloc;
 
-use evm::ExitError;
+// Allows to use `#[derive(EvmData)]` in the tests of this crate.
+#[cfg(test)]
+extern crate self as precompile_utils;
+
+use evm::{executor::PrecompileOutput, Context, ExitError, ExitReason, ExitSucceed};
 use frame_support::{
 	dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo},
-	traits::Get,
+	sp_runtime::DispatchError,
+	traits::{Get, PalletInfoAccess},
 };
-use pallet_evm::{GasWeightMapping, Log};
-use sp_core::{H160, H256};
+use pallet_evm::{AddressMapping, GasWeightMapping, Log, Runner};
+use sp_core::{H160, H256, U256};
 use sp_std::{marker::PhantomData, vec, vec::Vec};
 
 mod data;
 
 pub use data::{Address, Bytes, EvmData, EvmDataReader, EvmDataWriter};
-pub use precompile_utils_macro::{generate_function_selector, keccak256};
+pub use precompile_utils_macro::{generate_function_selector, keccak256, precompile, EvmData};
 
 #[cfg(test)]
 mod tests;
@@ -214,6 +219,151 @@ where
 			<Runtime as frame_system::Config>::DbWeight::get().read,
 		)
 	}
+
+	/// Perform an EVM call from `source` to `target`, like a CALL opcode would do.
+	/// The subcall doesn't pay fees, and its used gas should thus be recorded as a cost of the
+	/// precompile. As it is not a transaction, it doesn't increase the nonce of `source`.
+	///
+	/// Precompiles don't have access to the executor running them, so the subcall is performed
+	/// by a new executor through the runtime `Runner`. It has some consequences that callers must
+	/// take care of:
+	/// - The `Runner` handles the call as a transaction, and increases the nonce of `source`,
+	/// which is thus restored afterward.
+	/// - The new executor starts with an empty call stack, and doesn't know about the call depth
+	/// of the precompile. Precompiles must prevent unbounded nesting of subcalls themselves.
+	/// - Changes to the storage are not tracked by the substate of the calling executor. They are
+	/// reverted along with the call frame of the precompile if it fails, but not if the precompile
+	/// is called outside of an EVM execution (e.g. in tests). Precompiles that can fail after a
+	/// subcall succeeded should run in a storage transaction.
+	/// - `source` is the caller of the precompile as seen in its context, which is not the actual
+	/// caller when the precompile is executed with DELEGATECALL or CALLCODE.
+	/// - The `Runner` handles the call as a transaction, so the gas used by the subcall includes
+	/// the 21000 intrinsic gas of a transaction (and the cost of its call data), which a CALL
+	/// opcode doesn't pay.
+	/// - The `Runner` deposits a `Log` event for each log of the subcall. The logs are returned
+	/// in the outcome to be added to the logs of the precompile, which are deposited again at the
+	/// end of the transaction, so the events deposited by the subcall are removed.
+	pub fn evm_subcall(
+		source: H160,
+		target: H160,
+		value: U256,
+		call_data: Vec<u8>,
+		gas_limit: u64,
+	) -> SubcallOutcome {
+		let source_account = Runtime::AddressMapping::into_account_id(source);
+		let nonce = frame_system::Pallet::<Runtime>::account_nonce(&source_account);
+		let event_count = frame_system::Pallet::<Runtime>::event_count();
+
+		let result = Runtime::Runner::call(
+			source,
+			target,
+			call_data,
+			value,
+			gas_limit,
+			None,
+			None,
+			Runtime::config(),
+		);
+
+		frame_system::Account::<Runtime>::mutate(&source_account, |account| account.nonce = nonce);
+
+		match result {
+			Ok(info) => {
+				Self::remove_log_events(event_count, &info.logs);
+
+				SubcallOutcome {
+					succeeded: matches!(info.exit_reason, ExitReason::Succeed(_)),
+					used_gas: info.used_gas.low_u64(),
+					logs: info.logs,
+				}
+			}
+			Err(e) => {
+				let e: DispatchError = e.into();
+				log::trace!(
+					target: "precompile-utils",
+					"Subcall to {:?} failed with error: {:?}",
+					target,
+					e
+				);
+
+				SubcallOutcome {
+					succeeded: false,
+					used_gas: 0,
+					logs: vec![],
+				}
+			}
+		}
+	}
+
+	/// Remove the `Log` events matching `logs` among the events deposited since there were
+	/// `event_count` events.
+	///
+	/// `frame_system` doesn't allow other pallets to remove events, so its storage is accessed
+	/// directly. Events are indexed by their position when deposited with topics, which `Log`
+	/// events are not: if an event deposited after the first removed one has topics, the events
+	/// are kept as is.
+	fn remove_log_events(event_count: u32, logs: &[Log]) {
+		if logs.is_empty() {
+			return;
+		}
+
+		let pallet_name = <frame_system::Pallet<Runtime> as PalletInfoAccess>::name();
+		let events_key = storage_key(pallet_name, "Events");
+		let event_count_key = storage_key(pallet_name, "EventCount");
+
+		let mut events: Vec<
+			frame_system::EventRecord<
+				<Runtime as frame_system::Config>::Event,
+				<Runtime as frame_system::Config>::Hash,
+			>,
+		> = frame_support::storage::unhashed::get_or_default(&events_key);
+
+		let new_events = events.split_off((event_count as usize).min(events.len()));
+		let mut logs = logs.iter().peekable();
+		let mut removed_any = false;
+
+		for record in new_events {
+			let is_next_log = logs.peek().map_or(false, |log| {
+				let event: <Runtime as frame_system::Config>::Event =
+					<Runtime as pallet_evm::Config>::Event::from(pallet_evm::Event::Log(
+						Log::clone(log),
+					))
+					.into();
+				record.event == event
+			});
+
+			if is_next_log {
+				logs.next();
+				removed_any = true;
+			} else if removed_any && !record.topics.is_empty() {
+				return;
+			} else {
+				events.push(record);
+			}
+		}
+
+		frame_support::storage::unhashed::put(&event_count_key, &(events.len() as u32));
+		frame_support::storage::unhashed::put(&events_key, &events);
+	}
+}
+
+/// Key of a storage value of a pallet.
+fn storage_key(pallet_name: &str, storage_name: &str) -> [u8; 32] {
+	let mut key = [0u8; 32];
+	key[..16].copy_from_slice(&sp_io::hashing::twox_128(pallet_name.as_bytes()));
+	key[16..].copy_from_slice(&sp_io::hashing::twox_128(storage_name.as_bytes()));
+	key
+}
+
+/// Outcome of an EVM subcall performed by a precompile.
+#[derive(Clone, Debug)]
+pub struct SubcallOutcome {
+	/// Whether the subcall succeeded.
+	pub succeeded: bool,
+	/// Gas used by the subcall.
+	pub used_gas: u64,
+	/// Logs emitted by the subcall.
+	pub logs: Vec<Log>,
 }
 
 /// Custom Gasometer to record costs in precompiles.
@@ -298,3 +448,63 @@ impl Gasometer {
 		})
 	}
 }
+
+/// State of a precompile execution, given to the functions of precompiles declared using the
+/// `#[precompile]` macro.
+/// It gives access to the call context, records used gas and collects the emitted logs.
+#[derive(Clone, Debug)]
+pub struct PrecompileHandle<'a> {
+	context: &'a Context,
+	gasometer: Gasometer,
+	logs: Vec<Log>,
+}
+
+impl<'a> PrecompileHandle<'a> {
+	/// Create a new handle with provided gas limit and context.
+	pub fn new(target_gas: Option<u64>, context: &'a Context) -> Self {
+		Self {
+			context,
+			gasometer: Gasometer::new(target_gas),
+			logs: vec![],
+		}
+	}
+
+	/// Context of the call.
+	pub fn context(&self) -> &Context {
+		self.context
+	}
+
+	/// Gasometer of the call.
+	pub fn gasometer(&mut self) -> &mut Gasometer {
+		&mut self.gasometer
+	}
+
+	/// Record cost, and return error if it goes out of gas.
+	pub fn record_cost(&mut self, cost: u64) -> EvmResult {
+		self.gasometer.record_cost(cost)
+	}
+
+	/// Compute remaining gas.
+	/// Returns error if out of gas.
+	/// Returns None if no gas limit.
+	pub fn remaining_gas(&self) -> EvmResult<Option<u64>> {
+		self.gasometer.remaining_gas()
+	}
+
+	/// Add logs to the output.
+	/// Their cost is not recorded, which should be done as early as possible with
+	/// `Gasometer::record_log_costs_manual`.
+	pub fn add_logs(&mut self, logs: Vec<Log>) {
+		self.logs.extend(logs);
+	}
+
+	/// Build the output of a successful call returning provided data.
+	pub fn build(self, output: Vec<u8>) -> PrecompileOutput {
+		PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: self.gasometer.used_gas(),
+			output,
+			logs: self.logs,
+		}
+	}
+}
@@ -384,8 +384,10 @@ fn write_address_nested_array() {
 
 	assert_eq!(reader.read::<U256>().expect("read offset"), 0x20.into()); // 0x00
 	assert_eq!(reader.read::<U256>().expect("read size"), 2.into()); // 0x20
+
+	// Offsets of elements are relative to the start of the elements (0x40).
 	assert_eq!(reader.read::<U256>().expect("read 1st offset"), 0x40.into()); // 0x40
-	assert_eq!(reader.read::<U256>().expect("read 2st offset"), 0xa0.into()); // 0x60
+	assert_eq!(reader.read::<U256>().expect("read 2st offset"), 0xc0.into()); // 0x60
 	assert_eq!(reader.read::<U256>().expect("read 1st size"), 3.into()); // 0x80
 	assert_eq!(reader.read::<Address>().expect("read 1-1"), array[0][0]); // 0xA0
 	assert_eq!(reader.read::<Address>().expect("read 1-2"), array[0][1]); // 0xC0
@@ -437,8 +439,9 @@ fn write_multiple_arrays() {
 	// We can read this "manualy" using simpler functions since arrays are 32-byte aligned.
 	let mut reader = EvmDataReader::new(&writer_output);
 
+	// Offsets are relative to the start of the data.
 	assert_eq!(reader.read::<U256>().expect("read 1st offset"), 0x40.into()); // 0x00
-	assert_eq!(reader.read::<U256>().expect("read 2nd offset"), 0xa0.into()); // 0x20
+	assert_eq!(reader.read::<U256>().expect("read 2nd offset"), 0xc0.into()); // 0x20
 	assert_eq!(reader.read::<U256>().expect("read 1st size"), 3.into()); // 0x40
 	assert_eq!(reader.read::<Address>().expect("read 1-1"), array1[0]); // 0x60
 	assert_eq!(reader.read::<Address>().expect("read 1-2"), array1[1]); // 0x80
@@ -574,10 +577,10 @@ fn write_vec_bytes() {
 	// Length of vec
 	assert_eq!(reader.read::<U256>().expect("read offset"), 2.into());
 
-	// Relative offset of first bytes object
+	// Relative offset of first bytes object (from the start of the elements)
 	assert_eq!(reader.read::<U256>().expect("read offset"), 64.into());
-	// Relative offset of second bytes object
-	assert_eq!(reader.read::<U256>().expect("read offset"), 192.into());
+	// Relative offset of second bytes object (from the start of the elements)
+	assert_eq!(reader.read::<U256>().expect("read offset"), 224.into());
 
 	// Length of first bytes object
 	assert_eq!(reader.read::<U256>().expect("read size"), data.len().into());
@@ -617,3 +620,309 @@ fn read_vec_of_bytes() {
 
 	assert_eq!(vec![Bytes::from(&data[..]), Bytes::from(&data[..])], parsed);
 }
+
+#[test]
+fn write_tuple() {
+	let writer_output = EvmDataWriter::new()
+		.write((U256::from(42u64), true, Address(H160::repeat_byte(0x11))))
+		.build();
+
+	let expected_output = EvmDataWriter::new()
+		.write(U256::from(42u64))
+		.write(true)
+		.write(Address(H160::repeat_byte(0x11)))
+		.build();
+
+	assert_eq!(writer_output, expected_output);
+}
+
+#[test]
+fn read_tuple() {
+	let writer_output = EvmDataWriter::new()
+		.write(U256::from(42u64))
+		.write(true)
+		.build();
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: (U256, bool) = reader.read().expect("to correctly parse (U256, bool)");
+
+	assert_eq!((U256::from(42u64), true), parsed);
+}
+
+#[test]
+fn write_unit() {
+	let writer_output = EvmDataWriter::new().write(()).build();
+
+	assert!(writer_output.is_empty());
+}
+
+#[test]
+fn write_dynamic_arguments_after_selector() {
+	#[precompile_utils_macro::generate_function_selector]
+	#[derive(Debug, PartialEq, num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
+	enum FakeAction {
+		Action1 = "action1()",
+	}
+
+	let writer_output = EvmDataWriter::new()
+		.write_selector(FakeAction::Action1)
+		.write(U256::from(42u64))
+		.write(Bytes::from("abc"))
+		.build();
+
+	// abi.encodeWithSelector(selector, uint256(42), bytes("abc"))
+	let mut expected = (FakeAction::Action1 as u32).to_be_bytes().to_vec();
+	expected.extend_from_slice(&EvmDataWriter::new().write(U256::from(42u64)).build());
+	expected.extend_from_slice(&EvmDataWriter::new().write(U256::from(0x40)).build());
+	expected.extend_from_slice(&EvmDataWriter::new().write(U256::from(3)).build());
+	let mut padded = b"abc".to_vec();
+	padded.resize(32, 0);
+	expected.extend_from_slice(&padded);
+
+	assert_eq!(writer_output, expected);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	assert_eq!(
+		reader.read_selector::<FakeAction>().expect("read selector"),
+		FakeAction::Action1
+	);
+	assert_eq!(reader.read::<U256>().expect("read uint"), U256::from(42u64));
+	assert_eq!(
+		reader.read::<Bytes>().expect("read bytes"),
+		Bytes::from("abc")
+	);
+}
+
+fn decode_hex(hex: &str) -> Vec<u8> {
+	let hex: String = hex.split_whitespace().collect();
+	(0..hex.len())
+		.step_by(2)
+		.map(|i| u8::from_str_radix(&hex[i..i + 2], 16).expect("valid hex"))
+		.collect()
+}
+
+#[precompile_utils_macro::generate_function_selector]
+#[derive(Debug, PartialEq, num_enum::TryFromPrimitive, num_enum::IntoPrimitive)]
+enum SolcAction {
+	F = "f(uint256,uint32[],bytes10,bytes)",
+	G = "g(uint256[][],string[])",
+}
+
+// Calldata of `f(0x123, [0x456, 0x789], "1234567890", "Hello, world!")` as encoded by solc, taken
+// from the examples of the Solidity ABI specification. The offsets of `uint32[]` (0x80) and
+// `bytes` (0xe0) are relative to the start of the arguments.
+const SOLC_CALLDATA_F: &str = "
+	8be65246
+	0000000000000000000000000000000000000000000000000000000000000123
+	0000000000000000000000000000000000000000000000000000000000000080
+	3132333435363738393000000000000000000000000000000000000000000000
+	00000000000000000000000000000000000000000000000000000000000000e0
+	0000000000000000000000000000000000000000000000000000000000000002
+	0000000000000000000000000000000000000000000000000000000000000456
+	0000000000000000000000000000000000000000000000000000000000000789
+	000000000000000000000000000000000000000000000000000000000000000d
+	48656c6c6f2c20776f726c642100000000000000000000000000000000000000
+";
+
+// Calldata of `g([[1, 2], [3]], ["one", "two", "three"])` as encoded by solc, taken from the
+// examples of the Solidity ABI specification. The offsets of the inner arrays and strings are
+// relative to the start of the elements of the outer array.
+const SOLC_CALLDATA_G: &str = "
+	2289b18c
+	0000000000000000000000000000000000000000000000000000000000000040
+	0000000000000000000000000000000000000000000000000000000000000140
+	0000000000000000000000000000000000000000000000000000000000000002
+	0000000000000000000000000000000000000000000000000000000000000040
+	00000000000000000000000000000000000000000000000000000000000000a0
+	0000000000000000000000000000000000000000000000000000000000000002
+	0000000000000000000000000000000000000000000000000000000000000001
+	0000000000000000000000000000000000000000000000000000000000000002
+	0000000000000000000000000000000000000000000000000000000000000001
+	0000000000000000000000000000000000000000000000000000000000000003
+	0000000000000000000000000000000000000000000000000000000000000003
+	0000000000000000000000000000000000000000000000000000000000000060
+	00000000000000000000000000000000000000000000000000000000000000a0
+	00000000000000000000000000000000000000000000000000000000000000e0
+	0000000000000000000000000000000000000000000000000000000000000003
+	6f6e650000000000000000000000000000000000000000000000000000000000
+	0000000000000000000000000000000000000000000000000000000000000003
+	74776f0000000000000000000000000000000000000000000000000000000000
+	0000000000000000000000000000000000000000000000000000000000000005
+	7468726565000000000000000000000000000000000000000000000000000000
+";
+
+fn solc_bytes10() -> H256 {
+	let mut bytes10 = [0u8; 32];
+	bytes10[0..10].copy_from_slice(b"1234567890");
+	H256(bytes10)
+}
+
+#[test]
+fn read_solc_calldata_with_multiple_dynamic_arguments() {
+	let input = decode_hex(SOLC_CALLDATA_F);
+	let mut reader = EvmDataReader::new(&input);
+
+	assert_eq!(
+		reader.read_selector::<SolcAction>().expect("read selector"),
+		SolcAction::F
+	);
+	assert_eq!(
+		reader.read::<U256>().expect("read uint256"),
+		U256::from(0x123)
+	);
+	assert_eq!(
+		reader.read::<Vec<u32>>().expect("read uint32[]"),
+		vec![0x456, 0x789]
+	);
+	assert_eq!(reader.read::<H256>().expect("read bytes10"), solc_bytes10());
+	assert_eq!(
+		reader.read::<Bytes>().expect("read bytes"),
+		Bytes::from("Hello, world!")
+	);
+}
+
+#[test]
+fn write_solc_calldata_with_multiple_dynamic_arguments() {
+	let output = EvmDataWriter::new()
+		.write_selector(SolcAction::F)
+		.write(U256::from(0x123))
+		.write(vec![0x456u32, 0x789])
+		.write(solc_bytes10())
+		.write(Bytes::from("Hello, world!"))
+		.build();
+
+	assert_eq!(output, decode_hex(SOLC_CALLDATA_F));
+}
+
+#[test]
+fn read_solc_calldata_with_nested_dynamic_arguments() {
+	let input = decode_hex(SOLC_CALLDATA_G);
+	let mut reader = EvmDataReader::new(&input);
+
+	assert_eq!(
+		reader.read_selector::<SolcAction>().expect("read selector"),
+		SolcAction::G
+	);
+	assert_eq!(
+		reader.read::<Vec<Vec<U256>>>().expect("read uint256[][]"),
+		vec![vec![U256::from(1), U256::from(2)], vec![U256::from(3)]]
+	);
+	assert_eq!(
+		reader.read::<Vec<Bytes>>().expect("read string[]"),
+		vec![Bytes::from("one"), Bytes::from("two"), Bytes::from("three")]
+	);
+}
+
+#[test]
+fn write_solc_calldata_with_nested_dynamic_arguments() {
+	let output = EvmDataWriter::new()
+		.write_selector(SolcAction::G)
+		.write(vec![
+			vec![U256::from(1), U256::from(2)],
+			vec![U256::from(3)],
+		])
+		.write(vec![
+			Bytes::from("one"),
+			Bytes::from("two"),
+			Bytes::from("three"),
+		])
+		.build();
+
+	assert_eq!(output, decode_hex(SOLC_CALLDATA_G));
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, EvmData)]
+struct StaticStruct {
+	value: U256,
+	flag: bool,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, EvmData)]
+struct DynamicStruct {
+	value: U256,
+	data: Bytes,
+	inner: StaticStruct,
+}
+
+#[test]
+fn write_static_struct() {
+	let value = StaticStruct {
+		value: U256::from(42u64),
+		flag: true,
+	};
+
+	let writer_output = EvmDataWriter::new().write(value.clone()).build();
+
+	// Static structs are encoded inline.
+	let expected_output = EvmDataWriter::new()
+		.write(U256::from(42u64))
+		.write(true)
+		.build();
+
+	assert_eq!(writer_output, expected_output);
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: StaticStruct = reader.read().expect("to correctly parse StaticStruct");
+
+	assert_eq!(value, parsed);
+}
+
+#[test]
+fn write_dynamic_struct() {
+	let value = DynamicStruct {
+		value: U256::from(42u64),
+		data: Bytes::from("abc"),
+		inner: StaticStruct {
+			value: U256::from(43u64),
+			flag: true,
+		},
+	};
+
+	let writer_output = EvmDataWriter::new().write(value.clone()).build();
+
+	// We can read this "manualy" using simpler functions since structs are 32-byte aligned.
+	let mut reader = EvmDataReader::new(&writer_output);
+
+	assert_eq!(reader.read::<U256>().expect("read offset"), 0x20.into()); // 0x00
+	assert_eq!(reader.read::<U256>().expect("read value"), 42.into()); // 0x20
+	assert_eq!(reader.read::<U256>().expect("read offset"), 0x80.into()); // 0x40
+	assert_eq!(reader.read::<U256>().expect("read inner value"), 43.into()); // 0x60
+	assert_eq!(reader.read::<bool>().expect("read inner flag"), true); // 0x80
+	assert_eq!(reader.read::<U256>().expect("read data size"), 3.into()); // 0xA0
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: DynamicStruct = reader.read().expect("to correctly parse DynamicStruct");
+
+	assert_eq!(value, parsed);
+}
+
+#[test]
+fn read_dynamic_struct_array() {
+	let array = vec![
+		DynamicStruct {
+			value: U256::from(1u64),
+			data: Bytes::from("first"),
+			inner: StaticStruct {
+				value: U256::from(2u64),
+				flag: false,
+			},
+		},
+		DynamicStruct {
+			value: U256::from(3u64),
+			data: Bytes::from("second"),
+			inner: StaticStruct {
+				value: U256::from(4u64),
+				flag: true,
+			},
+		},
+	];
+
+	let writer_output = EvmDataWriter::new().write(array.clone()).build();
+
+	let mut reader = EvmDataReader::new(&writer_output);
+	let parsed: Vec<DynamicStruct> = reader
+		.read()
+		.expect("to correctly parse Vec<DynamicStruct>");
+
+	assert_eq!(array, parsed);
+}
@@ -0,0 +1,79 @@
+// SPDX-License-Identifier: GPL-3.0-only
+pragma solidity >=0.8.0;
+
+/**
+ * @title Xcm Transactor Interface
+ *
+ * The interface through which solidity contracts will interact with xcm transactor pallet
+ * Address :    0x000000000000000000000000000000000000080a
+ */
+interface XcmTransactor {
+    // A multilocation is defined by its number of parents and the encoded junctions (interior)
+    struct Multilocation {
+        uint8 parents;
+        bytes[] interior;
+    }
+
+    /**
+     * Retrieve the account owning a derivative index
+     * Selector: 71b0edfa
+     *
+     * @param index The derivative index
+     * @return owner The owner of the derivative index, or the zero address if not registered
+     */
+    function index_to_account(uint16 index) external view returns (address owner);
+
+    /**
+     * Retrieve the information needed to transact in a destination chain
+     * Selector: f87f493f
+     *
+     * @param multilocation The location of the destination chain
+     * @return transact_extra_weight The extra weight involved in the XCM message
+     * @return fee_per_second The fee per second charged in the destination chain
+     * @return max_weight The maximum weight the destination chain allows for a transact
+     */
+    function transact_info(Multilocation memory multilocation)
+        external
+        view
+        returns (
+            uint64 transact_extra_weight,
+            uint256 fee_per_second,
+            uint64 max_weight
+        );
+
+    /**
+     * Transact through XCM using the fee asset identified by its multilocation
+     * Selector: 9f89f03e
+     *
+     * @param transactor The transactor to be used (0: Relay)
+     * @param index The derivative index to use, which must be owned by the caller
+     * @param fee_asset The asset paying for the fees in the destination chain
+     * @param weight The weight of the inner call in the destination chain
+     * @param inner_call The SCALE encoded call to dispatch in the destination chain
+     */
+    function transact_through_derivative_multilocation(
+        uint8 transactor,
+        uint16 index,
+        Multilocation memory fee_asset,
+        uint64 weight,
+        bytes memory inner_call
+    ) external;
+
+    /**
+     * Transact through XCM using the fee asset identified by its erc20 address
+     * Selector: 267d4062
+     *
+     * @param transactor The transactor to be used (0: Relay)
+     * @param index The derivative index to use, which must be owned by the caller
+     * @param currency_id The erc20 address of the asset paying for the fees
+     * @param weight The weight of the inner call in the destination chain
+     * @param inner_call The SCALE encoded call to dispatch in the destination chain
+     */
+    function transact_through_derivative(
+        uint8 transactor,
+        uint16 index,
+        address currency_id,
+        uint64 weight,
+        bytes memory inner_call
+    ) external;
+}
@@ -0,0 +1,167 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Precompile to xcm-transactor runtime methods via the EVM
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+use codec::Decode;
+use frame_support::dispatch::{Dispatchable, GetDispatchInfo, PostDispatchInfo};
+use pallet_evm::AddressMapping;
+use precompile_utils::{
+	error, Address, Bytes, EvmData, EvmResult, PrecompileHandle, RuntimeHelper,
+};
+use sp_core::{H160, U256};
+use sp_std::{boxed::Box, marker::PhantomData};
+use xcm::v1::MultiLocation;
+use xtokens_precompiles::{AccountIdToCurrencyId, MultiLocationWrapper};
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+type RuntimeCall<Runtime> = <Runtime as frame_system::Config>::Call;
+
+pub type TransactorOf<Runtime> = <Runtime as pallet_xcm_transactor::Config>::Transactor;
+
+pub type CurrencyIdOf<Runtime> = <Runtime as pallet_xcm_transactor::Config>::CurrencyId;
+
+/// Solidity representation of `pallet_xcm_transactor::RemoteTransactInfo`.
+#[derive(Clone, Debug, Default, PartialEq, Eq, EvmData)]
+pub struct TransactInfo {
+	pub transact_extra_weight: u64,
+	pub fee_per_second: U256,
+	pub max_weight: u64,
+}
+
+/// A precompile to wrap the functionality from xcm-transactor
+pub struct XcmTransactorWrapper<Runtime>(PhantomData<Runtime>);
+
+#[precompile_utils::precompile]
+impl<Runtime> XcmTransactorWrapper<Runtime>
+where
+	Runtime: pallet_xcm_transactor::Config + pallet_evm::Config + frame_system::Config,
+	Runtime::AccountId: Into<H160>,
+	RuntimeCall<Runtime>: Dispatchable<PostInfo = PostDispatchInfo> + GetDispatchInfo,
+	RuntimeCall<Runtime>: From<pallet_xcm_transactor::Call<Runtime>>,
+	<RuntimeCall<Runtime> as Dispatchable>::Origin: From<Option<Runtime::AccountId>>,
+	Runtime: AccountIdToCurrencyId<Runtime::AccountId, CurrencyIdOf<Runtime>>,
+{
+	/// Returns the zero address if the index is not registered.
+	#[precompile::public("index_to_account(uint16)")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn index_to_account(index: u16) -> EvmResult<Address> {
+		Ok(
+			pallet_xcm_transactor::Pallet::<Runtime>::index_to_account(index)
+				.map(|account| Address(account.into()))
+				.unwrap_or_default(),
+		)
+	}
+
+	#[precompile::public("transact_info((uint8,bytes[]))")]
+	#[precompile::view]
+	#[precompile::gas(reads = 1)]
+	fn transact_info(multilocation: MultiLocationWrapper) -> EvmResult<TransactInfo> {
+		let location: MultiLocation = multilocation.into();
+		let remote_info = pallet_xcm_transactor::Pallet::<Runtime>::transact_info(location)
+			.ok_or_else(|| error("Transact Info not set"))?;
+
+		Ok(TransactInfo {
+			transact_extra_weight: remote_info.transact_extra_weight,
+			fee_per_second: remote_info.fee_per_second.into(),
+			max_weight: remote_info.max_weight,
+		})
+	}
+
+	#[precompile::public(
+		"transact_through_derivative_multilocation(uint8,uint16,(uint8,bytes[]),uint64,bytes)"
+	)]
+	fn transact_through_derivative_multilocation(
+		handle: &mut PrecompileHandle,
+		transactor: u8,
+		index: u16,
+		fee_asset: MultiLocationWrapper,
+		weight: u64,
+		inner_call: Bytes,
+	) -> EvmResult {
+		let transactor = Self::decode_transactor(transactor)?;
+
+		let call =
+			pallet_xcm_transactor::Call::<Runtime>::transact_through_derivative_multilocation(
+				transactor,
+				index,
+				Box::new(fee_asset.into()),
+				weight,
+				inner_call.0,
+			);
+
+		Self::dispatch(handle, call)
+	}
+
+	#[precompile::public("transact_through_derivative(uint8,uint16,address,uint64,bytes)")]
+	fn transact_through_derivative(
+		handle: &mut PrecompileHandle,
+		transactor: u8,
+		index: u16,
+		currency_id: Address,
+		weight: u64,
+		inner_call: Bytes,
+	) -> EvmResult {
+		let transactor = Self::decode_transactor(transactor)?;
+
+		// We convert the address into a currency id xcm-transactor understands
+		// This involves a DB read in moonbeam, hence the db Read
+		handle.record_cost(RuntimeHelper::<Runtime>::db_read_gas_cost())?;
+		let currency_account = Runtime::AddressMapping::into_account_id(currency_id.0);
+		let currency_id: CurrencyIdOf<Runtime> = Runtime::account_to_currency_id(currency_account)
+			.ok_or_else(|| error("cannot convert into currency id"))?;
+
+		let call = pallet_xcm_transactor::Call::<Runtime>::transact_through_derivative(
+			transactor,
+			index,
+			currency_id,
+			weight,
+			inner_call.0,
+		);
+
+		Self::dispatch(handle, call)
+	}
+
+	/// Transactors are identified by the index of their variant.
+	fn decode_transactor(transactor: u8) -> EvmResult<TransactorOf<Runtime>> {
+		TransactorOf::<Runtime>::decode(&mut &[transactor][..])
+			.map_err(|_| error("Non-existent transactor"))
+	}
+
+	/// Dispatch the call with the caller as origin (if enough gas).
+	fn dispatch(
+		handle: &mut PrecompileHandle,
+		call: pallet_xcm_transactor::Call<Runtime>,
+	) -> EvmResult {
+		let origin = Runtime::AddressMapping::into_account_id(handle.context().caller);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			handle.remaining_gas()?,
+		)?;
+		handle.record_cost(used_gas)?;
+
+		Ok(())
+	}
+}
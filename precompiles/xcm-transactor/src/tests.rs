@@ -0,0 +1,228 @@
+// Copyright 2019-2021 PureStake Inc.
+// This file is part of Moonbeam.
+
+// Moonbeam is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Moonbeam is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::mock::*;
+use crate::*;
+
+use evm::{executor::PrecompileOutput, ExitSucceed};
+use pallet_evm::PrecompileSet;
+use precompile_utils::{error, EvmDataReader, EvmDataWriter};
+
+fn evm_test_context(caller: Account) -> evm::Context {
+	evm::Context {
+		address: Account::Precompile.into(),
+		caller: caller.into(),
+		apparent_value: From::from(0),
+	}
+}
+
+fn execute(caller: Account, input: Vec<u8>) -> Option<EvmResult<PrecompileOutput>> {
+	Precompiles::<Runtime>::execute(
+		Account::Precompile.into(),
+		&input,
+		None,
+		&evm_test_context(caller),
+	)
+}
+
+fn read_output<T: EvmData>(caller: Account, input: Vec<u8>) -> T {
+	let output = execute(caller, input)
+		.expect("precompile exists")
+		.expect("call succeeds")
+		.output;
+
+	EvmDataReader::new(&output).read().expect("output is valid")
+}
+
+fn relay() -> MultiLocationWrapper {
+	MultiLocation::parent().into()
+}
+
+fn setup_alice_derivative() {
+	assert!(XcmTransactor::register(Origin::root(), Account::Alice, 0).is_ok());
+	assert!(XcmTransactor::set_transact_info(
+		Origin::root(),
+		Box::new(MultiLocation::parent()),
+		1_000,
+		1_000_000_000,
+		1_000_000
+	)
+	.is_ok());
+}
+
+#[test]
+fn selectors() {
+	assert_eq!(Action::IndexToAccount as u32, 0x71b0edfa);
+	assert_eq!(Action::TransactInfo as u32, 0xf87f493f);
+	assert_eq!(
+		Action::TransactThroughDerivativeMultilocation as u32,
+		0x9f89f03e
+	);
+	assert_eq!(Action::TransactThroughDerivative as u32, 0x267d4062);
+}
+
+#[test]
+fn index_to_account_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let input = || {
+			EvmDataWriter::new()
+				.write_selector(Action::IndexToAccount)
+				.write(0u16)
+				.build()
+		};
+
+		assert_eq!(
+			read_output::<Address>(Account::Bob, input()),
+			Address::default()
+		);
+
+		assert!(XcmTransactor::register(Origin::root(), Account::Alice, 0).is_ok());
+
+		assert_eq!(
+			read_output::<Address>(Account::Bob, input()),
+			Address(Account::Alice.into())
+		);
+	});
+}
+
+#[test]
+fn transact_info_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let input = || {
+			EvmDataWriter::new()
+				.write_selector(Action::TransactInfo)
+				.write(relay())
+				.build()
+		};
+
+		assert_eq!(
+			execute(Account::Bob, input()),
+			Some(Err(error("Transact Info not set")))
+		);
+
+		setup_alice_derivative();
+
+		assert_eq!(
+			read_output::<TransactInfo>(Account::Bob, input()),
+			TransactInfo {
+				transact_extra_weight: 1_000,
+				fee_per_second: 1_000_000_000.into(),
+				max_weight: 1_000_000,
+			}
+		);
+	});
+}
+
+#[test]
+fn transact_through_derivative_multilocation_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_alice_derivative();
+
+		let result = execute(
+			Account::Alice,
+			EvmDataWriter::new()
+				.write_selector(Action::TransactThroughDerivativeMultilocation)
+				.write(0u8)
+				.write(0u16)
+				.write(relay())
+				.write(4_000u64)
+				.write(Bytes(vec![1u8, 2u8]))
+				.build(),
+		)
+		.expect("precompile exists")
+		.expect("transact succeeds");
+
+		assert_eq!(result.exit_status, ExitSucceed::Returned);
+		assert_eq!(sent_xcm().len(), 1);
+		assert!(events().contains(&Event::XcmTransactor(
+			pallet_xcm_transactor::Event::TransactedDerivative(
+				Account::Alice,
+				MultiLocation::parent(),
+				vec![1u8, 2u8],
+				0
+			)
+		)));
+	});
+}
+
+#[test]
+fn transact_through_derivative_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_alice_derivative();
+
+		execute(
+			Account::Alice,
+			EvmDataWriter::new()
+				.write_selector(Action::TransactThroughDerivative)
+				.write(0u8)
+				.write(0u16)
+				.write(Address(Account::RelayAsset.into()))
+				.write(4_000u64)
+				.write(Bytes(vec![1u8, 2u8]))
+				.build(),
+		)
+		.expect("precompile exists")
+		.expect("transact succeeds");
+
+		assert_eq!(sent_xcm().len(), 1);
+	});
+}
+
+#[test]
+fn transact_through_derivative_fails_for_non_owner() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_alice_derivative();
+
+		let input = EvmDataWriter::new()
+			.write_selector(Action::TransactThroughDerivative)
+			.write(0u8)
+			.write(0u16)
+			.write(Address(Account::RelayAsset.into()))
+			.write(4_000u64)
+			.write(Bytes(vec![1u8, 2u8]))
+			.build();
+
+		assert!(matches!(execute(Account::Bob, input), Some(Err(_))));
+		assert!(sent_xcm().is_empty());
+	});
+}
+
+#[test]
+fn transact_through_derivative_fails_with_invalid_arguments() {
+	ExtBuilder::default().build().execute_with(|| {
+		setup_alice_derivative();
+
+		let input = |transactor: u8, currency: Account| {
+			EvmDataWriter::new()
+				.write_selector(Action::TransactThroughDerivative)
+				.write(transactor)
+				.write(0u16)
+				.write(Address(currency.into()))
+				.write(4_000u64)
+				.write(Bytes(vec![1u8, 2u8]))
+				.build()
+		};
+
+		assert_eq!(
+			execute(Account::Alice, input(1, Account::RelayAsset)),
+			Some(Err(error("Non-existent transactor")))
+		);
+		assert_eq!(
+			execute(Account::Alice, input(0, Account::Bob)),
+			Some(Err(error("cannot convert into currency id")))
+		);
+	});
+}
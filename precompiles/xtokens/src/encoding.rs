@@ -21,8 +21,11 @@ use precompile_utils::{error, Bytes, EvmData, EvmDataReader, EvmDataWriter, EvmR
 use sp_core::U256;
 
 use frame_support::ensure;
-use sp_std::vec::Vec;
-use xcm::v1::{Junction, Junctions, MultiLocation, NetworkId};
+use sp_std::{
+	convert::{TryFrom, TryInto},
+	vec::Vec,
+};
+use xcm::v1::{AssetId, Fungibility, Junction, Junctions, MultiAsset, MultiLocation, NetworkId};
 
 // Function to convert network id to bytes
 // We dont implement EVMData here as these bytes will be appended only
@@ -218,6 +221,10 @@ impl EvmData for JunctionWrapper {
 		};
 		EvmData::write(writer, encoded_bytes);
 	}
+
+	fn has_static_size() -> bool {
+		false
+	}
 }
 
 // Junctions are defined by the number of Junction items that they point to
@@ -261,6 +268,10 @@ impl EvmData for JunctionsWrapper {
 			.collect();
 		EvmData::write(writer, encoded);
 	}
+
+	fn has_static_size() -> bool {
+		false
+	}
 }
 
 // MultiLocations are defined by their number of parents (u8) and
@@ -288,11 +299,14 @@ impl Into<MultiLocation> for MultiLocationWrapper {
 
 impl EvmData for MultiLocationWrapper {
 	fn read(reader: &mut EvmDataReader) -> EvmResult<Self> {
-		let num_parents = reader
+		// The MultiLocation struct contains dynamic data, and is thus behind a pointer.
+		let mut inner_reader = reader.read_pointer()?;
+
+		let num_parents = inner_reader
 			.read::<u8>()
 			.map_err(|_| error("tried to parse array offset out of bounds"))?;
 
-		let junctions: JunctionsWrapper = reader.read()?;
+		let junctions: JunctionsWrapper = inner_reader.read()?;
 
 		Ok(MultiLocationWrapper(MultiLocation {
 			parents: num_parents,
@@ -301,7 +315,49 @@ impl EvmData for MultiLocationWrapper {
 	}
 
 	fn write(writer: &mut EvmDataWriter, value: Self) {
-		EvmData::write(writer, U256::from(value.0.parents));
-		EvmData::write(writer, JunctionsWrapper(value.0.interior));
+		let inner_writer = EvmDataWriter::new()
+			.write(U256::from(value.0.parents))
+			.write(JunctionsWrapper(value.0.interior));
+
+		writer.write_pointer(inner_writer.build());
+	}
+
+	fn has_static_size() -> bool {
+		false
+	}
+}
+
+// MultiAssets are defined as concrete fungible assets, i.e. the MultiLocation of the asset and
+// the amount of it
+
+// A wrapper to be able to implement here the evmData reader
+#[derive(Clone, Eq, PartialEq, EvmData)]
+pub struct MultiAssetWrapper {
+	pub location: MultiLocationWrapper,
+	pub amount: U256,
+}
+
+impl From<(MultiLocation, U256)> for MultiAssetWrapper {
+	fn from((location, amount): (MultiLocation, U256)) -> Self {
+		MultiAssetWrapper {
+			location: location.into(),
+			amount,
+		}
+	}
+}
+
+impl TryFrom<MultiAssetWrapper> for MultiAsset {
+	type Error = ExitError;
+
+	fn try_from(asset: MultiAssetWrapper) -> Result<Self, ExitError> {
+		Ok(MultiAsset {
+			id: AssetId::Concrete(asset.location.into()),
+			fun: Fungibility::Fungible(
+				asset
+					.amount
+					.try_into()
+					.map_err(|_| error("Amount is too large for provided balance type"))?,
+			),
+		})
 	}
 }
@@ -32,9 +32,9 @@ use sp_std::{
 	marker::PhantomData,
 };
 mod encoding;
-pub use encoding::MultiLocationWrapper;
+pub use encoding::{MultiAssetWrapper, MultiLocationWrapper};
 use sp_std::boxed::Box;
-use xcm::v1::{AssetId, Fungibility, MultiAsset, MultiLocation};
+use xcm::v1::{MultiAsset, MultiLocation};
 #[cfg(test)]
 mod mock;
 #[cfg(test)]
@@ -49,6 +49,9 @@ pub type CurrencyIdOf<Runtime> = <Runtime as orml_xtokens::Config>::CurrencyId;
 pub enum Action {
 	Transfer = "transfer(address, u256, bytes[], u64)",
 	TransferMultiAsset = "transfer_multiasset(bytes[], u256, bytes[], u64)",
+	TransferWithFee = "transfer_with_fee(address, u256, u256, bytes[], u64)",
+	TransferMultiAssetWithFee =
+		"transfer_multiasset_with_fee((bytes[], u256), (bytes[], u256), bytes[], u64)",
 }
 
 /// This trait ensure we can convert AccountIds to CurrencyIds
@@ -81,6 +84,10 @@ where
 			// Check for accessor methods first. These return results immediately
 			Action::Transfer => Self::transfer(input, target_gas, context),
 			Action::TransferMultiAsset => Self::transfer_multiasset(input, target_gas, context),
+			Action::TransferWithFee => Self::transfer_with_fee(input, target_gas, context),
+			Action::TransferMultiAssetWithFee => {
+				Self::transfer_multiasset_with_fee(input, target_gas, context)
+			}
 		}
 	}
 }
@@ -121,9 +128,7 @@ where
 				.ok_or(error("cannot convert into currency id"))?;
 
 		let origin = Runtime::AddressMapping::into_account_id(context.caller);
-		let to_balance = amount
-			.try_into()
-			.map_err(|_| error("Amount is too large for provided balance type"))?;
+		let to_balance = Self::balance(amount)?;
 
 		let call = orml_xtokens::Call::<Runtime>::transfer(
 			to_currency_id,
@@ -170,15 +175,11 @@ where
 		let weight: u64 = input.read::<u64>()?;
 
 		let origin = Runtime::AddressMapping::into_account_id(context.caller);
-		let to_balance = amount
-			.try_into()
-			.map_err(|_| error("Amount is too large for provided balance type"))?;
+		let asset: MultiAsset =
+			MultiAssetWrapper::from((asset_multilocation, amount)).try_into()?;
 
 		let call = orml_xtokens::Call::<Runtime>::transfer_multiasset(
-			Box::new(MultiAsset {
-				id: AssetId::Concrete(asset_multilocation),
-				fun: Fungibility::Fungible(to_balance),
-			}),
+			Box::new(asset),
 			Box::new(destination),
 			weight,
 		);
@@ -198,4 +199,110 @@ where
 			logs: Default::default(),
 		})
 	}
+
+	fn transfer_with_fee(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// Bound check
+		input.expect_arguments(3)?;
+		let currency_address: H160 = input.read::<Address>()?.into();
+		let amount: U256 = input.read()?;
+		// The fee is paid in the same currency, on top of the amount
+		let fee: U256 = input.read()?;
+
+		let destination: MultiLocation = input.read::<MultiLocationWrapper>()?.into();
+
+		// Bound check
+		input.expect_arguments(1)?;
+		let weight: u64 = input.read::<u64>()?;
+
+		let currency_id = Self::currency_id(currency_address)?;
+
+		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+		let amount = Self::balance(amount)?;
+		let fee = Self::balance(fee)?;
+
+		let call = orml_xtokens::Call::<Runtime>::transfer_with_fee(
+			currency_id,
+			amount,
+			fee,
+			Box::new(destination),
+			weight,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			gasometer.remaining_gas()?,
+		)?;
+
+		gasometer.record_cost(used_gas)?;
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: Default::default(),
+			logs: Default::default(),
+		})
+	}
+
+	fn transfer_multiasset_with_fee(
+		mut input: EvmDataReader,
+		target_gas: Option<u64>,
+		context: &Context,
+	) -> EvmResult<PrecompileOutput> {
+		let mut gasometer = Gasometer::new(target_gas);
+
+		// The asset to send and the asset paying the fees, on top of it, are chosen separately.
+		// For now we are assuming these are concrete fungible assets
+		let asset: MultiAsset = input.read::<MultiAssetWrapper>()?.try_into()?;
+		let fee: MultiAsset = input.read::<MultiAssetWrapper>()?.try_into()?;
+
+		// read destination
+		let destination: MultiLocation = input.read::<MultiLocationWrapper>()?.into();
+
+		// Bound check
+		input.expect_arguments(1)?;
+		let weight: u64 = input.read::<u64>()?;
+
+		let origin = Runtime::AddressMapping::into_account_id(context.caller);
+
+		let call = orml_xtokens::Call::<Runtime>::transfer_multiasset_with_fee(
+			Box::new(asset),
+			Box::new(fee),
+			Box::new(destination),
+			weight,
+		);
+
+		let used_gas = RuntimeHelper::<Runtime>::try_dispatch(
+			Some(origin).into(),
+			call,
+			gasometer.remaining_gas()?,
+		)?;
+
+		gasometer.record_cost(used_gas)?;
+
+		Ok(PrecompileOutput {
+			exit_status: ExitSucceed::Returned,
+			cost: gasometer.used_gas(),
+			output: Default::default(),
+			logs: Default::default(),
+		})
+	}
+
+	// We convert the address into a currency id xtokens understands
+	fn currency_id(address: H160) -> EvmResult<CurrencyIdOf<Runtime>> {
+		let account = Runtime::AddressMapping::into_account_id(address);
+		Runtime::account_to_currency_id(account).ok_or(error("cannot convert into currency id"))
+	}
+
+	fn balance(amount: U256) -> EvmResult<XBalanceOf<Runtime>> {
+		amount
+			.try_into()
+			.map_err(|_| error("Amount is too large for provided balance type"))
+	}
 }
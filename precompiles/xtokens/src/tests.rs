@@ -23,13 +23,13 @@ use orml_xtokens::Event as XtokensEvent;
 use crate::encoding::{
 	network_id_from_bytes, network_id_to_bytes, JunctionWrapper, JunctionsWrapper,
 };
-use crate::{Action, MultiLocationWrapper, PrecompileOutput};
+use crate::{Action, MultiAssetWrapper, MultiLocationWrapper, PrecompileOutput};
 use num_enum::TryFromPrimitive;
 use pallet_evm::{ExitSucceed, PrecompileSet};
 use precompile_utils::{error, Address, EvmDataReader, EvmDataWriter};
 use sha3::{Digest, Keccak256};
 use sp_core::{H160, U256};
-use sp_std::convert::TryInto;
+use sp_std::convert::{TryFrom, TryInto};
 use xcm::v1::{AssetId, Fungibility, Junction, Junctions, MultiAsset, MultiLocation, NetworkId};
 
 #[test]
@@ -48,6 +48,24 @@ fn test_selector_enum() {
 		Action::try_from_primitive(u32::from_be_bytes(buffer)).unwrap(),
 		Action::TransferMultiAsset,
 	);
+
+	buffer.copy_from_slice(
+		&Keccak256::digest(b"transfer_with_fee(address, u256, u256, bytes[], u64)")[0..4],
+	);
+	assert_eq!(
+		Action::try_from_primitive(u32::from_be_bytes(buffer)).unwrap(),
+		Action::TransferWithFee,
+	);
+
+	buffer.copy_from_slice(
+		&Keccak256::digest(
+			b"transfer_multiasset_with_fee((bytes[], u256), (bytes[], u256), bytes[], u64)",
+		)[0..4],
+	);
+	assert_eq!(
+		Action::try_from_primitive(u32::from_be_bytes(buffer)).unwrap(),
+		Action::TransferMultiAssetWithFee,
+	);
 }
 
 #[test]
@@ -229,6 +247,45 @@ fn junction_decoder_works() {
 	});
 }
 
+#[test]
+fn multi_asset_decoder_works() {
+	ExtBuilder::default().build().execute_with(|| {
+		let para_asset = MultiLocation::new(
+			1,
+			Junctions::X2(Junction::Parachain(2), Junction::GeneralIndex(5u128)),
+		);
+		let asset = MultiAssetWrapper::from((para_asset.clone(), U256::from(500)));
+
+		let writer_output = EvmDataWriter::new().write(asset).build();
+
+		let mut reader = EvmDataReader::new(&writer_output);
+		let parsed: MultiAssetWrapper = reader.read().expect("to correctly parse asset");
+
+		let expected = MultiAsset {
+			id: AssetId::Concrete(para_asset),
+			fun: Fungibility::Fungible(500),
+		};
+		assert_eq!(MultiAsset::try_from(parsed), Ok(expected));
+	});
+}
+
+#[test]
+fn multi_asset_decoder_rejects_too_large_amounts() {
+	ExtBuilder::default().build().execute_with(|| {
+		let asset = MultiAssetWrapper::from((MultiLocation::parent(), U256::max_value()));
+
+		let writer_output = EvmDataWriter::new().write(asset).build();
+
+		let mut reader = EvmDataReader::new(&writer_output);
+		let parsed: MultiAssetWrapper = reader.read().expect("to correctly parse asset");
+
+		assert_eq!(
+			MultiAsset::try_from(parsed),
+			Err(error("Amount is too large for provided balance type"))
+		);
+	});
+}
+
 #[test]
 fn network_id_decoder_works() {
 	ExtBuilder::default().build().execute_with(|| {
@@ -555,3 +612,161 @@ fn transfer_multi_asset_non_reserve_to_non_reserve() {
 			assert!(events().contains(&expected));
 		});
 }
+
+#[test]
+fn transfer_with_fee_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let destination = MultiLocation::new(
+				1,
+				Junctions::X1(Junction::AccountId32 {
+					network: NetworkId::Any,
+					id: [1u8; 32],
+				}),
+			);
+
+			// We are transferring asset 0, which we have instructed to be the relay asset
+			assert_eq!(
+				Precompiles::execute(
+					Precompile.into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::TransferWithFee)
+						.write(Address(AssetId(0u128).into()))
+						.write(U256::from(500))
+						.write(U256::from(50))
+						.write(MultiLocationWrapper::from(destination.clone()))
+						.write(U256::from(4000000))
+						.build(),
+					None,
+					&evm::Context {
+						address: Precompile.into(),
+						caller: Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 3000,
+					output: vec![],
+					logs: vec![]
+				}))
+			);
+			let expected: crate::mock::Event = XtokensEvent::TransferredWithFee(
+				Alice,
+				CurrencyId::OtherReserve(0u128),
+				500,
+				50,
+				destination,
+			)
+			.into();
+			// Assert that the events vector contains the one expected
+			assert!(events().contains(&expected));
+		});
+}
+
+#[test]
+fn transfer_with_fee_too_large_amount_fails() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let destination = MultiLocation::new(
+				1,
+				Junctions::X1(Junction::AccountId32 {
+					network: NetworkId::Any,
+					id: [1u8; 32],
+				}),
+			);
+
+			assert_eq!(
+				Precompiles::execute(
+					Precompile.into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::TransferWithFee)
+						.write(Address(AssetId(0u128).into()))
+						.write(U256::from(500))
+						.write(U256::max_value())
+						.write(MultiLocationWrapper::from(destination))
+						.write(U256::from(4000000))
+						.build(),
+					None,
+					&evm::Context {
+						address: Precompile.into(),
+						caller: Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Err(error("Amount is too large for provided balance type")))
+			);
+		});
+}
+
+#[test]
+fn transfer_multi_asset_with_fee_works() {
+	ExtBuilder::default()
+		.with_balances(vec![(Alice, 1000)])
+		.build()
+		.execute_with(|| {
+			let destination = MultiLocation::new(
+				1,
+				Junctions::X1(Junction::AccountId32 {
+					network: NetworkId::Any,
+					id: [1u8; 32],
+				}),
+			);
+
+			let asset_location = MultiLocation::new(
+				1,
+				Junctions::X2(Junction::Parachain(2), Junction::GeneralIndex(5u128)),
+			);
+
+			// The fee asset is given separately from the asset sent
+			assert_eq!(
+				Precompiles::execute(
+					Precompile.into(),
+					&EvmDataWriter::new()
+						.write_selector(Action::TransferMultiAssetWithFee)
+						.write(MultiAssetWrapper::from((
+							asset_location.clone(),
+							U256::from(500)
+						)))
+						.write(MultiAssetWrapper::from((
+							asset_location.clone(),
+							U256::from(50)
+						)))
+						.write(MultiLocationWrapper::from(destination.clone()))
+						.write(U256::from(4000000))
+						.build(),
+					None,
+					&evm::Context {
+						address: Precompile.into(),
+						caller: Alice.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					cost: 3000,
+					output: vec![],
+					logs: vec![]
+				}))
+			);
+			let expected: crate::mock::Event = XtokensEvent::TransferredMultiAssetWithFee(
+				Alice,
+				MultiAsset {
+					id: AssetId::Concrete(asset_location.clone()),
+					fun: Fungibility::Fungible(500),
+				},
+				MultiAsset {
+					id: AssetId::Concrete(asset_location),
+					fun: Fungibility::Fungible(50),
+				},
+				destination,
+			)
+			.into();
+			// Assert that the events vector contains the one expected
+			assert!(events().contains(&expected));
+		});
+}
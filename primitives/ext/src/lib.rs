@@ -72,4 +72,9 @@ pub trait MoonbeamExt {
 	fn call_list_new(&mut self) {
 		Event::CallListNew().emit();
 	}
+
+	/// Whether the client listener requests the state of the accounts accessed by the EVM.
+	fn state_tracing(&mut self) -> bool {
+		evm_tracing_events::state_tracing()
+	}
 }
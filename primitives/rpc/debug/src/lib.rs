@@ -18,10 +18,13 @@
 
 use codec::{Decode, Encode};
 use ethereum::TransactionV0 as Transaction;
-use ethereum_types::H256;
+use ethereum_types::{H160, H256, U256};
 use sp_std::vec::Vec;
 
 sp_api::decl_runtime_apis! {
+	// V2 adds `trace_call`, which traces an arbitrary call on top of the state of a block without
+	// applying any extrinsic.
+	#[api_version(2)]
 	pub trait DebugRuntimeApi {
 		fn trace_transaction(
 			extrinsics: Vec<Block::Extrinsic>,
@@ -32,6 +35,18 @@ sp_api::decl_runtime_apis! {
 			extrinsics: Vec<Block::Extrinsic>,
 			known_transactions: Vec<H256>,
 		) -> Result<(), sp_runtime::DispatchError>;
+
+		/// Trace a call, or a contract creation if `to` is `None`. `gas_limit` cannot exceed the
+		/// block gas limit.
+		fn trace_call(
+			from: H160,
+			to: Option<H160>,
+			data: Vec<u8>,
+			value: U256,
+			gas_limit: U256,
+			gas_price: Option<U256>,
+			nonce: Option<U256>,
+		) -> Result<(), sp_runtime::DispatchError>;
 	}
 }
 
@@ -40,6 +55,10 @@ pub enum TracerInput {
 	None,
 	Blockscout,
 	CallTracer,
+	PrestateTracer,
+	FourByteTracer,
+	OpcountTracer,
+	NoopTracer,
 }
 
 /// DebugRuntimeApi V2 result. Trace response is stored in client and runtime api call response is
@@ -74,6 +74,17 @@ impl Event {
 /// - Inside the client to forward those events to the client listener.
 pub trait Listener {
 	fn event(&mut self, event: Event);
+
+	/// Whether the runtime must also emit the state of the accounts accessed by the EVM, which
+	/// requires additional storage reads (i.e. prestateTracer).
+	fn state_tracing(&self) -> bool {
+		false
+	}
+}
+
+/// Whether the global `Listener` implementor requests the state of the accessed accounts.
+pub fn state_tracing() -> bool {
+	listener::with(|listener| listener.state_tracing()).unwrap_or(false)
 }
 
 #[derive(Clone, Debug, Encode, Decode, PartialEq, Eq)]
@@ -90,6 +90,32 @@ pub enum RuntimeEvent {
 		index: H256,
 		value: H256,
 	},
+	/// State of an account touched by the transaction. Not emitted by the EVM itself but by the
+	/// tracer, which reads it before the account is first accessed and once the transaction
+	/// is applied.
+	AccountState {
+		stage: StateStage,
+		address: H160,
+		balance: U256,
+		nonce: U256,
+		code: Vec<u8>,
+	},
+	/// State of a storage slot touched by the transaction, see `AccountState`.
+	StorageState {
+		stage: StateStage,
+		address: H160,
+		index: H256,
+		value: H256,
+	},
+}
+
+/// Moment at which an `AccountState` or `StorageState` event was read.
+#[derive(Clone, Copy, Debug, Encode, Decode, PartialEq, Eq)]
+pub enum StateStage {
+	/// Before the transaction first accessed it.
+	Pre,
+	/// After the transaction has been applied.
+	Post,
 }
 
 #[cfg(feature = "evm-tracing")]
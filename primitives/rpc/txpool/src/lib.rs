@@ -21,7 +21,8 @@
 
 use codec::{Decode, Encode};
 use ethereum::TransactionV0 as Transaction;
-use sp_runtime::traits::Block as BlockT;
+use sp_core::{H160, U256};
+use sp_runtime::{traits::Block as BlockT, transaction_validity::TransactionTag};
 use sp_std::vec::Vec;
 
 #[derive(Eq, PartialEq, Clone, Encode, Decode, sp_runtime::RuntimeDebug)]
@@ -30,6 +31,19 @@ pub struct TxPoolResponse {
 	pub future: Vec<Transaction>,
 }
 
+/// Sender of an Ethereum transaction, read from the SCALE encoded `(H160, U256)` sender and nonce
+/// tag that pallet-ethereum provides in the validity of the transaction. Other extrinsics don't
+/// provide such a tag (signed extrinsics provide a shorter `(AccountId, Index)` one).
+pub fn tagged_sender(provides: &[TransactionTag]) -> Option<H160> {
+	provides.iter().find_map(|tag| {
+		let mut input = &tag[..];
+		match <(H160, U256)>::decode(&mut input) {
+			Ok((sender, _nonce)) if input.is_empty() => Some(sender),
+			_ => None,
+		}
+	})
+}
+
 sp_api::decl_runtime_apis! {
 	pub trait TxPoolRuntimeApi {
 		fn extrinsic_filter(
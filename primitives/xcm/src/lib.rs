@@ -19,7 +19,7 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
 use frame_support::{
-	traits::{Get, OriginTrait},
+	traits::{tokens::fungibles::Mutate, Get, OriginTrait},
 	weights::{constants::WEIGHT_PER_SECOND, Weight},
 };
 use xcm::v1::{
@@ -30,12 +30,13 @@ use xcm::v1::{
 };
 use xcm_builder::TakeRevenue;
 use xcm_executor::traits::FilterAssetLocation;
+use xcm_executor::traits::MatchesFungibles;
 use xcm_executor::traits::WeightTrader;
 
 use sp_runtime::traits::Zero;
 
 use sp_std::borrow::Borrow;
-use sp_std::{convert::TryInto, marker::PhantomData};
+use sp_std::{convert::TryInto, marker::PhantomData, vec::Vec};
 
 /// Converter struct implementing `AssetIdConversion` converting a numeric asset ID
 /// (must be `TryFrom/TryInto<u128>`) into a MultiLocation Value and Viceversa through
@@ -112,105 +113,185 @@ where
 }
 
 // We need to know how to charge for incoming assets
-// This takes the first fungible asset, and takes whatever UnitPerSecondGetter establishes
-// UnitsToWeightRatio trait, which needs to be implemented by AssetIdInfoGetter
+// This takes the first fungible asset in payment for which UnitsToWeightRatio establishes a
+// units per second (and that can cover the weight), and charges whatever that ratio dictates.
+// The assets in payment are tried in the order given by Priority. The holding is a map ordered by
+// asset id, so assets with the same priority are tried in the order of their `MultiLocation`.
+// UnitsToWeightRatio trait needs to be implemented by AssetIdInfoGetter
+// The weight bought and the amount paid are tracked per asset, so that refunds are done in the
+// same asset that paid for the weight. Whatever is not refunded is handed to R on drop.
 pub struct FirstAssetTrader<
 	AssetId: From<AssetType> + Clone,
 	AssetType: From<MultiLocation> + Clone,
 	AssetIdInfoGetter: UnitsToWeightRatio<AssetId>,
 	R: TakeRevenue,
+	Priority: FeeAssetPriority = (),
 >(
-	Weight,
-	Option<(MultiLocation, u128, u128)>,
-	PhantomData<(AssetId, AssetType, AssetIdInfoGetter, R)>,
+	Vec<AssetTraderRecord>,
+	PhantomData<(AssetId, AssetType, AssetIdInfoGetter, R, Priority)>,
 );
+
+/// What a trader has charged so far in a given asset
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct AssetTraderRecord {
+	/// The asset the weight was paid with
+	pub location: MultiLocation,
+	/// The weight bought with this asset and not yet refunded
+	pub weight: Weight,
+	/// The amount paid with this asset and not yet refunded
+	pub amount: u128,
+	/// The units per second used to price the weight
+	pub units_per_second: u128,
+}
+
 impl<
 		AssetId: From<AssetType> + Clone,
 		AssetType: From<MultiLocation> + Clone,
 		AssetIdInfoGetter: UnitsToWeightRatio<AssetId>,
 		R: TakeRevenue,
-	> WeightTrader for FirstAssetTrader<AssetId, AssetType, AssetIdInfoGetter, R>
+		Priority: FeeAssetPriority,
+	> FirstAssetTrader<AssetId, AssetType, AssetIdInfoGetter, R, Priority>
+{
+	/// The amount of the asset at `location` required to buy `weight`, if the asset has a
+	/// units per second registered
+	fn weight_price(location: &MultiLocation, weight: Weight) -> Option<(u128, u128)> {
+		let asset_type: AssetType = location.clone().into();
+		let units_per_second = AssetIdInfoGetter::get_units_per_second(AssetId::from(asset_type))?;
+		let amount = units_per_second.checked_mul(weight as u128)? / (WEIGHT_PER_SECOND as u128);
+		Some((amount, units_per_second))
+	}
+}
+
+impl<
+		AssetId: From<AssetType> + Clone,
+		AssetType: From<MultiLocation> + Clone,
+		AssetIdInfoGetter: UnitsToWeightRatio<AssetId>,
+		R: TakeRevenue,
+		Priority: FeeAssetPriority,
+	> WeightTrader for FirstAssetTrader<AssetId, AssetType, AssetIdInfoGetter, R, Priority>
 {
 	fn new() -> Self {
-		FirstAssetTrader(0, None, PhantomData)
+		FirstAssetTrader(Vec::new(), PhantomData)
 	}
 	fn buy_weight(
 		&mut self,
 		weight: Weight,
 		payment: xcm_executor::Assets,
 	) -> Result<xcm_executor::Assets, XcmError> {
-		let first_asset = payment
-			.clone()
+		// Assets are tried by priority, the first one able to pay is charged. The sort is stable
+		// and the holding is ordered by asset id, so assets with the same priority are tried in
+		// the order of their `MultiLocation`.
+		let mut locations: Vec<MultiLocation> = payment
 			.fungible_assets_iter()
-			.next()
-			.ok_or(XcmError::TooExpensive)?;
-
-		// We are only going to check first asset for now. This should be sufficient for simple token
-		// transfers. We will see later if we change this.
-		match (first_asset.id, first_asset.fun) {
-			(xcmAssetId::Concrete(id), Fungibility::Fungible(_)) => {
-				let asset_type: AssetType = id.clone().into();
-				let asset_id: AssetId = AssetId::from(asset_type);
-				if let Some(units_per_second) = AssetIdInfoGetter::get_units_per_second(asset_id) {
-					let amount = units_per_second * (weight as u128) / (WEIGHT_PER_SECOND as u128);
-					let required = MultiAsset {
-						fun: Fungibility::Fungible(amount),
-						id: xcmAssetId::Concrete(id.clone()),
-					};
-					let unused = payment
-						.checked_sub(required)
-						.map_err(|_| XcmError::TooExpensive)?;
-					self.0 = self.0.saturating_add(weight);
-
-					// In case the asset matches the one the trader already stored before, add
-					// to later refund
-
-					// Else we are always going to substract the weight if we can, but we latter do
-					// not refund it
-
-					// In short, we only refund on the asset the trader first succesfully was able
-					// to pay for an execution
-					let new_asset = match self.1.clone() {
-						Some((prev_id, prev_amount, units_per_second)) => {
-							if prev_id == id.clone() {
-								Some((id, prev_amount.saturating_add(amount), units_per_second))
-							} else {
-								None
-							}
-						}
-						None => Some((id, amount, units_per_second)),
-					};
-
-					// Due to the trait bound, we can only refund one asset.
-					if let Some(new_asset) = new_asset {
-						self.0 = self.0.saturating_add(weight);
-						self.1 = Some(new_asset);
-					};
-					return Ok(unused);
-				} else {
-					return Err(XcmError::TooExpensive);
-				};
+			.filter_map(|asset| match asset.id {
+				xcmAssetId::Concrete(id) => Some(id),
+				_ => None,
+			})
+			.collect();
+		locations.sort_by_key(|location| Priority::priority(location));
+
+		for id in locations {
+			let (amount, units_per_second) = match Self::weight_price(&id, weight) {
+				Some(price) => price,
+				None => continue,
+			};
+			let required = MultiAsset {
+				fun: Fungibility::Fungible(amount),
+				id: xcmAssetId::Concrete(id.clone()),
+			};
+			let unused = match payment.clone().checked_sub(required) {
+				Ok(unused) => unused,
+				Err(_) => continue,
+			};
+
+			match self.0.iter_mut().find(|record| record.location == id) {
+				Some(record) => {
+					record.weight = record.weight.saturating_add(weight);
+					record.amount = record.amount.saturating_add(amount);
+				}
+				None => self.0.push(AssetTraderRecord {
+					location: id,
+					weight,
+					amount,
+					units_per_second,
+				}),
 			}
-			_ => return Err(XcmError::TooExpensive),
+
+			return Ok(unused);
 		}
+
+		Err(XcmError::TooExpensive)
 	}
 
+	// Due to the trait bound, we can only refund one asset per call. We refund from the asset
+	// that most recently paid for weight, up to the weight bought with it.
 	fn refund_weight(&mut self, weight: Weight) -> Option<MultiAsset> {
-		if let Some((id, prev_amount, units_per_second)) = self.1.clone() {
-			let weight = weight.min(self.0);
-			self.0 -= weight;
-			let amount = units_per_second * (weight as u128) / (WEIGHT_PER_SECOND as u128);
-			self.1 = Some((
-				id.clone(),
-				prev_amount.saturating_sub(amount),
-				units_per_second,
-			));
-			Some(MultiAsset {
-				fun: Fungibility::Fungible(amount),
-				id: xcmAssetId::Concrete(id.clone()),
-			})
-		} else {
-			None
+		let record = self.0.iter_mut().rev().find(|record| record.weight > 0)?;
+		let weight = weight.min(record.weight);
+		let amount = (record.units_per_second.saturating_mul(weight as u128)
+			/ (WEIGHT_PER_SECOND as u128))
+			.min(record.amount);
+		record.weight -= weight;
+		record.amount -= amount;
+
+		if amount.is_zero() {
+			return None;
+		}
+
+		Some(MultiAsset {
+			fun: Fungibility::Fungible(amount),
+			id: xcmAssetId::Concrete(record.location.clone()),
+		})
+	}
+}
+
+impl<
+		AssetId: From<AssetType> + Clone,
+		AssetType: From<MultiLocation> + Clone,
+		AssetIdInfoGetter: UnitsToWeightRatio<AssetId>,
+		R: TakeRevenue,
+		Priority: FeeAssetPriority,
+	> Drop for FirstAssetTrader<AssetId, AssetType, AssetIdInfoGetter, R, Priority>
+{
+	fn drop(&mut self) {
+		for record in self.0.iter() {
+			if !record.amount.is_zero() {
+				R::take_revenue(MultiAsset {
+					fun: Fungibility::Fungible(record.amount),
+					id: xcmAssetId::Concrete(record.location.clone()),
+				});
+			}
+		}
+	}
+}
+
+/// Deposits the fees charged by a trader into `ReceiverAccount`, e.g. the treasury
+/// Only the assets matched by Matcher are deposited, the rest are dropped
+pub struct XcmFeesToAccount<Assets, Matcher, AccountId, ReceiverAccount>(
+	PhantomData<(Assets, Matcher, AccountId, ReceiverAccount)>,
+);
+impl<
+		Assets: Mutate<AccountId>,
+		Matcher: MatchesFungibles<Assets::AssetId, Assets::Balance>,
+		AccountId: Clone,
+		ReceiverAccount: Get<AccountId>,
+	> TakeRevenue for XcmFeesToAccount<Assets, Matcher, AccountId, ReceiverAccount>
+{
+	fn take_revenue(revenue: MultiAsset) {
+		match Matcher::matches_fungibles(&revenue) {
+			Ok((asset_id, amount)) => {
+				// This can fail if the amount is below the minimum balance of the asset, in
+				// which case the fee is lost as it would have been without a receiver
+				if !amount.is_zero() {
+					if let Err(e) = Assets::mint_into(asset_id, &ReceiverAccount::get(), amount) {
+						log::debug!(target: "xcm", "take revenue failed minting: {:?}", e);
+					}
+				}
+			}
+			Err(_) => log::debug!(
+				target: "xcm",
+				"take revenue failed matching fungible"
+			),
 		}
 	}
 }
@@ -260,13 +341,13 @@ impl<NativeTrader: WeightTrader, OtherTrader: WeightTrader> WeightTrader
 		}
 
 		let other = self.other_trader.refund_weight(weight);
-		match other {
+		match other.clone() {
 			Some(MultiAsset {
 				fun: Fungibility::Fungible(amount),
 				id: xcmAssetId::Concrete(_id),
 			}) => {
 				if !amount.is_zero() {
-					return native;
+					return other;
 				}
 			}
 			_ => {}
@@ -325,3 +406,142 @@ pub trait UnitsToWeightRatio<AssetId> {
 	// Get units per second from asset type
 	fn get_units_per_second(asset_id: AssetId) -> Option<u128>;
 }
+
+// Defines the order in which FirstAssetTrader tries the assets it is paid with
+pub trait FeeAssetPriority {
+	/// The priority of the asset at `location`, assets with a lower value are tried first
+	fn priority(location: &MultiLocation) -> u32;
+}
+
+// All the assets have the same priority, so they are tried in the order of their `MultiLocation`
+// (the order of the holding)
+impl FeeAssetPriority for () {
+	fn priority(_location: &MultiLocation) -> u32 {
+		0
+	}
+}
+
+// The assets listed by Preferred are tried first, in the order of the list, and then the others
+pub struct PreferredFeeAssets<Preferred>(PhantomData<Preferred>);
+impl<Preferred: Get<Vec<MultiLocation>>> FeeAssetPriority for PreferredFeeAssets<Preferred> {
+	fn priority(location: &MultiLocation) -> u32 {
+		Preferred::get()
+			.iter()
+			.position(|preferred| preferred == location)
+			.map(|position| position as u32)
+			.unwrap_or(u32::MAX)
+	}
+}
+
+// Defines the trait to obtain the destination of a transactor
+// This is used by xcm-transactor to know where to send Transact messages
+pub trait XcmTransact {
+	/// The location Transact messages are sent to
+	fn destination(self) -> MultiLocation;
+}
+
+/// The utility calls xcm-transactor knows how to encode for a destination chain
+pub enum UtilityAvailableCalls {
+	AsDerivative(u16, Vec<u8>),
+}
+
+// Defines the trait to encode utility calls for a given destination chain
+// Each destination might have the utility pallet at a different index
+pub trait UtilityEncodeCall {
+	fn encode_call(self, call: UtilityAvailableCalls) -> Vec<u8>;
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use frame_support::parameter_types;
+	use xcm::v1::Junction::PalletInstance;
+	use xcm_executor::Assets;
+
+	// Every asset is charged 1e6 units per second, i.e. 10 units for a weight of 1e7
+	pub struct SameUnitsPerSecond;
+	impl UnitsToWeightRatio<MultiLocation> for SameUnitsPerSecond {
+		fn get_units_per_second(_asset_id: MultiLocation) -> Option<u128> {
+			Some(1_000_000)
+		}
+	}
+
+	type Trader<Priority> =
+		FirstAssetTrader<MultiLocation, MultiLocation, SameUnitsPerSecond, (), Priority>;
+
+	fn relay_asset() -> MultiLocation {
+		MultiLocation::parent()
+	}
+
+	fn other_asset() -> MultiLocation {
+		MultiLocation::new(1, X1(PalletInstance(5u8)))
+	}
+
+	parameter_types! {
+		pub PreferredAssets: Vec<MultiLocation> = vec![other_asset()];
+	}
+
+	// Holds 100 units of each asset. The relay asset has the lowest `MultiLocation`
+	fn payment() -> Assets {
+		let mut payment = Assets::new();
+		for location in vec![relay_asset(), other_asset()] {
+			payment.subsume(MultiAsset {
+				id: xcmAssetId::Concrete(location),
+				fun: Fungibility::Fungible(100),
+			});
+		}
+		payment
+	}
+
+	fn balance(assets: &Assets, location: MultiLocation) -> Option<u128> {
+		assets
+			.fungible
+			.get(&xcmAssetId::Concrete(location))
+			.cloned()
+	}
+
+	#[test]
+	fn lowest_location_is_charged_by_default() {
+		let mut trader = Trader::<()>::new();
+
+		let unused = trader.buy_weight(10_000_000, payment()).unwrap();
+
+		assert_eq!(balance(&unused, relay_asset()), Some(90));
+		assert_eq!(balance(&unused, other_asset()), Some(100));
+	}
+
+	#[test]
+	fn preferred_asset_is_charged() {
+		let mut trader = Trader::<PreferredFeeAssets<PreferredAssets>>::new();
+
+		let unused = trader.buy_weight(10_000_000, payment()).unwrap();
+
+		assert_eq!(balance(&unused, relay_asset()), Some(100));
+		assert_eq!(balance(&unused, other_asset()), Some(90));
+
+		// The refund is done in the asset that paid
+		assert_eq!(
+			trader.refund_weight(10_000_000),
+			Some(MultiAsset {
+				id: xcmAssetId::Concrete(other_asset()),
+				fun: Fungibility::Fungible(10),
+			})
+		);
+	}
+
+	#[test]
+	fn other_assets_are_charged_when_preferred_cannot_pay() {
+		let mut trader = Trader::<PreferredFeeAssets<PreferredAssets>>::new();
+
+		// Only the relay asset is held
+		let mut payment = Assets::new();
+		payment.subsume(MultiAsset {
+			id: xcmAssetId::Concrete(relay_asset()),
+			fun: Fungibility::Fungible(100),
+		});
+
+		let unused = trader.buy_weight(10_000_000, payment).unwrap();
+
+		assert_eq!(balance(&unused, relay_asset()), Some(90));
+	}
+}
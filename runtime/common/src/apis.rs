@@ -97,14 +97,16 @@ macro_rules! impl_runtime_apis_plus_common {
 				> {
 					#[cfg(feature = "evm-tracing")]
 					{
-						use moonbeam_evm_tracer::tracer::EvmTracer;
+						use moonbeam_evm_tracer::tracer::{recover_signer, EvmTracer};
 						// Apply the a subset of extrinsics: all the substrate-specific or ethereum
 						// transactions that preceded the requested transaction.
 						for ext in extrinsics.into_iter() {
 							let _ = match &ext.function {
 								Call::Ethereum(transact(t)) => {
 									if t == transaction {
-										EvmTracer::new().trace(|| Executive::apply_extrinsic(ext));
+										EvmTracer::<Runtime>::new()
+											.with_sender(|| recover_signer(t))
+											.trace(|| Executive::apply_extrinsic(ext));
 										return Ok(());
 									} else {
 										Executive::apply_extrinsic(ext)
@@ -133,7 +135,7 @@ macro_rules! impl_runtime_apis_plus_common {
 				> {
 					#[cfg(feature = "evm-tracing")]
 					{
-						use moonbeam_evm_tracer::tracer::EvmTracer;
+						use moonbeam_evm_tracer::tracer::{recover_signer, EvmTracer};
 						use sha3::{Digest, Keccak256};
 
 						let mut config = <Runtime as pallet_evm::Config>::config().clone();
@@ -147,8 +149,10 @@ macro_rules! impl_runtime_apis_plus_common {
 										H256::from_slice(Keccak256::digest(&rlp::encode(transaction)).as_slice());
 									if known_transactions.contains(&eth_extrinsic_hash) {
 										// Each known extrinsic is a new call stack.
-										EvmTracer::emit_new();
-										EvmTracer::new().trace(|| Executive::apply_extrinsic(ext));
+										EvmTracer::<Runtime>::emit_new();
+										EvmTracer::<Runtime>::new()
+											.with_sender(|| recover_signer(transaction))
+											.trace(|| Executive::apply_extrinsic(ext));
 									} else {
 										let _ = Executive::apply_extrinsic(ext);
 									}
@@ -166,6 +170,56 @@ macro_rules! impl_runtime_apis_plus_common {
 						"Missing `evm-tracing` compile time feature flag.",
 					))
 				}
+
+				fn trace_call(
+					from: H160,
+					to: Option<H160>,
+					data: Vec<u8>,
+					value: U256,
+					gas_limit: U256,
+					gas_price: Option<U256>,
+					nonce: Option<U256>,
+				) -> Result<
+					(),
+					sp_runtime::DispatchError,
+				> {
+					#[cfg(feature = "evm-tracing")]
+					{
+						use moonbeam_evm_tracer::tracer::EvmTracer;
+
+						// The call is executed on top of the state it is called at. Its changes
+						// are never committed, as they only live in the overlay of the runtime api.
+						// The call doesn't pay for its gas, which is thus bounded by the block gas
+						// limit like a transaction would be.
+						if gas_limit > <Runtime as pallet_evm::Config>::BlockGasLimit::get() {
+							return Err(sp_runtime::DispatchError::Other(
+								"Gas limit exceeds the block gas limit",
+							));
+						}
+						let gas_limit = gas_limit.low_u64();
+						let config = <Runtime as pallet_evm::Config>::config();
+						let mut result = Ok(());
+						EvmTracer::<Runtime>::new().with_sender(|| Some(from)).trace(|| {
+							result = match to {
+								Some(to) => <Runtime as pallet_evm::Config>::Runner::call(
+									from, to, data, value, gas_limit, gas_price, nonce, config,
+								)
+								.map(|_| ()),
+								None => <Runtime as pallet_evm::Config>::Runner::create(
+									from, data, value, gas_limit, gas_price, nonce, config,
+								)
+								.map(|_| ()),
+							}
+							.map_err(|err| err.into());
+						});
+
+						result
+					}
+					#[cfg(not(feature = "evm-tracing"))]
+					Err(sp_runtime::DispatchError::Other(
+						"Missing `evm-tracing` compile time feature flag.",
+					))
+				}
 			}
 
 			impl moonbeam_rpc_primitives_txpool::TxPoolRuntimeApi<Block> for Runtime {
@@ -400,6 +454,11 @@ macro_rules! impl_runtime_apis_plus_common {
 					use pallet_crowdloan_rewards::Pallet as PalletCrowdloanRewardsBench;
 					use parachain_staking::Pallet as ParachainStakingBench;
 					use pallet_author_mapping::Pallet as PalletAuthorMappingBench;
+					// Only the runtimes supporting XCM benchmark its instructions and pallets
+					#[cfg(feature = "xcm-benchmarks")]
+					use pallet_xcm_weights::benchmarks::Pallet as XcmWeightsBench;
+					#[cfg(feature = "xcm-benchmarks")]
+					use pallet_xcm_transactor::Pallet as XcmTransactorBench;
 
 					let mut list = Vec::<BenchmarkList>::new();
 
@@ -407,6 +466,10 @@ macro_rules! impl_runtime_apis_plus_common {
 					list_benchmark!(list, extra, parachain_staking, ParachainStakingBench::<Runtime>);
 					list_benchmark!(list, extra, pallet_crowdloan_rewards, PalletCrowdloanRewardsBench::<Runtime>);
 					list_benchmark!(list, extra, pallet_author_mapping, PalletAuthorMappingBench::<Runtime>);
+					#[cfg(feature = "xcm-benchmarks")]
+					list_benchmark!(list, extra, pallet_xcm_weights, XcmWeightsBench::<Runtime>);
+					#[cfg(feature = "xcm-benchmarks")]
+					list_benchmark!(list, extra, pallet_xcm_transactor, XcmTransactorBench::<Runtime>);
 
 					let storage_info = AllPalletsWithSystem::storage_info();
 
@@ -426,6 +489,10 @@ macro_rules! impl_runtime_apis_plus_common {
 					use pallet_crowdloan_rewards::Pallet as PalletCrowdloanRewardsBench;
 					use parachain_staking::Pallet as ParachainStakingBench;
 					use pallet_author_mapping::Pallet as PalletAuthorMappingBench;
+					#[cfg(feature = "xcm-benchmarks")]
+					use pallet_xcm_weights::benchmarks::Pallet as XcmWeightsBench;
+					#[cfg(feature = "xcm-benchmarks")]
+					use pallet_xcm_transactor::Pallet as XcmTransactorBench;
 					let whitelist: Vec<TrackedStorageKey> = vec![];
 
 					let mut batches = Vec::<BenchmarkBatch>::new();
@@ -449,6 +516,15 @@ macro_rules! impl_runtime_apis_plus_common {
 						pallet_author_mapping,
 						PalletAuthorMappingBench::<Runtime>
 					);
+					#[cfg(feature = "xcm-benchmarks")]
+					add_benchmark!(params, batches, pallet_xcm_weights, XcmWeightsBench::<Runtime>);
+					#[cfg(feature = "xcm-benchmarks")]
+					add_benchmark!(
+						params,
+						batches,
+						pallet_xcm_transactor,
+						XcmTransactorBench::<Runtime>
+					);
 					add_benchmark!(params, batches, frame_system, SystemBench::<Runtime>);
 
 					if batches.is_empty() {
@@ -48,6 +48,27 @@ impl<T: AuthorMappingConfig> Migration for AuthorMappingTwoXToBlake<T> {
 	}
 }
 
+/// Sets the XCM version assumed for destinations whose supported version we do not know yet, so
+/// that outbound messages can be wrapped for them
+pub struct PolkadotXcmSafeVersion<T, SafeVersion>(PhantomData<(T, SafeVersion)>);
+impl<T: pallet_xcm::Config, SafeVersion: Get<u32>> Migration
+	for PolkadotXcmSafeVersion<T, SafeVersion>
+{
+	fn friendly_name(&self) -> &str {
+		"MM_PolkadotXcm_SafeVersion"
+	}
+
+	fn migrate(&self, _available_weight: Weight) -> Weight {
+		if let Err(e) = pallet_xcm::Pallet::<T>::force_default_xcm_version(
+			frame_system::RawOrigin::Root.into(),
+			Some(SafeVersion::get()),
+		) {
+			log::error!("Failed to set the safe XCM version: {:?}", e);
+		}
+		T::DbWeight::get().writes(1)
+	}
+}
+
 pub struct CommonMigrations<Runtime>(PhantomData<Runtime>);
 impl<Runtime> Get<Vec<Box<dyn Migration>>> for CommonMigrations<Runtime>
 where
@@ -64,3 +85,21 @@ where
 		vec![Box::new(migration_author_mapping_twox_to_blake)]
 	}
 }
+
+/// The common migrations, plus the ones for runtimes supporting XCM
+pub struct XcmMigrations<Runtime, SafeVersion>(PhantomData<(Runtime, SafeVersion)>);
+impl<Runtime, SafeVersion> Get<Vec<Box<dyn Migration>>> for XcmMigrations<Runtime, SafeVersion>
+where
+	Runtime: pallet_author_mapping::Config + pallet_xcm::Config,
+	SafeVersion: Get<u32> + 'static,
+{
+	fn get() -> Vec<Box<dyn Migration>> {
+		let migration_polkadot_xcm_safe_version = PolkadotXcmSafeVersion::<Runtime, SafeVersion> {
+			0: Default::default(),
+		};
+
+		let mut migrations = CommonMigrations::<Runtime>::get();
+		migrations.push(Box::new(migration_polkadot_xcm_safe_version));
+		migrations
+	}
+}
@@ -26,12 +26,21 @@
 
 pub mod tracer {
 	use codec::Encode;
-	use evm_tracing_events::{EvmEvent, GasometerEvent, RuntimeEvent};
+	use ethereum::TransactionV0 as Transaction;
+	use ethereum_types::{H160, H256};
+	use evm_tracing_events::{runtime::StateStage, EvmEvent, GasometerEvent, RuntimeEvent};
 
 	use evm::tracing::{using as evm_using, EventListener as EvmListener};
+	use evm::Opcode;
 	use evm_gasometer::tracing::{using as gasometer_using, EventListener as GasometerListener};
 	use evm_runtime::tracing::{using as runtime_using, EventListener as RuntimeListener};
-	use sp_std::{cell::RefCell, rc::Rc};
+	use sha3::{Digest, Keccak256};
+	use sp_std::{
+		cell::RefCell,
+		collections::{btree_map::BTreeMap, btree_set::BTreeSet},
+		marker::PhantomData,
+		rc::Rc,
+	};
 
 	struct ListenerProxy<T>(pub Rc<RefCell<T>>);
 	impl<T: GasometerListener> GasometerListener for ListenerProxy<T> {
@@ -52,10 +61,35 @@ pub mod tracer {
 		}
 	}
 
-	pub struct EvmTracer;
-	impl EvmTracer {
+	pub struct EvmTracer<T> {
+		/// Whether the state of the accessed accounts is read and emitted, as requested by the
+		/// client listener.
+		state_tracing: bool,
+		/// Accounts touched by the traced transaction alongside the storage slots accessed in
+		/// each of them.
+		accessed: BTreeMap<H160, BTreeSet<H256>>,
+		_marker: PhantomData<T>,
+	}
+
+	impl<T: pallet_evm::Config> EvmTracer<T> {
 		pub fn new() -> Self {
-			Self
+			Self {
+				state_tracing: moonbeam_primitives_ext::moonbeam_ext::state_tracing(),
+				accessed: BTreeMap::new(),
+				_marker: PhantomData,
+			}
+		}
+
+		/// Emit the state of the sender before the transaction is applied, and in particular
+		/// before the transaction fee is withdrawn from its balance. The sender is only resolved
+		/// when the state is traced.
+		pub fn with_sender<F: FnOnce() -> Option<H160>>(mut self, sender: F) -> Self {
+			if self.state_tracing {
+				if let Some(sender) = sender() {
+					self.access_account(sender);
+				}
+			}
+			self
 		}
 		/// Setup event listeners and execute provided closure.
 		///
@@ -75,23 +109,154 @@ pub mod tracer {
 			let f = || gasometer_using(&mut gasometer, f);
 			let f = || evm_using(&mut evm, f);
 			f();
+
+			// Everything the transaction touched is now in its final state.
+			let tracer = wrapped.borrow();
+			if !tracer.state_tracing {
+				return;
+			}
+			for (address, indexes) in tracer.accessed.iter() {
+				Self::emit_account_state(StateStage::Post, *address);
+				for index in indexes.iter() {
+					Self::emit_storage_state(StateStage::Post, *address, *index);
+				}
+			}
 		}
 
 		pub fn emit_new() {
 			moonbeam_primitives_ext::moonbeam_ext::call_list_new();
 		}
+
+		/// Emit the state of `address` the first time it is accessed.
+		fn access_account(&mut self, address: H160) {
+			if self.state_tracing && !self.accessed.contains_key(&address) {
+				self.accessed.insert(address, BTreeSet::new());
+				Self::emit_account_state(StateStage::Pre, address);
+			}
+		}
+
+		/// Emit the value of a storage slot the first time it is accessed.
+		fn access_storage(&mut self, address: H160, index: H256) {
+			self.access_account(address);
+			if let Some(indexes) = self.accessed.get_mut(&address) {
+				if indexes.insert(index) {
+					Self::emit_storage_state(StateStage::Pre, address, index);
+				}
+			}
+		}
+
+		/// Record the state accessed by an opcode before it is executed.
+		fn access_opcode(&mut self, address: H160, opcode: Opcode, stack: &evm::Stack) {
+			if !self.state_tracing {
+				return;
+			}
+			let peek_address = |n: usize| stack.peek(n).ok().map(H160::from);
+			match opcode {
+				Opcode::SLOAD | Opcode::SSTORE => {
+					if let Ok(index) = stack.peek(0) {
+						self.access_storage(address, index);
+					}
+				}
+				Opcode::BALANCE
+				| Opcode::EXTCODESIZE
+				| Opcode::EXTCODECOPY
+				| Opcode::EXTCODEHASH
+				| Opcode::SUICIDE => {
+					if let Some(target) = peek_address(0) {
+						self.access_account(target);
+					}
+				}
+				Opcode::CALL | Opcode::CALLCODE | Opcode::DELEGATECALL | Opcode::STATICCALL => {
+					if let Some(target) = peek_address(1) {
+						self.access_account(target);
+					}
+				}
+				_ => (),
+			}
+		}
+
+		fn emit_account_state(stage: StateStage, address: H160) {
+			let account = pallet_evm::Pallet::<T>::account_basic(&address);
+			let event = RuntimeEvent::AccountState {
+				stage,
+				address,
+				balance: account.balance,
+				nonce: account.nonce,
+				code: pallet_evm::Pallet::<T>::account_codes(address),
+			};
+			moonbeam_primitives_ext::moonbeam_ext::runtime_event(event.encode());
+		}
+
+		fn emit_storage_state(stage: StateStage, address: H160, index: H256) {
+			let event = RuntimeEvent::StorageState {
+				stage,
+				address,
+				index,
+				value: pallet_evm::Pallet::<T>::account_storages(address, index),
+			};
+			moonbeam_primitives_ext::moonbeam_ext::runtime_event(event.encode());
+		}
+	}
+
+	/// Sender of a signed Ethereum transaction.
+	pub fn recover_signer(transaction: &Transaction) -> Option<H160> {
+		let mut sig = [0u8; 65];
+		let mut msg = [0u8; 32];
+		sig[0..32].copy_from_slice(&transaction.signature.r()[..]);
+		sig[32..64].copy_from_slice(&transaction.signature.s()[..]);
+		sig[64] = transaction.signature.standard_v();
+		msg.copy_from_slice(
+			&ethereum::LegacyTransactionMessage::from(transaction.clone()).hash()[..],
+		);
+		let pubkey = sp_io::crypto::secp256k1_ecdsa_recover(&sig, &msg).ok()?;
+		Some(H160::from(H256::from_slice(
+			Keccak256::digest(&pubkey).as_slice(),
+		)))
 	}
 
-	impl EvmListener for EvmTracer {
+	impl<T: pallet_evm::Config> EvmListener for EvmTracer<T> {
 		/// Proxies `evm::tracing::Event` to the host.
 		fn event(&mut self, event: evm::tracing::Event) {
+			// Those events are emitted before any balance or nonce is modified.
+			match &event {
+				evm::tracing::Event::TransactCall {
+					caller, address, ..
+				}
+				| evm::tracing::Event::TransactCreate {
+					caller, address, ..
+				}
+				| evm::tracing::Event::TransactCreate2 {
+					caller, address, ..
+				}
+				| evm::tracing::Event::Create {
+					caller, address, ..
+				} => {
+					self.access_account(*caller);
+					self.access_account(*address);
+				}
+				evm::tracing::Event::Call {
+					code_address,
+					context,
+					..
+				} => {
+					self.access_account(context.address);
+					self.access_account(*code_address);
+				}
+				evm::tracing::Event::Suicide {
+					address, target, ..
+				} => {
+					self.access_account(*address);
+					self.access_account(*target);
+				}
+				_ => (),
+			}
 			let event: EvmEvent = event.into();
 			let message = event.encode();
 			moonbeam_primitives_ext::moonbeam_ext::evm_event(message);
 		}
 	}
 
-	impl GasometerListener for EvmTracer {
+	impl<T: pallet_evm::Config> GasometerListener for EvmTracer<T> {
 		/// Proxies `evm_gasometer::tracing::Event` to the host.
 		fn event(&mut self, event: evm_gasometer::tracing::Event) {
 			let event: GasometerEvent = event.into();
@@ -100,9 +265,18 @@ pub mod tracer {
 		}
 	}
 
-	impl RuntimeListener for EvmTracer {
+	impl<T: pallet_evm::Config> RuntimeListener for EvmTracer<T> {
 		/// Proxies `evm_runtime::tracing::Event` to the host.
 		fn event(&mut self, event: evm_runtime::tracing::Event) {
+			if let evm_runtime::tracing::Event::Step {
+				context,
+				opcode,
+				stack,
+				..
+			} = &event
+			{
+				self.access_opcode(context.address, *opcode, stack);
+			}
 			let event: RuntimeEvent = event.into();
 			let message = event.encode();
 			moonbeam_primitives_ext::moonbeam_ext::runtime_event(message);
@@ -31,6 +31,7 @@ include!(concat!(env!("OUT_DIR"), "/wasm_binary.rs"));
 use cumulus_pallet_parachain_system::RelaychainBlockNumberProvider;
 use fp_rpc::TransactionStatus;
 use pallet_evm_precompile_assets_erc20::AccountIdAssetIdConversion;
+use pallet_evm_precompile_proxy::{EvmProxyCallFilter, EvmSubCall};
 use xtokens_precompiles::AccountIdToCurrencyId;
 
 use sp_runtime::traits::Hash as THash;
@@ -44,20 +45,20 @@ use frame_support::{
 	},
 	weights::{
 		constants::{RocksDbWeight, WEIGHT_PER_SECOND},
-		DispatchClass, GetDispatchInfo, IdentityFee, Weight,
+		DispatchClass, GetDispatchInfo, IdentityFee, Weight, WeightToFeePolynomial,
 	},
 	PalletId,
 };
 
 use xcm_builder::{
-	AccountKey20Aliases, AllowTopLevelPaidExecutionFrom, ConvertedConcreteAssetId,
-	CurrencyAdapter as XcmCurrencyAdapter, EnsureXcmOrigin, FixedWeightBounds, FungiblesAdapter,
-	IsConcrete, LocationInverter, ParentAsSuperuser, ParentIsDefault, RelayChainAsNative,
-	SiblingParachainAsNative, SiblingParachainConvertsVia, SignedAccountKey20AsNative,
-	SovereignSignedViaLocation, TakeWeightCredit, UsingComponents,
+	AccountKey20Aliases, AllowKnownQueryResponses, AsPrefixedGeneralIndex,
+	ConvertedConcreteAssetId, CurrencyAdapter as XcmCurrencyAdapter, EnsureXcmOrigin,
+	FixedWeightBounds, FungiblesAdapter, IsConcrete, LocationInverter, ParentAsSuperuser,
+	ParentIsDefault, RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
+	SignedAccountKey20AsNative, SovereignSignedViaLocation, TakeWeightCredit, UsingComponents,
 };
 
-use xcm_executor::traits::JustTry;
+use xcm_executor::traits::{Convert as XcmConvert, JustTry};
 
 use frame_system::{EnsureOneOf, EnsureRoot, EnsureSigned};
 pub use moonbeam_core_primitives::{
@@ -102,7 +103,10 @@ use xcm::v1::{
 use nimbus_primitives::{CanAuthor, NimbusId};
 
 mod precompiles;
-use precompiles::{MoonbasePrecompiles, ASSET_PRECOMPILE_ADDRESS_PREFIX};
+pub use precompiles::{
+	MoonbasePrecompiles, FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX,
+	LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX,
+};
 
 #[cfg(any(feature = "std", test))]
 pub use sp_runtime::BuildStorage;
@@ -383,24 +387,28 @@ pub type SlowAdjustingFeeUpdate<R> =
 // We just take the lowest 128 bits
 impl AccountIdAssetIdConversion<AccountId, AssetId> for Runtime {
 	/// The way to convert an account to assetId is by ensuring that the prefix is 0XFFFFFFFF
-	/// and by taking the lowest 128 bits as the assetId
-	fn account_to_asset_id(account: AccountId) -> Option<AssetId> {
+	/// (xcm assets) or 0XFFFFFFFE (local assets) and by taking the lowest 128 bits as the assetId
+	fn account_to_asset_id(account: AccountId) -> Option<([u8; 4], AssetId)> {
 		let h160_account: H160 = account.into();
+		let mut prefix = [0u8; 4];
 		let mut data = [0u8; 16];
 		let (prefix_part, id_part) = h160_account.as_fixed_bytes().split_at(4);
-		if prefix_part == ASSET_PRECOMPILE_ADDRESS_PREFIX {
+		prefix.copy_from_slice(prefix_part);
+		if prefix == FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX
+			|| prefix == LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX
+		{
 			data.copy_from_slice(id_part);
 			let asset_id: AssetId = u128::from_be_bytes(data).into();
-			Some(asset_id)
+			Some((prefix, asset_id))
 		} else {
 			None
 		}
 	}
 
 	// The opposite conversion
-	fn asset_id_to_account(asset_id: AssetId) -> AccountId {
+	fn asset_id_to_account(prefix: [u8; 4], asset_id: AssetId) -> AccountId {
 		let mut data = [0u8; 20];
-		data[0..4].copy_from_slice(ASSET_PRECOMPILE_ADDRESS_PREFIX);
+		data[0..4].copy_from_slice(&prefix);
 		data[4..20].copy_from_slice(&asset_id.to_be_bytes());
 		H160::from_slice(&data)
 	}
@@ -858,6 +866,32 @@ impl InstanceFilter<Call> for ProxyType {
 	}
 }
 
+impl EvmProxyCallFilter for ProxyType {
+	fn is_evm_proxy_call_allowed(&self, call: &EvmSubCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// Parachain staking, democracy and collectives precompiles, without transfer.
+			ProxyType::NonTransfer => {
+				call.value.is_zero()
+					&& (call.to == H160::from_low_u64_be(2048)
+						|| call.to == H160::from_low_u64_be(2051)
+						|| call.to == H160::from_low_u64_be(2056)
+						|| call.to == H160::from_low_u64_be(2057))
+			}
+			// Democracy and collectives (council and technical committee) precompiles.
+			ProxyType::Governance => {
+				call.value.is_zero()
+					&& (call.to == H160::from_low_u64_be(2051)
+						|| call.to == H160::from_low_u64_be(2056)
+						|| call.to == H160::from_low_u64_be(2057))
+			}
+			// Parachain staking precompile.
+			ProxyType::Staking => call.value.is_zero() && call.to == H160::from_low_u64_be(2048),
+			ProxyType::CancelProxy => false,
+		}
+	}
+}
+
 impl pallet_proxy::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -876,7 +910,7 @@ impl pallet_proxy::Config for Runtime {
 impl pallet_migrations::Config for Runtime {
 	type Event = Event;
 	//TODO wire up our correct list of migrations here. Maybe this shouldn't be in `runtime_common`.
-	type MigrationsList = runtime_common::migrations::CommonMigrations<Runtime>;
+	type MigrationsList = runtime_common::migrations::XcmMigrations<Runtime, SafeXcmVersion>;
 }
 
 parameter_types! {
@@ -896,6 +930,16 @@ parameter_types! {
 			PalletInstance(<Runtime as frame_system::Config>::PalletInfo::index::<Balances>().unwrap() as u8)
 		)
 	};
+	// Local assets location, prefix of the multilocations identifying the local assets
+	// (Parent, Self Para Id, Self LocalAssets pallet index), followed by the asset id as
+	// GeneralIndex
+	pub LocalAssetsPalletLocation: MultiLocation = MultiLocation {
+		parents:1,
+		interior: Junctions::X2(
+			Parachain(ParachainInfo::parachain_id().into()),
+			PalletInstance(<Runtime as frame_system::Config>::PalletInfo::index::<LocalAssets>().unwrap() as u8)
+		)
+	};
 }
 
 /// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
@@ -910,20 +954,31 @@ pub type LocationToAccountId = (
 	AccountKey20Aliases<RelayNetwork, AccountId>,
 );
 
+/// Matches the non-reserve fungible assets against AsAssetType
+pub type AssetsMatcher = (
+	ConvertedConcreteAssetId<
+		AssetId,
+		Balance,
+		xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>,
+		JustTry,
+	>,
+);
+
+/// Converts the multilocations of the local assets into their AssetId
+pub type LocalAssetsLocationConverter =
+	AsPrefixedGeneralIndex<LocalAssetsPalletLocation, AssetId, JustTry>;
+
+/// Matches the local fungible assets, of which we are the reserve
+pub type LocalAssetsMatcher =
+	ConvertedConcreteAssetId<AssetId, Balance, LocalAssetsLocationConverter, JustTry>;
+
 // The non-reserve fungible transactor type
 // It will use pallet-assets, and the Id will be matched against AsAssetType
 pub type FungiblesTransactor = FungiblesAdapter<
 	// Use this fungibles implementation:
 	Assets,
 	// Use this currency when it is a fungible asset matching the given location or name:
-	(
-		ConvertedConcreteAssetId<
-			AssetId,
-			Balance,
-			xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>,
-			JustTry,
-		>,
-	),
+	AssetsMatcher,
 	// Do a simple punn to convert an AccountId20 MultiLocation into a native chain account ID:
 	LocationToAccountId,
 	// Our chain's account ID type (we can't get away without mentioning it explicitly):
@@ -948,8 +1003,28 @@ pub type LocalAssetTransactor = XcmCurrencyAdapter<
 	(),
 >;
 
-// We use both transactors
-pub type AssetTransactors = (LocalAssetTransactor, FungiblesTransactor);
+/// The transactor for the assets created locally, of which we are the reserve
+pub type LocalFungiblesTransactor = FungiblesAdapter<
+	// Use this fungibles implementation:
+	LocalAssets,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	LocalAssetsMatcher,
+	// Do a simple punn to convert an AccountId20 MultiLocation into a native chain account ID:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// We dont allow teleports.
+	(),
+	// We dont track any teleports
+	(),
+>;
+
+// We use the three transactors
+pub type AssetTransactors = (
+	LocalAssetTransactor,
+	FungiblesTransactor,
+	LocalFungiblesTransactor,
+);
 
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
 /// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
@@ -975,46 +1050,78 @@ pub type XcmOriginToTransactDispatchOrigin = (
 	SignedAccountKey20AsNative<RelayNetwork, Origin>,
 );
 
+/// The XCM version assumed for destinations that did not tell us the version they support
+pub const SAFE_XCM_VERSION: u32 = 1;
+
+parameter_types! {
+	pub const SafeXcmVersion: u32 = SAFE_XCM_VERSION;
+	// The fees charged by the asset trader are deposited in the treasury
+	pub XcmFeesAccount: AccountId = Treasury::account_id();
+	// When paid with several assets, the asset trader charges the relay asset first
+	pub XcmPreferredFeeAssets: Vec<MultiLocation> = vec![MultiLocation::parent()];
+}
+
+// The origins allowed to execute are maintained on-chain by the xcm-barrier pallet
+// Responses to our own queries (e.g. version subscriptions) are always allowed
+pub type Barrier = (
+	TakeWeightCredit,
+	AllowKnownQueryResponses<PolkadotXcm>,
+	XcmBarrier,
+);
+
 parameter_types! {
 	// To be changed probably with a value we feel comfortable
 	pub UnitWeightCost: Weight = 200_000_000;
 }
 
-// Allow paid executions
-pub type XcmBarrier = (TakeWeightCredit, AllowTopLevelPaidExecutionFrom<Everything>);
+// Each instruction is weighed with UnitWeightCost. pallet_xcm_weights::WeightInfoBounds is to
+// replace it once the output of the pallet_xcm_weights benchmarks is committed
+pub type XcmWeigher = FixedWeightBounds<UnitWeightCost, Call>;
 
 pub struct XcmExecutorConfig;
 impl xcm_executor::Config for XcmExecutorConfig {
 	type Call = Call;
 	type XcmSender = XcmRouter;
 	// How to withdraw and deposit an asset.
-	type AssetTransactor = AssetTransactors;
+	// The deposits are tracked by AssetTrap, to trap what is left in holding
+	type AssetTransactor = pallet_asset_trap::TrackDeposits<AssetTransactors>;
 	type OriginConverter = XcmOriginToTransactDispatchOrigin;
 	// Filter to the reserve withdraw operations
 	type IsReserve = xcm_primitives::MultiNativeAsset;
 	type IsTeleporter = (); // No teleport
 	type LocationInverter = LocationInverter<Ancestry>;
-	type Barrier = XcmBarrier;
-	type Weigher = FixedWeightBounds<UnitWeightCost, Call>;
+	type Barrier = Barrier;
+	type Weigher = XcmWeigher;
 	// We use two traders
 	// When we receive the self-reserve asset, we use pallet-transaction-payment
 	// When we receive a non-reserve asset, we use AssetManager to fetch how many
 	// units per second we should charge
-	type Trader = xcm_primitives::MultiWeightTraders<
-		UsingComponents<
-			IdentityFee<Balance>,
-			SelfReserve,
-			AccountId,
-			Balances,
-			DealWithFees<Runtime>,
+	// The fees are tracked by AssetTrap too
+	type Trader = pallet_asset_trap::TrackFees<
+		xcm_primitives::MultiWeightTraders<
+			UsingComponents<
+				IdentityFee<Balance>,
+				SelfReserve,
+				AccountId,
+				Balances,
+				DealWithFees<Runtime>,
+			>,
+			xcm_primitives::FirstAssetTrader<
+				AssetId,
+				AssetType,
+				AssetManager,
+				xcm_primitives::XcmFeesToAccount<Assets, AssetsMatcher, AccountId, XcmFeesAccount>,
+				xcm_primitives::PreferredFeeAssets<XcmPreferredFeeAssets>,
+			>,
 		>,
-		xcm_primitives::FirstAssetTrader<AssetId, AssetType, AssetManager, ()>,
 	>;
-	type ResponseHandler = (); // Don't handle responses for now.
+	type ResponseHandler = PolkadotXcm;
 	type SubscriptionService = PolkadotXcm;
 }
 
-type XcmExecutor = xcm_executor::XcmExecutor<XcmExecutorConfig>;
+// The assets left in holding by the executions are trapped, to be claimed back from AssetTrap
+type XcmExecutor =
+	pallet_asset_trap::TrapUnspentAssets<Runtime, xcm_executor::XcmExecutor<XcmExecutorConfig>>;
 
 parameter_types! {
 	pub const MaxDownwardMessageWeight: Weight = MAXIMUM_BLOCK_WEIGHT / 10;
@@ -1024,11 +1131,23 @@ parameter_types! {
 pub type LocalOriginToLocation =
 	xcm_primitives::SignedToAccountId20<Origin, AccountId, RelayNetwork>;
 
+/// Converts the Xcm origin of pallet-xcm (e.g. of a `Transact` from the relay) into its location
+pub struct XcmPassthroughToLocation;
+impl xcm_executor::traits::Convert<Origin, MultiLocation> for XcmPassthroughToLocation {
+	fn convert(o: Origin) -> Result<MultiLocation, Origin> {
+		match Into::<Result<pallet_xcm::Origin, Origin>>::into(o.clone()) {
+			Ok(pallet_xcm::Origin::Xcm(location)) => Ok(location),
+			_ => Err(o),
+		}
+	}
+}
+
 /// The means for routing XCM messages which are not for local execution into the right message
 /// queues.
 pub type XcmRouter = (
 	// Two routers - use UMP to communicate with the relay chain:
-	cumulus_primitives_utility::ParentAsUmp<ParachainSystem, ()>,
+	// Messages are wrapped to the version the destination supports, as known by PolkadotXcm
+	cumulus_primitives_utility::ParentAsUmp<ParachainSystem, PolkadotXcm>,
 	// ..and XCMP to communicate with the sibling chains.
 	XcmpQueue,
 );
@@ -1042,7 +1161,7 @@ impl pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor;
 	type XcmTeleportFilter = ();
 	type XcmReserveTransferFilter = Everything;
-	type Weigher = FixedWeightBounds<UnitWeightCost, Call>;
+	type Weigher = XcmWeigher;
 	type LocationInverter = LocationInverter<Ancestry>;
 }
 
@@ -1055,7 +1174,7 @@ impl cumulus_pallet_xcmp_queue::Config for Runtime {
 	type Event = Event;
 	type XcmExecutor = XcmExecutor;
 	type ChannelInfo = ParachainSystem;
-	type VersionWrapper = ();
+	type VersionWrapper = PolkadotXcm;
 }
 
 impl cumulus_pallet_dmp_queue::Config for Runtime {
@@ -1098,6 +1217,32 @@ impl pallet_assets::Config for Runtime {
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
 }
 
+// Local assets are created by users, who pay a deposit for the asset and its metadata
+parameter_types! {
+	pub const LocalAssetDeposit: Balance = 100 * currency::UNIT;
+	pub const LocalApprovalDeposit: Balance = 0;
+	pub const LocalMetadataDepositBase: Balance = currency::deposit(1, 68);
+	pub const LocalMetadataDepositPerByte: Balance = currency::deposit(0, 1);
+}
+
+pub type LocalAssetInstance = pallet_assets::Instance1;
+
+impl pallet_assets::Config<LocalAssetInstance> for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = AssetsForceOrigin;
+	type AssetDeposit = LocalAssetDeposit;
+	type MetadataDepositBase = LocalMetadataDepositBase;
+	type MetadataDepositPerByte = LocalMetadataDepositPerByte;
+	type ApprovalDeposit = LocalApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
 // Our AssetType. For now we only handle Xcm Assets
 #[derive(Clone, Eq, Debug, PartialEq, Ord, PartialOrd, Encode, Decode)]
 pub enum AssetType {
@@ -1161,7 +1306,8 @@ impl pallet_asset_manager::AssetRegistrar<Runtime> for AssetRegistrar {
 		// TODO uncomment when we feel comfortable
 		/*
 		// The asset has been created. Let's put the revert code in the precompile address
-		let precompile_address = Runtime::asset_id_to_account(asset);
+		let precompile_address =
+			Runtime::asset_id_to_account(FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX, asset);
 		pallet_evm::AccountCodes::<Runtime>::insert(
 			precompile_address,
 			vec![0x60, 0x00, 0x60, 0x00, 0xfd],
@@ -1177,6 +1323,23 @@ impl pallet_asset_manager::AssetRegistrar<Runtime> for AssetRegistrar {
 			metadata.is_frozen,
 		)
 	}
+
+	fn create_local_asset(
+		asset: AssetId,
+		creator: AccountId,
+		min_balance: Balance,
+	) -> DispatchResult {
+		// The creator becomes the owner of the asset, and pays its deposit
+		LocalAssets::create(Origin::signed(creator), asset, creator, min_balance)
+	}
+}
+
+// Local asset ids are taken from the counter as they are
+pub struct LocalAssetIdCreator;
+impl pallet_asset_manager::LocalAssetIdCreator<Runtime> for LocalAssetIdCreator {
+	fn create_asset_id_from_counter(counter: u128) -> AssetId {
+		counter
+	}
 }
 
 #[derive(Clone, Eq, Debug, PartialEq, Ord, PartialOrd, Encode, Decode)]
@@ -1187,6 +1350,16 @@ pub struct AssetRegistrarMetadata {
 	pub is_frozen: bool,
 }
 
+// What a second of weight costs in native tokens, following the fee multiplier
+pub struct NativeFeePerSecond;
+impl Get<u128> for NativeFeePerSecond {
+	fn get() -> u128 {
+		TransactionPayment::next_fee_multiplier().saturating_mul_int(
+			<Runtime as pallet_transaction_payment::Config>::WeightToFee::calc(&WEIGHT_PER_SECOND),
+		)
+	}
+}
+
 impl pallet_asset_manager::Config for Runtime {
 	type Event = Event;
 	type Balance = Balance;
@@ -1195,13 +1368,91 @@ impl pallet_asset_manager::Config for Runtime {
 	type AssetType = AssetType;
 	type AssetRegistrar = AssetRegistrar;
 	type AssetModifierOrigin = EnsureRoot<AccountId>;
+	type NativeFeePerSecond = NativeFeePerSecond;
+	type LocalAssetIdCreator = LocalAssetIdCreator;
 }
 
-// Our currencyId. We distinguish for now between SelfReserve, and Others, defined by their Id.
+#[cfg(feature = "xcm-benchmarks")]
+impl pallet_xcm_weights::benchmarks::Config for Runtime {
+	type XcmConfig = XcmExecutorConfig;
+
+	fn account(index: u32) -> MultiLocation {
+		let mut key = [0u8; 20];
+		key[..4].copy_from_slice(&(index + 1).to_le_bytes());
+		xcm::v1::Junction::AccountKey20 {
+			network: NetworkId::Any,
+			key,
+		}
+		.into()
+	}
+
+	fn destination() -> MultiLocation {
+		MultiLocation::parent()
+	}
+
+	fn reserve_location() -> MultiLocation {
+		MultiLocation::new(1, Junctions::X1(Parachain(1000)))
+	}
+
+	fn reserve_asset(index: u32, amount: u128) -> xcm::v1::MultiAsset {
+		let location = MultiLocation::new(
+			1,
+			Junctions::X2(
+				Parachain(1000),
+				xcm::v1::Junction::GeneralIndex(index.into()),
+			),
+		);
+		let asset_type = AssetType::Xcm(location.clone());
+		let asset_id: AssetId = asset_type.clone().into();
+
+		// Registered and accepted as fees, like any asset coming from another chain
+		if AssetManager::asset_id_type(asset_id).is_none() {
+			let metadata = AssetRegistrarMetadata {
+				name: b"Benchmark".to_vec(),
+				symbol: b"BENCH".to_vec(),
+				decimals: 12,
+				is_frozen: false,
+			};
+			assert!(AssetManager::register_asset(Origin::root(), asset_type, metadata, 1).is_ok());
+			assert!(AssetManager::set_asset_units_per_second(Origin::root(), asset_id, 1).is_ok());
+		}
+
+		(location, amount).into()
+	}
+}
+
+#[cfg(feature = "xcm-benchmarks")]
+impl pallet_xcm_transactor::benchmarks::Config for Runtime {
+	fn transactor() -> Transactors {
+		Transactors::Relay
+	}
+
+	fn currency_id() -> CurrencyId {
+		let asset_type = AssetType::Xcm(MultiLocation::parent());
+		let asset_id: AssetId = asset_type.clone().into();
+
+		// The relay asset, registered as it would be to transact in the relay
+		if AssetManager::asset_id_type(asset_id).is_none() {
+			let metadata = AssetRegistrarMetadata {
+				name: b"Relay".to_vec(),
+				symbol: b"RELAY".to_vec(),
+				decimals: 12,
+				is_frozen: false,
+			};
+			assert!(AssetManager::register_asset(Origin::root(), asset_type, metadata, 1).is_ok());
+		}
+
+		CurrencyId::OtherReserve(asset_id)
+	}
+}
+
+// Our currencyId. We distinguish between SelfReserve, Others, defined by their Id, and the local
+// assets, also defined by their Id, of which we are the reserve.
 #[derive(Clone, Eq, Debug, PartialEq, Ord, PartialOrd, Encode, Decode)]
 pub enum CurrencyId {
 	SelfReserve,
 	OtherReserve(AssetId),
+	LocalAssetReserve(AssetId),
 }
 
 impl AccountIdToCurrencyId<AccountId, CurrencyId> for Runtime {
@@ -1210,8 +1461,14 @@ impl AccountIdToCurrencyId<AccountId, CurrencyId> for Runtime {
 			// the self-reserve currency is identified by the pallet-balances address
 			a if a == H160::from_low_u64_be(2050) => Some(CurrencyId::SelfReserve),
 			// the rest of the currencies, by their corresponding erc20 address
-			_ => Runtime::account_to_asset_id(account)
-				.map(|asset_id| CurrencyId::OtherReserve(asset_id)),
+			// local and xcm assets are told apart by the prefix of their address
+			_ => Runtime::account_to_asset_id(account).map(|(prefix, asset_id)| {
+				if prefix == LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX {
+					CurrencyId::LocalAssetReserve(asset_id)
+				} else {
+					CurrencyId::OtherReserve(asset_id)
+				}
+			}),
 		}
 	}
 }
@@ -1230,6 +1487,9 @@ where
 				Some(multi)
 			}
 			CurrencyId::OtherReserve(asset) => AssetXConverter::reverse_ref(asset).ok(),
+			CurrencyId::LocalAssetReserve(asset) => {
+				LocalAssetsLocationConverter::reverse_ref(asset).ok()
+			}
 		}
 	}
 }
@@ -1255,11 +1515,62 @@ impl orml_xtokens::Config for Runtime {
 		CurrencyIdtoMultiLocation<xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>>;
 	type XcmExecutor = XcmExecutor;
 	type SelfLocation = SelfLocation;
-	type Weigher = FixedWeightBounds<UnitWeightCost, Call>;
+	type Weigher = XcmWeigher;
 	type BaseXcmWeight = BaseXcmWeight;
 	type LocationInverter = LocationInverter<Ancestry>;
 }
 
+// The destinations we can transact into through xcm-transactor
+#[derive(Clone, Eq, Debug, PartialEq, Ord, PartialOrd, Encode, Decode)]
+pub enum Transactors {
+	Relay,
+}
+
+impl xcm_primitives::XcmTransact for Transactors {
+	fn destination(self) -> MultiLocation {
+		match self {
+			Transactors::Relay => MultiLocation::parent(),
+		}
+	}
+}
+
+impl xcm_primitives::UtilityEncodeCall for Transactors {
+	fn encode_call(self, call: xcm_primitives::UtilityAvailableCalls) -> Vec<u8> {
+		match self {
+			// The utility pallet index in the (westend based) relay runtime, and the index of
+			// as_derivative in it
+			Transactors::Relay => match call {
+				xcm_primitives::UtilityAvailableCalls::AsDerivative(index, inner_call) => {
+					let mut call = RelayUtilityAsDerivativeIndices::get().encode();
+					call.append(&mut index.encode());
+					call.extend(inner_call);
+					call
+				}
+			},
+		}
+	}
+}
+
+parameter_types! {
+	// (utility pallet index, as_derivative call index) in the relay runtime
+	pub const RelayUtilityAsDerivativeIndices: (u8, u8) = (16, 1);
+}
+
+impl pallet_xcm_transactor::Config for Runtime {
+	type Event = Event;
+	type Transactor = Transactors;
+	type DerivativeAddressRegistrationOrigin = EnsureRoot<AccountId>;
+	type SovereignAccountDispatcherOrigin = EnsureRoot<AccountId>;
+	type CurrencyId = CurrencyId;
+	type CurrencyIdToMultiLocation =
+		CurrencyIdtoMultiLocation<xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>>;
+	type AccountIdToMultiLocation = xcm_primitives::AccountIdToMultiLocation<AccountId>;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type XcmSender = XcmRouter;
+	type AssetTransactor = AssetTransactors;
+	type WeightInfo = pallet_xcm_transactor::weights::SubstrateWeight<Runtime>;
+}
+
 /// Call filter used during Phase 3 of the Moonriver rollout
 pub struct MaintenanceFilter;
 impl Contains<Call> for MaintenanceFilter {
@@ -1270,13 +1581,15 @@ impl Contains<Call> for MaintenanceFilter {
 			Call::Ethereum(_) => false,
 			Call::EVM(_) => false,
 			Call::XTokens(_) => false,
+			Call::XcmTransactor(_) => false,
+			Call::AssetTrap(_) => false,
 			_ => true,
 		}
 	}
 }
 
 /// Normal Call Filter
-/// We dont allow to create nor mint assets, this for now is disabled
+/// We dont allow to create nor mint xcm assets, this for now is disabled
 /// We only allow transfers. For now creation of assets will go through
 /// asset-manager, while minting/burning only happens through xcm messages
 /// This can change in the future
@@ -1291,11 +1604,72 @@ impl Contains<Call> for NormalFilter {
 				pallet_assets::Call::transfer_approved(..) => true,
 				_ => false,
 			},
+			// Local assets are created through AssetManager, which assigns their ids. Their
+			// owners manage them with the rest of the calls
+			Call::LocalAssets(method) => match method {
+				pallet_assets::Call::create(..) => false,
+				_ => true,
+			},
+			// Only the version management calls, which require Root, are allowed. Transfers
+			// go through XTokens
+			Call::PolkadotXcm(method) => match method {
+				pallet_xcm::Call::force_xcm_version(..) => true,
+				pallet_xcm::Call::force_default_xcm_version(..) => true,
+				pallet_xcm::Call::force_subscribe_version_notify(..) => true,
+				pallet_xcm::Call::force_unsubscribe_version_notify(..) => true,
+				_ => false,
+			},
+			// The transactor weights are estimates until they are benchmarked on the reference
+			// hardware, so the calls signed accounts can make are disabled for now
+			Call::XcmTransactor(method) => match method {
+				pallet_xcm_transactor::Call::transact_through_derivative(..) => false,
+				pallet_xcm_transactor::Call::transact_through_derivative_multilocation(..) => false,
+				_ => true,
+			},
 			_ => true,
 		}
 	}
 }
 
+parameter_types! {
+	// Paid execution is allowed from local origins, the relay and siblings (and their accounts),
+	// unpaid execution only from the relay, and version subscriptions from the relay and siblings
+	pub XcmBarrierInitialRules: Vec<(pallet_xcm_barrier::BarrierRule, MultiLocation, bool)> = vec![
+		(pallet_xcm_barrier::BarrierRule::PaidExecution, MultiLocation::here(), true),
+		(pallet_xcm_barrier::BarrierRule::PaidExecution, MultiLocation::parent(), true),
+		(pallet_xcm_barrier::BarrierRule::UnpaidExecution, MultiLocation::parent(), false),
+		(pallet_xcm_barrier::BarrierRule::VersionSubscription, MultiLocation::parent(), true),
+	];
+	// Rejected messages are reported by an event for at most this many origins per block
+	pub const XcmBarrierMaxRejectionEvents: u32 = 10;
+}
+
+impl pallet_xcm_barrier::Config for Runtime {
+	type Event = Event;
+	type BarrierOrigin = EnsureRoot<AccountId>;
+	type InitialRules = XcmBarrierInitialRules;
+	type MaxRejectionEvents = XcmBarrierMaxRejectionEvents;
+}
+
+parameter_types! {
+	// XTokens executes the transfers from the location of the sender under any network
+	pub const AnyNetwork: NetworkId = NetworkId::Any;
+}
+
+impl pallet_asset_trap::Config for Runtime {
+	type Event = Event;
+	// Local accounts claim what their transfers left, other chains through a `Transact` with
+	// the Xcm origin kind
+	type ClaimOrigin = EnsureXcmOrigin<
+		Origin,
+		(
+			xcm_primitives::SignedToAccountId20<Origin, AccountId, AnyNetwork>,
+			XcmPassthroughToLocation,
+		),
+	>;
+	type AssetTransactor = AssetTransactors;
+}
+
 impl pallet_maintenance_mode::Config for Runtime {
 	type Event = Event;
 	type NormalCallFilter = NormalFilter;
@@ -1341,11 +1715,15 @@ construct_runtime! {
 		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin} = 26,
 		DmpQueue: cumulus_pallet_dmp_queue::{Pallet, Call, Storage, Event<T>} = 27,
 		// PolkadotXcm and Assets are filtered by AssetManager and XTokens for now
-		PolkadotXcm: pallet_xcm::{Pallet, Event<T>, Origin} = 28,
+		PolkadotXcm: pallet_xcm::{Pallet, Call, Storage, Event<T>, Origin, Config} = 28,
 		Assets: pallet_assets::{Pallet, Call, Storage, Event<T>} = 29,
 		XTokens: orml_xtokens::{Pallet, Call, Storage, Event<T>} = 30,
 		AssetManager: pallet_asset_manager::{Pallet, Call, Storage, Event<T>} = 31,
 		Migrations: pallet_migrations::{Pallet, Storage, Config, Event<T>} = 32,
+		XcmTransactor: pallet_xcm_transactor::{Pallet, Call, Storage, Event<T>} = 33,
+		XcmBarrier: pallet_xcm_barrier::{Pallet, Call, Storage, Event, Config} = 34,
+		LocalAssets: pallet_assets::<Instance1>::{Pallet, Call, Storage, Event<T>} = 35,
+		AssetTrap: pallet_asset_trap::{Pallet, Call, Storage, Event} = 36,
 	}
 }
 
@@ -1406,6 +1784,16 @@ pub type Executive = frame_executive::Executive<
 // }
 // ```
 runtime_common::impl_runtime_apis_plus_common! {
+	impl pallet_xcm_barrier_runtime_api::XcmBarrierApi<Block> for Runtime {
+		fn allowed_origins(rule: pallet_xcm_barrier::BarrierRule) -> Vec<(MultiLocation, bool)> {
+			XcmBarrier::allowed_origins(rule)
+		}
+
+		fn is_allowed(rule: pallet_xcm_barrier::BarrierRule, origin: MultiLocation) -> bool {
+			XcmBarrier::is_allowed(rule, &origin)
+		}
+	}
+
 	impl sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block> for Runtime {
 		fn validate_transaction(
 			source: TransactionSource,
@@ -14,21 +14,28 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::{CouncilInstance, LocalAssetInstance, TechCommitteeInstance};
 use crowdloan_rewards_precompiles::CrowdloanRewardsWrapper;
 use evm::{executor::PrecompileOutput, Context, ExitError};
+use frame_support::parameter_types;
 use pallet_democracy_precompiles::DemocracyWrapper;
 use pallet_evm::{AddressMapping, Precompile, PrecompileSet};
 use pallet_evm_precompile_assets_erc20::Erc20AssetsPrecompileSet;
 use pallet_evm_precompile_balances_erc20::{Erc20BalancesPrecompile, Erc20Metadata};
+use pallet_evm_precompile_batch::BatchPrecompile;
 use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+use pallet_evm_precompile_collective::CollectivePrecompile;
 use pallet_evm_precompile_dispatch::Dispatch;
+use pallet_evm_precompile_identity::IdentityWrapper;
 use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_proxy::ProxyWrapper;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 use parachain_staking_precompiles::ParachainStakingWrapper;
 use sp_core::H160;
 use sp_std::fmt::Debug;
 use sp_std::marker::PhantomData;
+use xcm_transactor_precompiles::XcmTransactorWrapper;
 use xtokens_precompiles::XtokensWrapper;
 
 /// ERC20 metadata for the native token.
@@ -52,8 +59,26 @@ impl Erc20Metadata for NativeErc20Metadata {
 }
 
 /// The asset precompile address prefix. Addresses that match against this prefix will be routed
-/// to Erc20AssetsPrecompileSet
-pub const ASSET_PRECOMPILE_ADDRESS_PREFIX: &[u8] = &[255u8; 4];
+/// to Erc20AssetsPrecompileSet, of the xcm assets
+pub const FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX: [u8; 4] = [255u8; 4];
+
+/// The local asset precompile address prefix. Addresses that match against this prefix will be
+/// routed to Erc20AssetsPrecompileSet, of the local assets
+pub const LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX: [u8; 4] = [255u8, 255u8, 255u8, 254u8];
+
+parameter_types! {
+	/// Local assets expose the mint, burn and asset management functions through their precompiles
+	pub const IsLocal: bool = true;
+}
+
+/// The precompile set of the local assets
+pub type LocalAssetsPrecompileSet<R> = Erc20AssetsPrecompileSet<R, LocalAssetInstance, IsLocal>;
+
+/// Addresses of the precompiles, besides the ones of the assets.
+const PRECOMPILE_ADDRESSES: &[u64] = &[
+	1, 2, 3, 4, 5, 6, 7, 8, 1024, 1025, 1026, 2048, 2049, 2050, 2051, 2052, 2053, 2054, 2055, 2056,
+	2057, 2058,
+];
 
 /// The PrecompileSet installed in the Moonbase runtime.
 /// We include the nine Istanbul precompiles
@@ -69,9 +94,18 @@ where
 	/// Return all addresses that contain precompiles. This can be used to populate dummy code
 	/// under the precompile.
 	pub fn used_addresses() -> impl Iterator<Item = R::AccountId> {
-		sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 1024, 1025, 1026, 2048, 2049, 2050, 2051]
-			.into_iter()
-			.map(|x| R::AddressMapping::into_account_id(hash(x)))
+		PRECOMPILE_ADDRESSES
+			.iter()
+			.map(|x| R::AddressMapping::into_account_id(hash(*x)))
+	}
+
+	/// Return whether a precompile lives at the given address. This can be used by the client
+	/// to tell calls to precompiles from calls to contracts.
+	pub fn is_precompile(address: &H160) -> bool {
+		let prefix = &address.as_bytes()[0..4];
+		prefix == FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX
+			|| prefix == LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX
+			|| PRECOMPILE_ADDRESSES.iter().any(|x| *address == hash(*x))
 	}
 }
 
@@ -82,10 +116,17 @@ where
 	CrowdloanRewardsWrapper<R>: Precompile,
 	Erc20BalancesPrecompile<R, NativeErc20Metadata>: Precompile,
 	// We require PrecompileSet here because indeed we are dealing with a set of precompiles
-	// This precompile set does additional checks, e.g., total supply not being 0
+	// This precompile set does additional checks, e.g., the asset existing
 	Erc20AssetsPrecompileSet<R>: PrecompileSet,
+	LocalAssetsPrecompileSet<R>: PrecompileSet,
 	DemocracyWrapper<R>: Precompile,
 	XtokensWrapper<R>: Precompile,
+	BatchPrecompile<R>: Precompile,
+	ProxyWrapper<R>: Precompile,
+	IdentityWrapper<R>: Precompile,
+	CollectivePrecompile<R, CouncilInstance>: Precompile,
+	CollectivePrecompile<R, TechCommitteeInstance>: Precompile,
+	XcmTransactorWrapper<R>: Precompile,
 {
 	fn execute(
 		address: H160,
@@ -123,10 +164,31 @@ where
 				Some(DemocracyWrapper::<R>::execute(input, target_gas, context))
 			}
 			a if a == hash(2052) => Some(XtokensWrapper::<R>::execute(input, target_gas, context)),
+			a if a == hash(2053) => Some(BatchPrecompile::<R>::execute_at(
+				a, input, target_gas, context,
+			)),
+			a if a == hash(2054) => Some(ProxyWrapper::<R>::execute(input, target_gas, context)),
+			a if a == hash(2055) => Some(IdentityWrapper::<R>::execute(input, target_gas, context)),
+			a if a == hash(2056) => Some(CollectivePrecompile::<R, CouncilInstance>::execute(
+				input, target_gas, context,
+			)),
+			a if a == hash(2057) => {
+				Some(CollectivePrecompile::<R, TechCommitteeInstance>::execute(
+					input, target_gas, context,
+				))
+			}
+			a if a == hash(2058) => Some(XcmTransactorWrapper::<R>::execute(
+				input, target_gas, context,
+			)),
 			// If the address matches asset prefix, the we route through the asset precompile set
-			a if &a.to_fixed_bytes()[0..4] == ASSET_PRECOMPILE_ADDRESS_PREFIX => {
+			a if a.to_fixed_bytes()[0..4] == FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX => {
 				Erc20AssetsPrecompileSet::<R>::execute(address, input, target_gas, context)
 			}
+			// If the address matches local asset prefix, the we route through the local asset
+			// precompile set
+			a if a.to_fixed_bytes()[0..4] == LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX => {
+				LocalAssetsPrecompileSet::<R>::execute(address, input, target_gas, context)
+			}
 			_ => None,
 		}
 	}
@@ -27,7 +27,7 @@ pub use moonbase_runtime::{
 	Call, CrowdloanRewards, Ethereum, Event, Executive, FixedGasPrice, InflationInfo,
 	ParachainStaking, Range, Runtime, System, TransactionConverter, UncheckedExtrinsic, WEEKS,
 };
-use moonbase_runtime::{AssetRegistrarMetadata, AssetType};
+use moonbase_runtime::{AssetRegistrarMetadata, AssetType, SAFE_XCM_VERSION};
 use nimbus_primitives::NimbusId;
 use pallet_evm::GenesisAccount;
 use sp_core::H160;
@@ -234,6 +234,14 @@ impl ExtBuilder {
 			&mut t,
 		)
 		.unwrap();
+
+		<pallet_xcm::GenesisConfig as GenesisBuild<Runtime>>::assimilate_storage(
+			&pallet_xcm::GenesisConfig {
+				safe_xcm_version: Some(SAFE_XCM_VERSION),
+			},
+			&mut t,
+		)
+		.unwrap();
 		let mut ext = sp_io::TestExternalities::new(t);
 
 		let assets = self.assets.clone();
@@ -23,13 +23,25 @@ mod common;
 mod tests {
 	use super::common::*;
 
+	use frame_support::traits::Get;
 	use pallet_evm::AddressMapping;
 	use sha3::{Digest, Keccak256};
-	use sp_core::{H160, H256};
+	use sp_core::{H160, H256, U256};
 
+	use evm_tracing_events::{Event, Listener};
 	use moonbeam_rpc_primitives_debug::runtime_decl_for_DebugRuntimeApi::DebugRuntimeApi;
 	use std::str::FromStr;
 
+	/// Collects the events proxied to the host functions.
+	#[derive(Default)]
+	struct EventCollector(Vec<Event>);
+
+	impl Listener for EventCollector {
+		fn event(&mut self, event: Event) {
+			self.0.push(event);
+		}
+	}
+
 	#[test]
 	fn debug_runtime_api_trace_transaction() {
 		let alith = <Runtime as pallet_evm::Config>::AddressMapping::into_account_id(
@@ -87,4 +99,92 @@ mod tests {
 				.is_ok());
 			});
 	}
+
+	#[test]
+	fn debug_runtime_api_trace_call() {
+		let alith = H160::from_str("6be02d1d3665660d22ff9624b7be0551ee1ac91b")
+			.expect("internal H160 is valid; qed");
+		let alith_account_id =
+			<Runtime as pallet_evm::Config>::AddressMapping::into_account_id(alith);
+		ExtBuilder::default()
+			.with_balances(vec![
+				(alith_account_id, 2_000 * UNIT),
+				(AccountId::from(BOB), 1_000 * UNIT),
+			])
+			.build()
+			.execute_with(|| {
+				let mut collector = EventCollector::default();
+				let result = evm_tracing_events::using(&mut collector, || {
+					Runtime::trace_call(
+						alith,
+						Some(H160::from(BOB)),
+						Vec::new(),
+						U256::from(1),
+						U256::from(100_000),
+						None,
+						None,
+					)
+				});
+				assert!(result.is_ok());
+				assert!(collector.0.iter().any(|e| matches!(e, Event::Evm(_))));
+			});
+	}
+
+	#[test]
+	fn debug_runtime_api_trace_call_rejects_gas_above_block_limit() {
+		let alith = H160::from_str("6be02d1d3665660d22ff9624b7be0551ee1ac91b")
+			.expect("internal H160 is valid; qed");
+		let alith_account_id =
+			<Runtime as pallet_evm::Config>::AddressMapping::into_account_id(alith);
+		ExtBuilder::default()
+			.with_balances(vec![
+				(alith_account_id, 2_000 * UNIT),
+				(AccountId::from(BOB), 1_000 * UNIT),
+			])
+			.build()
+			.execute_with(|| {
+				let block_gas_limit = <Runtime as pallet_evm::Config>::BlockGasLimit::get();
+				let result = Runtime::trace_call(
+					alith,
+					Some(H160::from(BOB)),
+					Vec::new(),
+					U256::from(1),
+					block_gas_limit + 1,
+					None,
+					None,
+				);
+				assert!(result.is_err());
+			});
+	}
+
+	#[test]
+	fn debug_runtime_api_trace_call_create() {
+		let alith = H160::from_str("6be02d1d3665660d22ff9624b7be0551ee1ac91b")
+			.expect("internal H160 is valid; qed");
+		let alith_account_id =
+			<Runtime as pallet_evm::Config>::AddressMapping::into_account_id(alith);
+		ExtBuilder::default()
+			.with_balances(vec![(alith_account_id, 2_000 * UNIT)])
+			.build()
+			.execute_with(|| {
+				let mut collector = EventCollector::default();
+				let result = evm_tracing_events::using(&mut collector, || {
+					// Init code returning an empty contract.
+					Runtime::trace_call(
+						alith,
+						None,
+						vec![0x60, 0x00, 0x60, 0x00, 0xf3],
+						U256::zero(),
+						U256::from(100_000),
+						None,
+						None,
+					)
+				});
+				assert!(result.is_ok());
+				assert!(collector.0.iter().any(|e| matches!(
+					e,
+					Event::Evm(evm_tracing_events::EvmEvent::TransactCreate { .. })
+				)));
+			});
+	}
 }
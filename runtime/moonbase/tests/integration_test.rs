@@ -31,14 +31,16 @@ use frame_support::{
 };
 use moonbase_runtime::{
 	currency::UNIT, AccountId, AssetId, AssetManager, AssetRegistrarMetadata, AssetType, Assets,
-	Balances, BlockWeights, Call, CrowdloanRewards, Event, ParachainStaking, Precompiles, Runtime,
-	System,
+	Balances, BlockWeights, Call, CrowdloanRewards, Event, LocalAssetDeposit, LocalAssets,
+	ParachainStaking, Precompiles, ProxyType, Runtime, System,
+	FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX, LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX,
 };
 use nimbus_primitives::NimbusId;
 use pallet_evm::PrecompileSet;
 use pallet_evm_precompile_assets_erc20::{
 	AccountIdAssetIdConversion, Action as AssetAction, SELECTOR_LOG_APPROVAL, SELECTOR_LOG_TRANSFER,
 };
+use pallet_evm_precompile_proxy::{EvmProxyCallFilter, EvmSubCall};
 use xtokens_precompiles::{Action as XtokensAction, MultiLocationWrapper};
 
 use pallet_transaction_payment::Multiplier;
@@ -1071,7 +1073,8 @@ fn asset_erc20_precompiles_supply_and_balance() {
 			assert_eq!(Assets::total_supply(0u128), 1_000 * UNIT);
 
 			// Convert the assetId to its corresponding precompile address
-			let asset_precompile_address = Runtime::asset_id_to_account(0u128);
+			let asset_precompile_address =
+				Runtime::asset_id_to_account(FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX, 0u128);
 
 			// The expected result for both total supply and balance of is the same, as only Alice
 			// holds balance
@@ -1119,6 +1122,96 @@ fn asset_erc20_precompiles_supply_and_balance() {
 		});
 }
 
+#[test]
+fn local_assets_can_be_created_and_used_through_erc20_precompiles() {
+	ExtBuilder::default()
+		.with_balances(vec![(AccountId::from(ALICE), 1_000 * UNIT)])
+		.build()
+		.execute_with(|| {
+			assert_ok!(AssetManager::create_local_asset(
+				origin_of(AccountId::from(ALICE)),
+				1u128,
+			));
+
+			// The first local asset takes the first id of the counter, and its deposit is
+			// reserved from its creator
+			assert_eq!(
+				AssetManager::local_asset_creator(0u128),
+				Some(AccountId::from(ALICE))
+			);
+			assert_eq!(
+				Balances::reserved_balance(AccountId::from(ALICE)),
+				LocalAssetDeposit::get()
+			);
+
+			// The local asset is reachable at the local address of its id, before having supply
+			let asset_precompile_address =
+				Runtime::asset_id_to_account(LOCAL_ASSET_PRECOMPILE_ADDRESS_PREFIX, 0u128);
+
+			// The creator is the issuer of the asset, and can mint it through the precompile
+			let mint_output = Precompiles::execute(
+				asset_precompile_address,
+				&EvmDataWriter::new()
+					.write_selector(AssetAction::Mint)
+					.write(EvmAddress(ALICE.into()))
+					.write(U256::from(1000 * UNIT))
+					.build(),
+				None,
+				&evm::Context {
+					address: asset_precompile_address,
+					caller: ALICE.into(),
+					apparent_value: From::from(0),
+				},
+			)
+			.expect("the local asset exists")
+			.expect("the issuer can mint");
+			assert_eq!(mint_output.exit_status, ExitSucceed::Returned);
+			assert_eq!(
+				LocalAssets::balance(0u128, AccountId::from(ALICE)),
+				1_000 * UNIT
+			);
+
+			assert_eq!(
+				Precompiles::execute(
+					asset_precompile_address,
+					&EvmDataWriter::new()
+						.write_selector(AssetAction::TotalSupply)
+						.build(),
+					None,
+					&evm::Context {
+						address: asset_precompile_address,
+						caller: ALICE.into(),
+						apparent_value: From::from(0),
+					},
+				),
+				Some(Ok(PrecompileOutput {
+					exit_status: ExitSucceed::Returned,
+					output: EvmDataWriter::new().write(U256::from(1000 * UNIT)).build(),
+					cost: 1000,
+					logs: Default::default(),
+				}))
+			);
+		});
+}
+
+#[test]
+fn local_assets_cannot_be_created_directly() {
+	ExtBuilder::default()
+		.with_balances(vec![(AccountId::from(ALICE), 1_000 * UNIT)])
+		.build()
+		.execute_with(|| {
+			let call = Call::LocalAssets(pallet_assets::Call::create(
+				0u128,
+				AccountId::from(ALICE),
+				1u128,
+			));
+			assert_noop!(
+				call.dispatch(origin_of(AccountId::from(ALICE))),
+				DispatchError::BadOrigin
+			);
+		});
+}
+
 #[test]
 fn asset_erc20_precompiles_transfer() {
 	ExtBuilder::default()
@@ -1129,7 +1222,8 @@ fn asset_erc20_precompiles_transfer() {
 		])
 		.build()
 		.execute_with(|| {
-			let asset_precompile_address = Runtime::asset_id_to_account(0u128);
+			let asset_precompile_address =
+				Runtime::asset_id_to_account(FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX, 0u128);
 
 			// Expected result for a transfer
 			let expected_result = Some(Ok(PrecompileOutput {
@@ -1203,7 +1297,8 @@ fn asset_erc20_precompiles_approve() {
 		])
 		.build()
 		.execute_with(|| {
-			let asset_precompile_address = Runtime::asset_id_to_account(0u128);
+			let asset_precompile_address =
+				Runtime::asset_id_to_account(FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX, 0u128);
 
 			// Expected result for approve
 			let expected_result = Some(Ok(PrecompileOutput {
@@ -1327,7 +1422,10 @@ fn xtokens_precompiles_transfer() {
 			let relay_asset_id: AssetId = AssetType::Xcm(MultiLocation::parent()).into();
 
 			// Its address is
-			let asset_precompile_address = Runtime::asset_id_to_account(relay_asset_id);
+			let asset_precompile_address = Runtime::asset_id_to_account(
+				FOREIGN_ASSET_PRECOMPILE_ADDRESS_PREFIX,
+				relay_asset_id,
+			);
 
 			// Alice has 1000 tokens. She should be able to send through precompile
 			let destination = MultiLocation::new(
@@ -1501,3 +1599,24 @@ fn ethereum_invalid_transaction() {
 		);
 	});
 }
+
+#[test]
+fn evm_proxy_call_filter_allows_governance_precompiles() {
+	let call = |to: u64, value: u64| EvmSubCall {
+		to: H160::from_low_u64_be(to),
+		value: value.into(),
+		call_data: vec![],
+	};
+
+	// Democracy, council and technical committee precompiles.
+	for &address in &[2051, 2056, 2057] {
+		assert!(ProxyType::Governance.is_evm_proxy_call_allowed(&call(address, 0)));
+		assert!(ProxyType::NonTransfer.is_evm_proxy_call_allowed(&call(address, 0)));
+		assert!(!ProxyType::Governance.is_evm_proxy_call_allowed(&call(address, 1)));
+		assert!(!ProxyType::Staking.is_evm_proxy_call_allowed(&call(address, 0)));
+	}
+
+	// Parachain staking precompile.
+	assert!(!ProxyType::Governance.is_evm_proxy_call_allowed(&call(2048, 0)));
+	assert!(ProxyType::NonTransfer.is_evm_proxy_call_allowed(&call(2048, 0)));
+}
@@ -24,7 +24,12 @@ use pallet_evm::{Account as EVMAccount, AddressMapping, FeeCalculator, GenesisAc
 use sp_core::{Public, H160, H256, U256};
 
 use fp_rpc::runtime_decl_for_EthereumRuntimeRPCApi::EthereumRuntimeRPCApi;
-use moonbeam_rpc_primitives_txpool::runtime_decl_for_TxPoolRuntimeApi::TxPoolRuntimeApi;
+use moonbeam_rpc_primitives_txpool::{
+	runtime_decl_for_TxPoolRuntimeApi::TxPoolRuntimeApi, tagged_sender,
+};
+use parity_scale_codec::Encode;
+use sp_runtime::transaction_validity::TransactionSource;
+use sp_transaction_pool::runtime_api::runtime_decl_for_TaggedTransactionQueue::TaggedTransactionQueue;
 use std::collections::BTreeMap;
 use std::str::FromStr;
 
@@ -289,6 +294,30 @@ fn txpool_runtime_api_extrinsic_filter() {
 	});
 }
 
+#[test]
+fn txpool_tagged_sender_of_ethereum_transaction() {
+	let alith = H160::from_str("6be02d1d3665660d22ff9624b7be0551ee1ac91b")
+		.expect("internal H160 is valid; qed");
+	ExtBuilder::default()
+		.with_balances(vec![(AccountId::from(alith), 2_000 * UNIT)])
+		.build()
+		.execute_with(|| {
+			let validity = Runtime::validate_transaction(
+				TransactionSource::External,
+				unchecked_eth_tx(VALID_ETH_TX),
+				H256::default(),
+			)
+			.expect("transaction is valid");
+			assert_eq!(tagged_sender(&validity.provides), Some(alith));
+
+			// The `(AccountId, Index)` tag of signed extrinsics is not mistaken for a sender.
+			assert_eq!(
+				tagged_sender(&[(AccountId::from(ALICE), 0u32).encode()]),
+				None
+			);
+		});
+}
+
 // Some Priority-related test ideas
 // 1. Eth balance transfer with various gas prices. Priority == gas price
 // 2. Eth contract call with various gas prices. Priority == gas price
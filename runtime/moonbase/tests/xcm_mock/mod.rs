@@ -17,9 +17,11 @@
 pub mod parachain;
 pub mod relay_chain;
 
+use frame_support::traits::GenesisBuild;
 use sp_runtime::AccountId32;
 use xcm_simulator::{decl_test_network, decl_test_parachain, decl_test_relay_chain};
 pub const PARAALICE: [u8; 20] = [1u8; 20];
+pub const PARA_FEES_ACCOUNT: [u8; 20] = [0xFEu8; 20];
 pub const RELAYALICE: AccountId32 = AccountId32::new([0u8; 32]);
 
 decl_test_parachain! {
@@ -83,6 +85,12 @@ pub fn para_ext(para_id: u32) -> sp_io::TestExternalities {
 	.assimilate_storage(&mut t)
 	.unwrap();
 
+	GenesisBuild::<Runtime>::assimilate_storage(
+		&pallet_xcm_barrier::GenesisConfig::default(),
+		&mut t,
+	)
+	.unwrap();
+
 	let mut ext = sp_io::TestExternalities::new(t);
 	ext.execute_with(|| {
 		System::set_block_number(1);
@@ -111,7 +119,11 @@ pub fn relay_ext() -> sp_io::TestExternalities {
 
 pub type RelayChainPalletXcm = pallet_xcm::Pallet<relay_chain::Runtime>;
 pub type Assets = pallet_assets::Pallet<parachain::Runtime>;
+pub type LocalAssets = pallet_assets::Pallet<parachain::Runtime, parachain::LocalAssetInstance>;
 pub type AssetManager = pallet_asset_manager::Pallet<parachain::Runtime>;
 pub type XTokens = orml_xtokens::Pallet<parachain::Runtime>;
+pub type XcmTransactor = pallet_xcm_transactor::Pallet<parachain::Runtime>;
+pub type XcmBarrier = pallet_xcm_barrier::Pallet<parachain::Runtime>;
+pub type AssetTrap = pallet_asset_trap::Pallet<parachain::Runtime>;
 pub type RelayBalances = pallet_balances::Pallet<relay_chain::Runtime>;
 pub type ParaBalances = pallet_balances::Pallet<parachain::Runtime>;
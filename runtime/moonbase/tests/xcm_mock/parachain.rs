@@ -19,7 +19,7 @@
 use frame_support::{
 	construct_runtime, parameter_types,
 	traits::{Everything, Get, Nothing, PalletInfo as PalletInfoTrait},
-	weights::Weight,
+	weights::{constants::WEIGHT_PER_SECOND, Weight},
 };
 use frame_system::EnsureRoot;
 use parity_scale_codec::{Decode, Encode};
@@ -39,13 +39,17 @@ use xcm::v1::{
 	Junctions, MultiLocation, NetworkId, Outcome, Xcm,
 };
 use xcm_builder::{
-	AccountKey20Aliases, AllowTopLevelPaidExecutionFrom, ConvertedConcreteAssetId,
-	CurrencyAdapter as XcmCurrencyAdapter, EnsureXcmOrigin, FixedRateOfFungible, FixedWeightBounds,
-	FungiblesAdapter, IsConcrete, LocationInverter, ParentAsSuperuser, ParentIsDefault,
-	RelayChainAsNative, SiblingParachainAsNative, SiblingParachainConvertsVia,
-	SignedAccountKey20AsNative, SovereignSignedViaLocation, TakeWeightCredit,
+	AccountKey20Aliases, AllowKnownQueryResponses, AsPrefixedGeneralIndex,
+	ConvertedConcreteAssetId, CurrencyAdapter as XcmCurrencyAdapter, EnsureXcmOrigin,
+	FixedRateOfFungible, FixedWeightBounds, FungiblesAdapter, IsConcrete, LocationInverter,
+	ParentAsSuperuser, ParentIsDefault, RelayChainAsNative, SiblingParachainAsNative,
+	SiblingParachainConvertsVia, SignedAccountKey20AsNative, SovereignSignedViaLocation,
+	TakeWeightCredit,
+};
+use xcm_executor::{
+	traits::{Convert as XcmConvert, JustTry},
+	Config, XcmExecutor,
 };
-use xcm_executor::{traits::JustTry, Config, XcmExecutor};
 
 use xcm_simulator::{
 	DmpMessageHandlerT as DmpMessageHandler, XcmpMessageFormat,
@@ -129,6 +133,24 @@ impl pallet_assets::Config for Runtime {
 	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
 }
 
+pub type LocalAssetInstance = pallet_assets::Instance1;
+
+impl pallet_assets::Config<LocalAssetInstance> for Runtime {
+	type Event = Event;
+	type Balance = Balance;
+	type AssetId = AssetId;
+	type Currency = Balances;
+	type ForceOrigin = EnsureRoot<AccountId>;
+	type AssetDeposit = AssetDeposit;
+	type MetadataDepositBase = MetadataDepositBase;
+	type MetadataDepositPerByte = MetadataDepositPerByte;
+	type ApprovalDeposit = ApprovalDeposit;
+	type StringLimit = AssetsStringLimit;
+	type Freezer = ();
+	type Extra = ();
+	type WeightInfo = pallet_assets::weights::SubstrateWeight<Runtime>;
+}
+
 /// Type for specifying how a `MultiLocation` can be converted into an `AccountId`. This is used
 /// when determining ownership of accounts for asset transacting and when attempting to use XCM
 /// `Transact` in order to determine the dispatch Origin.
@@ -164,21 +186,46 @@ pub type XcmOriginToTransactDispatchOrigin = (
 
 parameter_types! {
 	pub const UnitWeightCost: Weight = 1;
+	// The account receiving the fees charged by the asset trader
+	pub XcmFeesAccount: AccountId = AccountId::from(super::PARA_FEES_ACCOUNT);
 }
 
+/// Matches the non-reserve fungible assets against AsAssetType
+pub type AssetsMatcher = (
+	ConvertedConcreteAssetId<
+		AssetId,
+		Balance,
+		xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>,
+		JustTry,
+	>,
+);
+
 // Instructing how incoming xcm assets will be handled
 pub type FungiblesTransactor = FungiblesAdapter<
 	// Use this fungibles implementation:
 	Assets,
 	// Use this currency when it is a fungible asset matching the given location or name:
-	(
-		ConvertedConcreteAssetId<
-			AssetId,
-			Balance,
-			xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>,
-			JustTry,
-		>,
-	),
+	AssetsMatcher,
+	// Do a simple punn to convert an AccountId32 MultiLocation into a native chain account ID:
+	LocationToAccountId,
+	// Our chain's account ID type (we can't get away without mentioning it explicitly):
+	AccountId,
+	// We dont allow teleports.
+	(),
+	// We dont track any teleports
+	(),
+>;
+
+/// Converts the multilocations of the local assets into their AssetId
+pub type LocalAssetsLocationConverter =
+	AsPrefixedGeneralIndex<LocalAssetsPalletLocation, AssetId, JustTry>;
+
+// Instructing how our local assets will be handled
+pub type LocalFungiblesTransactor = FungiblesAdapter<
+	// Use this fungibles implementation:
+	LocalAssets,
+	// Use this currency when it is a fungible asset matching the given location or name:
+	ConvertedConcreteAssetId<AssetId, Balance, LocalAssetsLocationConverter, JustTry>,
 	// Do a simple punn to convert an AccountId32 MultiLocation into a native chain account ID:
 	LocationToAccountId,
 	// Our chain's account ID type (we can't get away without mentioning it explicitly):
@@ -204,10 +251,34 @@ pub type LocalAssetTransactor = XcmCurrencyAdapter<
 >;
 
 // These will be our transactors
-pub type AssetTransactors = (LocalAssetTransactor, FungiblesTransactor);
+pub type AssetTransactors = (
+	LocalAssetTransactor,
+	FungiblesTransactor,
+	LocalFungiblesTransactor,
+);
 pub type XcmRouter = super::ParachainXcmRouter<MsgQueue>;
 
-pub type Barrier = (TakeWeightCredit, AllowTopLevelPaidExecutionFrom<Everything>);
+pub type Barrier = (
+	TakeWeightCredit,
+	AllowKnownQueryResponses<PolkadotXcm>,
+	XcmBarrier,
+);
+
+parameter_types! {
+	// Paid execution from the relay and its descendants, unpaid execution from no one
+	pub XcmBarrierInitialRules: Vec<(pallet_xcm_barrier::BarrierRule, MultiLocation, bool)> = vec![
+		(pallet_xcm_barrier::BarrierRule::PaidExecution, MultiLocation::parent(), true),
+	];
+	// Rejected messages are reported by an event for at most this many origins per block
+	pub const XcmBarrierMaxRejectionEvents: u32 = 10;
+}
+
+impl pallet_xcm_barrier::Config for Runtime {
+	type Event = Event;
+	type BarrierOrigin = EnsureRoot<AccountId>;
+	type InitialRules = XcmBarrierInitialRules;
+	type MaxRejectionEvents = XcmBarrierMaxRejectionEvents;
+}
 
 parameter_types! {
 	// This value is high enough to charge for meaningful weights but low enough not to
@@ -226,30 +297,47 @@ parameter_types! {
 			PalletInstance(<Runtime as frame_system::Config>::PalletInfo::index::<Balances>().unwrap() as u8)
 		)
 	};
+	pub LocalAssetsPalletLocation: MultiLocation = MultiLocation {
+		parents:1,
+		interior: Junctions::X2(
+			Parachain(MsgQueue::parachain_id().into()),
+			PalletInstance(<Runtime as frame_system::Config>::PalletInfo::index::<LocalAssets>().unwrap() as u8)
+		)
+	};
 }
 
 pub struct XcmConfig;
 impl Config for XcmConfig {
 	type Call = Call;
 	type XcmSender = XcmRouter;
-	type AssetTransactor = AssetTransactors;
+	type AssetTransactor = pallet_asset_trap::TrackDeposits<AssetTransactors>;
 	type OriginConverter = XcmOriginToTransactDispatchOrigin;
 	type IsReserve = xcm_primitives::MultiNativeAsset;
 	type IsTeleporter = ();
 	type LocationInverter = LocationInverter<Ancestry>;
 	type Barrier = Barrier;
 	type Weigher = FixedWeightBounds<UnitWeightCost, Call>;
-	type Trader = xcm_primitives::MultiWeightTraders<
-		FixedRateOfFungible<ParaTokensPerSecond, ()>,
-		xcm_primitives::FirstAssetTrader<AssetId, AssetType, AssetManager, ()>,
+	type Trader = pallet_asset_trap::TrackFees<
+		xcm_primitives::MultiWeightTraders<
+			FixedRateOfFungible<ParaTokensPerSecond, ()>,
+			xcm_primitives::FirstAssetTrader<
+				AssetId,
+				AssetType,
+				AssetManager,
+				xcm_primitives::XcmFeesToAccount<Assets, AssetsMatcher, AccountId, XcmFeesAccount>,
+			>,
+		>,
 	>;
-	type ResponseHandler = ();
+	type ResponseHandler = PolkadotXcm;
 	type SubscriptionService = PolkadotXcm;
 }
 
+pub type XcmExecutorWithTrap =
+	pallet_asset_trap::TrapUnspentAssets<Runtime, XcmExecutor<XcmConfig>>;
+
 impl cumulus_pallet_xcm::Config for Runtime {
 	type Event = Event;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = XcmExecutorWithTrap;
 }
 
 // Our currencyId. We distinguish for now between SelfReserve, and Others, defined by their Id.
@@ -257,6 +345,7 @@ impl cumulus_pallet_xcm::Config for Runtime {
 pub enum CurrencyId {
 	SelfReserve,
 	OtherReserve(AssetId),
+	LocalAssetReserve(AssetId),
 }
 
 // How to convert from CurrencyId to MultiLocation
@@ -273,6 +362,9 @@ where
 				Some(multi)
 			}
 			CurrencyId::OtherReserve(asset) => AssetXConverter::reverse_ref(asset).ok(),
+			CurrencyId::LocalAssetReserve(asset) => {
+				LocalAssetsLocationConverter::reverse_ref(asset).ok()
+			}
 		}
 	}
 }
@@ -295,7 +387,7 @@ impl orml_xtokens::Config for Runtime {
 	type AccountIdToMultiLocation = xcm_primitives::AccountIdToMultiLocation<AccountId>;
 	type CurrencyIdConvert =
 		CurrencyIdtoMultiLocation<xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>>;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = XcmExecutorWithTrap;
 	type SelfLocation = SelfLocation;
 	type Weigher = xcm_builder::FixedWeightBounds<UnitWeightCost, Call>;
 	type BaseXcmWeight = BaseXcmWeight;
@@ -440,19 +532,45 @@ pub mod mock_msg_queue {
 
 impl mock_msg_queue::Config for Runtime {
 	type Event = Event;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = XcmExecutorWithTrap;
 }
 
 pub type LocalOriginToLocation =
 	xcm_primitives::SignedToAccountId20<Origin, AccountId, RelayNetwork>;
 
+pub struct XcmPassthroughToLocation;
+impl XcmConvert<Origin, MultiLocation> for XcmPassthroughToLocation {
+	fn convert(o: Origin) -> Result<MultiLocation, Origin> {
+		match Into::<Result<pallet_xcm::Origin, Origin>>::into(o.clone()) {
+			Ok(pallet_xcm::Origin::Xcm(location)) => Ok(location),
+			_ => Err(o),
+		}
+	}
+}
+
+parameter_types! {
+	pub const AnyNetwork: NetworkId = NetworkId::Any;
+}
+
+impl pallet_asset_trap::Config for Runtime {
+	type Event = Event;
+	type ClaimOrigin = EnsureXcmOrigin<
+		Origin,
+		(
+			xcm_primitives::SignedToAccountId20<Origin, AccountId, AnyNetwork>,
+			XcmPassthroughToLocation,
+		),
+	>;
+	type AssetTransactor = AssetTransactors;
+}
+
 impl pallet_xcm::Config for Runtime {
 	type Event = Event;
 	type SendXcmOrigin = EnsureXcmOrigin<Origin, LocalOriginToLocation>;
 	type XcmRouter = XcmRouter;
 	type ExecuteXcmOrigin = EnsureXcmOrigin<Origin, LocalOriginToLocation>;
 	type XcmExecuteFilter = Everything;
-	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type XcmExecutor = XcmExecutorWithTrap;
 	// Do not allow teleports
 	type XcmTeleportFilter = Nothing;
 	type XcmReserveTransferFilter = Everything;
@@ -527,6 +645,21 @@ impl pallet_asset_manager::AssetRegistrar<Runtime> for AssetRegistrar {
 			false,
 		)
 	}
+
+	fn create_local_asset(
+		asset: AssetId,
+		creator: AccountId,
+		min_balance: Balance,
+	) -> DispatchResult {
+		LocalAssets::create(Origin::signed(creator), asset, creator, min_balance)
+	}
+}
+
+pub struct LocalAssetIdCreator;
+impl pallet_asset_manager::LocalAssetIdCreator<Runtime> for LocalAssetIdCreator {
+	fn create_asset_id_from_counter(counter: u128) -> AssetId {
+		counter
+	}
 }
 
 #[derive(Clone, Eq, Debug, PartialEq, Ord, PartialOrd, Encode, Decode)]
@@ -536,6 +669,10 @@ pub struct AssetMetadata {
 	pub decimals: u8,
 }
 
+parameter_types! {
+	pub const NativeFeePerSecond: u128 = WEIGHT_PER_SECOND as u128;
+}
+
 impl pallet_asset_manager::Config for Runtime {
 	type Event = Event;
 	type Balance = Balance;
@@ -544,6 +681,59 @@ impl pallet_asset_manager::Config for Runtime {
 	type AssetType = AssetType;
 	type AssetRegistrar = AssetRegistrar;
 	type AssetModifierOrigin = EnsureRoot<AccountId>;
+	type NativeFeePerSecond = NativeFeePerSecond;
+	type LocalAssetIdCreator = LocalAssetIdCreator;
+}
+
+// The destinations we can transact into through xcm-transactor
+#[derive(Clone, Eq, Debug, PartialEq, Ord, PartialOrd, Encode, Decode)]
+pub enum Transactors {
+	Relay,
+}
+
+impl xcm_primitives::XcmTransact for Transactors {
+	fn destination(self) -> MultiLocation {
+		match self {
+			Transactors::Relay => MultiLocation::parent(),
+		}
+	}
+}
+
+impl xcm_primitives::UtilityEncodeCall for Transactors {
+	fn encode_call(self, call: xcm_primitives::UtilityAvailableCalls) -> Vec<u8> {
+		match self {
+			Transactors::Relay => match call {
+				xcm_primitives::UtilityAvailableCalls::AsDerivative(index, inner_call) => {
+					// The utility pallet index in the mocked relay, and the index of
+					// as_derivative in it
+					let utility_index =
+						<super::relay_chain::Runtime as frame_system::Config>::PalletInfo::index::<
+							super::relay_chain::Utility,
+						>()
+						.unwrap() as u8;
+					let mut call = (utility_index, 1u8).encode();
+					call.append(&mut index.encode());
+					call.extend(inner_call);
+					call
+				}
+			},
+		}
+	}
+}
+
+impl pallet_xcm_transactor::Config for Runtime {
+	type Event = Event;
+	type Transactor = Transactors;
+	type DerivativeAddressRegistrationOrigin = EnsureRoot<AccountId>;
+	type SovereignAccountDispatcherOrigin = EnsureRoot<AccountId>;
+	type CurrencyId = CurrencyId;
+	type CurrencyIdToMultiLocation =
+		CurrencyIdtoMultiLocation<xcm_primitives::AsAssetType<AssetId, AssetType, AssetManager>>;
+	type AccountIdToMultiLocation = xcm_primitives::AccountIdToMultiLocation<AccountId>;
+	type LocationInverter = LocationInverter<Ancestry>;
+	type XcmSender = XcmRouter;
+	type AssetTransactor = AssetTransactors;
+	type WeightInfo = ();
 }
 
 type UncheckedExtrinsic = frame_system::mocking::MockUncheckedExtrinsic<Runtime>;
@@ -563,5 +753,9 @@ construct_runtime!(
 		CumulusXcm: cumulus_pallet_xcm::{Pallet, Event<T>, Origin},
 		XTokens: orml_xtokens::{Pallet, Call, Storage, Event<T>},
 		AssetManager: pallet_asset_manager::{Pallet, Call, Storage, Event<T>},
+		XcmTransactor: pallet_xcm_transactor::{Pallet, Call, Storage, Event<T>},
+		XcmBarrier: pallet_xcm_barrier::{Pallet, Call, Storage, Event, Config},
+		LocalAssets: pallet_assets::<Instance1>::{Pallet, Call, Storage, Event<T>},
+		AssetTrap: pallet_asset_trap::{Pallet, Call, Storage, Event},
 	}
 );
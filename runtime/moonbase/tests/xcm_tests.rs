@@ -17,15 +17,23 @@
 //! Moonbase Runtime Integration Tests
 
 mod xcm_mock;
-use frame_support::assert_ok;
+use frame_support::{assert_ok, traits::PalletInfo};
+use parity_scale_codec::Encode;
+use polkadot_parachain::primitives::Id as ParaId;
+use sp_runtime::traits::AccountIdConversion;
 use xcm_mock::parachain;
 use xcm_mock::relay_chain;
 use xcm_mock::*;
 
 use xcm::v1::{
+	AssetId::Concrete,
+	Error as XcmError,
+	Fungibility::Fungible,
 	Junction::{self, AccountId32, AccountKey20, PalletInstance, Parachain},
 	Junctions::*,
-	MultiLocation, NetworkId,
+	MultiAsset, MultiAssets, MultiLocation, NetworkId, Order, Outcome, SendXcm,
+	WildMultiAsset::All,
+	Xcm,
 };
 use xcm_simulator::TestExt;
 
@@ -323,6 +331,88 @@ fn send_para_a_asset_to_para_b() {
 	});
 }
 
+#[test]
+fn send_para_a_local_asset_to_para_b() {
+	MockNet::reset();
+
+	// The first local asset created in para A gets the first id of the counter
+	let local_asset_id: parachain::AssetId = 0;
+	let local_assets_index =
+		<parachain::Runtime as frame_system::Config>::PalletInfo::index::<parachain::LocalAssets>()
+			.unwrap() as u8;
+	let para_a_local_asset = MultiLocation::new(
+		1,
+		X3(
+			Parachain(1),
+			PalletInstance(local_assets_index),
+			Junction::GeneralIndex(local_asset_id),
+		),
+	);
+	let source_location = parachain::AssetType::Xcm(para_a_local_asset);
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"ParaALocalToken".to_vec(),
+		symbol: b"ParaALocal".to_vec(),
+		decimals: 18,
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::create_local_asset(
+			parachain::Origin::signed(PARAALICE.into()),
+			1u128
+		));
+		assert_ok!(LocalAssets::mint(
+			parachain::Origin::signed(PARAALICE.into()),
+			local_asset_id,
+			PARAALICE.into(),
+			1000
+		));
+	});
+
+	ParaB::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+	});
+
+	let dest = MultiLocation {
+		parents: 1,
+		interior: X2(
+			Parachain(2),
+			AccountKey20 {
+				network: NetworkId::Any,
+				key: PARAALICE.into(),
+			},
+		),
+	};
+
+	ParaA::execute_with(|| {
+		// free execution, full amount received
+		assert_ok!(XTokens::transfer(
+			parachain::Origin::signed(PARAALICE.into()),
+			parachain::CurrencyId::LocalAssetReserve(local_asset_id),
+			100,
+			Box::new(dest),
+			800000
+		));
+		assert_eq!(LocalAssets::balance(local_asset_id, &PARAALICE.into()), 900);
+	});
+
+	ParaB::execute_with(|| {
+		// free execution, full amount received
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 100);
+	});
+}
+
 #[test]
 fn send_para_a_asset_from_para_b_to_para_c() {
 	MockNet::reset();
@@ -572,6 +662,103 @@ fn receive_relay_asset_with_trader() {
 	ParaA::execute_with(|| {
 		// non-free execution, not full amount received
 		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 90);
+		// the fee went to the fees account
+		assert_eq!(Assets::balance(source_id, &PARA_FEES_ACCOUNT.into()), 10);
+	});
+}
+
+#[test]
+fn receive_assets_paying_fees_with_several_assets() {
+	MockNet::reset();
+
+	let relay_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let relay_id: parachain::AssetId = relay_location.clone().into();
+	// Another asset whose reserve is the relay
+	let other_location = parachain::AssetType::Xcm(MultiLocation::new(1, X1(PalletInstance(5u8))));
+	let other_id: parachain::AssetId = other_location.clone().into();
+
+	// Both assets are charged at 1e6 units per second
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			relay_location,
+			parachain::AssetMetadata {
+				name: b"RelayToken".to_vec(),
+				symbol: b"Relay".to_vec(),
+				decimals: 12,
+			},
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			relay_id,
+			1_000_000u128
+		));
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			other_location,
+			parachain::AssetMetadata {
+				name: b"OtherToken".to_vec(),
+				symbol: b"Other".to_vec(),
+				decimals: 12,
+			},
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			other_id,
+			1_000_000u128
+		));
+	});
+
+	let asset = |location: MultiLocation, amount: u128| MultiAsset {
+		id: Concrete(location),
+		fun: Fungible(amount),
+	};
+	let buy_execution = |fees: MultiAsset| Order::BuyExecution {
+		fees,
+		weight: 0,
+		debt: 10_000_000,
+		halt_on_error: false,
+		instructions: vec![],
+	};
+	let dest: MultiLocation = AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+
+	// We buy 1e7 weight twice, once with each asset, so each asset pays 1e7*1e6/1e12 = 10
+	let message = Xcm::ReserveAssetDeposited {
+		assets: vec![
+			asset(MultiLocation::parent(), 100),
+			asset(MultiLocation::new(1, X1(PalletInstance(5u8))), 100),
+		]
+		.into(),
+		effects: vec![
+			buy_execution(asset(MultiLocation::parent(), 100)),
+			buy_execution(asset(MultiLocation::new(1, X1(PalletInstance(5u8))), 100)),
+			Order::DepositAsset {
+				assets: All.into(),
+				max_assets: 2,
+				beneficiary: dest,
+			},
+		],
+	};
+	Relay::execute_with(|| {
+		assert_ok!(<relay_chain::XcmRouter as SendXcm>::send_xcm(
+			Parachain(1).into(),
+			message
+		));
+	});
+
+	ParaA::execute_with(|| {
+		// Each asset paid for the weight it bought
+		assert_eq!(Assets::balance(relay_id, &PARAALICE.into()), 90);
+		assert_eq!(Assets::balance(other_id, &PARAALICE.into()), 90);
+		// And the fees went to the fees account in each asset
+		assert_eq!(Assets::balance(relay_id, &PARA_FEES_ACCOUNT.into()), 10);
+		assert_eq!(Assets::balance(other_id, &PARA_FEES_ACCOUNT.into()), 10);
 	});
 }
 
@@ -629,3 +816,461 @@ fn error_when_not_paying_enough() {
 		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
 	});
 }
+
+#[test]
+fn assets_not_paying_enough_are_trapped_and_claimed() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			parachain::AssetMetadata {
+				name: b"RelayToken".to_vec(),
+				symbol: b"Relay".to_vec(),
+				decimals: 12,
+			},
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			1_000_000u128
+		));
+	});
+
+	let dest: MultiLocation = Junction::AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	// The 5 tokens sent do not pay for the 1e7 weight, which costs 10
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, 5).into()),
+			0,
+			10_000_000u64,
+		));
+	});
+
+	let trapped: MultiAssets = vec![MultiAsset {
+		id: Concrete(MultiLocation::parent()),
+		fun: Fungible(5),
+	}]
+	.into();
+	ParaA::execute_with(|| {
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+		// The tokens were trapped for the relay
+		let hash = AssetTrap::trap_hash(&MultiLocation::parent(), &trapped);
+		assert_eq!(AssetTrap::asset_traps(hash), 1);
+
+		// Which claims them back, as with a `Transact` of origin kind Xcm
+		assert_ok!(AssetTrap::claim_assets(
+			parachain::Origin::from(pallet_xcm::Origin::Xcm(MultiLocation::parent())),
+			Box::new(trapped.clone()),
+			Box::new(dest.clone()),
+		));
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 5);
+		assert_eq!(AssetTrap::asset_traps(hash), 0);
+	});
+}
+
+#[test]
+fn assets_left_in_holding_are_trapped() {
+	MockNet::reset();
+
+	let relay_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let relay_id: parachain::AssetId = relay_location.clone().into();
+	// Another asset whose reserve is the relay
+	let other_location = parachain::AssetType::Xcm(MultiLocation::new(1, X1(PalletInstance(5u8))));
+	let other_id: parachain::AssetId = other_location.clone().into();
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			relay_location,
+			parachain::AssetMetadata {
+				name: b"RelayToken".to_vec(),
+				symbol: b"Relay".to_vec(),
+				decimals: 12,
+			},
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			relay_id,
+			1_000_000u128
+		));
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			other_location,
+			parachain::AssetMetadata {
+				name: b"OtherToken".to_vec(),
+				symbol: b"Other".to_vec(),
+				decimals: 12,
+			},
+			1u128,
+		));
+	});
+
+	let asset = |location: MultiLocation, amount: u128| MultiAsset {
+		id: Concrete(location),
+		fun: Fungible(amount),
+	};
+	let dest: MultiLocation = AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+
+	// Only one asset is deposited, which is the relay asset, so the other one is left in holding
+	let message = Xcm::ReserveAssetDeposited {
+		assets: vec![
+			asset(MultiLocation::parent(), 100),
+			asset(MultiLocation::new(1, X1(PalletInstance(5u8))), 100),
+		]
+		.into(),
+		effects: vec![
+			Order::BuyExecution {
+				fees: asset(MultiLocation::parent(), 100),
+				weight: 0,
+				debt: 10_000_000,
+				halt_on_error: false,
+				instructions: vec![],
+			},
+			Order::DepositAsset {
+				assets: All.into(),
+				max_assets: 1,
+				beneficiary: dest.clone(),
+			},
+		],
+	};
+	Relay::execute_with(|| {
+		assert_ok!(<relay_chain::XcmRouter as SendXcm>::send_xcm(
+			Parachain(1).into(),
+			message
+		));
+	});
+
+	ParaA::execute_with(|| {
+		assert_eq!(Assets::balance(relay_id, &PARAALICE.into()), 90);
+		assert_eq!(Assets::balance(other_id, &PARAALICE.into()), 0);
+
+		let trapped: MultiAssets =
+			vec![asset(MultiLocation::new(1, X1(PalletInstance(5u8))), 100)].into();
+		let hash = AssetTrap::trap_hash(&MultiLocation::parent(), &trapped);
+		assert_eq!(AssetTrap::asset_traps(hash), 1);
+
+		assert_ok!(AssetTrap::claim_assets(
+			parachain::Origin::from(pallet_xcm::Origin::Xcm(MultiLocation::parent())),
+			Box::new(trapped),
+			Box::new(dest),
+		));
+		assert_eq!(Assets::balance(other_id, &PARAALICE.into()), 100);
+	});
+}
+
+#[test]
+fn barrier_rejects_disallowed_origins() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+
+	// register relay asset in parachain A, and disallow paid execution from the relay
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+		assert_ok!(XcmBarrier::disallow_origin(
+			parachain::Origin::root(),
+			pallet_xcm_barrier::BarrierRule::PaidExecution,
+			Box::new(MultiLocation::parent())
+		));
+	});
+
+	let dest: MultiLocation = AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	let transfer = || {
+		Relay::execute_with(|| {
+			assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+				relay_chain::Origin::signed(RELAYALICE),
+				Box::new(Parachain(1).into().into()),
+				Box::new(dest.clone().into()),
+				Box::new((Here, 123).into()),
+				0,
+				123,
+			));
+		});
+	};
+
+	transfer();
+
+	// The message was rejected
+	ParaA::execute_with(|| {
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 0);
+		assert!(parachain::System::events().iter().any(|r| matches!(
+			r.event,
+			parachain::Event::MsgQueue(parachain::mock_msg_queue::Event::ExecutedDownward(
+				_,
+				Outcome::Error(XcmError::Barrier)
+			))
+		)));
+		assert!(parachain::System::events().iter().any(|r| r.event
+			== parachain::Event::XcmBarrier(pallet_xcm_barrier::Event::ExecutionRejected(
+				MultiLocation::parent()
+			))));
+
+		// Allow the relay (but not its descendants) again
+		assert_ok!(XcmBarrier::allow_origin(
+			parachain::Origin::root(),
+			pallet_xcm_barrier::BarrierRule::PaidExecution,
+			Box::new(MultiLocation::parent()),
+			false
+		));
+	});
+
+	transfer();
+
+	ParaA::execute_with(|| {
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 123);
+	});
+}
+
+#[test]
+fn transact_through_derivative_multilocation() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+		// Root can register a derivative index for PARAALICE
+		assert_ok!(XcmTransactor::register(
+			parachain::Origin::root(),
+			PARAALICE.into(),
+			0,
+		));
+		// 1 relay token per 1000 weight
+		assert_ok!(XcmTransactor::set_transact_info(
+			parachain::Origin::root(),
+			Box::new(MultiLocation::parent()),
+			3000,
+			1_000_000_000,
+			20_000_000_000
+		));
+	});
+
+	// Send relay tokens to PARAALICE, so that it can pay for the fees
+	let dest: MultiLocation = AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, 10_000_000).into()),
+			0,
+			123,
+		));
+	});
+
+	ParaA::execute_with(|| {
+		assert_eq!(Assets::balance(source_id, &PARAALICE.into()), 10_000_000);
+	});
+
+	// Fund the derivative account of our sovereign account in the relay
+	let para_a_account: relay_chain::AccountId = ParaId::from(1).into_account();
+	let derivative = relay_chain::Utility::derivative_account_id(para_a_account, 0);
+	Relay::execute_with(|| {
+		assert_ok!(RelayBalances::transfer(
+			relay_chain::Origin::signed(RELAYALICE),
+			derivative.clone(),
+			1000
+		));
+	});
+
+	// The derivative account sends 100 tokens back to RELAYALICE
+	let inner_call = relay_chain::Call::Balances(
+		pallet_balances::Call::<relay_chain::Runtime>::transfer(RELAYALICE, 100),
+	);
+
+	ParaA::execute_with(|| {
+		assert_ok!(XcmTransactor::transact_through_derivative_multilocation(
+			parachain::Origin::signed(PARAALICE.into()),
+			parachain::Transactors::Relay,
+			0,
+			Box::new(MultiLocation::parent()),
+			// 4000000000 + 3000 weight is 4000003 relay tokens, burnt from PARAALICE
+			4_000_000_000,
+			inner_call.encode(),
+		));
+		assert_eq!(
+			Assets::balance(source_id, &PARAALICE.into()),
+			10_000_000 - 4_000_003
+		);
+	});
+
+	Relay::execute_with(|| {
+		assert_eq!(RelayBalances::free_balance(&derivative), 900);
+	});
+}
+
+#[test]
+fn transact_through_sovereign() {
+	MockNet::reset();
+
+	let source_location = parachain::AssetType::Xcm(MultiLocation::parent());
+	let source_id: parachain::AssetId = source_location.clone().into();
+	let asset_metadata = parachain::AssetMetadata {
+		name: b"RelayToken".to_vec(),
+		symbol: b"Relay".to_vec(),
+		decimals: 12,
+	};
+
+	ParaA::execute_with(|| {
+		assert_ok!(AssetManager::register_asset(
+			parachain::Origin::root(),
+			source_location,
+			asset_metadata,
+			1u128,
+		));
+		assert_ok!(AssetManager::set_asset_units_per_second(
+			parachain::Origin::root(),
+			source_id,
+			0u128
+		));
+		assert_ok!(XcmTransactor::set_transact_info(
+			parachain::Origin::root(),
+			Box::new(MultiLocation::parent()),
+			3000,
+			1_000_000_000,
+			20_000_000_000
+		));
+	});
+
+	// Send relay tokens to PARAALICE, so that it can pay for the fees. These are held by our
+	// sovereign account in the relay
+	let dest: MultiLocation = AccountKey20 {
+		network: NetworkId::Any,
+		key: PARAALICE,
+	}
+	.into();
+	Relay::execute_with(|| {
+		assert_ok!(RelayChainPalletXcm::reserve_transfer_assets(
+			relay_chain::Origin::signed(RELAYALICE),
+			Box::new(Parachain(1).into().into()),
+			Box::new(dest.clone().into()),
+			Box::new((Here, 10_000_000).into()),
+			0,
+			123,
+		));
+	});
+
+	// Our sovereign account sends 100 tokens to RELAYALICE
+	let call = relay_chain::Call::Balances(
+		pallet_balances::Call::<relay_chain::Runtime>::transfer(RELAYALICE, 100),
+	);
+
+	ParaA::execute_with(|| {
+		// Only root can transact through the sovereign account
+		assert!(XcmTransactor::transact_through_sovereign(
+			parachain::Origin::signed(PARAALICE.into()),
+			Box::new(MultiLocation::parent()),
+			PARAALICE.into(),
+			Box::new(MultiLocation::parent()),
+			4_000_000_000,
+			call.encode(),
+		)
+		.is_err());
+
+		assert_ok!(XcmTransactor::transact_through_sovereign(
+			parachain::Origin::root(),
+			Box::new(MultiLocation::parent()),
+			PARAALICE.into(),
+			Box::new(MultiLocation::parent()),
+			4_000_000_000,
+			call.encode(),
+		));
+		assert_eq!(
+			Assets::balance(source_id, &PARAALICE.into()),
+			10_000_000 - 4_000_003
+		);
+	});
+
+	Relay::execute_with(|| {
+		assert_eq!(
+			RelayBalances::free_balance(&RELAYALICE),
+			INITIAL_BALANCE - 10_000_000 + 100
+		);
+	});
+}
+
+#[test]
+fn subscribe_to_relay_xcm_version() {
+	MockNet::reset();
+
+	ParaA::execute_with(|| {
+		// Only root can subscribe
+		assert!(parachain::PolkadotXcm::force_subscribe_version_notify(
+			parachain::Origin::signed(PARAALICE.into()),
+			Box::new(MultiLocation::parent().into()),
+		)
+		.is_err());
+
+		assert_ok!(parachain::PolkadotXcm::force_subscribe_version_notify(
+			parachain::Origin::root(),
+			Box::new(MultiLocation::parent().into()),
+		));
+	});
+
+	// The relay answers with the version it supports, which is now used to wrap our messages
+	ParaA::execute_with(|| {
+		assert!(parachain::System::events().iter().any(|r| r.event
+			== parachain::Event::PolkadotXcm(pallet_xcm::Event::SupportedVersionChanged(
+				MultiLocation::parent(),
+				1
+			))));
+	});
+}
@@ -14,12 +14,16 @@
 // You should have received a copy of the GNU General Public License
 // along with Moonbeam.  If not, see <http://www.gnu.org/licenses/>.
 
+use crate::{CouncilInstance, TechCommitteeInstance};
 use crowdloan_rewards_precompiles::CrowdloanRewardsWrapper;
 use evm::{executor::PrecompileOutput, Context, ExitError};
 use pallet_evm::{AddressMapping, Precompile, PrecompileSet};
+use pallet_evm_precompile_batch::BatchPrecompile;
 use pallet_evm_precompile_bn128::{Bn128Add, Bn128Mul, Bn128Pairing};
+use pallet_evm_precompile_collective::CollectivePrecompile;
 use pallet_evm_precompile_dispatch::Dispatch;
 use pallet_evm_precompile_modexp::Modexp;
+use pallet_evm_precompile_proxy::ProxyWrapper;
 use pallet_evm_precompile_sha3fips::Sha3FIPS256;
 use pallet_evm_precompile_simple::{ECRecover, ECRecoverPublicKey, Identity, Ripemd160, Sha256};
 use parachain_staking_precompiles::ParachainStakingWrapper;
@@ -27,6 +31,11 @@ use sp_core::H160;
 use sp_std::fmt::Debug;
 use sp_std::marker::PhantomData;
 
+/// Addresses of the precompiles.
+const PRECOMPILE_ADDRESSES: &[u64] = &[
+	1, 2, 3, 4, 5, 6, 7, 8, 1024, 1025, 1026, 2048, 2049, 2053, 2054, 2056, 2057,
+];
+
 /// The PrecompileSet installed in the Moonbeam runtime.
 /// We include the nine Istanbul precompiles
 /// (https://github.com/ethereum/go-ethereum/blob/3c46f557/core/vm/contracts.go#L69)
@@ -41,9 +50,15 @@ where
 	/// Return all addresses that contain precompiles. This can be used to populate dummy code
 	/// under the precompile.
 	pub fn used_addresses() -> impl Iterator<Item = R::AccountId> {
-		sp_std::vec![1, 2, 3, 4, 5, 6, 7, 8, 1024, 1025, 1026, 2048, 2049]
-			.into_iter()
-			.map(|x| R::AddressMapping::into_account_id(hash(x)))
+		PRECOMPILE_ADDRESSES
+			.iter()
+			.map(|x| R::AddressMapping::into_account_id(hash(*x)))
+	}
+
+	/// Return whether a precompile lives at the given address. This can be used by the client
+	/// to tell calls to precompiles from calls to contracts.
+	pub fn is_precompile(address: &H160) -> bool {
+		PRECOMPILE_ADDRESSES.iter().any(|x| *address == hash(*x))
 	}
 }
 
@@ -56,6 +71,10 @@ where
 	Dispatch<R>: Precompile,
 	ParachainStakingWrapper<R>: Precompile,
 	CrowdloanRewardsWrapper<R>: Precompile,
+	BatchPrecompile<R>: Precompile,
+	ProxyWrapper<R>: Precompile,
+	CollectivePrecompile<R, CouncilInstance>: Precompile,
+	CollectivePrecompile<R, TechCommitteeInstance>: Precompile,
 {
 	fn execute(
 		address: H160,
@@ -84,6 +103,18 @@ where
 			a if a == hash(2049) => Some(CrowdloanRewardsWrapper::<R>::execute(
 				input, target_gas, context,
 			)),
+			a if a == hash(2053) => Some(BatchPrecompile::<R>::execute_at(
+				a, input, target_gas, context,
+			)),
+			a if a == hash(2054) => Some(ProxyWrapper::<R>::execute(input, target_gas, context)),
+			a if a == hash(2056) => Some(CollectivePrecompile::<R, CouncilInstance>::execute(
+				input, target_gas, context,
+			)),
+			a if a == hash(2057) => {
+				Some(CollectivePrecompile::<R, TechCommitteeInstance>::execute(
+					input, target_gas, context,
+				))
+			}
 			_ => None,
 		}
 	}
@@ -53,6 +53,7 @@ use pallet_evm::{
 	Account as EVMAccount, EnsureAddressNever, EnsureAddressRoot, FeeCalculator, GasWeightMapping,
 	IdentityAddressMapping, Runner,
 };
+use pallet_evm_precompile_proxy::{EvmProxyCallFilter, EvmSubCall};
 use pallet_transaction_payment::{CurrencyAdapter, Multiplier, TargetedFeeAdjustment};
 pub use parachain_staking::{InflationInfo, Range};
 use parity_scale_codec::{Decode, Encode, MaxEncodedLen};
@@ -796,6 +797,30 @@ impl InstanceFilter<Call> for ProxyType {
 	}
 }
 
+impl EvmProxyCallFilter for ProxyType {
+	fn is_evm_proxy_call_allowed(&self, call: &EvmSubCall) -> bool {
+		match self {
+			ProxyType::Any => true,
+			// Parachain staking and collectives precompiles, without transfer.
+			ProxyType::NonTransfer => {
+				call.value.is_zero()
+					&& (call.to == H160::from_low_u64_be(2048)
+						|| call.to == H160::from_low_u64_be(2056)
+						|| call.to == H160::from_low_u64_be(2057))
+			}
+			// Collectives (council and technical committee) precompiles.
+			ProxyType::Governance => {
+				call.value.is_zero()
+					&& (call.to == H160::from_low_u64_be(2056)
+						|| call.to == H160::from_low_u64_be(2057))
+			}
+			// Parachain staking precompile.
+			ProxyType::Staking => call.value.is_zero() && call.to == H160::from_low_u64_be(2048),
+			ProxyType::CancelProxy => false,
+		}
+	}
+}
+
 impl pallet_proxy::Config for Runtime {
 	type Event = Event;
 	type Call = Call;
@@ -29,9 +29,10 @@ use frame_support::{
 	weights::{DispatchClass, Weight},
 	StorageHasher, Twox128,
 };
-use moonriver_runtime::{BlockWeights, Precompiles};
+use moonriver_runtime::{BlockWeights, Precompiles, ProxyType};
 use nimbus_primitives::NimbusId;
 use pallet_evm::PrecompileSet;
+use pallet_evm_precompile_proxy::{EvmProxyCallFilter, EvmSubCall};
 use pallet_transaction_payment::Multiplier;
 use parachain_staking::Bond;
 use parity_scale_codec::Encode;
@@ -1120,3 +1121,24 @@ fn ethereum_invalid_transaction() {
 		);
 	});
 }
+
+#[test]
+fn evm_proxy_call_filter_allows_governance_precompiles() {
+	let call = |to: u64, value: u64| EvmSubCall {
+		to: H160::from_low_u64_be(to),
+		value: value.into(),
+		call_data: vec![],
+	};
+
+	// Council and technical committee precompiles.
+	for &address in &[2056, 2057] {
+		assert!(ProxyType::Governance.is_evm_proxy_call_allowed(&call(address, 0)));
+		assert!(ProxyType::NonTransfer.is_evm_proxy_call_allowed(&call(address, 0)));
+		assert!(!ProxyType::Governance.is_evm_proxy_call_allowed(&call(address, 1)));
+		assert!(!ProxyType::Staking.is_evm_proxy_call_allowed(&call(address, 0)));
+	}
+
+	// Parachain staking precompile.
+	assert!(!ProxyType::Governance.is_evm_proxy_call_allowed(&call(2048, 0)));
+	assert!(ProxyType::NonTransfer.is_evm_proxy_call_allowed(&call(2048, 0)));
+}
@@ -0,0 +1,70 @@
+import { expect } from "chai";
+
+import {
+  ALITH,
+  ALITH_PRIV_KEY,
+  GENESIS_ACCOUNT,
+  GENESIS_ACCOUNT_PRIVATE_KEY,
+  TEST_ACCOUNT,
+} from "../util/constants";
+import { createTransaction } from "../util/transactions";
+import { describeDevMoonbeam } from "../util/setup-dev-tests";
+import { customWeb3Request } from "../util/providers";
+
+describeDevMoonbeam("TxPool - Filtering by sender", (context) => {
+  let genesisTxHash, alithTxHash;
+  before("Setup: Create transactions from 2 senders", async () => {
+    genesisTxHash = (
+      await customWeb3Request(context.web3, "eth_sendRawTransaction", [
+        await createTransaction(context.web3, {
+          from: GENESIS_ACCOUNT,
+          privateKey: GENESIS_ACCOUNT_PRIVATE_KEY,
+          to: TEST_ACCOUNT,
+          value: "0x200",
+          gas: 21000,
+        }),
+      ])
+    ).result;
+    alithTxHash = (
+      await customWeb3Request(context.web3, "eth_sendRawTransaction", [
+        await createTransaction(context.web3, {
+          from: ALITH,
+          privateKey: ALITH_PRIV_KEY,
+          to: TEST_ACCOUNT,
+          value: "0x200",
+          gas: 21000,
+          nonce: 1, // future nonce
+        }),
+      ])
+    ).result;
+  });
+
+  it("should return the transactions of a sender with txpool_contentFrom", async function () {
+    const content = await customWeb3Request(context.web3, "txpool_contentFrom", [GENESIS_ACCOUNT]);
+    expect(content.result.pending[context.web3.utils.toHex(0)].hash).to.equal(genesisTxHash);
+    expect(content.result.queued).to.be.empty;
+
+    const alithContent = await customWeb3Request(context.web3, "txpool_contentFrom", [ALITH]);
+    expect(alithContent.result.pending).to.be.empty;
+    expect(alithContent.result.queued[context.web3.utils.toHex(1)].hash).to.equal(alithTxHash);
+  });
+
+  it("should filter txpool_inspect by sender", async function () {
+    const inspect = await customWeb3Request(context.web3, "txpool_inspect", [ALITH]);
+    expect(Object.keys(inspect.result.pending)).to.be.empty;
+    expect(Object.keys(inspect.result.queued)).to.deep.equal([ALITH.toLowerCase()]);
+  });
+
+  it("should paginate txpool_content over senders", async function () {
+    // Senders are ordered by address.
+    const first = await customWeb3Request(context.web3, "txpool_content", [{ count: 1 }]);
+    expect(Object.keys(first.result.pending)).to.deep.equal([GENESIS_ACCOUNT.toLowerCase()]);
+    expect(Object.keys(first.result.queued)).to.be.empty;
+
+    const second = await customWeb3Request(context.web3, "txpool_content", [
+      { after: GENESIS_ACCOUNT, count: 1 },
+    ]);
+    expect(Object.keys(second.result.pending)).to.be.empty;
+    expect(Object.keys(second.result.queued)).to.deep.equal([ALITH.toLowerCase()]);
+  });
+});
@@ -0,0 +1,89 @@
+import { expect } from "chai";
+import Web3 from "web3";
+
+import { GENESIS_ACCOUNT, TEST_ACCOUNT } from "../util/constants";
+import { createTransfer } from "../util/transactions";
+import { describeDevMoonbeam } from "../util/setup-dev-tests";
+import { customWeb3Request } from "../util/providers";
+
+// web3 doesn't support custom subscriptions, so the notifications are read from the provider.
+const txpoolSubscribe = async (web3: Web3) => {
+  const provider = web3.currentProvider as any;
+  const subscriptionId = await new Promise<string>((resolve, reject) =>
+    provider.send(
+      { jsonrpc: "2.0", id: 1, method: "txpool_subscribe", params: [] },
+      (error, response) => (error ? reject(error) : resolve(response.result))
+    )
+  );
+
+  const events = [];
+  provider.on("data", (message) => {
+    if (message.params.subscription == subscriptionId) {
+      events.push(message.params.result);
+    }
+  });
+  return events;
+};
+
+const waitForEvents = async (events: any[], count: number) => {
+  while (events.length < count) {
+    await new Promise((resolve) => setTimeout(resolve, 100));
+  }
+  return events;
+};
+
+describeDevMoonbeam("TxPool - Subscription to pending transactions", (context) => {
+  let events;
+  before("Setup: Subscribe to the pool", async () => {
+    events = await txpoolSubscribe(await context.createWeb3("ws"));
+  });
+
+  it("should send the transaction body", async function () {
+    const txHash = (
+      await customWeb3Request(context.web3, "eth_sendRawTransaction", [
+        await createTransfer(context.web3, TEST_ACCOUNT, 512),
+      ])
+    ).result;
+
+    const [event] = await waitForEvents(events, 1);
+    expect(event.type).to.equal("pending");
+    expect(event.transaction).to.include({
+      hash: txHash,
+      from: GENESIS_ACCOUNT.toLowerCase(),
+      to: TEST_ACCOUNT,
+      value: "0x200",
+    });
+  });
+});
+
+describeDevMoonbeam("TxPool - Subscription to replaced transactions", (context) => {
+  let events;
+  before("Setup: Subscribe to the pool", async () => {
+    events = await txpoolSubscribe(await context.createWeb3("ws"));
+  });
+
+  it("should notify the replacement", async function () {
+    const firstHash = (
+      await customWeb3Request(context.web3, "eth_sendRawTransaction", [
+        await createTransfer(context.web3, TEST_ACCOUNT, 512, {
+          nonce: 0,
+          gasPrice: 1_000_000_000,
+        }),
+      ])
+    ).result;
+    const secondHash = (
+      await customWeb3Request(context.web3, "eth_sendRawTransaction", [
+        await createTransfer(context.web3, TEST_ACCOUNT, 512, {
+          nonce: 0,
+          gasPrice: 2_000_000_000,
+        }),
+      ])
+    ).result;
+
+    await waitForEvents(events, 3);
+    expect(events.map((event) => event.type)).to.deep.equal(["pending", "pending", "replaced"]);
+    expect(events[0].transaction.hash).to.equal(firstHash);
+    expect(events[1].transaction.hash).to.equal(secondHash);
+    expect(events[2]).to.deep.equal({ type: "replaced", hash: firstHash, by: secondHash });
+  });
+});
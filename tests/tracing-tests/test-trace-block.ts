@@ -0,0 +1,129 @@
+import { expect } from "chai";
+import { customWeb3Request } from "../util/providers";
+import { describeDevMoonbeam } from "../util/setup-dev-tests";
+import { createContract, createContractExecution } from "../util/transactions";
+
+const GENESIS_CONTRACT_ADDRESSES = [
+  "0xc2bf5f29a4384b1ab0c063e1c666f02121b6084a",
+  "0x42e2ee7ba8975c473157634ac2af4098190fc741",
+  "0xf8cef78e923919054037a1d03662bbd884ff4edf",
+];
+
+describeDevMoonbeam("Trace block, transaction and get", (context) => {
+  let subcallsTxHash: string;
+
+  before("Setup: Create 4 blocks with TraceFilter contracts", async function () {
+    const { contract, rawTx } = await createContract(context.web3, "TraceFilter", {}, [false]);
+    await context.createBlock({ transactions: [rawTx] });
+
+    const { rawTx: rawTx2 } = await createContract(context.web3, "TraceFilter", {}, [true]);
+    await context.createBlock({ transactions: [rawTx2] });
+
+    const { rawTx: rawTx3 } = await createContract(context.web3, "TraceFilter", {}, [false]);
+    const { rawTx: rawTx4 } = await createContract(context.web3, "TraceFilter", { nonce: 3 }, [
+      false,
+    ]);
+    await context.createBlock({ transactions: [rawTx3, rawTx4] });
+
+    const { txResults } = await context.createBlock({
+      transactions: [
+        await createContractExecution(context.web3, {
+          contract,
+          contractCall: contract.methods.subcalls(
+            GENESIS_CONTRACT_ADDRESSES[1],
+            GENESIS_CONTRACT_ADDRESSES[2]
+          ),
+        }),
+      ],
+    });
+    subcallsTxHash = txResults[0].result;
+  });
+
+  it("should return the traces of a block", async function () {
+    let response = await customWeb3Request(context.web3, "trace_block", ["0x03"]);
+
+    expect(response.result.length).to.equal(2);
+    expect(response.result[0].blockNumber).to.equal(3);
+    expect(response.result[0].transactionPosition).to.equal(0);
+    expect(response.result[1].blockNumber).to.equal(3);
+    expect(response.result[1].transactionPosition).to.equal(1);
+  });
+
+  it("should match trace_filter for a single block", async function () {
+    let block = await customWeb3Request(context.web3, "trace_block", ["0x04"]);
+    let filter = await customWeb3Request(context.web3, "trace_filter", [
+      {
+        fromBlock: "0x04",
+        toBlock: "0x04",
+      },
+    ]);
+
+    expect(block.result).to.deep.equal(filter.result);
+  });
+
+  it("should return no traces for the genesis block", async function () {
+    let response = await customWeb3Request(context.web3, "trace_block", ["0x00"]);
+
+    expect(response.result).to.deep.equal([]);
+  });
+
+  it("should return the traces of a transaction", async function () {
+    let response = await customWeb3Request(context.web3, "trace_transaction", [
+      "0x38543a19a4fdf101ff6607f712a2283e0056d849f7dbe36715b464c6b08e317e",
+    ]);
+
+    expect(response.result.length).to.equal(1);
+    expect(response.result[0]).to.include({
+      blockNumber: 1,
+      subtraces: 0,
+      transactionHash: "0x38543a19a4fdf101ff6607f712a2283e0056d849f7dbe36715b464c6b08e317e",
+      transactionPosition: 0,
+      type: "create",
+    });
+  });
+
+  it("should return the sub-calls of a transaction", async function () {
+    let response = await customWeb3Request(context.web3, "trace_transaction", [subcallsTxHash]);
+
+    expect(response.result.length).to.equal(7);
+    expect(response.result[0].traceAddress).to.deep.equal([]);
+    expect(response.result[6].traceAddress).to.deep.equal([1, 1]);
+    for (const trace of response.result) {
+      expect(trace.transactionHash).to.equal(subcallsTxHash);
+    }
+  });
+
+  it("should return null for an unknown transaction", async function () {
+    let response = await customWeb3Request(context.web3, "trace_transaction", [
+      "0x0000000000000000000000000000000000000000000000000000000000000001",
+    ]);
+
+    expect(response.error).to.not.exist;
+    expect(response.result).to.equal(null);
+  });
+
+  it("should return the trace at a given trace address", async function () {
+    let response = await customWeb3Request(context.web3, "trace_get", [
+      subcallsTxHash,
+      ["0x1", "0x0"],
+    ]);
+
+    expect(response.result.traceAddress).to.deep.equal([1, 0]);
+    expect(response.result.subtraces).to.equal(0);
+    expect(response.result.blockNumber).to.equal(4);
+  });
+
+  it("should return the root trace for empty indices", async function () {
+    let response = await customWeb3Request(context.web3, "trace_get", [subcallsTxHash, []]);
+
+    expect(response.result.traceAddress).to.deep.equal([]);
+    expect(response.result.subtraces).to.equal(2);
+  });
+
+  it("should return null for a missing trace address", async function () {
+    let response = await customWeb3Request(context.web3, "trace_get", [subcallsTxHash, ["0x5"]]);
+
+    expect(response.error).to.not.exist;
+    expect(response.result).to.equal(null);
+  });
+});
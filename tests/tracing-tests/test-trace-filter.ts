@@ -66,6 +66,32 @@ describeDevMoonbeam("Trace filter - Contract creation ", (context) => {
     });
   });
 
+  it("should accept block hashes", async function () {
+    const block = await context.web3.eth.getBlock(1);
+    let response = await customWeb3Request(context.web3, "trace_filter", [
+      {
+        fromBlock: block.hash,
+        toBlock: block.hash,
+      },
+    ]);
+
+    expect(response.result.length).to.equal(1);
+    expect(response.result[0].blockNumber).to.equal(1);
+  });
+
+  it("should filter contract creations by created address", async function () {
+    let response = await customWeb3Request(context.web3, "trace_filter", [
+      {
+        fromBlock: "0x01",
+        toBlock: "0x01",
+        toAddress: ["0xc2bf5f29a4384b1ab0c063e1c666f02121b6084a"],
+      },
+    ]);
+
+    expect(response.result.length).to.equal(1);
+    expect(response.result[0].type).to.equal("create");
+  });
+
   it("should be able to replay reverted contract", async function () {
     // Perform RPC call.
     let response = await customWeb3Request(context.web3, "trace_filter", [
@@ -59,6 +59,32 @@ async function nestedSingle(context) {
   );
 }
 
+// Produces a +58,000 step trace, and returns the hash of the transaction.
+async function overflowingTrace(context) {
+  const { contract, rawTx } = await createContract(context.web3, "OverflowingTrace", {}, [false]);
+  const { txResults } = await context.createBlock({
+    transactions: [rawTx],
+  });
+  let receipt = await context.web3.eth.getTransactionReceipt(txResults[0].result);
+  let nonce = await context.web3.eth.getTransactionCount(GENESIS_ACCOUNT);
+  let callTx = await context.web3.eth.accounts.signTransaction(
+    {
+      from: GENESIS_ACCOUNT,
+      to: receipt.contractAddress,
+      gas: "0x100000",
+      value: "0x00",
+      nonce: nonce,
+      data: contract.methods.set_and_loop(10).encodeABI(),
+    },
+    GENESIS_ACCOUNT_PRIVATE_KEY
+  );
+  const data = await customWeb3Request(context.web3, "eth_sendRawTransaction", [
+    callTx.rawTransaction,
+  ]);
+  await context.createBlock();
+  return data.result;
+}
+
 describeDevMoonbeam(
   "Trace",
   (context) => {
@@ -69,31 +95,8 @@ describeDevMoonbeam(
     // "Allocator ran out of space"'.
     it("should not overflow Wasm memory", async function () {
       this.timeout(15000);
-      const { contract, rawTx } = await createContract(context.web3, "OverflowingTrace", {}, [
-        false,
-      ]);
-      const { txResults } = await context.createBlock({
-        transactions: [rawTx],
-      });
-      let receipt = await context.web3.eth.getTransactionReceipt(txResults[0].result);
-      let nonce = await context.web3.eth.getTransactionCount(GENESIS_ACCOUNT);
-      // Produce a +58,000 step trace.
-      let callTx = await context.web3.eth.accounts.signTransaction(
-        {
-          from: GENESIS_ACCOUNT,
-          to: receipt.contractAddress,
-          gas: "0x100000",
-          value: "0x00",
-          nonce: nonce,
-          data: contract.methods.set_and_loop(10).encodeABI(),
-        },
-        GENESIS_ACCOUNT_PRIVATE_KEY
-      );
-      const data = await customWeb3Request(context.web3, "eth_sendRawTransaction", [
-        callTx.rawTransaction,
-      ]);
-      await context.createBlock();
-      let trace = await customWeb3Request(context.web3, "debug_traceTransaction", [data.result]);
+      const hash = await overflowingTrace(context);
+      let trace = await customWeb3Request(context.web3, "debug_traceTransaction", [hash]);
       expect(trace.result.stepLogs.length).to.equal(58219);
     });
 
@@ -426,3 +429,79 @@ describeDevMoonbeam("Trace", (context) => {
     });
   });
 });
+
+describeDevMoonbeam("Trace limits", (context) => {
+  it("should accept a Go duration timeout", async function () {
+    const send = await nestedSingle(context);
+    await context.createBlock();
+    let traceTx = await customWeb3Request(context.web3, "debug_traceTransaction", [
+      send.result,
+      { tracer: "callTracer", timeout: "1m30s" },
+    ]);
+    expect(traceTx.error).to.not.exist;
+    expect(traceTx.result.calls.length).to.be.equal(1);
+  });
+
+  it("should interrupt a trace exceeding its timeout", async function () {
+    const send = await nestedSingle(context);
+    await context.createBlock();
+    let traceTx = await customWeb3Request(context.web3, "debug_traceTransaction", [
+      send.result,
+      { timeout: "0s" },
+    ]);
+    expect(traceTx.error.code).to.be.equal(-32010);
+    expect(traceTx.error.message).to.be.equal("execution timeout");
+  });
+
+  it("should reject an invalid timeout", async function () {
+    const send = await nestedSingle(context);
+    await context.createBlock();
+    let traceTx = await customWeb3Request(context.web3, "debug_traceTransaction", [
+      send.result,
+      { timeout: "10 seconds" },
+    ]);
+    expect(traceTx.error.message).to.contain("invalid timeout");
+  });
+});
+
+describeDevMoonbeam(
+  "Trace limits - Maximum steps",
+  (context) => {
+    it("should reject a trace exceeding the maximum steps", async function () {
+      this.timeout(15000);
+      const hash = await overflowingTrace(context);
+      let traceTx = await customWeb3Request(context.web3, "debug_traceTransaction", [hash]);
+      expect(traceTx.error.code).to.be.equal(-32011);
+      expect(traceTx.error.message).to.be.equal("trace exceeds the maximum of 1000 steps");
+    });
+
+    it("should not count steps for native tracers", async function () {
+      this.timeout(15000);
+      const hash = await overflowingTrace(context);
+      let traceTx = await customWeb3Request(context.web3, "debug_traceTransaction", [
+        hash,
+        { tracer: "callTracer" },
+      ]);
+      expect(traceTx.error).to.not.exist;
+    });
+  },
+  false,
+  ["--ethapi-debug-max-steps=1000"]
+);
+
+describeDevMoonbeam(
+  "Trace limits - Maximum response size",
+  (context) => {
+    it("should reject a response exceeding the maximum size", async function () {
+      this.timeout(15000);
+      const hash = await overflowingTrace(context);
+      let traceTx = await customWeb3Request(context.web3, "debug_traceTransaction", [hash]);
+      expect(traceTx.error.code).to.be.equal(-32011);
+      expect(traceTx.error.message).to.be.equal(
+        "response exceeds the maximum size of 1048576 bytes"
+      );
+    });
+  },
+  false,
+  ["--ethapi-debug-max-response-size=1"]
+);
@@ -38,7 +38,10 @@ let nodeStarted = false;
 
 // This will start a moonbeam dev node, only 1 at a time (check every 100ms).
 // This will prevent race condition on the findAvailablePorts which uses the PID of the process
-export async function startMoonbeamDevNode(withWasm?: boolean): Promise<{
+export async function startMoonbeamDevNode(
+  withWasm?: boolean,
+  extraArgs: string[] = []
+): Promise<{
   p2pPort: number;
   rpcPort: number;
   wsPort: number;
@@ -66,6 +69,7 @@ export async function startMoonbeamDevNode(withWasm?: boolean): Promise<{
     `--rpc-port=${rpcPort}`,
     `--ws-port=${wsPort}`,
     `--tmp`,
+    ...extraArgs,
   ];
   if (ETHAPI_CMD != "") {
     args.push("--wasm-runtime-overrides=/");
@@ -50,7 +50,8 @@ interface InternalDevTestContext extends DevTestContext {
 export function describeDevMoonbeam(
   title: string,
   cb: (context: DevTestContext) => void,
-  withWasm?: boolean
+  withWasm?: boolean,
+  extraArgs: string[] = []
 ) {
   describe(title, function () {
     // Set timeout to 5000 for all tests.
@@ -67,7 +68,7 @@ export function describeDevMoonbeam(
     before("Starting Moonbeam Test Node", async function () {
       this.timeout(SPAWNING_TIME);
       const init = !DEBUG_MODE
-        ? await startMoonbeamDevNode(withWasm)
+        ? await startMoonbeamDevNode(withWasm, extraArgs)
         : {
             runningNode: null,
             p2pPort: 19931,